@@ -0,0 +1,230 @@
+-- Multi-list support and the per-email metadata extracted at population time
+-- (trailers, Fixes: tags, links, subject tags, diff summaries, bot and pull request fields)
+-- Written with IF NOT EXISTS so it also applies to databases created from the
+-- original sql/00_schema.sql before migrations existed
+
+-- Mailing lists (one per archive, e.g. bpf, netdev)
+CREATE TABLE IF NOT EXISTS mailing_lists (
+  list_id       SERIAL PRIMARY KEY,
+  name          TEXT NOT NULL UNIQUE,
+  clone_url     TEXT,
+  repo_path     TEXT,
+  created_at    TIMESTAMPTZ DEFAULT NOW()
+);
+
+ALTER TABLE patches
+  ADD COLUMN IF NOT EXISTS envelope_from TEXT,                    -- Sender when an in-body "From:" names a different author
+  ADD COLUMN IF NOT EXISTS sender_type   TEXT NOT NULL DEFAULT 'human',  -- human, patchwork_bot, kernel_test_robot, bpf_ci, syzbot, coverity
+  ADD COLUMN IF NOT EXISTS is_ping       BOOLEAN DEFAULT FALSE,   -- Short "ping"/"any update?" nudge
+  ADD COLUMN IF NOT EXISTS is_rejection  BOOLEAN DEFAULT FALSE,   -- Reply rejecting the patch ("NAK", "please drop this")
+  ADD COLUMN IF NOT EXISTS signature     TEXT,                    -- Text after the "-- " separator
+  ADD COLUMN IF NOT EXISTS is_cover_letter BOOLEAN DEFAULT FALSE,  -- [PATCH 0/N] introduction of a series
+  -- Subject tags, e.g. "[RFC PATCH bpf-next v3 2/5]"
+  ADD COLUMN IF NOT EXISTS patch_version INT,                     -- 1 when tagged without "vN", NULL for untagged subjects
+  ADD COLUMN IF NOT EXISTS is_rfc        BOOLEAN DEFAULT FALSE,
+  ADD COLUMN IF NOT EXISTS is_resend     BOOLEAN DEFAULT FALSE,
+  ADD COLUMN IF NOT EXISTS target_tree   TEXT,                    -- e.g., "net-next", "bpf-next"
+  -- Pull request fields (`git request-pull` emails)
+  ADD COLUMN IF NOT EXISTS is_pull_request       BOOLEAN DEFAULT FALSE,
+  ADD COLUMN IF NOT EXISTS pull_base_commit      TEXT,          -- "changes since commit ..."
+  ADD COLUMN IF NOT EXISTS pull_repository       TEXT,          -- Repository URL to pull from
+  ADD COLUMN IF NOT EXISTS pull_ref              TEXT,          -- e.g., "tags/for-netdev"
+  ADD COLUMN IF NOT EXISTS pull_head_commit      TEXT,          -- "fetch changes up to ..."
+  -- Revert fields ('Revert "..."' patches)
+  ADD COLUMN IF NOT EXISTS is_revert             BOOLEAN DEFAULT FALSE,
+  ADD COLUMN IF NOT EXISTS reverted_commit       TEXT,          -- "This reverts commit <sha>."
+  ADD COLUMN IF NOT EXISTS reverted_subject      TEXT,          -- Subject of the reverted commit
+  -- Patchwork automation headers
+  ADD COLUMN IF NOT EXISTS patchwork_id          BIGINT,        -- X-Patchwork-Id (patch ID in the patchwork REST API)
+  ADD COLUMN IF NOT EXISTS patchwork_delegate    TEXT,          -- X-Patchwork-Delegate
+  ADD COLUMN IF NOT EXISTS patchwork_state       TEXT,          -- X-Patchwork-State, lowercased
+  ADD COLUMN IF NOT EXISTS list_id           INT REFERENCES mailing_lists(list_id),  -- Archive this email was imported from
+  ADD COLUMN IF NOT EXISTS list_ids          TEXT[],                -- Lists named in List-Id/X-Mailing-List headers (cross-posts)
+  ADD COLUMN IF NOT EXISTS is_deleted        BOOLEAN DEFAULT FALSE,  -- Removed from the archive by a public-inbox deletion commit
+  -- Diff summary (NULL for emails without a diff)
+  ADD COLUMN IF NOT EXISTS files_changed     INT,
+  ADD COLUMN IF NOT EXISTS lines_added       INT,
+  ADD COLUMN IF NOT EXISTS lines_removed     INT,
+  ADD COLUMN IF NOT EXISTS diff_summary      JSONB,                 -- Files and hunks parsed from the embedded diff
+  ADD COLUMN IF NOT EXISTS git_patch_id      TEXT;                  -- Stable `git patch-id` of the diff, shared by reposts
+
+ALTER TABLE patch_threads
+  ADD COLUMN IF NOT EXISTS list_id           INT REFERENCES mailing_lists(list_id),  -- List of the root patch
+  ADD COLUMN IF NOT EXISTS has_rejection     BOOLEAN DEFAULT FALSE;  -- Someone other than the author rejected it (suggests "rejected")
+
+-- Trailers (Signed-off-by, Acked-by, Reviewed-by, Tested-by, ...) in each email
+CREATE TABLE IF NOT EXISTS patch_trailers (
+  trailer_id    BIGSERIAL PRIMARY KEY,
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  position      INT NOT NULL,       -- Order of the trailer in the message
+  tag           TEXT NOT NULL,      -- e.g., "Acked-by"
+  name          TEXT NOT NULL,
+  email         CITEXT NOT NULL,
+  UNIQUE (patch_id, position)
+);
+
+-- Commits fixed by each patch (Fixes: <sha> ("subject") tags)
+CREATE TABLE IF NOT EXISTS patch_fixes (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  fixes_commit  TEXT NOT NULL,      -- Abbreviated hash of the fixed commit (lowercase)
+  fixes_subject TEXT,               -- Subject quoted in the tag
+  PRIMARY KEY (patch_id, fixes_commit)
+);
+
+-- Link: trailers and lore URLs in each email
+CREATE TABLE IF NOT EXISTS patch_links (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  position      INT NOT NULL,       -- Order of the link in the message
+  url           TEXT NOT NULL,
+  message_id    TEXT,               -- Message-ID embedded in lore/msgid.link URLs
+  is_trailer    BOOLEAN DEFAULT FALSE,  -- "Link:" trailer rather than an inline URL
+  PRIMARY KEY (patch_id, position)
+);
+
+-- syzbot crash reports
+CREATE TABLE IF NOT EXISTS syzbot_reports (
+  patch_id          BIGINT PRIMARY KEY REFERENCES patches(patch_id) ON DELETE CASCADE,
+  title             TEXT NOT NULL,     -- Crash title, e.g., "KASAN: slab-use-after-free Read in ..."
+  extid             TEXT,              -- Bug ID used in the dashboard link
+  dashboard_url     TEXT,
+  head_commit       TEXT,
+  git_tree          TEXT,
+  console_log_url   TEXT,
+  kernel_config_url TEXT,
+  syz_repro_url     TEXT,              -- NULL until syzbot has a reproducer
+  c_repro_url       TEXT
+);
+
+-- Stable tree notifications ("added to the 6.6-stable tree", AUTOSEL, failed backports)
+CREATE TABLE IF NOT EXISTS stable_backports (
+  patch_id         BIGINT PRIMARY KEY REFERENCES patches(patch_id) ON DELETE CASCADE,
+  kind             TEXT NOT NULL,        -- added, autosel, failed
+  stable_versions  TEXT[] NOT NULL,      -- e.g., {6.6}
+  original_subject TEXT,                 -- Subject of the upstream patch
+  upstream_commit  TEXT                  -- Upstream commit hash
+);
+
+-- To/Cc recipients of each email
+CREATE TABLE IF NOT EXISTS patch_recipients (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  kind          TEXT NOT NULL,      -- "to" or "cc"
+  name          TEXT NOT NULL,
+  email         CITEXT NOT NULL,
+  PRIMARY KEY (patch_id, kind, email)
+);
+
+CREATE INDEX IF NOT EXISTS patches_list_id_idx ON patches (list_id);
+CREATE INDEX IF NOT EXISTS patches_list_ids_idx ON patches USING GIN (list_ids);
+CREATE INDEX IF NOT EXISTS patches_git_patch_id_idx ON patches (git_patch_id);
+CREATE INDEX IF NOT EXISTS patches_patchwork_id_idx ON patches (patchwork_id) WHERE patchwork_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS patches_target_tree_idx ON patches (target_tree, patch_version);
+CREATE INDEX IF NOT EXISTS patch_trailers_patch_idx ON patch_trailers (patch_id);
+CREATE INDEX IF NOT EXISTS patch_trailers_email_idx ON patch_trailers (email, tag);
+CREATE INDEX IF NOT EXISTS patch_fixes_commit_idx ON patch_fixes (fixes_commit text_pattern_ops);
+CREATE INDEX IF NOT EXISTS patch_fixes_subject_idx ON patch_fixes (LOWER(fixes_subject));
+CREATE INDEX IF NOT EXISTS patch_links_message_id_idx ON patch_links (message_id) WHERE message_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS syzbot_reports_extid_idx ON syzbot_reports (extid);
+CREATE INDEX IF NOT EXISTS stable_backports_subject_idx ON stable_backports (LOWER(original_subject));
+CREATE INDEX IF NOT EXISTS stable_backports_commit_idx ON stable_backports (upstream_commit text_pattern_ops);
+CREATE INDEX IF NOT EXISTS syzbot_reports_title_idx ON syzbot_reports USING GIN (to_tsvector('english', title));
+CREATE INDEX IF NOT EXISTS patch_recipients_email_idx ON patch_recipients (email, kind);
+CREATE INDEX IF NOT EXISTS patches_sender_type_idx ON patches (sender_type) WHERE sender_type <> 'human';
+CREATE INDEX IF NOT EXISTS patches_ping_idx ON patches (is_ping) WHERE is_ping = TRUE;
+CREATE INDEX IF NOT EXISTS patches_reverted_subject_idx ON patches (LOWER(reverted_subject)) WHERE is_revert = TRUE;
+CREATE INDEX IF NOT EXISTS patches_pull_request_idx ON patches (is_pull_request) WHERE is_pull_request = TRUE;
+CREATE INDEX IF NOT EXISTS patch_threads_list_id_idx ON patch_threads (list_id, last_activity_at DESC);
+
+-- Thread summaries gain the list, diffstat, ping count and rejection flag
+CREATE OR REPLACE VIEW thread_summary AS
+SELECT
+  pt.thread_id,
+  pt.root_patch_id,
+  pt.root_message_id,
+  pt.reply_count,
+  pt.participant_count,
+  p.sent_at as created_at,  -- Use root patch sent_at as thread creation time
+  pt.updated_at,
+  pt.last_activity_at,
+  p.subject as root_subject,
+  p.sent_at as root_sent_at,
+  a.display_name as root_author,
+  a.author_id as root_author_id,
+  pt.list_id,
+  -- Diffstat of the patches in the thread (replies excluded)
+  COALESCE(ds.files_changed, 0) as files_changed,
+  COALESCE(ds.lines_added, 0) as lines_added,
+  COALESCE(ds.lines_removed, 0) as lines_removed,
+  COALESCE(ds.ping_count, 0) as ping_count,
+  pt.has_rejection
+FROM patch_threads pt
+JOIN patches p ON pt.root_patch_id = p.patch_id
+JOIN authors a ON p.author_id = a.author_id
+LEFT JOIN LATERAL (
+  SELECT SUM(tp.files_changed) FILTER (WHERE tp.is_reply = FALSE) as files_changed,
+         SUM(tp.lines_added) FILTER (WHERE tp.is_reply = FALSE) as lines_added,
+         SUM(tp.lines_removed) FILTER (WHERE tp.is_reply = FALSE) as lines_removed,
+         COUNT(*) FILTER (WHERE tp.is_ping) as ping_count
+  FROM patch_replies pr
+  JOIN patches tp ON pr.patch_id = tp.patch_id
+  WHERE pr.thread_id = pt.thread_id
+) ds ON TRUE;
+
+-- One row per thread: bot notifications (with commit links) win over maintainer "applied" replies
+CREATE OR REPLACE VIEW merged_threads AS
+SELECT DISTINCT ON (pt.thread_id)
+  pt.thread_id,
+  pt.root_patch_id,
+  mp.merge_repository,
+  mp.merge_branch,
+  mp.merge_applied_by,
+  mp.sent_at as merge_date,
+  mp.patch_id as merge_notification_patch_id,
+  array_length(mp.merge_commit_links, 1) as commit_count
+FROM patch_threads pt
+JOIN patch_replies pr ON pt.thread_id = pr.thread_id
+JOIN patches mp ON pr.patch_id = mp.patch_id
+WHERE mp.is_merge_notification = TRUE
+ORDER BY pt.thread_id, (mp.sender_type = 'human'), mp.sent_at;
+
+-- Thread statistics also record whether someone other than the author rejected the patch
+CREATE OR REPLACE FUNCTION update_thread_stats(p_thread_id BIGINT)
+RETURNS VOID AS $$
+BEGIN
+  UPDATE patch_threads
+  SET 
+    reply_count = (SELECT COUNT(*) - 1 FROM patch_replies WHERE thread_id = p_thread_id),
+    participant_count = (SELECT COUNT(DISTINCT p.author_id) 
+                        FROM patch_replies pr 
+                        JOIN patches p ON pr.patch_id = p.patch_id 
+                        WHERE pr.thread_id = p_thread_id),
+    last_activity_at = (SELECT MAX(p.sent_at) 
+                       FROM patch_replies pr 
+                       JOIN patches p ON pr.patch_id = p.patch_id 
+                       WHERE pr.thread_id = p_thread_id),
+    has_rejection = EXISTS (SELECT 1
+                            FROM patch_replies pr
+                            JOIN patches p ON pr.patch_id = p.patch_id
+                            JOIN patch_threads t ON pr.thread_id = t.thread_id
+                            JOIN patches rp ON t.root_patch_id = rp.patch_id
+                            WHERE pr.thread_id = p_thread_id
+                              AND p.is_rejection = TRUE
+                              AND p.author_id <> rp.author_id),
+    updated_at = NOW()
+  WHERE thread_id = p_thread_id;
+  
+  -- Update thread_participants
+  DELETE FROM thread_participants WHERE thread_id = p_thread_id;
+  
+  INSERT INTO thread_participants (thread_id, author_id, reply_count, first_replied, last_replied)
+  SELECT 
+    p_thread_id,
+    p.author_id,
+    COUNT(*) as reply_count,
+    MIN(p.sent_at) as first_replied,
+    MAX(p.sent_at) as last_replied
+  FROM patch_replies pr
+  JOIN patches p ON pr.patch_id = p.patch_id
+  WHERE pr.thread_id = p_thread_id
+  GROUP BY p.author_id;
+END;
+$$ LANGUAGE plpgsql;
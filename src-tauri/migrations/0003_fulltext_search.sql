@@ -0,0 +1,11 @@
+-- Full-text search over subject and body
+-- Subject terms weigh more than body terms; the body is capped so huge
+-- emails (logs, large diffs) stay under the tsvector size limit
+
+ALTER TABLE patches ADD COLUMN IF NOT EXISTS search_vector TSVECTOR
+  GENERATED ALWAYS AS (
+    setweight(to_tsvector('english'::regconfig, coalesce(subject, '')), 'A') ||
+    setweight(to_tsvector('english'::regconfig, left(coalesce(body_text, ''), 200000)), 'B')
+  ) STORED;
+
+CREATE INDEX IF NOT EXISTS patches_search_vector_idx ON patches USING GIN (search_vector);
@@ -0,0 +1,6 @@
+-- Trigram indexes for fuzzy (typo tolerant) author and subject search
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS authors_display_name_trgm_idx ON authors USING GIN (display_name gin_trgm_ops);
+CREATE INDEX IF NOT EXISTS patches_subject_trgm_idx ON patches USING GIN (subject gin_trgm_ops);
@@ -0,0 +1,16 @@
+-- Quarantine for emails the parser could not handle
+-- Raw content and git metadata are kept so `retry_failed_parses` can recover them after parser fixes
+
+CREATE TABLE IF NOT EXISTS parse_failures (
+  failure_id      BIGSERIAL PRIMARY KEY,
+  commit_hash     TEXT NOT NULL UNIQUE,
+  list_id         INT REFERENCES mailing_lists(list_id),
+  raw_content     TEXT NOT NULL,
+  commit_metadata JSONB NOT NULL,      -- git_parser::CommitMetadata of the commit
+  error           TEXT NOT NULL,       -- Latest parse error
+  attempts        INT NOT NULL DEFAULT 1,
+  first_failed_at TIMESTAMPTZ DEFAULT NOW(),
+  last_attempt_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS parse_failures_list_id_idx ON parse_failures (list_id);
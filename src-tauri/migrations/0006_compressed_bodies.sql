@@ -0,0 +1,5 @@
+-- Optional compressed storage of message bodies
+-- When enabled, body_text is NULL and the zlib-compressed body is kept in body_compressed.
+-- Compressed bodies are not covered by the full-text body index (subjects still are).
+
+ALTER TABLE patches ADD COLUMN IF NOT EXISTS body_compressed BYTEA;
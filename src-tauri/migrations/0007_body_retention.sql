@@ -0,0 +1,4 @@
+-- Body retention: old bodies can be dropped while keeping all metadata
+-- Pruned bodies are re-read from the git archive when requested
+
+ALTER TABLE patches ADD COLUMN IF NOT EXISTS body_pruned BOOLEAN DEFAULT FALSE;
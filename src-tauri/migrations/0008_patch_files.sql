@@ -0,0 +1,36 @@
+-- Files touched by each patch, one row per file in its diff
+-- Lets path lookups use an index instead of scanning diff_summary JSONB
+
+CREATE TABLE IF NOT EXISTS patch_files (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  position      INT NOT NULL,       -- Order of the file in the diff
+  path          TEXT NOT NULL,      -- New path, or old path for deletions
+  old_path      TEXT,               -- Path before a rename (NULL otherwise)
+  lines_added   INT NOT NULL DEFAULT 0,
+  lines_removed INT NOT NULL DEFAULT 0,
+  change_type   TEXT NOT NULL,      -- "added", "deleted", "renamed" or "modified"
+  PRIMARY KEY (patch_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS patch_files_path_idx ON patch_files (path text_pattern_ops);
+CREATE INDEX IF NOT EXISTS patch_files_old_path_idx ON patch_files (old_path text_pattern_ops) WHERE old_path IS NOT NULL;
+
+-- Backfill from the diff summaries of patches stored before this table existed
+INSERT INTO patch_files (patch_id, position, path, old_path, lines_added, lines_removed, change_type)
+SELECT p.patch_id,
+       f.position - 1,
+       COALESCE(f.file->>'new_path', f.file->>'old_path'),
+       CASE WHEN (f.file->>'is_rename')::BOOLEAN THEN f.file->>'old_path' END,
+       COALESCE((f.file->>'added')::INT, 0),
+       COALESCE((f.file->>'removed')::INT, 0),
+       CASE
+         WHEN (f.file->>'is_new')::BOOLEAN THEN 'added'
+         WHEN (f.file->>'is_deleted')::BOOLEAN THEN 'deleted'
+         WHEN (f.file->>'is_rename')::BOOLEAN THEN 'renamed'
+         ELSE 'modified'
+       END
+FROM patches p
+CROSS JOIN LATERAL jsonb_array_elements(p.diff_summary->'files') WITH ORDINALITY AS f(file, position)
+WHERE p.diff_summary IS NOT NULL
+  AND COALESCE(f.file->>'new_path', f.file->>'old_path') IS NOT NULL
+ON CONFLICT (patch_id, position) DO NOTHING;
@@ -0,0 +1,20 @@
+-- Patch series as a first-class entity
+-- A series is rooted at its cover letter ([PATCH 0/N]) or, without one, its first patch.
+-- Patches stored before this migration are grouped by the next population run or `rebuild_series`.
+
+CREATE TABLE IF NOT EXISTS series (
+  series_id             BIGSERIAL PRIMARY KEY,
+  root_patch_id         BIGINT NOT NULL UNIQUE REFERENCES patches(patch_id) ON DELETE CASCADE,
+  cover_letter_patch_id BIGINT REFERENCES patches(patch_id) ON DELETE SET NULL,
+  version               INT,                 -- Subject "vN" (1 when untagged)
+  total                 INT,                 -- N of "[PATCH n/N]"
+  target_tree           TEXT,                -- e.g., "net-next", "bpf-next"
+  is_rfc                BOOLEAN DEFAULT FALSE,
+  list_id               INT REFERENCES mailing_lists(list_id),
+  created_at            TIMESTAMPTZ DEFAULT NOW()
+);
+
+ALTER TABLE patches ADD COLUMN IF NOT EXISTS series_id BIGINT REFERENCES series(series_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS patches_series_id_idx ON patches (series_id, series_number) WHERE series_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS patches_unassigned_series_idx ON patches (patch_id) WHERE is_series = TRUE AND series_id IS NULL;
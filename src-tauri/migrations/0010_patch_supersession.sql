@@ -0,0 +1,36 @@
+-- Links from each patch posting to the later version that replaces it (v1 -> v2 -> v3)
+-- A posting is superseded by the lowest newer version from the same author that has
+-- the same `git patch-id` or the same subject once its tags are stripped.
+
+-- Subject without Re:/Fwd: prefixes and "[...]" tag groups, lowercased
+CREATE OR REPLACE FUNCTION patch_subject_key(subject TEXT) RETURNS TEXT
+LANGUAGE SQL IMMUTABLE AS $$
+  SELECT lower(btrim(regexp_replace(
+    regexp_replace(subject, '^(\s*((re|fwd?|aw):|\[[^]]*\]))+', '', 'i'),
+    '\s+', ' ', 'g')))
+$$;
+
+ALTER TABLE patches ADD COLUMN IF NOT EXISTS superseded_by BIGINT REFERENCES patches(patch_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS patches_superseded_by_idx ON patches (superseded_by) WHERE superseded_by IS NOT NULL;
+CREATE INDEX IF NOT EXISTS patches_subject_key_idx ON patches (author_id, patch_subject_key(subject))
+  WHERE is_reply = FALSE AND patch_version IS NOT NULL;
+
+-- Link the postings already stored
+UPDATE patches p
+SET superseded_by = n.next_id
+FROM (
+  SELECT DISTINCT ON (old.patch_id) old.patch_id, newer.patch_id AS next_id
+  FROM patches old
+  JOIN patches newer ON newer.author_id = old.author_id
+    AND newer.is_reply = FALSE
+    AND newer.patch_version > old.patch_version
+    AND newer.is_cover_letter IS NOT DISTINCT FROM old.is_cover_letter
+    AND (newer.git_patch_id = old.git_patch_id
+         OR patch_subject_key(newer.subject) = patch_subject_key(old.subject))
+  WHERE old.is_reply = FALSE
+    AND old.patch_version IS NOT NULL
+    AND patch_subject_key(old.subject) <> ''
+  ORDER BY old.patch_id, newer.patch_version, newer.sent_at
+) n
+WHERE p.patch_id = n.patch_id;
@@ -0,0 +1,54 @@
+-- Local read/unread state
+-- Marking a thread read stores a watermark: messages sent up to it count as read, later
+-- replies arrive unread. Per-message rows override the watermark in either direction.
+
+CREATE TABLE IF NOT EXISTS thread_read_state (
+  thread_id     BIGINT PRIMARY KEY REFERENCES patch_threads(thread_id) ON DELETE CASCADE,
+  read_through  TIMESTAMPTZ NOT NULL,   -- sent_at of the newest message when marked read
+  updated_at    TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS patch_read_state (
+  patch_id      BIGINT PRIMARY KEY REFERENCES patches(patch_id) ON DELETE CASCADE,
+  is_read       BOOLEAN NOT NULL,
+  updated_at    TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Same columns as before, plus unread_count
+CREATE OR REPLACE VIEW thread_summary AS
+SELECT
+  pt.thread_id,
+  pt.root_patch_id,
+  pt.root_message_id,
+  pt.reply_count,
+  pt.participant_count,
+  p.sent_at as created_at,  -- Use root patch sent_at as thread creation time
+  pt.updated_at,
+  pt.last_activity_at,
+  p.subject as root_subject,
+  p.sent_at as root_sent_at,
+  a.display_name as root_author,
+  a.author_id as root_author_id,
+  pt.list_id,
+  -- Diffstat of the patches in the thread (replies excluded)
+  COALESCE(ds.files_changed, 0) as files_changed,
+  COALESCE(ds.lines_added, 0) as lines_added,
+  COALESCE(ds.lines_removed, 0) as lines_removed,
+  COALESCE(ds.ping_count, 0) as ping_count,
+  pt.has_rejection,
+  COALESCE(ds.unread_count, 0) as unread_count
+FROM patch_threads pt
+JOIN patches p ON pt.root_patch_id = p.patch_id
+JOIN authors a ON p.author_id = a.author_id
+LEFT JOIN thread_read_state trs ON trs.thread_id = pt.thread_id
+LEFT JOIN LATERAL (
+  SELECT SUM(tp.files_changed) FILTER (WHERE tp.is_reply = FALSE) as files_changed,
+         SUM(tp.lines_added) FILTER (WHERE tp.is_reply = FALSE) as lines_added,
+         SUM(tp.lines_removed) FILTER (WHERE tp.is_reply = FALSE) as lines_removed,
+         COUNT(*) FILTER (WHERE tp.is_ping) as ping_count,
+         COUNT(*) FILTER (WHERE NOT COALESCE(rs.is_read, tp.sent_at <= trs.read_through, FALSE)) as unread_count
+  FROM patch_replies pr
+  JOIN patches tp ON pr.patch_id = tp.patch_id
+  LEFT JOIN patch_read_state rs ON rs.patch_id = tp.patch_id
+  WHERE pr.thread_id = pt.thread_id
+) ds ON TRUE;
@@ -0,0 +1,8 @@
+-- Threads the user starred to keep on a personal shortlist
+
+CREATE TABLE IF NOT EXISTS thread_bookmarks (
+  thread_id     BIGINT PRIMARY KEY REFERENCES patch_threads(thread_id) ON DELETE CASCADE,
+  starred_at    TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS thread_bookmarks_starred_at_idx ON thread_bookmarks (starred_at DESC);
@@ -0,0 +1,27 @@
+-- User-defined labels ("verifier", "needs-backport", ...) attached to threads and patches
+
+CREATE TABLE IF NOT EXISTS labels (
+  label_id      SERIAL PRIMARY KEY,
+  name          TEXT NOT NULL,
+  color         TEXT,                   -- Display color, e.g. "#d73a4a"
+  created_at    TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS labels_name_idx ON labels (LOWER(name));
+
+CREATE TABLE IF NOT EXISTS thread_labels (
+  thread_id     BIGINT NOT NULL REFERENCES patch_threads(thread_id) ON DELETE CASCADE,
+  label_id      INT NOT NULL REFERENCES labels(label_id) ON DELETE CASCADE,
+  added_at      TIMESTAMPTZ DEFAULT NOW(),
+  PRIMARY KEY (thread_id, label_id)
+);
+
+CREATE TABLE IF NOT EXISTS patch_labels (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  label_id      INT NOT NULL REFERENCES labels(label_id) ON DELETE CASCADE,
+  added_at      TIMESTAMPTZ DEFAULT NOW(),
+  PRIMARY KEY (patch_id, label_id)
+);
+
+CREATE INDEX IF NOT EXISTS thread_labels_label_idx ON thread_labels (label_id);
+CREATE INDEX IF NOT EXISTS patch_labels_label_idx ON patch_labels (label_id);
@@ -0,0 +1,56 @@
+-- Change events on the "mlp_changes" channel for live-updating clients (see database/events.rs)
+-- Payloads are JSON objects tagged by "event": new-thread, thread-updated, patch-merged.
+-- Rebuilding threads rewrites unchanged rows, so updates only notify when a shown value changes.
+
+CREATE OR REPLACE FUNCTION notify_thread_change() RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_notify('mlp_changes', json_build_object(
+    'event', CASE WHEN TG_OP = 'INSERT' THEN 'new-thread' ELSE 'thread-updated' END,
+    'thread_id', NEW.thread_id
+  )::TEXT);
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS patch_threads_insert_notify ON patch_threads;
+CREATE TRIGGER patch_threads_insert_notify
+  AFTER INSERT ON patch_threads
+  FOR EACH ROW EXECUTE FUNCTION notify_thread_change();
+
+DROP TRIGGER IF EXISTS patch_threads_update_notify ON patch_threads;
+CREATE TRIGGER patch_threads_update_notify
+  AFTER UPDATE ON patch_threads
+  FOR EACH ROW
+  WHEN (OLD.reply_count IS DISTINCT FROM NEW.reply_count
+        OR OLD.participant_count IS DISTINCT FROM NEW.participant_count
+        OR OLD.last_activity_at IS DISTINCT FROM NEW.last_activity_at
+        OR OLD.has_rejection IS DISTINCT FROM NEW.has_rejection
+        OR OLD.review_status IS DISTINCT FROM NEW.review_status)
+  EXECUTE FUNCTION notify_thread_change();
+
+CREATE OR REPLACE FUNCTION notify_patch_merged() RETURNS TRIGGER AS $$
+BEGIN
+  PERFORM pg_notify('mlp_changes', json_build_object(
+    'event', 'patch-merged',
+    'patch_id', NEW.patch_id,
+    'in_reply_to', NEW.in_reply_to,
+    'repository', NEW.merge_repository,
+    'branch', NEW.merge_branch
+  )::TEXT);
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS patches_merged_insert_notify ON patches;
+CREATE TRIGGER patches_merged_insert_notify
+  AFTER INSERT ON patches
+  FOR EACH ROW
+  WHEN (NEW.is_merge_notification)
+  EXECUTE FUNCTION notify_patch_merged();
+
+DROP TRIGGER IF EXISTS patches_merged_update_notify ON patches;
+CREATE TRIGGER patches_merged_update_notify
+  AFTER UPDATE OF is_merge_notification ON patches
+  FOR EACH ROW
+  WHEN (NEW.is_merge_notification AND NOT COALESCE(OLD.is_merge_notification, FALSE))
+  EXECUTE FUNCTION notify_patch_merged();
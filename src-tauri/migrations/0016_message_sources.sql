@@ -0,0 +1,23 @@
+-- Every archive commit that carried a message, so duplicates across epochs and lists are recorded
+-- A Message-ID is stored once in patches; each further copy (a cross-post, a message
+-- re-added in a later epoch) adds a row here instead of being dropped silently.
+
+CREATE TABLE IF NOT EXISTS message_sources (
+  source_id     BIGSERIAL PRIMARY KEY,
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  commit_hash   TEXT NOT NULL,           -- Commit (or mbox key) the copy was read from
+  list_id       INT REFERENCES mailing_lists(list_id),
+  epoch         INT,                     -- public-inbox epoch, NULL for mbox imports and backfilled rows
+  first_seen    TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- mbox keys are derived from the Message-ID, so the same key can come from several lists
+CREATE UNIQUE INDEX IF NOT EXISTS message_sources_commit_list_idx ON message_sources (commit_hash, COALESCE(list_id, 0));
+CREATE INDEX IF NOT EXISTS message_sources_patch_id_idx ON message_sources (patch_id);
+
+-- The copy each stored message was inserted from
+INSERT INTO message_sources (patch_id, commit_hash, list_id)
+SELECT patch_id, commit_hash, list_id
+FROM patches
+WHERE commit_hash IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,14 @@
+-- Commits named by merge notifications, verified against a local kernel tree
+-- Each entry of patches.merge_commit_links is resolved to the full SHA it names
+-- and the first release tag containing it (see merges::verify_merge_commits).
+
+CREATE TABLE IF NOT EXISTS merge_commits (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,  -- The merge notification
+  link          TEXT NOT NULL,           -- Entry of merge_commit_links
+  commit_hash   TEXT,                    -- Full SHA in the kernel tree, NULL when it has no such commit
+  release_tag   TEXT,                    -- e.g. "v6.9-rc1", NULL while unreleased
+  verified_at   TIMESTAMPTZ DEFAULT NOW(),
+  PRIMARY KEY (patch_id, link)
+);
+
+CREATE INDEX IF NOT EXISTS merge_commits_commit_hash_idx ON merge_commits (commit_hash);
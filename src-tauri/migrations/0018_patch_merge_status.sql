@@ -0,0 +1,12 @@
+-- Merge status of individual patches, so partially applied series are visible
+-- Patchwork notifications list each patch of a series with its commit, or
+-- "(no matching commit)" when it was skipped (see merges::update_patch_merge_status).
+
+CREATE TABLE IF NOT EXISTS patch_merge_status (
+  patch_id        BIGINT PRIMARY KEY REFERENCES patches(patch_id) ON DELETE CASCADE,
+  merge_patch_id  BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,  -- The notification
+  is_applied      BOOLEAN NOT NULL,
+  commit_link     TEXT                    -- Commit the patch was applied as
+);
+
+CREATE INDEX IF NOT EXISTS patch_merge_status_merge_patch_id_idx ON patch_merge_status (merge_patch_id);
@@ -0,0 +1,62 @@
+-- Precomputed dashboard statistics (see database/stats.rs)
+-- get_enhanced_stats reads these instead of aggregating the whole archive on every call.
+-- They are refreshed after population and thread building, or with the refresh_stats command;
+-- the unique indexes allow REFRESH MATERIALIZED VIEW CONCURRENTLY, so readers are never blocked.
+
+-- Archive totals and thread statistics (a single row)
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_overview AS
+SELECT
+  1 as overview_id,
+  (SELECT COUNT(*) FROM authors) as total_authors,
+  (SELECT COUNT(*) FROM patches) as total_patches,
+  (SELECT COUNT(*) FROM author_emails) as total_emails,
+  (SELECT COUNT(*) FROM patches WHERE is_series = TRUE) as patches_with_series,
+  (SELECT COUNT(*) FROM patches WHERE sender_type <> 'human') as bot_messages,
+  (SELECT COUNT(*) FROM patches WHERE is_ping = TRUE) as ping_messages,
+  ts.total_threads,
+  ts.answered_threads,
+  (SELECT COUNT(DISTINCT pr.thread_id)
+   FROM patch_replies pr
+   JOIN patches p ON p.patch_id = pr.patch_id
+   WHERE p.is_merge_notification = TRUE) as merged_threads,
+  ts.avg_reply_count,
+  ts.avg_participant_count,
+  NOW() as refreshed_at
+FROM (
+  SELECT COUNT(*) as total_threads,
+         COUNT(*) FILTER (WHERE reply_count > 0) as answered_threads,
+         COALESCE(AVG(reply_count), 0)::FLOAT8 as avg_reply_count,
+         COALESCE(AVG(participant_count), 0)::FLOAT8 as avg_participant_count
+  FROM patch_threads
+) ts;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_overview_id_idx ON stats_overview (overview_id);
+
+-- Contributor leaderboard; message_count matches authors.patch_count (replies included)
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_contributors AS
+SELECT
+  a.author_id,
+  a.display_name,
+  COUNT(*)::INT as message_count,
+  (COUNT(*) FILTER (WHERE p.is_reply IS NOT TRUE))::INT as patch_count,
+  (COUNT(*) FILTER (WHERE p.is_reply = TRUE))::INT as reply_count,
+  MIN(p.sent_at) as first_active,
+  MAX(p.sent_at) as last_active
+FROM authors a
+JOIN patches p ON p.author_id = a.author_id
+GROUP BY a.author_id, a.display_name;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_contributors_author_id_idx ON stats_contributors (author_id);
+CREATE INDEX IF NOT EXISTS stats_contributors_message_count_idx ON stats_contributors (message_count DESC);
+
+-- Messages per day
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_daily_activity AS
+SELECT
+  DATE(sent_at) as day,
+  COUNT(*) as message_count,
+  COUNT(*) FILTER (WHERE is_reply IS NOT TRUE) as patch_count,
+  COUNT(*) FILTER (WHERE is_reply = TRUE) as reply_count
+FROM patches
+GROUP BY DATE(sent_at);
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_daily_activity_day_idx ON stats_daily_activity (day);
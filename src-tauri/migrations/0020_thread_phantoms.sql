@@ -0,0 +1,9 @@
+-- Threads rooted at a message missing from the archive (JWZ phantom containers)
+-- Replies to the same missing message form one thread, rooted at the earliest of them, with
+-- no parent and depth 1 in patch_replies. Once the message is stored the thread is rerooted
+-- on it, keeping its thread_id (see database/threading.rs).
+
+ALTER TABLE patch_threads ADD COLUMN IF NOT EXISTS root_phantom_message_id TEXT;
+
+CREATE INDEX IF NOT EXISTS patch_threads_root_phantom_idx ON patch_threads (root_phantom_message_id)
+  WHERE root_phantom_message_id IS NOT NULL;
@@ -0,0 +1,8 @@
+-- Thread families: the threads of every version (v1, v2, v3, ...) of a patch or series
+-- Threads are linked when a posting in one is superseded by a posting in the other (see
+-- 0010_patch_supersession.sql). family_id is the thread of the earliest version, NULL for
+-- threads without other versions; set each time threads are built.
+
+ALTER TABLE patch_threads ADD COLUMN IF NOT EXISTS family_id BIGINT REFERENCES patch_threads(thread_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS patch_threads_family_id_idx ON patch_threads (family_id) WHERE family_id IS NOT NULL;
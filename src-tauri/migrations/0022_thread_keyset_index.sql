@@ -0,0 +1,6 @@
+-- Keyset pagination of the thread list: (last_activity_at, thread_id) is the cursor of a page
+-- The index also serves the plain latest-activity order, so it replaces the single-column one.
+
+CREATE INDEX IF NOT EXISTS patch_threads_activity_keyset_idx ON patch_threads (last_activity_at DESC, thread_id DESC);
+
+DROP INDEX IF EXISTS patch_threads_last_activity_idx;
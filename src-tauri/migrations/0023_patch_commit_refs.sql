@@ -0,0 +1,24 @@
+-- Commit hashes each message refers to, extracted at parse time (see extract_commit_refs)
+-- Lets "which messages mention this commit" use an index instead of scanning bodies
+
+CREATE TABLE IF NOT EXISTS patch_commit_refs (
+  patch_id      BIGINT NOT NULL REFERENCES patches(patch_id) ON DELETE CASCADE,
+  commit_hash   TEXT NOT NULL,      -- Full or abbreviated hash, lowercased
+  kind          TEXT NOT NULL,      -- "fixes", "revert" or "mention"
+  PRIMARY KEY (patch_id, commit_hash)
+);
+
+CREATE INDEX IF NOT EXISTS patch_commit_refs_commit_idx ON patch_commit_refs (commit_hash text_pattern_ops);
+
+-- Backfill the Fixes: tags and reverts of messages stored before this table existed
+-- (mentions in the text are only picked up for messages parsed from now on)
+INSERT INTO patch_commit_refs (patch_id, commit_hash, kind)
+SELECT patch_id, fixes_commit, 'fixes'
+FROM patch_fixes
+ON CONFLICT DO NOTHING;
+
+INSERT INTO patch_commit_refs (patch_id, commit_hash, kind)
+SELECT patch_id, reverted_commit, 'revert'
+FROM patches
+WHERE reverted_commit IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,11 @@
+-- HEAD of each epoch repository as of the last population of a list
+-- fetch_updates reports the commits after these as new, even when an earlier fetch
+-- moved the refs without a population following it.
+
+CREATE TABLE IF NOT EXISTS populated_heads (
+  list_id       INT NOT NULL REFERENCES mailing_lists(list_id) ON DELETE CASCADE,
+  epoch         INT NOT NULL,
+  head_commit   TEXT NOT NULL,
+  updated_at    TIMESTAMPTZ DEFAULT NOW(),
+  PRIMARY KEY (list_id, epoch)
+);
@@ -0,0 +1,14 @@
+-- Names of authors merged into another one (merge_authors)
+-- The merged-away author row is deleted; when a later import sees the same name
+-- again it resolves to the surviving author instead of recreating the old one.
+
+CREATE TABLE IF NOT EXISTS author_aliases (
+  first_name    TEXT NOT NULL,
+  last_name     TEXT,
+  author_id     BIGINT NOT NULL REFERENCES authors(author_id) ON DELETE CASCADE,
+  created_at    TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- One target per name; a missing last name counts as a name of its own
+CREATE UNIQUE INDEX IF NOT EXISTS author_aliases_name_idx ON author_aliases (first_name, COALESCE(last_name, ''));
+CREATE INDEX IF NOT EXISTS author_aliases_author_idx ON author_aliases (author_id);
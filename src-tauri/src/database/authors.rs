@@ -1,5 +1,6 @@
 use sqlx::Row;
-use crate::database::{DatabaseManager, Author, Patch};
+use sqlx::FromRow;
+use crate::database::{stored_body, DatabaseManager, Author, DateRange, Page, Patch};
 
 impl DatabaseManager {
     /// Get comprehensive database statistics
@@ -52,8 +53,8 @@ impl DatabaseManager {
         Ok(authors)
     }
 
-    /// Search patches by author name or email with author info
-    pub async fn search_patches_by_author(&mut self, author_pattern: &str, limit: Option<usize>) -> Result<Vec<(Patch, Author)>, Box<dyn std::error::Error>> {
+    /// Search patches by author name or email with author info, sent within `dates`
+    pub async fn search_patches_by_author(&mut self, author_pattern: &str, limit: Option<usize>, list_id: Option<i32>, dates: DateRange) -> Result<Vec<(Patch, Author)>, Box<dyn std::error::Error>> {
         self.ensure_connected().await?;
 
         let pool = self.get_pool()?;
@@ -62,15 +63,22 @@ impl DatabaseManager {
         let limit_clause = limit.map_or(String::new(), |l| format!(" LIMIT {}", l));
         let results = sqlx::query(&format!(
             "SELECT p.patch_id, p.author_id, p.email_id, p.message_id, p.subject, p.sent_at, p.commit_hash, p.body_text, p.is_series, p.series_number, p.series_total, p.created_at,
-                    a.author_id, a.first_name, a.last_name, a.display_name, a.first_seen, a.patch_count
+                    a.author_id, a.first_name, a.last_name, a.display_name, a.first_seen, a.patch_count, p.body_compressed
              FROM patches p
              JOIN authors a ON p.author_id = a.author_id
              LEFT JOIN author_emails e ON p.email_id = e.email_id
-             WHERE LOWER(a.display_name) LIKE $1 OR LOWER(a.first_name) LIKE $1 OR LOWER(a.last_name) LIKE $1 OR LOWER(e.email) LIKE $1
+             WHERE (LOWER(a.display_name) LIKE $1 OR LOWER(a.first_name) LIKE $1 OR LOWER(a.last_name) LIKE $1 OR LOWER(e.email) LIKE $1)
+               AND NOT p.is_deleted
+               AND ($2::INT IS NULL OR p.list_id = $2)
+               AND ($3::TIMESTAMPTZ IS NULL OR p.sent_at >= $3)
+               AND ($4::TIMESTAMPTZ IS NULL OR p.sent_at < $4)
              ORDER BY p.sent_at DESC{}",
             limit_clause
         ))
         .bind(&search_pattern)
+        .bind(list_id)
+        .bind(dates.after)
+        .bind(dates.before)
         .fetch_all(pool)
         .await?;
 
@@ -84,7 +92,7 @@ impl DatabaseManager {
                 subject: row.get(4),
                 sent_at: row.get(5),
                 commit_hash: row.get(6),
-                body_text: row.get(7),
+                body_text: stored_body(row.get(7), row.get(18))?,
                 is_series: row.get(8),
                 series_number: row.get(9),
                 series_total: row.get(10),
@@ -106,22 +114,59 @@ impl DatabaseManager {
         Ok(patches_with_authors)
     }
 
-    /// Get patches by author ID, ordered by date
-    pub async fn get_patches_by_author(&mut self, author_id: i64) -> Result<Vec<Patch>, Box<dyn std::error::Error>> {
+    /// Get patches by author ID sent within `dates`, ordered by date
+    /// Without `limit` the page holds every patch of the author
+    pub async fn get_patches_by_author(&mut self, author_id: i64, limit: Option<usize>, offset: Option<usize>, dates: DateRange) -> Result<Page<Patch>, Box<dyn std::error::Error>> {
         self.ensure_connected().await?;
 
         let pool = self.get_pool()?;
-        let patches = sqlx::query_as::<_, Patch>(
-            "SELECT patch_id, author_id, email_id, message_id, subject, sent_at, commit_hash, body_text, is_series, series_number, series_total, created_at
-             FROM patches
-             WHERE author_id = $1
-             ORDER BY sent_at DESC"
-        )
-        .bind(author_id)
-        .fetch_all(pool)
-        .await?;
+        let offset = offset.unwrap_or(0) as i64;
+        // The total is counted separately so pages past the end still report it
+        let (rows, total_count): (Vec<sqlx::postgres::PgRow>, i64) = futures::try_join!(
+            sqlx::query(
+                "SELECT patch_id, author_id, email_id, message_id, subject, sent_at, commit_hash, body_text, is_series, series_number, series_total, created_at,
+                        body_compressed
+                 FROM patches
+                 WHERE author_id = $1
+                   AND NOT is_deleted
+                   AND ($4::TIMESTAMPTZ IS NULL OR sent_at >= $4)
+                   AND ($5::TIMESTAMPTZ IS NULL OR sent_at < $5)
+                 ORDER BY sent_at DESC
+                 LIMIT $2 OFFSET $3"
+            )
+            .bind(author_id)
+            .bind(limit.map(|limit| limit as i64))
+            .bind(offset)
+            .bind(dates.after)
+            .bind(dates.before)
+            .fetch_all(pool),
+            sqlx::query_scalar(
+                "SELECT COUNT(*)
+                 FROM patches
+                 WHERE author_id = $1
+                   AND NOT is_deleted
+                   AND ($2::TIMESTAMPTZ IS NULL OR sent_at >= $2)
+                   AND ($3::TIMESTAMPTZ IS NULL OR sent_at < $3)"
+            )
+            .bind(author_id)
+            .bind(dates.after)
+            .bind(dates.before)
+            .fetch_one(pool)
+        )?;
+
+        let mut items = Vec::with_capacity(rows.len());
+        for row in &rows {
+            // Bodies may be stored compressed, see DatabaseConfig::compress_bodies
+            let mut patch = Patch::from_row(row)?;
+            patch.body_text = stored_body(patch.body_text.take(), row.get("body_compressed"))?;
+            items.push(patch);
+        }
 
-        Ok(patches)
+        Ok(Page {
+            has_more: offset + (items.len() as i64) < total_count,
+            items,
+            total_count,
+        })
     }
 
     /// Refresh patch_count for all authors (run after bulk insertion)
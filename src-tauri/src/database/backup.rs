@@ -0,0 +1,150 @@
+use std::time::Instant;
+use sqlx::Row;
+use tokio::process::Command;
+use crate::database::schema::load_migrator;
+use crate::database::{DatabaseExportResult, DatabaseImportResult, DatabaseManager};
+
+/// Table in which sqlx records applied migrations
+const MIGRATIONS_TABLE: &str = "_sqlx_migrations";
+
+/// Run a PostgreSQL client tool and return its stdout, or its stderr as the error
+async fn run_pg_tool(mut command: Command, tool: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let output = command.output()
+        .await
+        .map_err(|e| format!("Failed to execute {} (are the PostgreSQL client tools installed?): {}", tool, e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}: {}",
+            tool,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ).into());
+    }
+    Ok(output.stdout)
+}
+
+/// Latest schema version recorded in an archive made by `export_database`
+/// Reads the migration history table's data without touching any database
+async fn archive_schema_version(path: &str) -> Result<Option<i64>, Box<dyn std::error::Error>> {
+    let mut command = Command::new("pg_restore");
+    command.arg("--data-only")
+        .arg("--table").arg(MIGRATIONS_TABLE)
+        .arg("--file").arg("-")
+        .arg(path);
+    let script = run_pg_tool(command, "pg_restore").await?;
+    let script = String::from_utf8_lossy(&script);
+
+    // Rows of the COPY block: version, description, installed_on, success, ...
+    let mut version = None;
+    let mut in_copy = false;
+    for line in script.lines() {
+        if line.starts_with("COPY ") && line.contains(MIGRATIONS_TABLE) {
+            in_copy = true;
+        } else if line == "\\." {
+            in_copy = false;
+        } else if in_copy {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.get(3) == Some(&"t") {
+                if let Ok(v) = fields[0].parse::<i64>() {
+                    version = version.max(Some(v));
+                }
+            }
+        }
+    }
+    Ok(version)
+}
+
+impl DatabaseManager {
+    /// Command for a PostgreSQL client tool, pointed at the configured server and database
+    fn pg_tool_command(&self, tool: &str) -> Command {
+        let mut command = Command::new(tool);
+        command.arg("--host").arg(&self.config.host)
+            .arg("--port").arg(self.config.port.to_string())
+            .arg("--username").arg(&self.config.user)
+            .arg("--dbname").arg(&self.config.database)
+            .arg("--no-password")
+            .env("PGPASSWORD", &self.config.password);
+        command
+    }
+
+    /// Dump schema and data into a single pg_dump archive at `path`
+    /// The archive is in pg_dump's custom format and can be loaded with `import_database`
+    pub async fn export_database(&self, path: &str) -> Result<DatabaseExportResult, Box<dyn std::error::Error>> {
+        let start = Instant::now();
+        println!("Exporting database '{}' to {}", self.config.database, path);
+
+        let mut command = self.pg_tool_command("pg_dump");
+        command.arg("--format=custom")
+            .arg("--file").arg(path);
+        run_pg_tool(command, "pg_dump").await?;
+
+        let size_bytes = tokio::fs::metadata(path).await?.len();
+        let duration_ms = start.elapsed().as_millis() as u64;
+        println!("Database export completed: {} bytes in {}ms", size_bytes, duration_ms);
+
+        Ok(DatabaseExportResult {
+            path: path.to_string(),
+            size_bytes,
+            duration_ms,
+        })
+    }
+
+    /// Restore an archive made by `export_database` into the connected database
+    ///
+    /// The database must be empty, and the archive's schema version must not be newer
+    /// than the migrations this build knows. Older archives are migrated after loading.
+    pub async fn import_database(&mut self, path: &str) -> Result<DatabaseImportResult, Box<dyn std::error::Error>> {
+        let start = Instant::now();
+
+        let archive_version = archive_schema_version(path).await?
+            .ok_or("Archive has no migration history; it was not exported from this application's database")?;
+        let supported_version = load_migrator().await?
+            .iter()
+            .map(|m| m.version)
+            .max()
+            .unwrap_or(0);
+        if archive_version > supported_version {
+            return Err(format!(
+                "Archive schema version {} is newer than this build supports ({}); upgrade the application first",
+                archive_version, supported_version
+            ).into());
+        }
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool_unchecked()?;
+        let existing_tables: i64 = sqlx::query(
+            "SELECT COUNT(*) AS count FROM information_schema.tables
+             WHERE table_schema = 'public' AND table_type = 'BASE TABLE'"
+        )
+        .fetch_one(pool)
+        .await?
+        .get("count");
+        if existing_tables > 0 {
+            return Err(format!(
+                "Database '{}' already has {} tables; restore into a fresh database or reset it first",
+                self.config.database, existing_tables
+            ).into());
+        }
+
+        println!("Restoring {} (schema version {}) into database '{}'", path, archive_version, self.config.database);
+        let mut command = self.pg_tool_command("pg_restore");
+        command.arg("--no-owner")
+            .arg("--exit-on-error")
+            .arg("--single-transaction")
+            .arg(path);
+        run_pg_tool(command, "pg_restore").await?;
+
+        // Bring archives from older builds up to the current schema
+        let status = self.migrate_database().await?;
+
+        let duration_ms = start.elapsed().as_millis() as u64;
+        println!("Database import completed in {}ms", duration_ms);
+
+        Ok(DatabaseImportResult {
+            archive_schema_version: archive_version,
+            migrations_applied: status.newly_applied,
+            duration_ms,
+        })
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::{Read, Write};
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use sqlx::Row;
+use crate::database::config::BODY_COMPRESSION_BATCH_SIZE;
+use crate::database::{BodyCompressionResult, BodyPruneResult, DatabaseManager};
+use crate::git_parser::{archive_is_readable, get_email_with_metadata_at, ParseError};
+use crate::mail_parser::parse_email_from_content;
+
+/// Compress a message body for the body_compressed column
+pub(crate) fn compress_body(body: &str) -> std::io::Result<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::with_capacity(body.len() / 3), Compression::default());
+    encoder.write_all(body.as_bytes())?;
+    encoder.finish()
+}
+
+/// Body of a patch row, whichever way it is stored
+/// Pass the body_text and body_compressed columns; plain text wins when both are set
+pub fn stored_body(body_text: Option<String>, body_compressed: Option<Vec<u8>>) -> std::io::Result<Option<String>> {
+    match (body_text, body_compressed) {
+        (Some(text), _) => Ok(Some(text)),
+        (None, Some(compressed)) => {
+            let mut body = String::new();
+            ZlibDecoder::new(compressed.as_slice()).read_to_string(&mut body)?;
+            Ok(Some(body))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+/// Re-read a pruned body from the git archive of its list at `repo_path`, parsed the same way as at population time
+/// Blocking: call from `spawn_blocking` in async code
+pub fn refetch_body_from_git(repo_path: &str, commit_hash: &str) -> Result<String, ParseError> {
+    let (content, metadata) = get_email_with_metadata_at(repo_path, commit_hash)?;
+    let email = parse_email_from_content(commit_hash, &content, &metadata).map_err(|e| ParseError {
+        message: format!("Failed to parse commit {}: {}", commit_hash, e),
+    })?;
+    Ok(email.body)
+}
+
+impl DatabaseManager {
+    /// Drop the bodies of messages older than `older_than_years` (or the configured
+    /// `body_retention_years`), keeping every other column
+    ///
+    /// Runs list by list (only `list_id` when given). Only rows backed by a real git commit
+    /// in a list whose archive can be opened are pruned, since those are the only bodies
+    /// `refetch_body_from_git` can bring back; mbox and Maildir imports are never touched.
+    pub async fn prune_bodies(&mut self, list_id: Option<i32>, older_than_years: Option<u32>) -> Result<BodyPruneResult, Box<dyn std::error::Error>> {
+        let years = older_than_years
+            .or(self.config.body_retention_years)
+            .ok_or("No retention period given and body_retention_years is not configured")?;
+
+        let lists: Vec<_> = self.get_mailing_lists().await?
+            .into_iter()
+            .filter(|list| list_id.is_none_or(|id| id == list.list_id))
+            .collect();
+        let pool = self.get_pool()?;
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(365 * years as i64);
+        let mut result = BodyPruneResult {
+            rows_pruned: 0,
+            cutoff: cutoff.to_rfc3339(),
+            skipped_lists: Vec::new(),
+        };
+
+        for list in lists {
+            let repo_path = list.repo_path.clone().unwrap_or_default();
+            let readable = tokio::task::spawn_blocking(move || archive_is_readable(&repo_path)).await?;
+            if !readable {
+                println!("Not pruning list '{}': its git archive can't be opened", list.name);
+                result.skipped_lists.push(list.name);
+                continue;
+            }
+
+            let pruned = sqlx::query(
+                "UPDATE patches
+                 SET body_text = NULL, body_compressed = NULL, body_pruned = TRUE
+                 WHERE list_id = $1
+                   AND sent_at < $2
+                   AND commit_hash ~ '^[0-9a-f]{40}$'
+                   AND (body_text IS NOT NULL OR body_compressed IS NOT NULL)"
+            )
+            .bind(list.list_id)
+            .bind(cutoff)
+            .execute(pool)
+            .await?;
+
+            println!("Pruned bodies of {} messages in '{}' sent before {}", pruned.rows_affected(), list.name, cutoff);
+            result.rows_pruned += pruned.rows_affected();
+        }
+
+        Ok(result)
+    }
+
+    /// Compress the plain text bodies already stored, in batches
+    /// Useful after turning on `compress_bodies` for an existing database
+    pub async fn compress_existing_bodies(&mut self) -> Result<BodyCompressionResult, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let mut result = BodyCompressionResult {
+            rows_compressed: 0,
+            bytes_before: 0,
+            bytes_after: 0,
+        };
+
+        loop {
+            let rows = sqlx::query(
+                "SELECT patch_id, body_text FROM patches
+                 WHERE body_text IS NOT NULL AND body_compressed IS NULL
+                 ORDER BY patch_id
+                 LIMIT $1"
+            )
+            .bind(BODY_COMPRESSION_BATCH_SIZE)
+            .fetch_all(pool)
+            .await?;
+
+            if rows.is_empty() {
+                break;
+            }
+
+            let mut patch_ids = Vec::with_capacity(rows.len());
+            let mut compressed = Vec::with_capacity(rows.len());
+            for row in &rows {
+                let body: String = row.get(1);
+                let data = compress_body(&body)?;
+                result.bytes_before += body.len() as u64;
+                result.bytes_after += data.len() as u64;
+                patch_ids.push(row.get::<i64, _>(0));
+                compressed.push(data);
+            }
+
+            sqlx::query(
+                "UPDATE patches p
+                 SET body_compressed = c.data, body_text = NULL
+                 FROM UNNEST($1::BIGINT[], $2::BYTEA[]) AS c(patch_id, data)
+                 WHERE p.patch_id = c.patch_id"
+            )
+            .bind(&patch_ids)
+            .bind(&compressed)
+            .execute(pool)
+            .await?;
+
+            result.rows_compressed += rows.len() as u64;
+            println!("Compressed {} bodies so far", result.rows_compressed);
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::database::DatabaseManager;
+
+impl DatabaseManager {
+    /// Add a thread to the starred shortlist
+    /// Returns false when it was already starred
+    pub async fn star_thread(&mut self, thread_id: i64) -> Result<bool, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM patch_threads WHERE thread_id = $1)")
+            .bind(thread_id)
+            .fetch_one(pool)
+            .await?;
+        if !exists {
+            return Err(format!("Thread {} does not exist", thread_id).into());
+        }
+
+        let starred = sqlx::query("INSERT INTO thread_bookmarks (thread_id) VALUES ($1) ON CONFLICT (thread_id) DO NOTHING")
+            .bind(thread_id)
+            .execute(pool)
+            .await?;
+
+        Ok(starred.rows_affected() > 0)
+    }
+
+    /// Remove a thread from the starred shortlist
+    /// Returns false when it was not starred
+    pub async fn unstar_thread(&mut self, thread_id: i64) -> Result<bool, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let removed = sqlx::query("DELETE FROM thread_bookmarks WHERE thread_id = $1")
+            .bind(thread_id)
+            .execute(pool)
+            .await?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+}
@@ -7,18 +7,26 @@ pub const DEFAULT_USER: &str = "postgres";
 pub const DEFAULT_PASSWORD: &str = "mysecretpassword";
 pub const DEFAULT_DATABASE: &str = "postgres";
 
-// Connection pool
-pub const MAX_CONNECTIONS: u32 = 100;
-pub const MIN_CONNECTIONS: u32 = 1;
-pub const MAX_LIFETIME_SECS: u64 = 300;
-pub const IDLE_TIMEOUT_SECS: u64 = 60;
-pub const ACQUIRE_TIMEOUT_SECS: u64 = 10;
+// Connection pool (defaults fit a stock server with max_connections = 100)
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 20;
+pub const DEFAULT_MIN_CONNECTIONS: u32 = 1;
+pub const DEFAULT_MAX_LIFETIME_SECS: u64 = 300;
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 60;
+pub const DEFAULT_ACQUIRE_TIMEOUT_SECS: u64 = 10;
+
+// Connection retry
+pub const DEFAULT_CONNECT_RETRIES: u32 = 5;
+pub const DEFAULT_CONNECT_BACKOFF_MS: u64 = 500;
+pub const MAX_CONNECT_BACKOFF_MS: u64 = 10_000;
 
 // Batch processing
 pub const PARSE_BATCH_SIZE: usize = 1000;
 pub const DB_INSERT_BATCH_SIZE: usize = 5000;
 pub const PROGRESS_UPDATE_INTERVAL_MS: u64 = 100;
 pub const CHANNEL_BUFFER_SIZE: usize = 100;
+pub const BODY_COMPRESSION_BATCH_SIZE: i64 = 1000;
+/// Reply batches of a thread build written at once (each holds a pool connection)
+pub const THREAD_INSERT_CONCURRENCY: usize = 4;
 
 /// Configuration for PostgreSQL database connection
 ///
@@ -33,10 +41,19 @@ pub const CHANNEL_BUFFER_SIZE: usize = 100;
 /// - `DB_USER`: Database username (default: "postgres")
 /// - `DB_PASSWORD`: Database password (default: "mysecretpassword")
 /// - `DB_NAME`: Database name (default: "postgres")
+/// - `DB_CONNECT_RETRIES`: Retries while the server is unreachable (default: 5)
+/// - `DB_CONNECT_BACKOFF_MS`: Initial retry delay, doubled per attempt (default: 500)
+/// - `DB_MAX_CONNECTIONS`: Connection pool size (default: 20), checked against the server's limit on connect
+/// - `DB_MIN_CONNECTIONS`: Connections kept open while idle (default: 1)
+/// - `DB_MAX_LIFETIME_SECS`: Age after which a pooled connection is replaced (default: 300)
+/// - `DB_IDLE_TIMEOUT_SECS`: Idle time after which a pooled connection is closed (default: 60)
+/// - `DB_ACQUIRE_TIMEOUT_SECS`: Wait for a free connection before failing (default: 10)
+/// - `DB_COMPRESS_BODIES`: Store new message bodies zlib-compressed, "true" or "false" (default: false)
+/// - `DB_BODY_RETENTION_YEARS`: Drop bodies of messages older than this many years (default: keep all)
 ///
 /// # Example
 /// ```rust
-/// use mailing_list_parser::database::DatabaseConfig;
+/// use mailing_list_parser::database::{DatabaseConfig, PoolConfig};
 ///
 /// // From environment variables
 /// let config = DatabaseConfig::from_env();
@@ -48,6 +65,11 @@ pub const CHANNEL_BUFFER_SIZE: usize = 100;
 ///     user: "myuser".to_string(),
 ///     password: "mypass".to_string(),
 ///     database: "mydb".to_string(),
+///     connect_retries: 3,
+///     connect_backoff_ms: 250,
+///     pool: PoolConfig::default(),
+///     compress_bodies: false,
+///     body_retention_years: None,
 /// };
 ///
 /// // Get connection string for debugging
@@ -60,6 +82,83 @@ pub struct DatabaseConfig {
     pub user: String,
     pub password: String,
     pub database: String,
+    #[serde(default = "default_connect_retries")]
+    pub connect_retries: u32,
+    #[serde(default = "default_connect_backoff_ms")]
+    pub connect_backoff_ms: u64,
+    #[serde(default)]
+    pub pool: PoolConfig,
+    /// Store new bodies compressed (body_compressed) instead of as body_text
+    /// Saves most of the space bodies take, but leaves them out of full-text body search
+    #[serde(default)]
+    pub compress_bodies: bool,
+    /// Retention policy: bodies of messages older than this are pruned after each population run
+    /// Metadata stays, and pruned bodies are re-read from git on request
+    #[serde(default)]
+    pub body_retention_years: Option<u32>,
+}
+
+/// Connection pool sizes, lifetimes and timeouts
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    pub max_lifetime_secs: u64,
+    pub idle_timeout_secs: u64,
+    pub acquire_timeout_secs: u64,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            min_connections: DEFAULT_MIN_CONNECTIONS,
+            max_lifetime_secs: DEFAULT_MAX_LIFETIME_SECS,
+            idle_timeout_secs: DEFAULT_IDLE_TIMEOUT_SECS,
+            acquire_timeout_secs: DEFAULT_ACQUIRE_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn from_env() -> Self {
+        fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        }
+        Self {
+            max_connections: env_or("DB_MAX_CONNECTIONS", DEFAULT_MAX_CONNECTIONS),
+            min_connections: env_or("DB_MIN_CONNECTIONS", DEFAULT_MIN_CONNECTIONS),
+            max_lifetime_secs: env_or("DB_MAX_LIFETIME_SECS", DEFAULT_MAX_LIFETIME_SECS),
+            idle_timeout_secs: env_or("DB_IDLE_TIMEOUT_SECS", DEFAULT_IDLE_TIMEOUT_SECS),
+            acquire_timeout_secs: env_or("DB_ACQUIRE_TIMEOUT_SECS", DEFAULT_ACQUIRE_TIMEOUT_SECS),
+        }
+    }
+
+    /// Check the settings are consistent on their own (server limits are checked on connect)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_connections == 0 {
+            return Err("max_connections must be at least 1".to_string());
+        }
+        if self.min_connections > self.max_connections {
+            return Err(format!(
+                "min_connections ({}) must not exceed max_connections ({})",
+                self.min_connections, self.max_connections
+            ));
+        }
+        if self.acquire_timeout_secs == 0 {
+            return Err("acquire_timeout_secs must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn default_connect_retries() -> u32 {
+    DEFAULT_CONNECT_RETRIES
+}
+
+fn default_connect_backoff_ms() -> u64 {
+    DEFAULT_CONNECT_BACKOFF_MS
 }
 
 impl Default for DatabaseConfig {
@@ -70,6 +169,11 @@ impl Default for DatabaseConfig {
             user: DEFAULT_USER.to_string(),
             password: DEFAULT_PASSWORD.to_string(),
             database: DEFAULT_DATABASE.to_string(),
+            connect_retries: DEFAULT_CONNECT_RETRIES,
+            connect_backoff_ms: DEFAULT_CONNECT_BACKOFF_MS,
+            pool: PoolConfig::default(),
+            compress_bodies: false,
+            body_retention_years: None,
         }
     }
 }
@@ -85,6 +189,22 @@ impl DatabaseConfig {
             user: std::env::var("DB_USER").unwrap_or_else(|_| DEFAULT_USER.to_string()),
             password: std::env::var("DB_PASSWORD").unwrap_or_else(|_| DEFAULT_PASSWORD.to_string()),
             database: std::env::var("DB_NAME").unwrap_or_else(|_| DEFAULT_DATABASE.to_string()),
+            connect_retries: std::env::var("DB_CONNECT_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_CONNECT_RETRIES),
+            connect_backoff_ms: std::env::var("DB_CONNECT_BACKOFF_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_CONNECT_BACKOFF_MS),
+            pool: PoolConfig::from_env(),
+            compress_bodies: std::env::var("DB_COMPRESS_BODIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(false),
+            body_retention_years: std::env::var("DB_BODY_RETENTION_YEARS")
+                .ok()
+                .and_then(|v| v.parse().ok()),
         }
     }
 
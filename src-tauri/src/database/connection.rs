@@ -1,34 +1,205 @@
+use serde::Serialize;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
+use thiserror::Error;
 use crate::database::config::*;
-use crate::database::DatabaseManager;
+use crate::database::{DatabaseManager, SchemaError};
+
+/// Why a connection attempt failed, classified so the UI can show
+/// an actionable message instead of a raw driver error
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConnectionError {
+    #[error("Database server {host}:{port} is unreachable after {attempts} attempt(s): {message}")]
+    ServerUnreachable {
+        host: String,
+        port: u16,
+        attempts: u32,
+        message: String,
+    },
+    #[error("Authentication failed for user '{user}': {message}")]
+    AuthFailed { user: String, message: String },
+    #[error("Database '{database}' does not exist: {message}")]
+    DatabaseMissing { database: String, message: String },
+    /// Connected, but the schema doesn't match this build; the connection is
+    /// kept so the suggested command can be run
+    #[error("{schema}")]
+    IncompatibleSchema { schema: SchemaError },
+    #[error("Invalid connection pool settings: {message}")]
+    InvalidConfig { message: String },
+    /// The pool could open more connections than the server accepts from this user
+    #[error("Connection pool size {requested} exceeds the server's limit of {limit} available connections ({setting}); lower DB_MAX_CONNECTIONS")]
+    PoolTooLarge {
+        requested: u32,
+        limit: i64,
+        setting: String,
+    },
+    #[error("Failed to connect to database: {message}")]
+    Other { message: String },
+}
+
+impl ConnectionError {
+    /// Classify a sqlx error raised while connecting with `config`
+    pub fn classify(error: &sqlx::Error, config: &DatabaseConfig, attempts: u32) -> Self {
+        let message = error.to_string();
+        match error {
+            sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => ConnectionError::ServerUnreachable {
+                host: config.host.clone(),
+                port: config.port,
+                attempts,
+                message,
+            },
+            sqlx::Error::Database(db_err) => match db_err.code().as_deref() {
+                // invalid_password / invalid_authorization_specification
+                Some("28P01") | Some("28000") => ConnectionError::AuthFailed {
+                    user: config.user.clone(),
+                    message,
+                },
+                // invalid_catalog_name
+                Some("3D000") => ConnectionError::DatabaseMissing {
+                    database: config.database.clone(),
+                    message,
+                },
+                // cannot_connect_now: server is still starting up
+                Some("57P03") => ConnectionError::ServerUnreachable {
+                    host: config.host.clone(),
+                    port: config.port,
+                    attempts,
+                    message,
+                },
+                _ => ConnectionError::Other { message },
+            },
+            _ => ConnectionError::Other { message },
+        }
+    }
+
+    /// Only an unreachable server is worth retrying; bad credentials or a
+    /// missing database won't fix themselves
+    fn is_retryable(&self) -> bool {
+        matches!(self, ConnectionError::ServerUnreachable { .. })
+    }
+}
+
+/// Refuse a pool that could open more connections than the server lets this user have
+/// One connection is kept free for the change listener (see `listen_for_changes`)
+async fn check_pool_limit(pool: &Pool<Postgres>, max_connections: u32) -> Result<(), ConnectionError> {
+    let (server_limit, role_limit, database_limit): (i32, i32, i32) = sqlx::query_as(
+        "SELECT current_setting('max_connections')::INT
+                  - CASE WHEN r.rolsuper THEN 0
+                         ELSE current_setting('superuser_reserved_connections')::INT
+                              + COALESCE(current_setting('reserved_connections', true)::INT, 0)
+                    END,
+                r.rolconnlimit,
+                d.datconnlimit
+         FROM pg_roles r, pg_database d
+         WHERE r.rolname = current_user AND d.datname = current_database()"
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ConnectionError::Other {
+        message: format!("Failed to read server connection limits: {}", e),
+    })?;
+
+    // -1 means unlimited for roles and databases
+    let mut limit = (i64::from(server_limit), "max_connections".to_string());
+    if role_limit >= 0 && i64::from(role_limit) < limit.0 {
+        limit = (i64::from(role_limit), "role connection limit".to_string());
+    }
+    if database_limit >= 0 && i64::from(database_limit) < limit.0 {
+        limit = (i64::from(database_limit), "database connection limit".to_string());
+    }
+    let available = limit.0 - 1;
+
+    if i64::from(max_connections) > available {
+        return Err(ConnectionError::PoolTooLarge {
+            requested: max_connections,
+            limit: available,
+            setting: limit.1,
+        });
+    }
+    Ok(())
+}
 
 impl DatabaseManager {
-    /// Establish database connection with optimized pool settings
-    pub async fn connect(&mut self) -> Result<(), sqlx::Error> {
-        let pool = PgPoolOptions::new()
-            .max_connections(MAX_CONNECTIONS)
-            .min_connections(MIN_CONNECTIONS)
-            .max_lifetime(std::time::Duration::from_secs(MAX_LIFETIME_SECS))
-            .idle_timeout(std::time::Duration::from_secs(IDLE_TIMEOUT_SECS))
-            .acquire_timeout(std::time::Duration::from_secs(ACQUIRE_TIMEOUT_SECS))
-            .connect(&self.config.connection_string())
-            .await?;
+    /// Establish database connection with the configured pool settings
+    ///
+    /// Fails with `PoolTooLarge` when `config.pool.max_connections` exceeds what the
+    /// server accepts, rather than erroring later once the pool fills up.
+    ///
+    /// While the server is unreachable the attempt is retried up to
+    /// `config.connect_retries` times with exponential backoff.
+    pub async fn connect(&mut self) -> Result<(), ConnectionError> {
+        let pool_config = self.config.pool.clone();
+        pool_config
+            .validate()
+            .map_err(|message| ConnectionError::InvalidConfig { message })?;
 
-        self.pool = Some(pool);
-        Ok(())
+        let connection_string = self.config.connection_string();
+        let mut backoff_ms = self.config.connect_backoff_ms;
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let result = PgPoolOptions::new()
+                .max_connections(pool_config.max_connections)
+                .min_connections(pool_config.min_connections)
+                .max_lifetime(std::time::Duration::from_secs(pool_config.max_lifetime_secs))
+                .idle_timeout(std::time::Duration::from_secs(pool_config.idle_timeout_secs))
+                .acquire_timeout(std::time::Duration::from_secs(pool_config.acquire_timeout_secs))
+                .connect(&connection_string)
+                .await;
+
+            match result {
+                Ok(pool) => {
+                    if let Err(error) = check_pool_limit(&pool, pool_config.max_connections).await {
+                        pool.close().await;
+                        return Err(error);
+                    }
+                    self.pool = Some(pool);
+                    self.check_schema_version()
+                        .await
+                        .map_err(|e| ConnectionError::Other {
+                            message: format!("Failed to check schema version: {}", e),
+                        })?;
+                    return Ok(());
+                }
+                Err(e) => {
+                    let error = ConnectionError::classify(&e, &self.config, attempts);
+                    if !error.is_retryable() || attempts > self.config.connect_retries {
+                        return Err(error);
+                    }
+                    eprintln!(
+                        "Database connection attempt {} failed ({}), retrying in {}ms",
+                        attempts, e, backoff_ms
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+                    backoff_ms = (backoff_ms * 2).min(MAX_CONNECT_BACKOFF_MS);
+                }
+            }
+        }
     }
 
     /// Ensure database connection is established, connecting if necessary
     pub async fn ensure_connected(&mut self) -> Result<(), sqlx::Error> {
         if self.pool.is_none() {
-            self.connect().await?;
+            self.connect()
+                .await
+                .map_err(|e| sqlx::Error::Configuration(Box::new(e)))?;
         }
         Ok(())
     }
 
     /// Get a reference to the connection pool if connected
+    /// Refuses while the schema is incompatible with this build, see `check_schema_version`
     pub fn get_pool(&self) -> Result<&Pool<Postgres>, sqlx::Error> {
+        if let Some(error) = &self.schema_error {
+            return Err(sqlx::Error::Configuration(Box::new(error.clone())));
+        }
+        self.get_pool_unchecked()
+    }
+
+    /// Connection pool without the schema check, for operations that create or repair the schema
+    pub(crate) fn get_pool_unchecked(&self) -> Result<&Pool<Postgres>, sqlx::Error> {
         self.pool.as_ref().ok_or_else(|| sqlx::Error::Configuration("Not connected to database".into()))
     }
 
@@ -36,7 +207,7 @@ impl DatabaseManager {
     pub async fn test_connection(&mut self) -> Result<bool, sqlx::Error> {
         self.ensure_connected().await?;
 
-        let pool = self.get_pool()?;
+        let pool = self.get_pool_unchecked()?;
         let result: (i32,) = sqlx::query_as("SELECT 1")
             .fetch_one(pool)
             .await?;
@@ -49,6 +220,7 @@ impl DatabaseManager {
         if let Some(pool) = self.pool.take() {
             pool.close().await;
         }
+        self.schema_error = None;
     }
 }
 
@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+/// Signature that opens the PostgreSQL binary COPY format
+const COPY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+/// OID of the `text` type, used as the element type of TEXT[] columns
+const TEXT_OID: i32 = 25;
+
+/// Microseconds between the Unix epoch and the PostgreSQL epoch (2000-01-01)
+const POSTGRES_EPOCH_OFFSET_MICROS: i64 = 946_684_800_000_000;
+
+/// Version byte that prefixes JSONB values in binary format
+const JSONB_VERSION: u8 = 1;
+
+/// Buffer of rows encoded in the binary COPY format
+///
+/// Each row is started with `row(column_count)`, followed by exactly that many
+/// field writes in table column order. `None` values are written as NULL.
+pub(crate) struct BinaryCopyBuffer {
+    data: Vec<u8>,
+}
+
+impl BinaryCopyBuffer {
+    pub fn new() -> Self {
+        let mut data = Vec::with_capacity(64 * 1024);
+        data.extend_from_slice(COPY_SIGNATURE);
+        data.extend_from_slice(&0i32.to_be_bytes());
+        data.extend_from_slice(&0i32.to_be_bytes());
+        Self { data }
+    }
+
+    /// Start a new row with the given number of fields
+    pub fn row(&mut self, columns: i16) -> &mut Self {
+        self.data.extend_from_slice(&columns.to_be_bytes());
+        self
+    }
+
+    fn null(&mut self) -> &mut Self {
+        self.data.extend_from_slice(&(-1i32).to_be_bytes());
+        self
+    }
+
+    fn field(&mut self, bytes: &[u8]) -> &mut Self {
+        self.data.extend_from_slice(&(bytes.len() as i32).to_be_bytes());
+        self.data.extend_from_slice(bytes);
+        self
+    }
+
+    pub fn text(&mut self, value: Option<&str>) -> &mut Self {
+        match value {
+            Some(v) => self.field(v.as_bytes()),
+            None => self.null(),
+        }
+    }
+
+    pub fn bytea(&mut self, value: Option<&[u8]>) -> &mut Self {
+        match value {
+            Some(v) => self.field(v),
+            None => self.null(),
+        }
+    }
+
+    pub fn bool(&mut self, value: Option<bool>) -> &mut Self {
+        match value {
+            Some(v) => self.field(&[v as u8]),
+            None => self.null(),
+        }
+    }
+
+    pub fn int4(&mut self, value: Option<i32>) -> &mut Self {
+        match value {
+            Some(v) => self.field(&v.to_be_bytes()),
+            None => self.null(),
+        }
+    }
+
+    pub fn int8(&mut self, value: Option<i64>) -> &mut Self {
+        match value {
+            Some(v) => self.field(&v.to_be_bytes()),
+            None => self.null(),
+        }
+    }
+
+    pub fn timestamptz(&mut self, value: Option<&DateTime<Utc>>) -> &mut Self {
+        match value {
+            Some(v) => self.field(&(v.timestamp_micros() - POSTGRES_EPOCH_OFFSET_MICROS).to_be_bytes()),
+            None => self.null(),
+        }
+    }
+
+    /// JSONB value given as serialized JSON text
+    pub fn jsonb(&mut self, value: Option<&str>) -> &mut Self {
+        match value {
+            Some(v) => {
+                let mut bytes = Vec::with_capacity(v.len() + 1);
+                bytes.push(JSONB_VERSION);
+                bytes.extend_from_slice(v.as_bytes());
+                self.field(&bytes)
+            }
+            None => self.null(),
+        }
+    }
+
+    /// One-dimensional TEXT[] value
+    pub fn text_array<S: AsRef<str>>(&mut self, value: Option<&[S]>) -> &mut Self {
+        let Some(items) = value else {
+            return self.null();
+        };
+
+        let mut bytes = Vec::new();
+        let dimensions: i32 = if items.is_empty() { 0 } else { 1 };
+        bytes.extend_from_slice(&dimensions.to_be_bytes());
+        bytes.extend_from_slice(&0i32.to_be_bytes()); // no NULL elements
+        bytes.extend_from_slice(&TEXT_OID.to_be_bytes());
+        if !items.is_empty() {
+            bytes.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            bytes.extend_from_slice(&1i32.to_be_bytes()); // lower bound
+            for item in items {
+                let item = item.as_ref().as_bytes();
+                bytes.extend_from_slice(&(item.len() as i32).to_be_bytes());
+                bytes.extend_from_slice(item);
+            }
+        }
+        self.field(&bytes)
+    }
+
+    /// Append the trailer and return the encoded bytes
+    fn finish(mut self) -> Vec<u8> {
+        self.data.extend_from_slice(&(-1i16).to_be_bytes());
+        self.data
+    }
+}
+
+/// Stream the rows of `buffer` with `COPY <target> FROM STDIN (FORMAT binary)`
+/// Returns the number of rows copied
+pub(crate) async fn copy_binary(
+    conn: &mut PgConnection,
+    target: &str,
+    buffer: BinaryCopyBuffer,
+) -> Result<u64, sqlx::Error> {
+    let statement = format!("COPY {} FROM STDIN (FORMAT binary)", target);
+    let mut copy = conn.copy_in_raw(&statement).await?;
+    if let Err(e) = copy.send(buffer.finish()).await {
+        copy.abort(e.to_string()).await?;
+        return Err(e);
+    }
+    copy.finish().await
+}
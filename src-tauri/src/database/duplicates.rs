@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use sqlx::Row;
+use crate::database::{
+    Author, DatabaseManager, DuplicateAuthorKind, DuplicateAuthorPair, DuplicateAuthorReport, DuplicateConfidence,
+};
+
+/// Base letter of a lowercase accented Latin letter
+fn strip_diacritic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'þ' => "th",
+        _ => return None,
+    })
+}
+
+/// Name compared case-insensitively ("john smith")
+fn case_key(name: &str) -> String {
+    name.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Name compared without case, accents, punctuation or word order
+/// ("Smith, José-Luis" and "jose luis smith" both give "jose luis smith")
+fn folded_key(name: &str) -> String {
+    let mut folded = String::with_capacity(name.len());
+    for c in name.to_lowercase().chars() {
+        match strip_diacritic(c) {
+            Some(base) => folded.push_str(base),
+            None if c.is_alphanumeric() => folded.push(c),
+            None => folded.push(' '),
+        }
+    }
+    let mut words: Vec<&str> = folded.split_whitespace().collect();
+    words.sort_unstable();
+    words.join(" ")
+}
+
+/// Mailbox an address delivers to: the `+tag` sub-address is dropped, and
+/// for gmail the dots in the local part and the googlemail.com alias
+fn mailbox_key(email: &str) -> String {
+    let email = email.to_lowercase();
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return email;
+    };
+    let local = local.split('+').next().unwrap_or(local);
+    match domain {
+        "gmail.com" | "googlemail.com" => format!("{}@gmail.com", local.replace('.', "")),
+        _ => format!("{}@{}", local, domain),
+    }
+}
+
+fn author_name(author: &Author) -> String {
+    match &author.last_name {
+        Some(last_name) => format!("{} {}", author.first_name, last_name),
+        None => author.first_name.clone(),
+    }
+}
+
+/// Single-word names ("root", "bot") are shared by unrelated people
+fn name_confidence(name: &str, confident: DuplicateConfidence) -> DuplicateConfidence {
+    if name.split_whitespace().count() < 2 {
+        DuplicateConfidence::Low
+    } else {
+        confident
+    }
+}
+
+/// Group authors by `key`, yielding every pair of authors sharing one
+fn pairs_by_key<'a>(keyed: impl Iterator<Item = (String, i64, &'a str)>) -> Vec<(i64, i64, String)> {
+    let mut groups: HashMap<String, Vec<(i64, &str)>> = HashMap::new();
+    for (key, author_id, evidence) in keyed {
+        let group = groups.entry(key).or_default();
+        if !group.iter().any(|(id, _)| *id == author_id) {
+            group.push((author_id, evidence));
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for group in groups.values().filter(|group| group.len() > 1) {
+        for (i, (a, a_evidence)) in group.iter().enumerate() {
+            for (b, b_evidence) in &group[i + 1..] {
+                let (a, b) = if a < b { (*a, *b) } else { (*b, *a) };
+                pairs.push((a, b, format!("{} / {}", a_evidence, b_evidence)));
+            }
+        }
+    }
+    pairs
+}
+
+impl DatabaseManager {
+    /// Scan authors for records that are likely the same person: addresses of the
+    /// same mailbox, and names differing only in case, accents, punctuation or word order
+    ///
+    /// With `auto_merge`, high-confidence pairs are merged into the author with more
+    /// patches (see `merge_authors`); each merge commits on its own.
+    pub async fn find_duplicate_authors(&mut self, auto_merge: bool) -> Result<DuplicateAuthorReport, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let authors = sqlx::query_as::<_, Author>(
+            "SELECT author_id, first_name, last_name, display_name, first_seen, patch_count FROM authors"
+        )
+        .fetch_all(pool)
+        .await?;
+        let emails: Vec<(i64, String)> = sqlx::query("SELECT author_id, email::TEXT FROM author_emails")
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+
+        let names: Vec<(i64, String)> = authors.iter()
+            .map(|author| (author.author_id, author_name(author)))
+            .collect();
+        let by_id: HashMap<i64, &Author> = authors.iter().map(|author| (author.author_id, author)).collect();
+
+        // Strongest evidence first; a pair is reported once
+        let mut found: Vec<(DuplicateAuthorKind, DuplicateConfidence, i64, i64, String)> = Vec::new();
+        let mut seen: HashSet<(i64, i64)> = HashSet::new();
+
+        for (a, b, evidence) in pairs_by_key(emails.iter().map(|(id, email)| (mailbox_key(email), *id, email.as_str()))) {
+            if seen.insert((a, b)) {
+                found.push((DuplicateAuthorKind::SameEmail, DuplicateConfidence::High, a, b, evidence));
+            }
+        }
+        for (a, b, evidence) in pairs_by_key(names.iter().map(|(id, name)| (case_key(name), *id, name.as_str()))) {
+            if seen.insert((a, b)) {
+                let confidence = name_confidence(&author_name(by_id[&a]), DuplicateConfidence::High);
+                found.push((DuplicateAuthorKind::SameName, confidence, a, b, evidence));
+            }
+        }
+        for (a, b, evidence) in pairs_by_key(names.iter().map(|(id, name)| (folded_key(name), *id, name.as_str()))) {
+            if seen.insert((a, b)) {
+                let confidence = name_confidence(&folded_key(&author_name(by_id[&a])), DuplicateConfidence::Medium);
+                found.push((DuplicateAuthorKind::NameVariant, confidence, a, b, evidence));
+            }
+        }
+        found.sort_by_key(|&(kind, confidence, a, b, _)| (confidence, kind, a, b));
+
+        let mut pairs: Vec<DuplicateAuthorPair> = found.into_iter()
+            .map(|(kind, confidence, a, b, evidence)| DuplicateAuthorPair {
+                kind,
+                confidence,
+                author_a: by_id[&a].clone(),
+                author_b: by_id[&b].clone(),
+                evidence,
+                merged: false,
+            })
+            .collect();
+
+        let mut authors_merged = 0;
+        if auto_merge {
+            // Where each merged-away author went, and the patch counts of the survivors
+            let mut merged_into: HashMap<i64, i64> = HashMap::new();
+            let mut patch_counts: HashMap<i64, i32> = authors.iter()
+                .map(|author| (author.author_id, author.patch_count))
+                .collect();
+            let resolve = |merged_into: &HashMap<i64, i64>, mut author_id: i64| {
+                while let Some(&target) = merged_into.get(&author_id) {
+                    author_id = target;
+                }
+                author_id
+            };
+
+            for pair in pairs.iter_mut().filter(|pair| pair.confidence == DuplicateConfidence::High) {
+                let a = resolve(&merged_into, pair.author_a.author_id);
+                let b = resolve(&merged_into, pair.author_b.author_id);
+                if a != b {
+                    let (target, source) = if (patch_counts[&a], -a) >= (patch_counts[&b], -b) { (a, b) } else { (b, a) };
+                    let merged = self.merge_authors(target, source).await?;
+                    patch_counts.insert(target, merged.patch_count);
+                    merged_into.insert(source, target);
+                    authors_merged += 1;
+                }
+                pair.merged = true;
+            }
+        }
+
+        Ok(DuplicateAuthorReport {
+            authors_scanned: authors.len() as u32,
+            pairs,
+            authors_merged,
+        })
+    }
+}
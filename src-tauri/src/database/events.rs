@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use crate::database::DatabaseManager;
+
+/// Channel the triggers of migration 0015 notify on
+pub const CHANGE_CHANNEL: &str = "mlp_changes";
+
+/// Change to the archive announced by the database, for live-updating the UI
+/// `event` names the kind, e.g. `{"event": "new-thread", "thread_id": 5}`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum ChangeEvent {
+    NewThread { thread_id: i64 },
+    /// Reply count, activity, rejection or review status changed
+    ThreadUpdated { thread_id: i64 },
+    /// A merge notification was stored (or recognized) for the patch it replies to
+    PatchMerged {
+        patch_id: i64,
+        in_reply_to: Option<String>,
+        repository: Option<String>,
+        branch: Option<String>,
+    },
+}
+
+impl ChangeEvent {
+    /// Name of the frontend event this is emitted as
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeEvent::NewThread { .. } => "new-thread",
+            ChangeEvent::ThreadUpdated { .. } => "thread-updated",
+            ChangeEvent::PatchMerged { .. } => "patch-merged",
+        }
+    }
+
+    /// Parse a notification payload (None for payloads this build does not know)
+    pub fn from_payload(payload: &str) -> Option<Self> {
+        serde_json::from_str(payload).ok()
+    }
+}
+
+impl DatabaseManager {
+    /// Open a dedicated connection listening for change events
+    /// It reconnects by itself; events sent while it is down are lost
+    pub async fn listen_for_changes(&self) -> Result<PgListener, sqlx::Error> {
+        let pool = self.get_pool_unchecked()?;
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(CHANGE_CHANNEL).await?;
+        Ok(listener)
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::{HashMap, HashSet};
+use crate::database::DatabaseManager;
+
+impl DatabaseManager {
+    /// Link the threads of a patch's or series' versions into families
+    ///
+    /// Two threads belong to the same family when a posting in one is superseded by a
+    /// posting in the other (see `link_superseded_patches`); families are the connected
+    /// groups of such threads, identified by the thread of the earliest version.
+    /// Returns the number of families
+    pub(crate) async fn link_thread_families(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let pool = self.get_pool()?;
+
+        let links: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT DISTINCT pr.thread_id, npr.thread_id
+             FROM patches p
+             JOIN patch_replies pr ON pr.patch_id = p.patch_id
+             JOIN patch_replies npr ON npr.patch_id = p.superseded_by
+             WHERE p.superseded_by IS NOT NULL
+               AND pr.thread_id <> npr.thread_id"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut neighbours: HashMap<i64, Vec<i64>> = HashMap::new();
+        for &(older, newer) in &links {
+            neighbours.entry(older).or_default().push(newer);
+            neighbours.entry(newer).or_default().push(older);
+        }
+
+        let thread_ids: Vec<i64> = neighbours.keys().copied().collect();
+        let started: HashMap<i64, chrono::DateTime<chrono::Utc>> = sqlx::query_as(
+            "SELECT pt.thread_id, p.sent_at
+             FROM patch_threads pt
+             JOIN patches p ON p.patch_id = pt.root_patch_id
+             WHERE pt.thread_id = ANY($1)"
+        )
+        .bind(&thread_ids)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        // Walk each connected group once, led by its earliest thread
+        let mut members: Vec<i64> = Vec::new();
+        let mut families: Vec<i64> = Vec::new();
+        let mut seen: HashSet<i64> = HashSet::new();
+        let mut family_count = 0u64;
+        for &start in &thread_ids {
+            if !seen.insert(start) {
+                continue;
+            }
+            let mut group = vec![start];
+            let mut next = 0;
+            while next < group.len() {
+                for &neighbour in &neighbours[&group[next]] {
+                    if seen.insert(neighbour) {
+                        group.push(neighbour);
+                    }
+                }
+                next += 1;
+            }
+            let Some(&head) = group.iter().min_by_key(|&&thread_id| (started.get(&thread_id), thread_id)) else {
+                continue;
+            };
+            families.extend(std::iter::repeat_n(head, group.len()));
+            members.extend(group);
+            family_count += 1;
+        }
+
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "UPDATE patch_threads SET family_id = NULL
+             WHERE family_id IS NOT NULL AND thread_id <> ALL($1)"
+        )
+        .bind(&members)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE patch_threads pt
+             SET family_id = f.family_id
+             FROM UNNEST($1::BIGINT[], $2::BIGINT[]) AS f(thread_id, family_id)
+             WHERE pt.thread_id = f.thread_id
+               AND pt.family_id IS DISTINCT FROM f.family_id"
+        )
+        .bind(&members)
+        .bind(&families)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(family_count)
+    }
+}
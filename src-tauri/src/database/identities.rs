@@ -0,0 +1,257 @@
+use std::collections::HashSet;
+use sqlx::{PgConnection, Row};
+use crate::database::{Author, DatabaseManager};
+use crate::mail_parser::parse_name_components;
+
+/// Author with this (normalized) name, created if there is none yet
+/// Names of authors merged away resolve to the author they were merged into
+/// Call inside a transaction: the name stays locked until it ends, so concurrent
+/// callers can't both create the author
+pub(crate) async fn find_or_create_author(conn: &mut PgConnection, name: &str) -> Result<i64, sqlx::Error> {
+    let (first_name, last_name, display_name) = parse_name_components(name);
+    // The unique constraint doesn't cover names without a last name (NULLs are distinct)
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1 || chr(31) || COALESCE($2, ''), 0))")
+        .bind(&first_name)
+        .bind(&last_name)
+        .execute(&mut *conn)
+        .await?;
+
+    let find_existing = || sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT COALESCE(
+           (SELECT author_id FROM author_aliases
+            WHERE first_name = $1 AND COALESCE(last_name, '') = COALESCE($2, '')),
+           (SELECT MIN(author_id) FROM authors
+            WHERE first_name = $1 AND last_name IS NOT DISTINCT FROM $2))"
+    )
+    .bind(&first_name)
+    .bind(&last_name);
+
+    if let Some(author_id) = find_existing().fetch_one(&mut *conn).await? {
+        return Ok(author_id);
+    }
+
+    // A population batch may have inserted the name meanwhile; it doesn't take the lock
+    let inserted: Option<i64> = sqlx::query_scalar(
+        "INSERT INTO authors (first_name, last_name, display_name)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (first_name, last_name) DO NOTHING
+         RETURNING author_id"
+    )
+    .bind(&first_name)
+    .bind(&last_name)
+    .bind(&display_name)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match inserted {
+        Some(author_id) => Ok(author_id),
+        None => find_existing().fetch_one(&mut *conn).await?
+            .ok_or(sqlx::Error::RowNotFound),
+    }
+}
+
+/// Move an address, and every patch sent from it, to another author
+pub(crate) async fn move_email(conn: &mut PgConnection, email_id: i64, target_author: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE author_emails SET author_id = $1, is_primary = FALSE WHERE email_id = $2")
+        .bind(target_author)
+        .bind(email_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("UPDATE patches SET author_id = $1 WHERE email_id = $2")
+        .bind(target_author)
+        .bind(email_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// After patches moved away from `authors`: re-count the participants of their
+/// threads, then delete those left without addresses or patches
+/// Returns the number of authors deleted
+pub(crate) async fn cleanup_moved_authors(conn: &mut PgConnection, authors: &[i64]) -> Result<u64, sqlx::Error> {
+    let thread_ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT DISTINCT thread_id FROM thread_participants WHERE author_id = ANY($1)"
+    )
+    .bind(authors)
+    .fetch_all(&mut *conn)
+    .await?;
+    for thread_id in thread_ids {
+        sqlx::query("SELECT update_thread_stats($1)")
+            .bind(thread_id)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let removed = sqlx::query(
+        "DELETE FROM authors a
+         WHERE a.author_id = ANY($1)
+           AND NOT EXISTS (SELECT 1 FROM author_emails ae WHERE ae.author_id = a.author_id)
+           AND NOT EXISTS (SELECT 1 FROM patches p WHERE p.author_id = a.author_id)"
+    )
+    .bind(authors)
+    .execute(&mut *conn)
+    .await?;
+    Ok(removed.rows_affected())
+}
+
+/// Make the name of `source_author` (and any names already redirected to it) resolve to
+/// `target_author`, so imports after a merge don't recreate the merged-away author
+async fn redirect_author_names(conn: &mut PgConnection, source_author: i64, target_author: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE author_aliases SET author_id = $1 WHERE author_id = $2")
+        .bind(target_author)
+        .bind(source_author)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query(
+        "INSERT INTO author_aliases (first_name, last_name, author_id)
+         SELECT first_name, last_name, $1 FROM authors WHERE author_id = $2
+         ON CONFLICT (first_name, (COALESCE(last_name, ''))) DO UPDATE SET author_id = EXCLUDED.author_id"
+    )
+    .bind(target_author)
+    .bind(source_author)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Recount an author's patches and return the row (None if it was deleted)
+async fn refresh_author(conn: &mut PgConnection, author_id: i64) -> Result<Option<Author>, sqlx::Error> {
+    sqlx::query_as::<_, Author>(
+        "UPDATE authors a
+         SET patch_count = (SELECT COUNT(*) FROM patches p WHERE p.author_id = a.author_id)
+         WHERE a.author_id = $1
+         RETURNING author_id, first_name, last_name, display_name, first_seen, patch_count"
+    )
+    .bind(author_id)
+    .fetch_optional(&mut *conn)
+    .await
+}
+
+impl DatabaseManager {
+    /// Merge `source_author_id` into `target_author_id`: its addresses and patches
+    /// move over atomically and the source record is deleted
+    /// The source's name is kept as an alias, so later imports under it resolve to the target
+    pub async fn merge_authors(&mut self, target_author_id: i64, source_author_id: i64) -> Result<Author, Box<dyn std::error::Error>> {
+        if target_author_id == source_author_id {
+            return Err("Cannot merge an author into itself".into());
+        }
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+        let mut tx = pool.begin().await?;
+
+        let found: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM authors WHERE author_id = ANY($1)")
+            .bind([target_author_id, source_author_id])
+            .fetch_one(&mut *tx)
+            .await?;
+        if found != 2 {
+            return Err(format!("Author {} or {} does not exist", target_author_id, source_author_id).into());
+        }
+
+        let email_ids: Vec<i64> = sqlx::query_scalar("SELECT email_id FROM author_emails WHERE author_id = $1")
+            .bind(source_author_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        for email_id in &email_ids {
+            move_email(&mut tx, *email_id, target_author_id).await?;
+        }
+        // Patches without a recorded address
+        sqlx::query("UPDATE patches SET author_id = $1 WHERE author_id = $2")
+            .bind(target_author_id)
+            .bind(source_author_id)
+            .execute(&mut *tx)
+            .await?;
+        redirect_author_names(&mut tx, source_author_id, target_author_id).await?;
+
+        cleanup_moved_authors(&mut tx, &[source_author_id]).await?;
+        let author = refresh_author(&mut tx, target_author_id).await?
+            .ok_or_else(|| format!("Author {} disappeared during merge", target_author_id))?;
+        tx.commit().await?;
+
+        println!("Merged author {} into {} ({} addresses moved)", source_author_id, target_author_id, email_ids.len());
+        Ok(author)
+    }
+
+    /// Split the given addresses of `author_id`, with the patches sent from them,
+    /// off to the author named `name` (created if needed)
+    /// A name merged into `author_id` earlier stops redirecting to it, so a wrong merge can be undone
+    /// Returns the author that received them
+    pub async fn split_author(&mut self, author_id: i64, emails: &[String], name: &str) -> Result<Author, Box<dyn std::error::Error>> {
+        if emails.is_empty() {
+            return Err("No addresses given to split off".into());
+        }
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+        let mut tx = pool.begin().await?;
+
+        let rows = sqlx::query("SELECT email_id, email::TEXT AS email FROM author_emails WHERE author_id = $1 AND email = ANY($2::CITEXT[])")
+            .bind(author_id)
+            .bind(emails)
+            .fetch_all(&mut *tx)
+            .await?;
+        let found: HashSet<String> = rows.iter()
+            .map(|row| row.get::<String, _>("email").to_lowercase())
+            .collect();
+        let missing: Vec<&String> = emails.iter()
+            .filter(|e| !found.contains(&e.to_lowercase()))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Addresses not owned by author {}: {:?}", author_id, missing).into());
+        }
+
+        let (first_name, last_name, _) = parse_name_components(name);
+        sqlx::query(
+            "DELETE FROM author_aliases
+             WHERE author_id = $1 AND first_name = $2 AND COALESCE(last_name, '') = COALESCE($3, '')"
+        )
+        .bind(author_id)
+        .bind(&first_name)
+        .bind(&last_name)
+        .execute(&mut *tx)
+        .await?;
+
+        let target_author_id = find_or_create_author(&mut tx, name).await?;
+        if target_author_id == author_id {
+            return Err(format!("'{}' is the name of author {} itself; choose a different name", name, author_id).into());
+        }
+
+        for row in &rows {
+            move_email(&mut tx, row.get("email_id"), target_author_id).await?;
+        }
+
+        cleanup_moved_authors(&mut tx, &[author_id]).await?;
+        refresh_author(&mut tx, author_id).await?;
+        let author = refresh_author(&mut tx, target_author_id).await?
+            .ok_or_else(|| format!("Author {} disappeared during split", target_author_id))?;
+        tx.commit().await?;
+
+        println!("Split {} addresses of author {} off to author {}", rows.len(), author_id, target_author_id);
+        Ok(author)
+    }
+
+    /// Set the name an author is displayed under
+    /// The (first_name, last_name) identity taken from mail stays unchanged
+    pub async fn set_author_display_name(&mut self, author_id: i64, display_name: &str) -> Result<Author, Box<dyn std::error::Error>> {
+        let display_name = crate::mail_parser::normalize_name(display_name);
+        if display_name.is_empty() {
+            return Err("Display name must not be empty".into());
+        }
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let author = sqlx::query_as::<_, Author>(
+            "UPDATE authors SET display_name = $2
+             WHERE author_id = $1
+             RETURNING author_id, first_name, last_name, display_name, first_seen, patch_count"
+        )
+        .bind(author_id)
+        .bind(&display_name)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| format!("Author {} does not exist", author_id))?;
+
+        Ok(author)
+    }
+}
@@ -0,0 +1,209 @@
+use serde_json::Value;
+use sqlx::PgPool;
+use crate::database::{DatabaseManager, IndexAdvisorReport, QueryIndexCheck};
+
+/// A query pattern the app relies on, and the index (as created by the migrations) serving it
+struct QueryPattern {
+    name: &'static str,
+    description: &'static str,
+    table: &'static str,
+    /// Representative query, literals standing in for the bound parameters
+    sql: &'static str,
+    index_name: &'static str,
+    unique: bool,
+    /// Index definition following `ON`
+    index_definition: &'static str,
+}
+
+impl QueryPattern {
+    fn index_sql(&self) -> String {
+        format!(
+            "CREATE {}INDEX CONCURRENTLY IF NOT EXISTS {} ON {}",
+            if self.unique { "UNIQUE " } else { "" },
+            self.index_name,
+            self.index_definition
+        )
+    }
+}
+
+const QUERY_PATTERNS: [QueryPattern; 8] = [
+    QueryPattern {
+        name: "author_search",
+        description: "Fuzzy author search by display name",
+        table: "authors",
+        sql: "SELECT author_id FROM authors WHERE display_name % 'example'",
+        index_name: "authors_display_name_trgm_idx",
+        unique: false,
+        index_definition: "authors USING GIN (display_name gin_trgm_ops)",
+    },
+    QueryPattern {
+        name: "author_patches",
+        description: "Patches of an author",
+        table: "patches",
+        sql: "SELECT patch_id FROM patches WHERE author_id = 1",
+        index_name: "patches_author_id_idx",
+        unique: false,
+        index_definition: "patches (author_id)",
+    },
+    QueryPattern {
+        name: "thread_listing",
+        description: "Threads by latest activity, a page after the cursor",
+        table: "patch_threads",
+        sql: "SELECT thread_id FROM patch_threads WHERE (last_activity_at, thread_id) < (NOW(), 1) ORDER BY last_activity_at DESC, thread_id DESC LIMIT 50",
+        index_name: "patch_threads_activity_keyset_idx",
+        unique: false,
+        index_definition: "patch_threads (last_activity_at DESC, thread_id DESC)",
+    },
+    QueryPattern {
+        name: "thread_listing_by_list",
+        description: "Threads of one mailing list by latest activity",
+        table: "patch_threads",
+        sql: "SELECT thread_id FROM patch_threads WHERE list_id = 1 ORDER BY last_activity_at DESC LIMIT 50",
+        index_name: "patch_threads_list_id_idx",
+        unique: false,
+        index_definition: "patch_threads (list_id, last_activity_at DESC)",
+    },
+    QueryPattern {
+        name: "thread_messages",
+        description: "Messages of a thread in thread order",
+        table: "patch_replies",
+        sql: "SELECT patch_id FROM patch_replies WHERE thread_id = 1 ORDER BY position_in_thread",
+        index_name: "patch_replies_thread_position_idx",
+        unique: false,
+        index_definition: "patch_replies (thread_id, position_in_thread)",
+    },
+    QueryPattern {
+        name: "message_id_lookup",
+        description: "Message lookup by Message-ID",
+        table: "patches",
+        sql: "SELECT patch_id FROM patches WHERE message_id = 'example@localhost'",
+        index_name: "patches_message_id_key",
+        unique: true,
+        index_definition: "patches (message_id)",
+    },
+    QueryPattern {
+        name: "reply_lookup",
+        description: "Replies to a Message-ID (threading, merge notifications)",
+        table: "patches",
+        sql: "SELECT patch_id FROM patches WHERE in_reply_to = 'example@localhost'",
+        index_name: "patches_in_reply_to_idx",
+        unique: false,
+        index_definition: "patches (in_reply_to)",
+    },
+    QueryPattern {
+        name: "commit_hash_check",
+        description: "Already stored commits, checked before each population batch",
+        table: "message_sources",
+        sql: "SELECT commit_hash FROM message_sources WHERE commit_hash = ANY(ARRAY['0000000']) AND COALESCE(list_id, 0) = 0",
+        index_name: "message_sources_commit_list_idx",
+        unique: true,
+        index_definition: "message_sources (commit_hash, COALESCE(list_id, 0))",
+    },
+];
+
+/// Plan of a pattern: node descriptions (outermost first), total cost, and whether it
+/// scans or sorts the pattern's table without an index
+/// Sequential scans and sorts are disabled while planning, so they only remain when no
+/// index can serve the query, whatever the table size.
+async fn explain_pattern(pool: &PgPool, pattern: &QueryPattern) -> Result<(Vec<String>, f64, bool), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET LOCAL enable_seqscan = off").execute(&mut *tx).await?;
+    sqlx::query("SET LOCAL enable_sort = off").execute(&mut *tx).await?;
+    let plan: Value = sqlx::query_scalar(&format!("EXPLAIN (FORMAT JSON) {}", pattern.sql))
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.rollback().await?;
+
+    let root = &plan[0]["Plan"];
+    let mut nodes = Vec::new();
+    let full_scan = walk_plan(root, pattern.table, &mut nodes);
+    Ok((nodes, root["Total Cost"].as_f64().unwrap_or(0.0), full_scan))
+}
+
+/// Describe each node of an EXPLAIN plan; true when one reads `table` sequentially or sorts
+fn walk_plan(node: &Value, table: &str, nodes: &mut Vec<String>) -> bool {
+    let node_type = node["Node Type"].as_str().unwrap_or("Unknown");
+    let relation = node["Relation Name"].as_str();
+
+    let mut description = node_type.to_string();
+    if let Some(index) = node["Index Name"].as_str() {
+        description.push_str(&format!(" using {}", index));
+    }
+    if let Some(relation) = relation {
+        description.push_str(&format!(" on {}", relation));
+    }
+    nodes.push(description);
+
+    let mut full_scan = (node_type == "Seq Scan" && relation == Some(table)) || node_type == "Sort";
+    for child in node["Plans"].as_array().into_iter().flatten() {
+        full_scan |= walk_plan(child, table, nodes);
+    }
+    full_scan
+}
+
+/// Whether the index is valid; None when it does not exist
+/// An interrupted CREATE INDEX CONCURRENTLY leaves an invalid index behind
+async fn index_validity(pool: &PgPool, index_name: &str) -> Result<Option<bool>, sqlx::Error> {
+    sqlx::query_scalar("SELECT indisvalid FROM pg_index WHERE indexrelid = to_regclass($1)")
+        .bind(index_name)
+        .fetch_optional(pool)
+        .await
+}
+
+impl DatabaseManager {
+    /// EXPLAIN the main query patterns (author search, thread listing, Message-ID lookup,
+    /// commit existence checks) and report those no index serves
+    ///
+    /// With `create`, the recommended index of each such pattern is built concurrently, so
+    /// the archive stays usable meanwhile, and the pattern is checked again. An invalid
+    /// index left by an interrupted build is dropped and rebuilt.
+    pub async fn advise_indexes(&mut self, create: bool) -> Result<IndexAdvisorReport, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let mut checks = Vec::new();
+        let mut created_indexes = Vec::new();
+
+        for pattern in &QUERY_PATTERNS {
+            let (mut plan, mut total_cost, mut full_scan) = explain_pattern(pool, pattern).await?;
+            let mut validity = index_validity(pool, pattern.index_name).await?;
+
+            if create && full_scan && validity != Some(true) {
+                if validity == Some(false) {
+                    println!("Dropping invalid index {}", pattern.index_name);
+                    sqlx::query(&format!("DROP INDEX CONCURRENTLY IF EXISTS {}", pattern.index_name))
+                        .execute(pool)
+                        .await?;
+                }
+                println!("Creating index {} for {}", pattern.index_name, pattern.name);
+                sqlx::query(&pattern.index_sql()).execute(pool).await?;
+                sqlx::query(&format!("ANALYZE {}", pattern.table)).execute(pool).await?;
+                created_indexes.push(pattern.index_name.to_string());
+
+                (plan, total_cost, full_scan) = explain_pattern(pool, pattern).await?;
+                validity = index_validity(pool, pattern.index_name).await?;
+            }
+
+            checks.push(QueryIndexCheck {
+                pattern: pattern.name.to_string(),
+                description: pattern.description.to_string(),
+                plan,
+                total_cost,
+                full_scan,
+                recommended_index: pattern.index_name.to_string(),
+                index_sql: pattern.index_sql(),
+                index_exists: validity == Some(true),
+                missing_index: full_scan && validity != Some(true),
+            });
+        }
+
+        let missing_indexes = checks.iter().filter(|check| check.missing_index).count() as u32;
+        println!("Index advisor: {} of {} query patterns lack an index", missing_indexes, checks.len());
+
+        Ok(IndexAdvisorReport {
+            checks,
+            missing_indexes,
+            created_indexes,
+        })
+    }
+}
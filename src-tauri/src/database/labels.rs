@@ -0,0 +1,144 @@
+use crate::database::{DatabaseManager, Label};
+
+/// Something a label can be attached to
+struct LabelTarget {
+    /// Link table holding (id column, label_id)
+    table: &'static str,
+    column: &'static str,
+    /// Table the id must exist in
+    target_table: &'static str,
+    noun: &'static str,
+}
+
+const THREAD_LABELS: LabelTarget = LabelTarget {
+    table: "thread_labels",
+    column: "thread_id",
+    target_table: "patch_threads",
+    noun: "Thread",
+};
+
+const PATCH_LABELS: LabelTarget = LabelTarget {
+    table: "patch_labels",
+    column: "patch_id",
+    target_table: "patches",
+    noun: "Patch",
+};
+
+impl DatabaseManager {
+    /// Create a label; names are unique regardless of case
+    pub async fn create_label(&mut self, name: &str, color: Option<&str>) -> Result<Label, Box<dyn std::error::Error>> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Label name must not be empty".into());
+        }
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let label = sqlx::query_as::<_, Label>(
+            "INSERT INTO labels (name, color) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING
+             RETURNING label_id, name, color, created_at"
+        )
+        .bind(name)
+        .bind(color)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| format!("Label '{}' already exists", name))?;
+
+        Ok(label)
+    }
+
+    /// Get all labels, by name
+    pub async fn get_labels(&mut self) -> Result<Vec<Label>, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let labels = sqlx::query_as::<_, Label>(
+            "SELECT label_id, name, color, created_at FROM labels ORDER BY LOWER(name)"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(labels)
+    }
+
+    /// Delete a label, removing it from every thread and patch
+    /// Returns false when there was no such label
+    pub async fn delete_label(&mut self, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let deleted = sqlx::query("DELETE FROM labels WHERE label_id = $1")
+            .bind(label_id)
+            .execute(pool)
+            .await?;
+
+        Ok(deleted.rows_affected() > 0)
+    }
+
+    /// Attach a label to a thread; false when it already had it
+    pub async fn add_thread_label(&mut self, thread_id: i64, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.add_label(THREAD_LABELS, thread_id, label_id).await
+    }
+
+    /// Detach a label from a thread; false when it did not have it
+    pub async fn remove_thread_label(&mut self, thread_id: i64, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.remove_label(THREAD_LABELS, thread_id, label_id).await
+    }
+
+    /// Attach a label to a single patch or reply; false when it already had it
+    pub async fn add_patch_label(&mut self, patch_id: i64, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.add_label(PATCH_LABELS, patch_id, label_id).await
+    }
+
+    /// Detach a label from a patch; false when it did not have it
+    pub async fn remove_patch_label(&mut self, patch_id: i64, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.remove_label(PATCH_LABELS, patch_id, label_id).await
+    }
+
+    async fn add_label(&mut self, target: LabelTarget, target_id: i64, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let (label_exists, target_exists): (bool, bool) = sqlx::query_as(&format!(
+            "SELECT EXISTS (SELECT 1 FROM labels WHERE label_id = $1),
+                    EXISTS (SELECT 1 FROM {} WHERE {} = $2)",
+            target.target_table, target.column
+        ))
+        .bind(label_id)
+        .bind(target_id)
+        .fetch_one(pool)
+        .await?;
+        if !label_exists {
+            return Err(format!("Label {} does not exist", label_id).into());
+        }
+        if !target_exists {
+            return Err(format!("{} {} does not exist", target.noun, target_id).into());
+        }
+
+        let added = sqlx::query(&format!(
+            "INSERT INTO {} ({}, label_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            target.table, target.column
+        ))
+        .bind(target_id)
+        .bind(label_id)
+        .execute(pool)
+        .await?;
+
+        Ok(added.rows_affected() > 0)
+    }
+
+    async fn remove_label(&mut self, target: LabelTarget, target_id: i64, label_id: i32) -> Result<bool, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let removed = sqlx::query(&format!("DELETE FROM {} WHERE {} = $1 AND label_id = $2", target.table, target.column))
+            .bind(target_id)
+            .bind(label_id)
+            .execute(pool)
+            .await?;
+
+        Ok(removed.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use crate::database::{DatabaseManager, MailingList};
+use crate::git_config::MailingListRepo;
+use crate::git_parser::EpochCommit;
+
+impl DatabaseManager {
+    /// Register a mailing list (or refresh its location) and return its list_id
+    pub async fn ensure_mailing_list(&mut self, list: &MailingListRepo) -> Result<i32, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let (list_id,): (i32,) = sqlx::query_as(
+            "INSERT INTO mailing_lists (name, clone_url, repo_path)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (name) DO UPDATE
+             SET clone_url = EXCLUDED.clone_url,
+                 repo_path = EXCLUDED.repo_path
+             RETURNING list_id"
+        )
+        .bind(&list.name)
+        .bind(&list.clone_url)
+        .bind(&list.repo_path)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(list_id)
+    }
+
+    /// Get all mailing lists that have been imported into the database
+    pub async fn get_mailing_lists(&mut self) -> Result<Vec<MailingList>, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let lists = sqlx::query_as::<_, MailingList>(
+            "SELECT list_id, name, clone_url, repo_path, created_at FROM mailing_lists ORDER BY name"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(lists)
+    }
+
+    /// HEAD of each epoch as of the last population of a list, keyed by epoch
+    pub async fn get_populated_heads(&mut self, list_id: i32) -> Result<HashMap<u32, String>, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let heads: Vec<(i32, String)> = sqlx::query_as(
+            "SELECT epoch, head_commit FROM populated_heads WHERE list_id = $1"
+        )
+        .bind(list_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(heads.into_iter().map(|(epoch, head)| (epoch as u32, head)).collect())
+    }
+
+    /// Remember the epoch HEADs a population of `list_id` got up to
+    pub(crate) async fn record_populated_heads(&self, list_id: i32, heads: &[EpochCommit]) -> Result<(), Box<dyn std::error::Error>> {
+        if heads.is_empty() {
+            return Ok(());
+        }
+        let pool = self.get_pool()?;
+        let epochs: Vec<i32> = heads.iter().map(|head| head.epoch as i32).collect();
+        let commits: Vec<&str> = heads.iter().map(|head| head.commit_hash.as_str()).collect();
+
+        sqlx::query(
+            "INSERT INTO populated_heads (list_id, epoch, head_commit)
+             SELECT $1, * FROM UNNEST($2::INT[], $3::TEXT[])
+             ON CONFLICT (list_id, epoch) DO UPDATE
+             SET head_commit = EXCLUDED.head_commit,
+                 updated_at = NOW()"
+        )
+        .bind(list_id)
+        .bind(&epochs)
+        .bind(&commits)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
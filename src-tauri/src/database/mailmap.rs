@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::path::Path;
+use sqlx::Row;
+use crate::database::identities::{cleanup_moved_authors, find_or_create_author, move_email};
+use crate::database::{DatabaseManager, MailmapImportResult};
+use crate::mail_parser::normalize_name;
+use crate::mailmap::load_mailmap;
+
+impl DatabaseManager {
+    /// Fold the identities listed in a `.mailmap` file (or a directory holding one)
+    /// into their canonical authors
+    ///
+    /// Each mapped address, and the patches sent from it, moves to the author owning
+    /// the proper address or, failing that, the author with the proper name. Authors
+    /// left without addresses or patches are removed and patch counts recomputed.
+    /// Addresses first seen after the import keep their own author until it is re-run.
+    pub async fn import_mailmap(&mut self, path: &str) -> Result<MailmapImportResult, Box<dyn std::error::Error>> {
+        let entries = load_mailmap(Path::new(path))
+            .map_err(|e| format!("Failed to read mailmap {}: {}", path, e))?;
+        println!("Applying {} mailmap entries from {}", entries.len(), path);
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+        let mut tx = pool.begin().await?;
+
+        let mut result = MailmapImportResult {
+            entries: entries.len() as u32,
+            emails_moved: 0,
+            emails_added: 0,
+            authors_removed: 0,
+            unmatched: 0,
+        };
+        let mut source_authors: HashSet<i64> = HashSet::new();
+
+        for entry in &entries {
+            let source = sqlx::query(
+                "SELECT ae.email_id, ae.author_id, a.display_name
+                 FROM author_emails ae
+                 JOIN authors a ON a.author_id = ae.author_id
+                 WHERE ae.email = $1"
+            )
+            .bind(&entry.commit_email)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some(source) = source else {
+                result.unmatched += 1;
+                continue;
+            };
+            let email_id: i64 = source.get("email_id");
+            let source_author: i64 = source.get("author_id");
+            let display_name: String = source.get("display_name");
+
+            // Name-qualified entries only apply to that name (or one already folded to the proper name)
+            if let Some(commit_name) = &entry.commit_name {
+                let names_match = |name: &String| normalize_name(name).eq_ignore_ascii_case(&display_name);
+                if !names_match(commit_name) && !entry.proper_name.as_ref().is_some_and(names_match) {
+                    result.unmatched += 1;
+                    continue;
+                }
+            }
+
+            // Canonical author: owner of the proper address, else the proper name, else unchanged
+            let mut target_author = None;
+            if let Some(proper_email) = &entry.proper_email {
+                target_author = sqlx::query_scalar("SELECT author_id FROM author_emails WHERE email = $1")
+                    .bind(proper_email)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            }
+            if target_author.is_none() {
+                if let Some(proper_name) = &entry.proper_name {
+                    target_author = Some(find_or_create_author(&mut tx, proper_name).await?);
+                }
+            }
+            let target_author = target_author.unwrap_or(source_author);
+
+            if target_author != source_author {
+                move_email(&mut tx, email_id, target_author).await?;
+                source_authors.insert(source_author);
+                result.emails_moved += 1;
+            }
+
+            if let Some(proper_email) = &entry.proper_email {
+                let added = sqlx::query(
+                    "INSERT INTO author_emails (author_id, email) VALUES ($1, $2)
+                     ON CONFLICT (email) DO NOTHING"
+                )
+                .bind(target_author)
+                .bind(proper_email)
+                .execute(&mut *tx)
+                .await?;
+                result.emails_added += added.rows_affected() as u32;
+
+                sqlx::query("UPDATE author_emails SET is_primary = (email = $2) WHERE author_id = $1")
+                    .bind(target_author)
+                    .bind(proper_email)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        let source_authors: Vec<i64> = source_authors.into_iter().collect();
+        result.authors_removed = cleanup_moved_authors(&mut tx, &source_authors).await? as u32;
+
+        tx.commit().await?;
+        self.refresh_author_patch_counts().await?;
+
+        println!(
+            "Mailmap applied: {} addresses moved, {} added, {} authors removed, {} entries unmatched",
+            result.emails_moved, result.emails_added, result.authors_removed, result.unmatched
+        );
+
+        Ok(result)
+    }
+}
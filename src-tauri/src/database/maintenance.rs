@@ -0,0 +1,44 @@
+use crate::database::{DatabaseManager, MaintenanceResult};
+
+/// Tables that grow with every population run
+const MAIN_TABLES: [&str; 13] = [
+    "authors",
+    "author_emails",
+    "patches",
+    "patch_trailers",
+    "patch_recipients",
+    "patch_files",
+    "patch_fixes",
+    "patch_commit_refs",
+    "patch_links",
+    "patch_threads",
+    "patch_replies",
+    "thread_participants",
+    "series",
+];
+
+impl DatabaseManager {
+    /// Refresh planner statistics of the main tables, e.g. after a big population run
+    /// With `vacuum` dead rows are reclaimed as well (VACUUM ANALYZE)
+    pub async fn run_maintenance(&mut self, vacuum: bool) -> Result<MaintenanceResult, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let command = if vacuum { "VACUUM (ANALYZE)" } else { "ANALYZE" };
+        println!("Running {} on {} tables...", command, MAIN_TABLES.len());
+        sqlx::query(&format!("{} {}", command, MAIN_TABLES.join(", ")))
+            .execute(pool)
+            .await?;
+
+        let duration_ms = start_time.elapsed().as_millis() as u64;
+        println!("Maintenance complete in {} ms", duration_ms);
+
+        Ok(MaintenanceResult {
+            tables: MAIN_TABLES.iter().map(|t| t.to_string()).collect(),
+            vacuumed: vacuum,
+            duration_ms,
+        })
+    }
+}
@@ -61,14 +61,14 @@ pub async fn get_thread_merge_status(
 pub async fn reprocess_merge_notifications(
     pool: &PgPool,
 ) -> Result<ReprocessResult, Box<dyn std::error::Error>> {
-    // Fetch all patches from patchwork bot that aren't already marked
+    // Fetch patchwork bot emails and replies (maintainer "applied" messages) that aren't already marked
     let patches = sqlx::query(
-        "SELECT p.patch_id, p.subject, p.body_text, ae.email
+        "SELECT p.patch_id, p.subject, p.body_text, p.body_compressed, ae.email, a.display_name, p.is_reply
          FROM patches p
          JOIN author_emails ae ON p.email_id = ae.email_id
-         WHERE ae.email ILIKE '%patchwork%'
-           AND p.is_merge_notification = FALSE
-           AND p.body_text IS NOT NULL"
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE (ae.email ILIKE '%patchwork%' OR p.is_reply = TRUE)
+           AND p.is_merge_notification = FALSE"
     )
     .fetch_all(pool)
     .await?;
@@ -81,8 +81,11 @@ pub async fn reprocess_merge_notifications(
     for row in patches {
         let patch_id: i64 = row.try_get("patch_id")?;
         let subject: String = row.try_get("subject")?;
-        let body: Option<String> = row.try_get("body_text").ok();
+        // Bodies may be stored compressed, see DatabaseConfig::compress_bodies
+        let body = crate::database::stored_body(row.try_get("body_text")?, row.try_get("body_compressed")?)?;
         let email: String = row.try_get("email")?;
+        let display_name: String = row.try_get("display_name")?;
+        let is_reply: bool = row.try_get::<Option<bool>, _>("is_reply")?.unwrap_or(false);
         
         // Create a minimal EmailInfo for detection
         let email_info = crate::mail_parser::EmailInfo {
@@ -93,7 +96,7 @@ pub async fn reprocess_merge_notifications(
             author_email: email,
             author_first_name: String::new(),
             author_last_name: None,
-            author_display_name: String::new(),
+            author_display_name: display_name,
             to: String::new(),
             date: String::new(),
             message_id: String::new(),
@@ -101,7 +104,18 @@ pub async fn reprocess_merge_notifications(
             headers: std::collections::HashMap::new(),
             in_reply_to: None,
             references: Vec::new(),
-            is_reply: false,
+            is_reply,
+            diff: None,
+            git_patch_id: None,
+            trailers: Vec::new(),
+            fixes: Vec::new(),
+            commit_refs: Vec::new(),
+            links: Vec::new(),
+            envelope_from: None,
+            recipients: Vec::new(),
+            list_ids: Vec::new(),
+            patchwork: Default::default(),
+            signature: None,
         };
         
         let (is_merge, merge_info_opt) = crate::mail_parser::detect_and_parse_merge(&email_info);
@@ -130,6 +144,111 @@ pub async fn reprocess_merge_notifications(
     })
 }
 
+/// Record in patch_merge_status which patches the merge notifications applied
+/// Patchwork summaries are matched to the patches of the notification's thread by series
+/// position, then by subject; skipped patches ("(no matching commit)") are recorded as not
+/// applied. Notifications without a summary apply the patch they reply to, or the whole
+/// series when that is a cover letter.
+/// Returns the number of patches marked applied
+pub async fn update_patch_merge_status(pool: &PgPool) -> Result<u64, Box<dyn std::error::Error>> {
+    let notifications = sqlx::query(
+        "SELECT p.patch_id, pr.thread_id, p.body_text, p.body_compressed
+         FROM patches p
+         JOIN patch_replies pr ON pr.patch_id = p.patch_id
+         WHERE p.is_merge_notification = TRUE"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let mut merge_patch_ids = Vec::new();
+    let mut thread_ids = Vec::new();
+    let mut series_numbers = Vec::new();
+    let mut series_totals = Vec::new();
+    let mut subjects = Vec::new();
+    let mut commit_links = Vec::new();
+    for row in &notifications {
+        let merge_patch_id: i64 = row.get(0);
+        let thread_id: i64 = row.get(1);
+        let body = crate::database::stored_body(row.get(2), row.get(3))?.unwrap_or_default();
+        for entry in crate::mail_parser::parse_merged_patch_entries(&body) {
+            merge_patch_ids.push(merge_patch_id);
+            thread_ids.push(thread_id);
+            series_numbers.push(entry.series_number);
+            series_totals.push(entry.series_total);
+            subjects.push(entry.subject);
+            commit_links.push(entry.commit_link);
+        }
+    }
+    let mut summarized: Vec<i64> = merge_patch_ids.clone();
+    summarized.dedup();
+    
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM patch_merge_status").execute(&mut *tx).await?;
+    
+    // Applied wins when several notifications mention a patch
+    sqlx::query(
+        "WITH entries AS (
+            SELECT *
+            FROM UNNEST($1::BIGINT[], $2::BIGINT[], $3::INT[], $4::INT[], $5::TEXT[], $6::TEXT[])
+                 WITH ORDINALITY AS e(merge_patch_id, thread_id, series_number, series_total, subject, commit_link, idx)
+         ),
+         matched AS (
+            SELECT DISTINCT ON (e.idx) p.patch_id, e.merge_patch_id, e.commit_link
+            FROM entries e
+            JOIN patch_replies pr ON pr.thread_id = e.thread_id
+            JOIN patches p ON p.patch_id = pr.patch_id
+            WHERE p.is_reply IS NOT TRUE
+              AND ((p.series_number = e.series_number AND p.series_total = e.series_total)
+                   OR patch_subject_key(p.subject) = patch_subject_key(e.subject))
+            ORDER BY e.idx,
+                     (p.series_number = e.series_number AND p.series_total = e.series_total) IS TRUE DESC,
+                     p.sent_at DESC
+         )
+         INSERT INTO patch_merge_status (patch_id, merge_patch_id, is_applied, commit_link)
+         SELECT DISTINCT ON (patch_id) patch_id, merge_patch_id, commit_link IS NOT NULL, commit_link
+         FROM matched
+         ORDER BY patch_id, commit_link IS NOT NULL DESC"
+    )
+    .bind(&merge_patch_ids)
+    .bind(&thread_ids)
+    .bind(&series_numbers)
+    .bind(&series_totals)
+    .bind(&subjects)
+    .bind(&commit_links)
+    .execute(&mut *tx)
+    .await?;
+    
+    sqlx::query(
+        "INSERT INTO patch_merge_status (patch_id, merge_patch_id, is_applied, commit_link)
+         SELECT DISTINCT ON (t.patch_id)
+                t.patch_id, n.patch_id, TRUE,
+                CASE WHEN t.patch_id = r.patch_id AND cardinality(n.merge_commit_links) = 1
+                     THEN n.merge_commit_links[1] END
+         FROM patches n
+         JOIN patches r ON r.message_id = n.in_reply_to AND r.is_reply IS NOT TRUE
+         JOIN patches t ON t.patch_id = r.patch_id
+                        OR (r.is_cover_letter AND r.series_id IS NOT NULL AND t.series_id = r.series_id
+                            AND t.is_reply IS NOT TRUE)
+         WHERE n.is_merge_notification = TRUE
+           AND NOT (n.patch_id = ANY($1))
+           AND t.is_cover_letter IS NOT TRUE
+         ORDER BY t.patch_id, n.sent_at
+         ON CONFLICT (patch_id) DO UPDATE
+         SET merge_patch_id = EXCLUDED.merge_patch_id, is_applied = TRUE, commit_link = EXCLUDED.commit_link
+         WHERE NOT patch_merge_status.is_applied"
+    )
+    .bind(&summarized)
+    .execute(&mut *tx)
+    .await?;
+    
+    let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM patch_merge_status WHERE is_applied")
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    
+    Ok(applied as u64)
+}
+
 /// Result of reprocessing operation
 #[derive(Debug, serde::Serialize)]
 pub struct ReprocessResult {
@@ -150,3 +269,112 @@ pub struct ThreadMergeStatus {
     pub merge_notification_patch_id: i64,
 }
 
+
+/// Result of checking merge commit links against a kernel tree
+#[derive(Debug, serde::Serialize)]
+pub struct MergeVerificationResult {
+    pub links_checked: usize,
+    /// Links naming a commit the tree has
+    pub commits_found: usize,
+    /// Found commits already part of a release
+    pub commits_released: usize,
+    /// Links without a recognizable commit hash
+    pub unparseable_links: usize,
+}
+
+/// A commit of a merge notification as verified against the kernel tree
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+pub struct MergeCommit {
+    pub link: String,
+    pub commit_hash: Option<String>,
+    pub release_tag: Option<String>,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Commit hash named by a merge link: ".../c/<sha>", "...?id=<sha>" or a bare hash
+pub fn commit_hash_from_link(link: &str) -> Option<String> {
+    let link = link.trim().trim_end_matches('/');
+    let candidate = match link.split_once("id=") {
+        Some((_, rest)) => rest.split('&').next().unwrap_or(rest),
+        None => link.rsplit('/').next().unwrap_or(link),
+    };
+    let is_hash = (7..=40).contains(&candidate.len()) && candidate.chars().all(|c| c.is_ascii_hexdigit());
+    is_hash.then(|| candidate.to_lowercase())
+}
+
+/// Resolve the commit links of merge notifications in a local kernel tree, storing the
+/// full SHA and release tag of each in merge_commits
+/// Links already verified as released are skipped; unreleased ones are checked again
+pub async fn verify_merge_commits(
+    pool: &PgPool,
+    kernel_tree_path: &str,
+) -> Result<MergeVerificationResult, Box<dyn std::error::Error>> {
+    let pending: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT p.patch_id, l.link
+         FROM patches p, UNNEST(p.merge_commit_links) AS l(link)
+         WHERE p.is_merge_notification = TRUE
+           AND NOT EXISTS (SELECT 1 FROM merge_commits mc
+                           WHERE mc.patch_id = p.patch_id AND mc.link = l.link AND mc.release_tag IS NOT NULL)"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let hashes: Vec<Option<String>> = pending.iter().map(|(_, link)| commit_hash_from_link(link)).collect();
+    let mut unique: Vec<String> = hashes.iter().flatten().cloned().collect();
+    unique.sort();
+    unique.dedup();
+    
+    let tree_path = kernel_tree_path.to_string();
+    let resolved = tokio::task::spawn_blocking(move || crate::git_parser::resolve_kernel_commits(&tree_path, &unique)).await??;
+    let by_hash: std::collections::HashMap<&str, &crate::git_parser::KernelCommit> = resolved.iter()
+        .map(|c| (c.requested.as_str(), c))
+        .collect();
+    
+    let mut patch_ids = Vec::new();
+    let mut links = Vec::new();
+    let mut commit_hashes = Vec::new();
+    let mut release_tags = Vec::new();
+    for ((patch_id, link), hash) in pending.iter().zip(&hashes) {
+        let commit = hash.as_deref().and_then(|h| by_hash.get(h));
+        patch_ids.push(*patch_id);
+        links.push(link.as_str());
+        commit_hashes.push(commit.and_then(|c| c.commit_hash.clone()));
+        release_tags.push(commit.and_then(|c| c.release_tag.clone()));
+    }
+    
+    sqlx::query(
+        "INSERT INTO merge_commits (patch_id, link, commit_hash, release_tag)
+         SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[], $4::TEXT[])
+         ON CONFLICT (patch_id, link) DO UPDATE
+         SET commit_hash = EXCLUDED.commit_hash, release_tag = EXCLUDED.release_tag, verified_at = NOW()"
+    )
+    .bind(&patch_ids)
+    .bind(&links)
+    .bind(&commit_hashes)
+    .bind(&release_tags)
+    .execute(pool)
+    .await?;
+    
+    Ok(MergeVerificationResult {
+        links_checked: pending.len(),
+        commits_found: commit_hashes.iter().filter(|h| h.is_some()).count(),
+        commits_released: release_tags.iter().filter(|t| t.is_some()).count(),
+        unparseable_links: hashes.iter().filter(|h| h.is_none()).count(),
+    })
+}
+
+/// Get the verified commits of a merge notification
+pub async fn get_merge_commits(
+    pool: &PgPool,
+    patch_id: i64,
+) -> Result<Vec<MergeCommit>, sqlx::Error> {
+    sqlx::query_as::<_, MergeCommit>(
+        "SELECT link, commit_hash, release_tag, verified_at
+         FROM merge_commits
+         WHERE patch_id = $1
+         ORDER BY link"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await
+}
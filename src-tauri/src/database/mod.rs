@@ -2,24 +2,75 @@
 mod config;
 mod models;
 mod connection;
+mod copy;
 mod schema;
 mod authors;
 mod patches;
 mod threading;
 mod population;
+mod lists;
+mod quarantine;
+mod bodies;
+mod backup;
+mod version;
+mod mailmap;
+mod identities;
+mod duplicates;
+mod series;
+mod families;
+mod orphans;
+mod read_state;
+mod bookmarks;
+mod labels;
+mod review_status;
+mod events;
+mod maintenance;
+mod stats;
+mod index_advisor;
 pub mod merges;
 
 // Re-export public types
-pub use config::DatabaseConfig;
+pub use config::{DatabaseConfig, PoolConfig};
+pub use connection::ConnectionError;
+pub use version::SchemaError;
+pub use events::{ChangeEvent, CHANGE_CHANNEL};
+pub use bodies::{refetch_body_from_git, stored_body};
 pub use models::{
     Author, 
     AuthorEmail, 
     Patch, 
+    Page,
+    DateRange,
     DatabaseSetupResult, 
+    MigrationStatus,
+    SchemaVersion,
     DatabasePopulationResult, 
-    ThreadBuildStats
+    ParseRetryResult,
+    BodyCompressionResult,
+    BodyPruneResult,
+    MailmapImportResult,
+    DuplicateAuthorKind,
+    DuplicateConfidence,
+    DuplicateAuthorPair,
+    DuplicateAuthorReport,
+    DatabaseExportResult,
+    DatabaseImportResult,
+    MaintenanceResult,
+    StatsRefreshResult,
+    QueryIndexCheck,
+    IndexAdvisorReport,
+    MailingList,
+    Label,
+    ReviewStatus,
+    ThreadBuildStats,
+    ThreadingOptions,
+    ThreadBuildProgress,
+    OrphanReprocessResult,
+    SeriesBuildStats
 };
 
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use sqlx::{Pool, Postgres};
 
 /// Main database manager for handling PostgreSQL connections and operations
@@ -27,9 +78,10 @@ use sqlx::{Pool, Postgres};
 /// This struct provides a high-level interface for:
 /// - Database connection management with connection pooling
 /// - Schema setup and database initialization
-/// - Author and patch data management
+/// - Author and patch data management, including mailmap identity folding
 /// - Optimized batch processing for large datasets
 /// - Progress reporting during data population
+/// - Exporting the database to a pg_dump archive and restoring it
 ///
 /// # Example
 /// ```rust
@@ -55,6 +107,10 @@ use sqlx::{Pool, Postgres};
 pub struct DatabaseManager {
     pool: Option<Pool<Postgres>>,
     config: DatabaseConfig,
+    /// Set to stop a running population between batches
+    population_cancel: Arc<AtomicBool>,
+    /// Incompatibility found by the last schema version check
+    schema_error: Option<SchemaError>,
 }
 
 impl DatabaseManager {
@@ -63,6 +119,8 @@ impl DatabaseManager {
         Self {
             pool: None,
             config,
+            population_cancel: Arc::new(AtomicBool::new(false)),
+            schema_error: None,
         }
     }
 }
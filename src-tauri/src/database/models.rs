@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 
@@ -23,6 +23,45 @@ pub struct AuthorEmail {
     pub first_seen: Option<DateTime<Utc>>,
 }
 
+/// One page of a listing, with the size of the whole listing for pagination controls
+#[derive(Debug, Serialize, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total_count: i64,
+    pub has_more: bool,
+}
+
+/// Time window results are scoped to; `after` is inclusive, `before` exclusive
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DateRange {
+    pub after: Option<DateTime<Utc>>,
+    pub before: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// Parse RFC 3339 timestamps or plain dates ("2024-03-10", midnight UTC)
+    pub fn parse(after: Option<&str>, before: Option<&str>) -> Result<Self, String> {
+        let parse_bound = |name: &str, value: &str| -> Result<DateTime<Utc>, String> {
+            let value = value.trim();
+            DateTime::parse_from_rfc3339(value)
+                .map(|date| date.with_timezone(&Utc))
+                .or_else(|_| chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map(|date| date.and_time(chrono::NaiveTime::MIN).and_utc()))
+                .map_err(|e| format!("Invalid {} date '{}': {}", name, value, e))
+        };
+        let range = DateRange {
+            after: after.map(|value| parse_bound("after", value)).transpose()?,
+            before: before.map(|value| parse_bound("before", value)).transpose()?,
+        };
+        if let (Some(after), Some(before)) = (range.after, range.before) {
+            if after >= before {
+                return Err(format!("'after' ({}) must be earlier than 'before' ({})", after.to_rfc3339(), before.to_rfc3339()));
+            }
+        }
+        Ok(range)
+    }
+}
+
 /// Patch (email) information
 #[derive(Debug, Serialize, Clone, FromRow)]
 pub struct Patch {
@@ -53,12 +92,93 @@ pub(crate) struct PatchData {
     pub is_series: bool,
     pub series_number: Option<i32>,
     pub series_total: Option<i32>,
+    pub is_cover_letter: bool,
+    pub subject_tags: crate::mail_parser::SubjectTags,
     pub in_reply_to: Option<String>,
     pub references: Vec<String>,
     pub is_reply: bool,
     // Merge notification fields
     pub is_merge_notification: bool,
     pub merge_info: Option<crate::mail_parser::MergeInfo>,
+    // `git request-pull` fields
+    pub pull_request: Option<crate::mail_parser::PullRequestInfo>,
+    pub revert: Option<crate::mail_parser::RevertInfo>,
+    pub syzbot_report: Option<crate::mail_parser::SyzbotReport>,
+    pub stable_backport: Option<crate::mail_parser::StableBackport>,
+    pub sender_type: crate::mail_parser::SenderType,
+    pub is_ping: bool,
+    pub is_rejection: bool,
+    pub list_id: Option<i32>,
+    // Parsed diff (files, hunks, line counts)
+    pub diff: Option<crate::diff_parser::DiffSummary>,
+    pub git_patch_id: Option<String>,
+    pub trailers: Vec<crate::mail_parser::Trailer>,
+    pub fixes: Vec<crate::mail_parser::FixesTag>,
+    pub commit_refs: Vec<crate::mail_parser::CommitRef>,
+    pub links: Vec<crate::mail_parser::MessageLink>,
+    pub envelope_from: Option<String>,
+    pub recipients: Vec<crate::mail_parser::Recipient>,
+    pub list_ids: Vec<String>,
+    pub patchwork: crate::mail_parser::PatchworkHeaders,
+    pub signature: Option<String>,
+}
+
+/// Mailing list archive tracked in the database
+#[derive(Debug, Serialize, Clone, FromRow)]
+pub struct MailingList {
+    pub list_id: i32,
+    pub name: String,
+    pub clone_url: Option<String>,
+    pub repo_path: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// User-defined label for threads and patches
+#[derive(Debug, Serialize, Clone, FromRow)]
+pub struct Label {
+    pub label_id: i32,
+    pub name: String,
+    pub color: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Review workflow status of a thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStatus {
+    #[default]
+    New,
+    UnderReview,
+    ChangesRequested,
+    Accepted,
+    Rejected,
+    Superseded,
+}
+
+impl ReviewStatus {
+    /// Value stored in the patch_threads.review_status column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::New => "new",
+            ReviewStatus::UnderReview => "under_review",
+            ReviewStatus::ChangesRequested => "changes_requested",
+            ReviewStatus::Accepted => "accepted",
+            ReviewStatus::Rejected => "rejected",
+            ReviewStatus::Superseded => "superseded",
+        }
+    }
+
+    /// Parse a patch_threads.review_status value (unknown values are treated as new)
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "under_review" => ReviewStatus::UnderReview,
+            "changes_requested" => ReviewStatus::ChangesRequested,
+            "accepted" => ReviewStatus::Accepted,
+            "rejected" => ReviewStatus::Rejected,
+            "superseded" => ReviewStatus::Superseded,
+            _ => ReviewStatus::New,
+        }
+    }
 }
 
 /// Result of database setup operation
@@ -69,6 +189,27 @@ pub struct DatabaseSetupResult {
     pub tables_created: Vec<String>,
 }
 
+/// Result of applying schema migrations
+#[derive(Debug, Serialize)]
+pub struct MigrationStatus {
+    /// Latest migration version now applied (None when there are no migrations)
+    pub current_version: Option<i64>,
+    /// Migrations applied by this run, e.g. "0002_add_stable_backports"
+    pub newly_applied: Vec<String>,
+    pub total_migrations: usize,
+}
+
+/// Schema version of the connected database compared to this build
+#[derive(Debug, Serialize)]
+pub struct SchemaVersion {
+    /// Latest applied migration (None for an uninitialized database)
+    pub current_version: Option<i64>,
+    /// Latest migration shipped with this build
+    pub expected_version: i64,
+    pub compatible: bool,
+    pub error: Option<crate::database::SchemaError>,
+}
+
 /// Result of database population operation
 #[derive(Debug, Serialize)]
 pub struct DatabasePopulationResult {
@@ -77,6 +218,158 @@ pub struct DatabasePopulationResult {
     pub total_authors_inserted: u32,
     pub total_emails_inserted: u32,
     pub errors: Vec<String>,
+    /// Stopped early by a cancellation request; the counts cover the work done until then
+    pub cancelled: bool,
+}
+
+/// Result of compressing stored bodies
+#[derive(Debug, Serialize)]
+pub struct BodyCompressionResult {
+    pub rows_compressed: u64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+/// Result of pruning old bodies
+#[derive(Debug, Serialize)]
+pub struct BodyPruneResult {
+    pub rows_pruned: u64,
+    /// Messages sent before this time (RFC 3339) lost their body
+    pub cutoff: String,
+    /// Lists left alone because their git archive couldn't be opened
+    pub skipped_lists: Vec<String>,
+}
+
+/// Result of exporting the database to an archive
+#[derive(Debug, Serialize)]
+pub struct DatabaseExportResult {
+    pub path: String,
+    pub size_bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// Result of ANALYZE (or VACUUM ANALYZE) over the main tables
+#[derive(Debug, Serialize)]
+pub struct MaintenanceResult {
+    pub tables: Vec<String>,
+    pub vacuumed: bool,
+    pub duration_ms: u64,
+}
+
+/// Index check of one query pattern (see `DatabaseManager::advise_indexes`)
+#[derive(Debug, Serialize)]
+pub struct QueryIndexCheck {
+    pub pattern: String,
+    pub description: String,
+    /// Plan nodes, outermost first, e.g. "Index Scan using patches_author_id_idx on patches"
+    pub plan: Vec<String>,
+    pub total_cost: f64,
+    /// The plan reads or sorts the table without an index
+    pub full_scan: bool,
+    pub recommended_index: String,
+    /// Statement creating the recommended index
+    pub index_sql: String,
+    pub index_exists: bool,
+    pub missing_index: bool,
+}
+
+/// Result of the index advisor
+#[derive(Debug, Serialize)]
+pub struct IndexAdvisorReport {
+    pub checks: Vec<QueryIndexCheck>,
+    pub missing_indexes: u32,
+    pub created_indexes: Vec<String>,
+}
+
+/// Result of refreshing the dashboard statistics views
+#[derive(Debug, Serialize)]
+pub struct StatsRefreshResult {
+    pub views: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of restoring the database from an archive
+#[derive(Debug, Serialize)]
+pub struct DatabaseImportResult {
+    /// Schema version recorded in the archive
+    pub archive_schema_version: i64,
+    /// Migrations applied after loading to reach the current schema
+    pub migrations_applied: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of folding author identities with a mailmap
+#[derive(Debug, Serialize)]
+pub struct MailmapImportResult {
+    pub entries: u32,
+    /// Addresses (with their patches) moved to a canonical author
+    pub emails_moved: u32,
+    /// Proper addresses that were not known yet
+    pub emails_added: u32,
+    /// Authors deleted because all their addresses were folded away
+    pub authors_removed: u32,
+    /// Entries whose commit address (or name) is not in the database
+    pub unmatched: u32,
+}
+
+/// Why two authors look like the same person
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateAuthorKind {
+    /// Addresses are the same mailbox (sub-address or gmail dots aside), names differ
+    SameEmail,
+    /// Names differ only in case
+    SameName,
+    /// Names differ in accents, punctuation or word order
+    NameVariant,
+}
+
+/// How likely a duplicate pair is one person; only high ones are merged automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateConfidence {
+    High,
+    Medium,
+    Low,
+}
+
+/// Two authors that are likely the same person
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateAuthorPair {
+    pub kind: DuplicateAuthorKind,
+    pub confidence: DuplicateConfidence,
+    pub author_a: Author,
+    pub author_b: Author,
+    /// The matching addresses or the shared name key
+    pub evidence: String,
+    /// Whether the pair was merged by this audit
+    pub merged: bool,
+}
+
+/// Result of scanning authors for duplicates
+#[derive(Debug, Serialize)]
+pub struct DuplicateAuthorReport {
+    pub authors_scanned: u32,
+    /// Most confident first
+    pub pairs: Vec<DuplicateAuthorPair>,
+    /// Authors merged away (only when auto-merge was requested)
+    pub authors_merged: u32,
+}
+
+/// Result of re-parsing quarantined emails
+#[derive(Debug, Serialize)]
+pub struct ParseRetryResult {
+    pub retried: u32,
+    pub recovered: u32,
+    pub still_failing: u32,
+    pub errors: Vec<String>,
+}
+
+/// Result of grouping patches into series
+#[derive(Debug, Serialize)]
+pub struct SeriesBuildStats {
+    pub series_created: u64,
+    pub patches_assigned: u64,
 }
 
 /// Statistics from thread building operation
@@ -86,6 +379,66 @@ pub struct ThreadBuildStats {
     pub total_replies: u32,
     pub orphaned_messages: u32,
     pub max_depth: i32,
+    /// Threads rooted at a message missing from the archive
+    pub phantom_roots: u32,
+    /// Groups of threads holding versions of the same patch or series
+    pub thread_families: u64,
+    /// Threads moved to accepted/superseded by the review status rules
+    pub review_status_changes: u64,
+    /// Patches a merge notification applied, see `merges::update_patch_merge_status`
+    pub applied_patches: u64,
     pub processing_time_ms: u64,
 }
 
+/// How far threading may guess a parent for messages whose references are not stored
+/// The defaults keep the plain subject fallback; narrowing it stops unrelated patches
+/// that share a subject from being linked years apart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThreadingOptions {
+    /// Link to the earliest message with the same normalized subject
+    pub subject_fallback: bool,
+    /// Only consider candidates sent at most this many days before or after the message
+    pub fallback_window_days: Option<u32>,
+    /// Only consider candidates from the same author
+    pub fallback_same_author: bool,
+    /// Only consider candidates from the same series (version, tree and patch count)
+    pub fallback_same_series: bool,
+}
+
+impl Default for ThreadingOptions {
+    fn default() -> Self {
+        Self {
+            subject_fallback: true,
+            fallback_window_days: None,
+            fallback_same_author: false,
+            fallback_same_series: false,
+        }
+    }
+}
+
+/// Progress of a thread build, reported as `thread-build-progress` events
+/// `processed`/`total` count the items of the current phase; the interim counts grow as threads are written
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ThreadBuildProgress {
+    /// fetching, linking, threads, replies, statistics, families, review-status, merge-status or complete
+    pub phase: String,
+    pub processed: u32,
+    pub total: u32,
+    /// Insert batch being written (1-based), for the batched phases
+    pub batch: Option<u32>,
+    pub threads: u32,
+    pub replies: u32,
+}
+
+/// Result of re-examining orphaned messages (replies threaded at the top level)
+#[derive(Debug, Serialize)]
+pub struct OrphanReprocessResult {
+    pub orphans_before: u32,
+    /// Orphans now attached below their parent
+    pub recovered: u32,
+    pub remaining: u32,
+    /// Statistics of the rebuild, None when nothing could be recovered and threads were left alone
+    pub thread_stats: Option<ThreadBuildStats>,
+}
+
@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use crate::database::{DatabaseManager, OrphanReprocessResult, ThreadBuildProgress, ThreadingOptions};
+
+impl DatabaseManager {
+    /// Messages threaded at the top level although they reply to something (In-Reply-To or References)
+    async fn orphaned_patch_ids(&self) -> Result<HashSet<i64>, Box<dyn std::error::Error>> {
+        let pool = self.get_pool()?;
+
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT pr.patch_id
+             FROM patch_replies pr
+             JOIN patches p ON p.patch_id = pr.patch_id
+             WHERE pr.parent_patch_id IS NULL
+               AND (p.in_reply_to IS NOT NULL OR cardinality(p.thread_references) > 0)"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Re-examine orphaned messages, e.g. after a deeper population stored their parents
+    ///
+    /// Threads are rebuilt only when that can recover something: an orphan references a
+    /// message now stored, or messages were stored since the last build (their References
+    /// may connect an orphan to its ancestors). The rebuild threads with `options`.
+    pub async fn reprocess_orphans(&mut self, options: &ThreadingOptions) -> Result<OrphanReprocessResult, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+
+        let orphans_before = self.orphaned_patch_ids().await?;
+        let pool = self.get_pool()?;
+
+        let (resolvable, unthreaded): (i64, i64) = sqlx::query_as(
+            "SELECT
+                (SELECT COUNT(*)
+                 FROM patch_replies pr
+                 JOIN patches p ON p.patch_id = pr.patch_id
+                 WHERE pr.parent_patch_id IS NULL
+                   AND EXISTS (
+                     SELECT 1 FROM patches ref
+                     WHERE ref.message_id = ANY(array_append(COALESCE(p.thread_references, '{}'), p.in_reply_to))
+                       AND ref.patch_id <> p.patch_id
+                   )),
+                (SELECT COUNT(*)
+                 FROM patches p
+                 WHERE NOT EXISTS (SELECT 1 FROM patch_replies pr WHERE pr.patch_id = p.patch_id))"
+        )
+        .fetch_one(pool)
+        .await?;
+
+        println!("Reprocessing {} orphaned messages: {} reference a stored message, {} messages not yet threaded",
+                 orphans_before.len(), resolvable, unthreaded);
+
+        if resolvable == 0 && unthreaded == 0 {
+            return Ok(OrphanReprocessResult {
+                orphans_before: orphans_before.len() as u32,
+                recovered: 0,
+                remaining: orphans_before.len() as u32,
+                thread_stats: None,
+            });
+        }
+
+        let thread_stats = self.build_thread_relationships_with_progress(options, None::<fn(ThreadBuildProgress)>).await?;
+        let orphans_after = self.orphaned_patch_ids().await?;
+        let recovered = orphans_before.difference(&orphans_after).count() as u32;
+        println!("Recovered {} orphaned messages, {} remain", recovered, orphans_after.len());
+
+        Ok(OrphanReprocessResult {
+            orphans_before: orphans_before.len() as u32,
+            recovered,
+            remaining: orphans_after.len() as u32,
+            thread_stats: Some(thread_stats),
+        })
+    }
+}
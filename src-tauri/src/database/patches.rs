@@ -1,42 +1,44 @@
 use std::collections::{HashMap, HashSet};
-use sqlx::{Pool, Postgres, Row};
-use chrono::{DateTime, Utc, NaiveDateTime};
+use sqlx::{PgConnection, Pool, Postgres, Row};
 use regex::Regex;
 use crate::mail_parser::EmailInfo;
 use crate::database::models::PatchData;
+use crate::database::bodies::compress_body;
+use crate::database::copy::{copy_binary, BinaryCopyBuffer};
+
+/// A batch insert that was rolled back, listing the commits it did not persist
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub(crate) struct BatchInsertError {
+    pub commit_hashes: Vec<String>,
+    pub message: String,
+}
 
 /// Static helper methods for patch operations
 pub(crate) struct PatchOps;
 
 impl PatchOps {
-    /// Check which commit hashes already exist in the database using batch queries
+    /// Check which commit hashes were already read into the given list
+    /// Commits whose message was stored from another epoch or list count too (see message_sources)
     pub async fn get_existing_commit_hashes(
         commit_hashes: &[String],
+        list_id: Option<i32>,
         pool: &Pool<Postgres>
     ) -> Result<HashSet<String>, Box<dyn std::error::Error>> {
         if commit_hashes.is_empty() {
             return Ok(HashSet::new());
         }
 
-        let mut existing_set = HashSet::new();
-
-        // Process in batches to avoid SQL parameter limits (1000 at a time)
-        for batch in commit_hashes.chunks(1000) {
-            let placeholders: Vec<String> = (1..=batch.len()).map(|i| format!("${}", i)).collect();
-            let query_str = format!("SELECT commit_hash FROM patches WHERE commit_hash IN ({})", placeholders.join(","));
-
-            let mut query = sqlx::query(&query_str);
-            for commit_hash in batch {
-                query = query.bind(commit_hash);
-            }
+        let existing: Vec<String> = sqlx::query_scalar(
+            "SELECT commit_hash FROM message_sources
+             WHERE commit_hash = ANY($1) AND COALESCE(list_id, 0) = COALESCE($2, 0)"
+        )
+        .bind(commit_hashes)
+        .bind(list_id)
+        .fetch_all(pool)
+        .await?;
 
-            let existing_rows = query.fetch_all(pool).await.unwrap_or_default();
-            for row in existing_rows {
-                existing_set.insert(row.get::<String, _>(0));
-            }
-        }
-
-        Ok(existing_set)
+        Ok(existing.into_iter().collect())
     }
 
     /// Collect unique author identities from email data
@@ -103,113 +105,123 @@ impl PatchOps {
     /// Returns: (email -> author_id, email -> email_id)
     pub async fn upsert_authors_and_emails(
         author_identities: &HashMap<(String, Option<String>), Vec<String>>,
-        pool: &Pool<Postgres>
+        conn: &mut PgConnection
     ) -> Result<(HashMap<String, i64>, HashMap<String, i64>), Box<dyn std::error::Error>> {
         if author_identities.is_empty() {
             return Ok((HashMap::new(), HashMap::new()));
         }
 
-        // Step 1: Insert authors (by name)
+        // Step 1: Stage author names with COPY and insert the new ones (sorted to keep lock order stable)
         let mut sorted_authors: Vec<_> = author_identities.keys().collect();
         sorted_authors.sort();
 
-        if !sorted_authors.is_empty() {
-            let mut insert_query = String::from("INSERT INTO authors (first_name, last_name, display_name) VALUES ");
-            let mut param_count = 1;
+        sqlx::query(
+            "CREATE TEMP TABLE IF NOT EXISTS authors_staging (
+                first_name TEXT NOT NULL, last_name TEXT, display_name TEXT NOT NULL
+             ) ON COMMIT DROP"
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query("TRUNCATE authors_staging").execute(&mut *conn).await?;
 
-            for (i, _) in sorted_authors.iter().enumerate() {
-                if i > 0 {
-                    insert_query.push(',');
-                }
-                insert_query.push_str(&format!("(${}, ${}, ${})", param_count, param_count + 1, param_count + 2));
-                param_count += 3;
-            }
-
-            insert_query.push_str(" ON CONFLICT (first_name, last_name) DO NOTHING");
-
-            let mut query = sqlx::query(&insert_query);
-            for (first_name, last_name) in &sorted_authors {
-                let display_name = if let Some(ln) = last_name {
-                    format!("{} {}", first_name, ln)
-                } else {
-                    first_name.to_string()
-                };
-                query = query.bind(first_name).bind(last_name).bind(display_name);
-            }
-
-            query.execute(pool).await?;
-        }
-
-        // Step 2: Get author IDs for all names
-        let mut author_id_by_name: HashMap<(String, Option<String>), i64> = HashMap::new();
+        let mut author_rows = BinaryCopyBuffer::new();
         for (first_name, last_name) in &sorted_authors {
-            let row = sqlx::query("SELECT author_id FROM authors WHERE first_name = $1 AND (last_name = $2 OR (last_name IS NULL AND $2 IS NULL))")
-                .bind(first_name)
-                .bind(last_name)
-                .fetch_one(pool)
-                .await?;
-            let author_id: i64 = row.get(0);
-            author_id_by_name.insert((first_name.to_string(), last_name.clone()), author_id);
+            let display_name = if let Some(ln) = last_name {
+                format!("{} {}", first_name, ln)
+            } else {
+                first_name.to_string()
+            };
+            author_rows.row(3)
+                .text(Some(first_name))
+                .text(last_name.as_deref())
+                .text(Some(&display_name));
         }
+        copy_binary(&mut *conn, "authors_staging (first_name, last_name, display_name)", author_rows).await?;
+
+        // Names of merged-away authors are not recreated (see author_aliases)
+        sqlx::query(
+            "INSERT INTO authors (first_name, last_name, display_name)
+             SELECT s.first_name, s.last_name, s.display_name FROM authors_staging s
+             WHERE NOT EXISTS (
+               SELECT 1 FROM author_aliases al
+               WHERE al.first_name = s.first_name AND COALESCE(al.last_name, '') = COALESCE(s.last_name, '')
+             )
+             ORDER BY s.first_name, s.last_name
+             ON CONFLICT (first_name, last_name) DO NOTHING"
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        // Step 2: Get author IDs for all names, following aliases to the surviving author
+        let rows = sqlx::query(
+            "SELECT s.first_name, s.last_name, COALESCE(
+               (SELECT al.author_id FROM author_aliases al
+                WHERE al.first_name = s.first_name AND COALESCE(al.last_name, '') = COALESCE(s.last_name, '')),
+               (SELECT MIN(a.author_id) FROM authors a
+                WHERE a.first_name = s.first_name AND a.last_name IS NOT DISTINCT FROM s.last_name)
+             )
+             FROM authors_staging s"
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        let author_id_by_name: HashMap<(String, Option<String>), i64> = rows.iter()
+            .map(|row| ((row.get(0), row.get(1)), row.get(2)))
+            .collect();
 
-        // Step 3: Insert author_emails
+        // Step 3: Stage author_emails with COPY and insert the new ones
         let mut all_emails_to_insert = Vec::new();
         for ((first_name, last_name), emails) in author_identities {
-            let author_id = author_id_by_name.get(&(first_name.clone(), last_name.clone())).unwrap();
+            let author_id = author_id_by_name.get(&(first_name.clone(), last_name.clone()))
+                .ok_or_else(|| format!("Author '{}' missing after upsert", first_name))?;
             for email in emails {
                 all_emails_to_insert.push((*author_id, email.clone()));
             }
         }
-
-        if !all_emails_to_insert.is_empty() {
-            all_emails_to_insert.sort_by(|a, b| a.1.cmp(&b.1));
-            
-            let mut insert_query = String::from("INSERT INTO author_emails (author_id, email) VALUES ");
-            let mut param_count = 1;
-
-            for (i, _) in all_emails_to_insert.iter().enumerate() {
-                if i > 0 {
-                    insert_query.push(',');
-                }
-                insert_query.push_str(&format!("(${}, ${})", param_count, param_count + 1));
-                param_count += 2;
-            }
-
-            insert_query.push_str(" ON CONFLICT (email) DO NOTHING");
-
-            let mut query = sqlx::query(&insert_query);
-            for (author_id, email) in &all_emails_to_insert {
-                query = query.bind(author_id).bind(email);
-            }
-
-            query.execute(pool).await?;
+        all_emails_to_insert.sort_by(|a, b| a.1.cmp(&b.1));
+
+        sqlx::query(
+            "CREATE TEMP TABLE IF NOT EXISTS author_emails_staging (
+                author_id BIGINT NOT NULL, email CITEXT NOT NULL
+             ) ON COMMIT DROP"
+        )
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query("TRUNCATE author_emails_staging").execute(&mut *conn).await?;
+
+        let mut email_rows = BinaryCopyBuffer::new();
+        for (author_id, email) in &all_emails_to_insert {
+            email_rows.row(2)
+                .int8(Some(*author_id))
+                .text(Some(email));
         }
+        copy_binary(&mut *conn, "author_emails_staging (author_id, email)", email_rows).await?;
+
+        sqlx::query(
+            "INSERT INTO author_emails (author_id, email)
+             SELECT author_id, email FROM author_emails_staging
+             ORDER BY email
+             ON CONFLICT (email) DO NOTHING"
+        )
+        .execute(&mut *conn)
+        .await?;
 
         // Step 4: Get email IDs for all emails
-        let all_emails: Vec<&String> = all_emails_to_insert.iter().map(|(_, email)| email).collect();
         let mut email_to_author_id = HashMap::new();
         let mut email_to_email_id = HashMap::new();
 
-        if !all_emails.is_empty() {
-            let placeholders: Vec<String> = (1..=all_emails.len()).map(|i| format!("${}", i)).collect();
-            let select_query = format!(
-                "SELECT email_id, author_id, email FROM author_emails WHERE email IN ({})",
-                placeholders.join(",")
-            );
-
-            let mut select = sqlx::query(&select_query);
-            for email in &all_emails {
-                select = select.bind(*email);
-            }
-
-            let rows = select.fetch_all(pool).await?;
-            for row in rows {
-                let email_id: i64 = row.get(0);
-                let author_id: i64 = row.get(1);
-                let email: String = row.get::<String, _>(2).to_lowercase();
-                email_to_author_id.insert(email.clone(), author_id);
-                email_to_email_id.insert(email, email_id);
-            }
+        let rows = sqlx::query(
+            "SELECT DISTINCT ae.email_id, ae.author_id, ae.email::TEXT
+             FROM author_emails ae
+             JOIN author_emails_staging s ON s.email = ae.email"
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        for row in rows {
+            let email_id: i64 = row.get(0);
+            let author_id: i64 = row.get(1);
+            let email: String = row.get::<String, _>(2).to_lowercase();
+            email_to_author_id.insert(email.clone(), author_id);
+            email_to_email_id.insert(email, email_id);
         }
 
         Ok((email_to_author_id, email_to_email_id))
@@ -219,7 +231,8 @@ impl PatchOps {
     fn prepare_patches_with_email_ids(
         emails: &[(String, EmailInfo)],
         email_to_author_id: &HashMap<String, i64>,
-        email_to_email_id: &HashMap<String, i64>
+        email_to_email_id: &HashMap<String, i64>,
+        list_id: Option<i32>
     ) -> Result<Vec<PatchData>, Box<dyn std::error::Error>> {
         let mut patches_data = Vec::new();
 
@@ -243,13 +256,22 @@ impl PatchOps {
             };
 
             // Parse date with multiple format fallbacks
-            let parsed_date = Self::parse_email_date(&email_info.date)?;
+            let parsed_date = crate::mail_parser::parse_email_date(&email_info.date)?;
 
             // Detect if it's a patch series
             let (is_series, series_number, series_total) = Self::detect_patch_series(&email_info.subject);
+            let is_cover_letter = !email_info.is_reply && Self::is_cover_letter(series_number);
+            let subject_tags = crate::mail_parser::parse_subject_tags(&email_info.subject);
             
             // Detect and parse merge notification
             let (is_merge, merge_info) = crate::mail_parser::detect_and_parse_merge(email_info);
+            let pull_request = crate::mail_parser::parse_pull_request(email_info);
+            let revert = crate::mail_parser::parse_revert(email_info);
+            let syzbot_report = crate::mail_parser::parse_syzbot_report(email_info);
+            let stable_backport = crate::mail_parser::parse_stable_backport(email_info);
+            let sender_type = crate::mail_parser::classify_sender(email_info);
+            let is_ping = !sender_type.is_bot() && crate::mail_parser::is_ping(email_info);
+            let is_rejection = !sender_type.is_bot() && crate::mail_parser::is_rejection(email_info);
 
             patches_data.push(PatchData {
                 author_id,
@@ -262,12 +284,33 @@ impl PatchOps {
                 is_series,
                 series_number,
                 series_total,
+                is_cover_letter,
+                subject_tags,
                 in_reply_to: email_info.in_reply_to.clone(),
                 references: email_info.references.clone(),
                 is_reply: email_info.is_reply,
                 // Merge notification fields
                 is_merge_notification: is_merge,
                 merge_info,
+                pull_request,
+                revert,
+                syzbot_report,
+                stable_backport,
+                sender_type,
+                is_ping,
+                is_rejection,
+                list_id,
+                diff: email_info.diff.clone(),
+                git_patch_id: email_info.git_patch_id.clone(),
+                trailers: email_info.trailers.clone(),
+                fixes: email_info.fixes.clone(),
+                commit_refs: email_info.commit_refs.clone(),
+                links: email_info.links.clone(),
+                envelope_from: email_info.envelope_from.clone(),
+                recipients: email_info.recipients.clone(),
+                list_ids: email_info.list_ids.clone(),
+                patchwork: email_info.patchwork.clone(),
+                signature: email_info.signature.clone(),
             });
         }
 
@@ -279,7 +322,10 @@ impl PatchOps {
         emails: &[(String, EmailInfo)],
         email_to_author_id: &HashMap<String, i64>,
         email_to_email_id: &HashMap<String, i64>,
-        pool: &Pool<Postgres>
+        list_id: Option<i32>,
+        epochs: &HashMap<String, u32>,
+        compress_bodies: bool,
+        conn: &mut PgConnection
     ) -> Result<u32, Box<dyn std::error::Error>> {
         // First, augment the maps with any missing emails from the database
         let mut complete_email_to_author_id = email_to_author_id.clone();
@@ -310,7 +356,7 @@ impl PatchOps {
                 query = query.bind(*email);
             }
             
-            let rows = query.fetch_all(pool).await?;
+            let rows = query.fetch_all(&mut *conn).await?;
             eprintln!("Found {} existing emails in database", rows.len());
             
             for row in rows {
@@ -340,98 +386,508 @@ impl PatchOps {
             }
         }
         
-        let patches_data = Self::prepare_patches_with_email_ids(emails, &complete_email_to_author_id, &complete_email_to_email_id)?;
+        let patches_data = Self::prepare_patches_with_email_ids(emails, &complete_email_to_author_id, &complete_email_to_email_id, list_id)?;
 
         if patches_data.is_empty() {
             return Ok(0);
         }
 
-        // PostgreSQL has a parameter limit of ~65535
-        // With 18 params per patch (including merge fields), we can do ~3640 patches per query
-        // Use 3500 to be safe
-        const MAX_PATCHES_PER_QUERY: usize = 3500;
+        // Patches are streamed with binary COPY, so there's no bind parameter limit to chunk around
+        let inserted_patches = Self::execute_patch_batch_insert(&patches_data, compress_bodies, &mut *conn).await?;
+        let duplicates = patches_data.len() as u32 - inserted_patches;
+        if duplicates > 0 {
+            println!("{} messages were already stored, recorded as additional sources", duplicates);
+        }
+        Self::record_message_sources(&patches_data, list_id, epochs, &mut *conn).await?;
+
+        let patch_ids = Self::lookup_annotated_patch_ids(&patches_data, &mut *conn).await?;
+        Self::insert_trailers(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_fixes(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_commit_refs(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_links(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_recipients(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_patch_files(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_syzbot_reports(&patches_data, &patch_ids, &mut *conn).await?;
+        Self::insert_stable_backports(&patches_data, &patch_ids, &mut *conn).await?;
 
-        let mut inserted_patches = 0u32;
+        Ok(inserted_patches)
+    }
 
-        // Insert in large batches for maximum throughput
-        for patch_batch in patches_data.chunks(MAX_PATCHES_PER_QUERY) {
-            let batch_count = Self::execute_patch_batch_insert(patch_batch, pool).await?;
-            inserted_patches += batch_count;
+    /// Insert patches by binary COPY into a staging table, then move the new ones into patches
+    /// Returns the number of patches actually inserted (existing message IDs are skipped)
+    /// With `compress_bodies`, bodies go to body_compressed and body_text stays NULL
+    async fn execute_patch_batch_insert(patches_data: &[PatchData], compress_bodies: bool, conn: &mut PgConnection) -> Result<u32, Box<dyn std::error::Error>> {
+        const PATCH_COLUMNS: &str = "author_id, email_id, message_id, subject, sent_at, commit_hash, envelope_from, sender_type, is_ping, is_rejection, body_text, signature, is_series, series_number, series_total, is_cover_letter, patch_version, is_rfc, is_resend, target_tree, in_reply_to, thread_references, is_reply, is_merge_notification, merge_repository, merge_branch, merge_applied_by, merge_commit_links, is_pull_request, pull_base_commit, pull_repository, pull_ref, pull_head_commit, is_revert, reverted_commit, reverted_subject, patchwork_id, patchwork_delegate, patchwork_state, list_id, list_ids, files_changed, lines_added, lines_removed, diff_summary, git_patch_id, body_compressed";
+        const PATCH_COLUMN_COUNT: i16 = 47;
+
+        // Column types only, so staging rows don't draw patch IDs from the sequence
+        sqlx::query(&format!(
+            "CREATE TEMP TABLE IF NOT EXISTS patches_staging ON COMMIT DROP AS SELECT {} FROM patches WITH NO DATA",
+            PATCH_COLUMNS
+        ))
+        .execute(&mut *conn)
+        .await?;
+        sqlx::query("TRUNCATE patches_staging").execute(&mut *conn).await?;
+
+        let mut rows = BinaryCopyBuffer::new();
+        for patch_data in patches_data {
+            let merge_info = patch_data.merge_info.as_ref();
+            let pull_request = patch_data.pull_request.as_ref();
+            let revert = patch_data.revert.as_ref();
+            
+            // Diff summary columns (NULL when the email carries no diff)
+            let diff = patch_data.diff.as_ref();
+            let diff_summary = diff.map(serde_json::to_string).transpose()?;
+            
+            let (body_text, body_compressed) = match &patch_data.body_text {
+                Some(body) if compress_bodies => (None, Some(compress_body(body)?)),
+                body => (body.as_deref(), None),
+            };
+            
+            rows.row(PATCH_COLUMN_COUNT)
+                .int8(Some(patch_data.author_id))
+                .int8(Some(patch_data.email_id))
+                .text(Some(&patch_data.message_id))
+                .text(Some(&patch_data.subject))
+                .timestamptz(Some(&patch_data.sent_at))
+                .text(Some(&patch_data.commit_hash))
+                .text(patch_data.envelope_from.as_deref())
+                .text(Some(patch_data.sender_type.as_str()))
+                .bool(Some(patch_data.is_ping))
+                .bool(Some(patch_data.is_rejection))
+                .text(body_text)
+                .text(patch_data.signature.as_deref())
+                .bool(Some(patch_data.is_series))
+                .int4(patch_data.series_number)
+                .int4(patch_data.series_total)
+                .bool(Some(patch_data.is_cover_letter))
+                .int4(patch_data.subject_tags.version)
+                .bool(Some(patch_data.subject_tags.is_rfc))
+                .bool(Some(patch_data.subject_tags.is_resend))
+                .text(patch_data.subject_tags.target_tree.as_deref())
+                .text(patch_data.in_reply_to.as_deref())
+                .text_array(Some(&patch_data.references))
+                .bool(Some(patch_data.is_reply))
+                .bool(Some(patch_data.is_merge_notification))
+                .text(merge_info.map(|m| m.repository.as_str()))
+                .text(merge_info.map(|m| m.branch.as_str()))
+                .text(merge_info.map(|m| m.applied_by.as_str()))
+                .text_array(merge_info.map(|m| m.commit_links.as_slice()))
+                .bool(Some(pull_request.is_some()))
+                .text(pull_request.map(|p| p.base_commit.as_str()))
+                .text(pull_request.map(|p| p.repository.as_str()))
+                .text(pull_request.and_then(|p| p.git_ref.as_deref()))
+                .text(pull_request.and_then(|p| p.head_commit.as_deref()))
+                .bool(Some(revert.is_some()))
+                .text(revert.and_then(|r| r.commit.as_deref()))
+                .text(revert.and_then(|r| r.subject.as_deref()))
+                .int8(patch_data.patchwork.id)
+                .text(patch_data.patchwork.delegate.as_deref())
+                .text(patch_data.patchwork.state.as_deref())
+                .int4(patch_data.list_id)
+                .text_array(Some(&patch_data.list_ids))
+                .int4(diff.map(|d| d.files.len() as i32))
+                .int4(diff.map(|d| d.total_added as i32))
+                .int4(diff.map(|d| d.total_removed as i32))
+                .jsonb(diff_summary.as_deref())
+                .text(patch_data.git_patch_id.as_deref())
+                .bytea(body_compressed.as_deref());
         }
 
-        Ok(inserted_patches)
+        copy_binary(&mut *conn, &format!("patches_staging ({})", PATCH_COLUMNS), rows).await?;
+
+        let result = sqlx::query(&format!(
+            "INSERT INTO patches ({0}) SELECT {0} FROM patches_staging ON CONFLICT (message_id) DO NOTHING",
+            PATCH_COLUMNS
+        ))
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.rows_affected() as u32)
+    }
+
+    /// Record the commit each message was read from, whether it was inserted or already stored
+    async fn record_message_sources(
+        patches_data: &[PatchData],
+        list_id: Option<i32>,
+        epochs: &HashMap<String, u32>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let message_ids: Vec<&str> = patches_data.iter().map(|p| p.message_id.as_str()).collect();
+        let commit_hashes: Vec<&str> = patches_data.iter().map(|p| p.commit_hash.as_str()).collect();
+        let epoch_numbers: Vec<Option<i32>> = patches_data.iter()
+            .map(|p| epochs.get(&p.commit_hash).map(|&epoch| epoch as i32))
+            .collect();
+
+        sqlx::query(
+            "INSERT INTO message_sources (patch_id, commit_hash, list_id, epoch)
+             SELECT p.patch_id, s.commit_hash, $3, s.epoch
+             FROM UNNEST($1::TEXT[], $2::TEXT[], $4::INT[]) AS s(message_id, commit_hash, epoch)
+             JOIN patches p ON p.message_id = s.message_id
+             ON CONFLICT (commit_hash, COALESCE(list_id, 0)) DO NOTHING"
+        )
+        .bind(&message_ids)
+        .bind(&commit_hashes)
+        .bind(list_id)
+        .bind(&epoch_numbers)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
     }
 
-    /// Execute batch insert for a chunk of patches
-    async fn execute_patch_batch_insert(patch_batch: &[PatchData], pool: &Pool<Postgres>) -> Result<u32, Box<dyn std::error::Error>> {
-        let mut query = String::from("INSERT INTO patches (author_id, email_id, message_id, subject, sent_at, commit_hash, body_text, is_series, series_number, series_total, in_reply_to, thread_references, is_reply, is_merge_notification, merge_repository, merge_branch, merge_applied_by, merge_commit_links) VALUES ");
-        let mut param_count = 1;
+    /// Look up patch IDs (by message_id) of patches that carry trailers, Fixes: tags, commit references, links, recipients, syzbot reports or stable notifications
+    async fn lookup_annotated_patch_ids(patches_data: &[PatchData], conn: &mut PgConnection) -> Result<HashMap<String, i64>, Box<dyn std::error::Error>> {
+        let message_ids: Vec<&str> = patches_data.iter()
+            .filter(|p| !p.trailers.is_empty() || !p.fixes.is_empty() || !p.commit_refs.is_empty() || !p.links.is_empty() || !p.recipients.is_empty() || p.syzbot_report.is_some() || p.stable_backport.is_some() || p.diff.is_some())
+            .map(|p| p.message_id.as_str())
+            .collect();
+        if message_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
 
-        for (i, _) in patch_batch.iter().enumerate() {
-            if i > 0 {
-                query.push(',');
+        let rows = sqlx::query("SELECT message_id, patch_id FROM patches WHERE message_id = ANY($1)")
+            .bind(&message_ids)
+            .fetch_all(&mut *conn)
+            .await?;
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    /// Store the trailers of freshly inserted patches in patch_trailers
+    async fn insert_trailers(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut patch_ids = Vec::new();
+        let mut positions = Vec::new();
+        let mut tags = Vec::new();
+        let mut names = Vec::new();
+        let mut emails = Vec::new();
+        for patch_data in patches_data {
+            let Some(&patch_id) = patch_id_by_message.get(&patch_data.message_id) else {
+                continue;
+            };
+            for (position, trailer) in patch_data.trailers.iter().enumerate() {
+                patch_ids.push(patch_id);
+                positions.push(position as i32);
+                tags.push(trailer.tag.as_str());
+                names.push(trailer.name.as_str());
+                emails.push(trailer.email.as_str());
             }
-            query.push_str(&format!("(${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${}, ${})",
-                                   param_count, param_count + 1, param_count + 2, param_count + 3,
-                                   param_count + 4, param_count + 5, param_count + 6, param_count + 7,
-                                   param_count + 8, param_count + 9, param_count + 10, param_count + 11,
-                                   param_count + 12, param_count + 13, param_count + 14, param_count + 15,
-                                   param_count + 16, param_count + 17));
-            param_count += 18;
-        }
-
-        query.push_str(" ON CONFLICT (message_id) DO NOTHING");
-
-        let mut insert_query = sqlx::query(&query);
-
-        for patch_data in patch_batch {
-            // Extract merge fields if present
-            let (merge_repo, merge_branch, merge_applied_by, merge_commit_links) = 
-                if let Some(ref merge_info) = patch_data.merge_info {
-                    (
-                        Some(merge_info.repository.clone()),
-                        Some(merge_info.branch.clone()),
-                        Some(merge_info.applied_by.clone()),
-                        Some(merge_info.commit_links.clone())
-                    )
-                } else {
-                    (None, None, None, None)
-                };
-            
-            insert_query = insert_query
-                .bind(patch_data.author_id)
-                .bind(patch_data.email_id)
-                .bind(&patch_data.message_id)
-                .bind(&patch_data.subject)
-                .bind(&patch_data.sent_at)
-                .bind(&patch_data.commit_hash)
-                .bind(&patch_data.body_text)
-                .bind(&patch_data.is_series)
-                .bind(&patch_data.series_number)
-                .bind(&patch_data.series_total)
-                .bind(&patch_data.in_reply_to)
-                .bind(&patch_data.references)
-                .bind(&patch_data.is_reply)
-                .bind(&patch_data.is_merge_notification)
-                .bind(merge_repo)
-                .bind(merge_branch)
-                .bind(merge_applied_by)
-                .bind(merge_commit_links);
-        }
-
-        insert_query.execute(pool).await?;
-        Ok(patch_batch.len() as u32)
+        }
+
+        if patch_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO patch_trailers (patch_id, position, tag, name, email)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::INT[], $3::TEXT[], $4::TEXT[], $5::TEXT[])
+             ON CONFLICT (patch_id, position) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&positions)
+        .bind(&tags)
+        .bind(&names)
+        .bind(&emails)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the Fixes: tags of freshly inserted patches in patch_fixes
+    async fn insert_fixes(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut patch_ids = Vec::new();
+        let mut commits = Vec::new();
+        let mut subjects = Vec::new();
+        for patch_data in patches_data {
+            let Some(&patch_id) = patch_id_by_message.get(&patch_data.message_id) else {
+                continue;
+            };
+            for fixes in &patch_data.fixes {
+                patch_ids.push(patch_id);
+                commits.push(fixes.commit.as_str());
+                subjects.push(fixes.subject.as_deref());
+            }
+        }
+
+        if patch_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO patch_fixes (patch_id, fixes_commit, fixes_subject)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[])
+             ON CONFLICT (patch_id, fixes_commit) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&commits)
+        .bind(&subjects)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
     }
 
-    /// Parse email date with multiple format support
-    fn parse_email_date(date_str: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
-        DateTime::parse_from_rfc2822(date_str)
-            .or_else(|_| DateTime::parse_from_rfc3339(date_str))
-            .map(|dt| dt.with_timezone(&Utc))
-            .or_else(|_| {
-                NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S")
-                    .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc))
-            })
+    /// Store the commit hashes referenced by freshly inserted patches in patch_commit_refs
+    async fn insert_commit_refs(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut patch_ids = Vec::new();
+        let mut commits = Vec::new();
+        let mut kinds = Vec::new();
+        for patch_data in patches_data {
+            let Some(&patch_id) = patch_id_by_message.get(&patch_data.message_id) else {
+                continue;
+            };
+            for commit_ref in &patch_data.commit_refs {
+                patch_ids.push(patch_id);
+                commits.push(commit_ref.commit.as_str());
+                kinds.push(commit_ref.kind.as_str());
+            }
+        }
+
+        if patch_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO patch_commit_refs (patch_id, commit_hash, kind)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[])
+             ON CONFLICT (patch_id, commit_hash) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&commits)
+        .bind(&kinds)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the Link: trailers and lore URLs of freshly inserted patches in patch_links
+    async fn insert_links(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut patch_ids = Vec::new();
+        let mut positions = Vec::new();
+        let mut urls = Vec::new();
+        let mut message_ids = Vec::new();
+        let mut is_trailers = Vec::new();
+        for patch_data in patches_data {
+            let Some(&patch_id) = patch_id_by_message.get(&patch_data.message_id) else {
+                continue;
+            };
+            for (position, link) in patch_data.links.iter().enumerate() {
+                patch_ids.push(patch_id);
+                positions.push(position as i32);
+                urls.push(link.url.as_str());
+                message_ids.push(link.message_id.as_deref());
+                is_trailers.push(link.is_trailer);
+            }
+        }
+
+        if patch_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO patch_links (patch_id, position, url, message_id, is_trailer)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::INT[], $3::TEXT[], $4::TEXT[], $5::BOOLEAN[])
+             ON CONFLICT (patch_id, position) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&positions)
+        .bind(&urls)
+        .bind(&message_ids)
+        .bind(&is_trailers)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the To/Cc recipients of freshly inserted patches in patch_recipients
+    async fn insert_recipients(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut patch_ids = Vec::new();
+        let mut kinds = Vec::new();
+        let mut names = Vec::new();
+        let mut emails = Vec::new();
+        for patch_data in patches_data {
+            let Some(&patch_id) = patch_id_by_message.get(&patch_data.message_id) else {
+                continue;
+            };
+            for recipient in &patch_data.recipients {
+                patch_ids.push(patch_id);
+                kinds.push(recipient.kind.as_str());
+                names.push(recipient.name.as_str());
+                emails.push(recipient.email.as_str());
+            }
+        }
+
+        if patch_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO patch_recipients (patch_id, kind, name, email)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[], $4::TEXT[])
+             ON CONFLICT (patch_id, kind, email) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&kinds)
+        .bind(&names)
+        .bind(&emails)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the files touched by the diffs of freshly inserted patches in patch_files
+    async fn insert_patch_files(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut patch_ids = Vec::new();
+        let mut positions = Vec::new();
+        let mut paths = Vec::new();
+        let mut old_paths = Vec::new();
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut change_types = Vec::new();
+        for patch_data in patches_data {
+            let (Some(&patch_id), Some(diff)) = (patch_id_by_message.get(&patch_data.message_id), &patch_data.diff) else {
+                continue;
+            };
+            for (position, file) in diff.files.iter().enumerate() {
+                if file.path().is_empty() {
+                    continue;
+                }
+                patch_ids.push(patch_id);
+                positions.push(position as i32);
+                paths.push(file.path());
+                old_paths.push(file.old_path.as_deref().filter(|_| file.is_rename));
+                added.push(file.added as i32);
+                removed.push(file.removed as i32);
+                change_types.push(file.change_type());
+            }
+        }
+
+        if patch_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO patch_files (patch_id, position, path, old_path, lines_added, lines_removed, change_type)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::INT[], $3::TEXT[], $4::TEXT[], $5::INT[], $6::INT[], $7::TEXT[])
+             ON CONFLICT (patch_id, position) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&positions)
+        .bind(&paths)
+        .bind(&old_paths)
+        .bind(&added)
+        .bind(&removed)
+        .bind(&change_types)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the syzbot reports among freshly inserted patches in syzbot_reports
+    async fn insert_syzbot_reports(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let reports: Vec<(i64, &crate::mail_parser::SyzbotReport)> = patches_data.iter()
+            .filter_map(|p| Some((*patch_id_by_message.get(&p.message_id)?, p.syzbot_report.as_ref()?)))
+            .collect();
+        if reports.is_empty() {
+            return Ok(());
+        }
+
+        let patch_ids: Vec<i64> = reports.iter().map(|(id, _)| *id).collect();
+        let column = |field: fn(&crate::mail_parser::SyzbotReport) -> Option<&str>| -> Vec<Option<&str>> {
+            reports.iter().map(|(_, report)| field(report)).collect()
+        };
+
+        sqlx::query(
+            "INSERT INTO syzbot_reports (patch_id, title, extid, dashboard_url, head_commit, git_tree,
+                                         console_log_url, kernel_config_url, syz_repro_url, c_repro_url)
+             SELECT * FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::TEXT[], $6::TEXT[],
+                                  $7::TEXT[], $8::TEXT[], $9::TEXT[], $10::TEXT[])
+             ON CONFLICT (patch_id) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(column(|r| Some(r.title.as_str())))
+        .bind(column(|r| r.extid.as_deref()))
+        .bind(column(|r| r.dashboard_url.as_deref()))
+        .bind(column(|r| r.head_commit.as_deref()))
+        .bind(column(|r| r.git_tree.as_deref()))
+        .bind(column(|r| r.console_log_url.as_deref()))
+        .bind(column(|r| r.kernel_config_url.as_deref()))
+        .bind(column(|r| r.syz_repro_url.as_deref()))
+        .bind(column(|r| r.c_repro_url.as_deref()))
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Store the stable tree notifications among freshly inserted patches in stable_backports
+    async fn insert_stable_backports(
+        patches_data: &[PatchData],
+        patch_id_by_message: &HashMap<String, i64>,
+        conn: &mut PgConnection
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let backports: Vec<(i64, &crate::mail_parser::StableBackport)> = patches_data.iter()
+            .filter_map(|p| Some((*patch_id_by_message.get(&p.message_id)?, p.stable_backport.as_ref()?)))
+            .collect();
+        if backports.is_empty() {
+            return Ok(());
+        }
+
+        // Arrays can't be UNNESTed as a column, so versions travel as comma-joined text
+        let patch_ids: Vec<i64> = backports.iter().map(|(id, _)| *id).collect();
+        let kinds: Vec<&str> = backports.iter().map(|(_, b)| b.kind.as_str()).collect();
+        let versions: Vec<String> = backports.iter().map(|(_, b)| b.stable_versions.join(",")).collect();
+        let subjects: Vec<Option<&str>> = backports.iter().map(|(_, b)| b.original_subject.as_deref()).collect();
+        let commits: Vec<Option<&str>> = backports.iter().map(|(_, b)| b.upstream_commit.as_deref()).collect();
+
+        sqlx::query(
+            "INSERT INTO stable_backports (patch_id, kind, stable_versions, original_subject, upstream_commit)
+             SELECT patch_id, kind, string_to_array(versions, ','), original_subject, upstream_commit
+             FROM UNNEST($1::BIGINT[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::TEXT[])
+                  AS t(patch_id, kind, versions, original_subject, upstream_commit)
+             ON CONFLICT (patch_id) DO NOTHING"
+        )
+        .bind(&patch_ids)
+        .bind(&kinds)
+        .bind(&versions)
+        .bind(&subjects)
+        .bind(&commits)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
     }
 
     /// Detect if email subject indicates a patch series
@@ -446,24 +902,89 @@ impl PatchOps {
         }
     }
 
+    /// A cover letter is patch 0 of a series ("[PATCH 0/N]" or "[PATCH 00/N]")
+    fn is_cover_letter(series_number: Option<i32>) -> bool {
+        series_number == Some(0)
+    }
+
+    /// Mark messages removed by public-inbox deletion commits
+    /// The rows stay (threads keep their shape) but searches and thread summaries skip them
+    pub async fn mark_messages_deleted(
+        message_ids: &[String],
+        pool: &Pool<Postgres>
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        if message_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let result = sqlx::query("UPDATE patches SET is_deleted = TRUE WHERE message_id = ANY($1)")
+            .bind(message_ids)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Insert batch to database (main entry point)
+    ///
+    /// Author upserts, patch inserts and annotation rows are written in a single
+    /// transaction, so a failure anywhere rolls back the whole batch and every
+    /// commit in it is reported as not persisted.
+    /// `epochs` gives the public-inbox epoch of each commit, recorded with its source.
     pub async fn insert_batch_to_db(
         emails: &[(String, EmailInfo)], 
+        list_id: Option<i32>,
+        epochs: &HashMap<String, u32>,
+        compress_bodies: bool,
         pool: &Pool<Postgres>
-    ) -> Result<(u32, u32), Box<dyn std::error::Error>> {
+    ) -> Result<(u32, u32), BatchInsertError> {
         if emails.is_empty() {
             return Ok((0, 0));
         }
 
+        let unpersisted = |e: Box<dyn std::error::Error>| BatchInsertError {
+            commit_hashes: emails.iter().map(|(hash, _)| hash.clone()).collect(),
+            message: e.to_string(),
+        };
+
+        let mut tx = pool.begin().await.map_err(|e| unpersisted(e.into()))?;
+
+        // Convert the error before awaiting again, the boxed error isn't Send
+        let result = Self::insert_batch_in_transaction(emails, list_id, epochs, compress_bodies, &mut tx)
+            .await
+            .map_err(unpersisted);
+
+        match result {
+            Ok(counts) => {
+                tx.commit().await.map_err(|e| unpersisted(e.into()))?;
+                Ok(counts)
+            }
+            Err(error) => {
+                if let Err(rollback_err) = tx.rollback().await {
+                    eprintln!("Failed to roll back batch insert: {}", rollback_err);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    /// Write one batch on an open transaction; the caller commits or rolls back
+    async fn insert_batch_in_transaction(
+        emails: &[(String, EmailInfo)],
+        list_id: Option<i32>,
+        epochs: &HashMap<String, u32>,
+        compress_bodies: bool,
+        conn: &mut PgConnection
+    ) -> Result<(u32, u32), Box<dyn std::error::Error>> {
         // Collect unique author identities (name -> emails mapping)
         let author_identities = Self::collect_unique_author_identities(emails);
         let author_count = author_identities.len() as u32;
 
         // Upsert authors and their emails, get ID mappings
-        let (email_to_author_id, email_to_email_id) = Self::upsert_authors_and_emails(&author_identities, pool).await?;
+        let (email_to_author_id, email_to_email_id) = Self::upsert_authors_and_emails(&author_identities, &mut *conn).await?;
 
         // Insert patches using the ID mappings
-        let inserted_patches = Self::insert_patches_with_email_ids(emails, &email_to_author_id, &email_to_email_id, pool).await?;
+        let inserted_patches = Self::insert_patches_with_email_ids(emails, &email_to_author_id, &email_to_email_id, list_id, epochs, compress_bodies, &mut *conn).await?;
 
         Ok((author_count, inserted_patches))
     }
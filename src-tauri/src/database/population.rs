@@ -1,5 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::time::Duration;
 use sqlx::Pool;
 use tokio::sync::mpsc;
@@ -8,14 +9,31 @@ use futures::future;
 use crate::database::{DatabaseManager, DatabasePopulationResult};
 use crate::database::config::*;
 use crate::database::patches::PatchOps;
-use crate::git_parser::get_all_commits_with_limit;
-use crate::mail_parser::parse_emails_parallel;
+use crate::git_config::MailingListRepo;
+use crate::git_parser::{default_mailing_list, epoch_heads_at, get_commits_in_date_range, stream_epoch_commits, EpochCommit};
+use crate::database::quarantine::record_parse_failures;
+use crate::mail_parser::{extract_message_id, parse_emails_parallel, ParseFailure};
 
 impl DatabaseManager {
+    /// Share a cancellation flag (e.g. one held in app state) with population runs
+    /// Setting it makes workers stop after their current batch
+    pub fn set_population_cancel_flag(&mut self, flag: Arc<AtomicBool>) {
+        self.population_cancel = flag;
+    }
+
+    /// Clear a stale cancellation request before a new population run starts
+    fn begin_population(&self) {
+        self.population_cancel.store(false, Ordering::Relaxed);
+    }
+
+    fn population_cancelled(&self) -> bool {
+        self.population_cancel.load(Ordering::Relaxed)
+    }
+
     /// Populate database with author/patch data using optimized parallel batch processing
     ///
     /// This method performs a complete data population cycle:
-    /// 1. Retrieves commits from the mail parser
+    /// 1. Streams commits from the git repository in chunks
     /// 2. Filters out already processed commits for efficiency
     /// 3. Processes emails in parallel batches with fallback to individual parsing
     /// 4. Inserts authors and patches in optimized batches
@@ -32,14 +50,224 @@ impl DatabaseManager {
     where
         F: Fn(u32, u32, String) + Send + Sync + 'static,
     {
+        let list = default_mailing_list()?;
+        self.populate_list(&list, limit, progress_callback).await
+    }
+
+    /// Populate database from a specific mailing list archive
+    /// Patches are tagged with the list's `list_id` so several archives can share one database.
+    /// Commits are streamed from the repository, so parsing starts while traversal continues.
+    pub async fn populate_list<F>(&mut self, list: &MailingListRepo, limit: Option<usize>, progress_callback: Option<F>) -> Result<DatabasePopulationResult, Box<dyn std::error::Error>>
+    where
+        F: Fn(u32, u32, String) + Send + Sync + 'static,
+    {
+        self.begin_population();
+        self.ensure_connected().await?;
+        self.setup_database().await?;
+
+        let list_id = self.ensure_mailing_list(list).await?;
+
+        // Total is only known once traversal finishes, so it grows as chunks arrive
+        let total_commits = Arc::new(AtomicU32::new(0));
+        let (chunk_tx, chunk_rx) = mpsc::channel::<Vec<EpochCommit>>(CHANNEL_BUFFER_SIZE);
+
+        let stream = stream_epoch_commits(&list.repo_path, limit, PARSE_BATCH_SIZE);
+        let traversal_total = total_commits.clone();
+        let traversal_cancel = self.population_cancel.clone();
+        let traversal_handle = tokio::task::spawn_blocking(move || {
+            for chunk in stream {
+                if traversal_cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let chunk = chunk?;
+                traversal_total.fetch_add(chunk.len() as u32, Ordering::Relaxed);
+                if chunk_tx.blocking_send(chunk).is_err() {
+                    break;
+                }
+            }
+            Ok::<_, crate::git_parser::ParseError>(())
+        });
+
+        println!("Starting streaming database population of list '{}'", list.name);
+
+        let mut result = self.run_population(list, list_id, chunk_rx, total_commits, progress_callback).await;
+
+        match traversal_handle.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                result.errors.push(format!("Commit traversal failed: {}", e));
+                result.success = false;
+            }
+            Err(e) => {
+                result.errors.push(format!("Commit traversal task failed: {}", e));
+                result.success = false;
+            }
+        }
+
+        // The walk started at each epoch's HEAD, so later fetches only need what comes after it
+        if result.success && !result.cancelled {
+            if let Err(e) = self.record_list_heads(list, list_id, None).await {
+                result.errors.push(format!("Failed to record populated heads: {}", e));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Populate database from an explicit list of commits (e.g. the result of `fetch_updates`)
+    /// Runs the same pipeline as `populate_database`, so already stored commits are skipped
+    pub async fn populate_commits<F>(&mut self, commits: Vec<EpochCommit>, progress_callback: Option<F>) -> Result<DatabasePopulationResult, Box<dyn std::error::Error>>
+    where
+        F: Fn(u32, u32, String) + Send + Sync + 'static,
+    {
+        let list = default_mailing_list()?;
+
+        self.begin_population();
         self.ensure_connected().await?;
         self.setup_database().await?;
 
-        let commits = get_all_commits_with_limit(limit)?;
-        let total_commits = commits.len() as u32;
+        let list_id = self.ensure_mailing_list(&list).await?;
+
+        let total_commits = Arc::new(AtomicU32::new(commits.len() as u32));
+        let chunks = chunk_by_epoch(&commits, PARSE_BATCH_SIZE);
+        let (chunk_tx, chunk_rx) = mpsc::channel::<Vec<EpochCommit>>(chunks.len().max(1));
+        for chunk in chunks {
+            // Capacity matches the number of chunks, so this never fails
+            let _ = chunk_tx.try_send(chunk);
+        }
+        drop(chunk_tx);
+
+        println!("Starting optimized database population of list '{}' with {} commits", list.name, commits.len());
+
+        let mut result = self.run_population(&list, list_id, chunk_rx, total_commits, progress_callback).await;
 
-        println!("Starting optimized database population with {} commits", total_commits);
+        // Only epoch HEADs that were part of the run count as populated; a date range
+        // of older commits leaves the recorded heads alone
+        if result.success && !result.cancelled {
+            if let Err(e) = self.record_list_heads(&list, list_id, Some(&commits)).await {
+                result.errors.push(format!("Failed to record populated heads: {}", e));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Record the current epoch HEADs of `list` as populated, or with `populated` only
+    /// those HEADs among the given commits
+    async fn record_list_heads(&self, list: &MailingListRepo, list_id: i32, populated: Option<&[EpochCommit]>) -> Result<(), Box<dyn std::error::Error>> {
+        let repo_path = list.repo_path.clone();
+        let mut heads = tokio::task::spawn_blocking(move || epoch_heads_at(&repo_path)).await??;
+        if let Some(populated) = populated {
+            let populated: HashSet<&EpochCommit> = populated.iter().collect();
+            heads.retain(|head| populated.contains(head));
+        }
+        self.record_populated_heads(list_id, &heads).await
+    }
+
+    /// Populate database with the default list's commits from a time window
+    pub async fn populate_date_range<F>(
+        &mut self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+        progress_callback: Option<F>
+    ) -> Result<DatabasePopulationResult, Box<dyn std::error::Error>>
+    where
+        F: Fn(u32, u32, String) + Send + Sync + 'static,
+    {
+        let commits = tokio::task::spawn_blocking(move || get_commits_in_date_range(start, end)).await??;
+        println!("Found {} commits between {} and {}", commits.len(), start, end);
+        self.populate_commits(commits, progress_callback).await
+    }
 
+    /// Insert already parsed emails (e.g. from an mbox import) under the given mailing list
+    /// Emails whose key is already stored are skipped; `progress` receives (inserted, total)
+    pub async fn insert_parsed_emails<P>(
+        &mut self,
+        list: &MailingListRepo,
+        emails: Vec<(String, crate::mail_parser::EmailInfo)>,
+        progress: P
+    ) -> Result<DatabasePopulationResult, Box<dyn std::error::Error>>
+    where
+        P: Fn(u32, u32),
+    {
+        self.begin_population();
+        self.ensure_connected().await?;
+        self.setup_database().await?;
+
+        let list_id = self.ensure_mailing_list(list).await?;
+        let pool = self.get_pool()?.clone();
+        let mut errors = Vec::new();
+
+        let keys: Vec<String> = emails.iter().map(|(key, _)| key.clone()).collect();
+        let existing = PatchOps::get_existing_commit_hashes(&keys, Some(list_id), &pool).await?;
+        let new_emails: Vec<(String, crate::mail_parser::EmailInfo)> = emails.into_iter()
+            .filter(|(key, _)| !existing.contains(key))
+            .collect();
+
+        let total = new_emails.len() as u32;
+        println!("Inserting {} parsed emails into list '{}' ({} already present)", total, list.name, existing.len());
+
+        let mut inserted_authors = 0u32;
+        let mut inserted_patches = 0u32;
+        let mut done = 0u32;
+
+        for batch in new_emails.chunks(DB_INSERT_BATCH_SIZE) {
+            if self.population_cancelled() {
+                println!("Import cancelled after {} of {} emails", done, total);
+                break;
+            }
+            match PatchOps::insert_batch_to_db(batch, Some(list_id), &HashMap::new(), self.config.compress_bodies, &pool).await {
+                Ok((authors_count, patches_count)) => {
+                    inserted_authors += authors_count;
+                    inserted_patches += patches_count;
+                }
+                Err(e) => {
+                    for commit_hash in &e.commit_hashes {
+                        errors.push(format!("Error inserting commit {}: {}", commit_hash, e));
+                    }
+                }
+            }
+            done += batch.len() as u32;
+            progress(done, total);
+        }
+
+        if inserted_patches > 0 {
+            if let Err(e) = self.refresh_author_patch_counts().await {
+                errors.push(format!("Failed to refresh author patch counts: {}", e));
+            }
+            if let Err(e) = self.assign_series().await {
+                errors.push(format!("Failed to group patch series: {}", e));
+            }
+            if let Err(e) = self.link_superseded_patches().await {
+                errors.push(format!("Failed to link superseded patches: {}", e));
+            }
+            if let Err(e) = self.refresh_stats().await {
+                errors.push(format!("Failed to refresh statistics: {}", e));
+            }
+        }
+
+        Ok(DatabasePopulationResult {
+            success: errors.is_empty(),
+            total_processed: total,
+            total_authors_inserted: inserted_authors,
+            total_emails_inserted: inserted_patches,
+            errors,
+            cancelled: self.population_cancelled(),
+        })
+    }
+
+    /// Run the population pipeline over incoming commit chunks, with optional progress reporting
+    async fn run_population<F>(
+        &mut self,
+        list: &MailingListRepo,
+        list_id: i32,
+        chunks: mpsc::Receiver<Vec<EpochCommit>>,
+        total_commits: Arc<AtomicU32>,
+        progress_callback: Option<F>
+    ) -> DatabasePopulationResult
+    where
+        F: Fn(u32, u32, String) + Send + Sync + 'static,
+    {
         // Get initial patch count
         let initial_patch_count = self.get_patch_count().await.unwrap_or(0);
 
@@ -47,7 +275,7 @@ impl DatabaseManager {
         let pool = self.pool.clone();
         let progress_reporter_handle = if let Some(callback) = progress_callback {
             Some(self.start_progress_reporter(
-                total_commits,
+                total_commits.clone(),
                 initial_patch_count,
                 pool.clone().unwrap(),
                 callback
@@ -56,134 +284,76 @@ impl DatabaseManager {
             None
         };
 
-        let result = self.process_commit_batches(&commits, total_commits).await;
+        let mut result = self.process_commit_batches(chunks, &list.repo_path, list_id).await;
 
         // Stop progress reporter
         if let Some(reporter) = progress_reporter_handle {
             reporter.abort();
         }
 
-        println!("Database population completed: {} processed, {} authors, {} patches",
-                 result.total_processed, result.total_authors_inserted, result.total_emails_inserted);
+        // Apply the body retention policy to this list, if configured
+        if self.config.body_retention_years.is_some() && !result.cancelled {
+            if let Err(e) = self.prune_bodies(Some(list_id), None).await {
+                result.errors.push(format!("Failed to prune old bodies: {}", e));
+            }
+        }
 
-        Ok(result)
+        // Keep the dashboard statistics in step with the new messages
+        if let Err(e) = self.refresh_stats().await {
+            result.errors.push(format!("Failed to refresh statistics: {}", e));
+        }
+
+        println!("Database population of list '{}' completed: {} of {} commits processed, {} authors, {} patches",
+                 list.name, result.total_processed, total_commits.load(Ordering::Relaxed),
+                 result.total_authors_inserted, result.total_emails_inserted);
+
+        result
     }
 
-    /// Process commits with parallel parsing and sequential optimized DB insertion
-    /// Architecture: Multiple parser tasks -> Channel -> Single DB inserter task
+    /// Process commit chunks with parallel parsing and sequential optimized DB insertion
+    /// Architecture: Commit chunks -> Multiple parser tasks -> Channel -> Single DB inserter task
+    /// Parser tasks are spawned as chunks arrive, so parsing overlaps commit traversal
     async fn process_commit_batches(
         &mut self,
-        commits: &[String],
-        _total_commits: u32
+        mut chunks: mpsc::Receiver<Vec<EpochCommit>>,
+        repo_path: &str,
+        list_id: i32
     ) -> DatabasePopulationResult
     {
         let mut errors = Vec::new();
-
-        // Filter out commits that already exist in the database
-        println!("Checking for existing commits in database...");
-        let pool = self.get_pool().unwrap();
-        let existing_commits = match PatchOps::get_existing_commit_hashes(commits, pool).await {
-            Ok(existing) => existing,
-            Err(e) => {
-                errors.push(format!("Error checking existing commits: {}", e));
-                HashSet::new()
-            }
-        };
-        
-        let new_commits: Vec<String> = commits.iter()
-            .filter(|commit_hash| !existing_commits.contains(*commit_hash))
-            .cloned()
-            .collect();
-
-        let skipped_count = commits.len() - new_commits.len();
-        if skipped_count > 0 {
-            println!("Skipping {} existing commits, processing {} new commits", skipped_count, new_commits.len());
-        } else {
-            println!("No existing commits found, processing all {} commits", new_commits.len());
-        }
-
-        if new_commits.is_empty() {
-            println!("All commits already exist in database - nothing to process");
-            return DatabasePopulationResult {
-                success: true,
-                total_processed: commits.len() as u32,
-                total_authors_inserted: 0,
-                total_emails_inserted: 0,
-                errors: vec![],
-            };
-        }
+        let pool = self.pool.clone().expect("Pool must exist");
 
         // Create channel for parsed emails
-        let (tx, mut rx) = mpsc::channel::<(Vec<(String, crate::mail_parser::EmailInfo)>, Vec<String>)>(CHANNEL_BUFFER_SIZE);
-        
-        let total_batches = (new_commits.len() + PARSE_BATCH_SIZE - 1) / PARSE_BATCH_SIZE;
-        println!("Starting parallel parsing of {} batches, sequential DB insertion", total_batches);
-        
-        // Spawn parallel parser tasks
-        let mut parser_handles = Vec::new();
-        for (batch_idx, commit_batch) in new_commits.chunks(PARSE_BATCH_SIZE).enumerate() {
-            let commit_batch_vec = commit_batch.to_vec();
-            let tx_clone = tx.clone();
-            
-            let handle = tokio::spawn(async move {
-                // Fetch commits
-                println!("Batch {} fetching {} commits", batch_idx + 1, commit_batch_vec.len());
-                let (email_contents, metadata_list) = match tokio::task::spawn_blocking(move || {
-                    // Fetch email contents
-                    let contents = crate::git_parser::get_multiple_email_content(&commit_batch_vec)?;
-                    // Extract commit hashes for metadata lookup
-                    let commit_hashes: Vec<String> = contents.iter().map(|(hash, _)| hash.clone()).collect();
-                    // Fetch commit metadata
-                    let metadata = crate::git_parser::get_commit_metadata(&commit_hashes)?;
-                    Ok::<_, crate::git_parser::ParseError>((contents, metadata))
-                }).await {
-                    Ok(Ok((contents, metadata))) => (contents, metadata),
-                    Ok(Err(e)) => {
-                        eprintln!("Failed to fetch batch {}: {}", batch_idx + 1, e);
-                        return;
-                    }
-                    Err(e) => {
-                        eprintln!("Task error fetching batch {}: {}", batch_idx + 1, e);
-                        return;
-                    }
-                };
-                
-                // Combine email contents with metadata
-                let emails_with_metadata: Vec<(String, String, crate::git_parser::CommitMetadata)> = email_contents
-                    .into_iter()
-                    .zip(metadata_list.into_iter())
-                    .map(|((hash, content), metadata)| (hash, content, metadata))
-                    .collect();
-                
-                // Parse emails
-                println!("Batch {} parsing {} emails", batch_idx + 1, emails_with_metadata.len());
-                let (parsed_emails, parse_errors) = parse_emails_parallel(emails_with_metadata).await;
-                println!("Batch {} parsed: {} emails, {} errors", batch_idx + 1, parsed_emails.len(), parse_errors.len());
-                
-                // Send to DB inserter via channel
-                if tx_clone.send((parsed_emails, parse_errors)).await.is_err() {
-                    eprintln!("Batch {}: Channel closed, DB inserter stopped", batch_idx + 1);
-                }
-            });
-            
-            parser_handles.push(handle);
-        }
-        
-        // Drop original sender so channel closes when all parsers finish
-        drop(tx);
+        // Each message carries (epoch, parsed emails, parse errors, Message-IDs removed by deletion commits, emails to quarantine)
+        let (tx, mut rx) = mpsc::channel::<(u32, Vec<(String, crate::mail_parser::EmailInfo)>, Vec<String>, Vec<String>, Vec<ParseFailure>)>(CHANNEL_BUFFER_SIZE);
         
         // Spawn single DB inserter task (sequential, optimized batching)
-        let pool = self.pool.clone().expect("Pool must exist");
+        let inserter_pool = pool.clone();
+        let inserter_cancel = self.population_cancel.clone();
+        let compress_bodies = self.config.compress_bodies;
         let db_handle = tokio::spawn(async move {
             let mut all_emails = Vec::new();
+            let mut epochs = HashMap::new();
             let mut all_errors = Vec::new();
+            let mut deleted_message_ids = Vec::new();
+            let mut parse_failures = Vec::new();
             let mut processed = 0u32;
             
             // Collect all parsed results from channel
-            while let Some((parsed_emails, parse_errors)) = rx.recv().await {
+            while let Some((epoch, parsed_emails, parse_errors, deleted_ids, failures)) = rx.recv().await {
                 processed += parsed_emails.len() as u32;
+                epochs.extend(parsed_emails.iter().map(|(hash, _)| (hash.clone(), epoch)));
                 all_emails.extend(parsed_emails);
                 all_errors.extend(parse_errors);
+                deleted_message_ids.extend(deleted_ids);
+                parse_failures.extend(failures);
+            }
+            
+            // Keep unparseable emails so a later parser can recover them
+            match record_parse_failures(&parse_failures, Some(list_id), &inserter_pool).await {
+                Ok(stored) if stored > 0 => println!("Quarantined {} unparseable emails", stored),
+                Ok(_) => {}
+                Err(e) => all_errors.push(format!("Error quarantining unparseable emails: {}", e)),
             }
             
             println!("All parsing complete. Inserting {} emails to database in optimized batches...", all_emails.len());
@@ -193,24 +363,164 @@ impl DatabaseManager {
             
             // Insert in large optimized batches (sequential to avoid deadlocks)
             for (batch_num, batch) in all_emails.chunks(DB_INSERT_BATCH_SIZE).enumerate() {
+                if inserter_cancel.load(Ordering::Relaxed) {
+                    println!("Population cancelled before batch {}", batch_num + 1);
+                    break;
+                }
                 println!("Inserting batch {}: {} emails", batch_num + 1, batch.len());
-                match PatchOps::insert_batch_to_db(batch, &pool).await {
+                match PatchOps::insert_batch_to_db(batch, Some(list_id), &epochs, compress_bodies, &inserter_pool).await {
                     Ok((authors_count, patches_count)) => {
                         inserted_authors += authors_count;
                         inserted_patches += patches_count;
                         println!("Batch {} inserted: {} authors, {} patches", batch_num + 1, authors_count, patches_count);
                     }
                     Err(e) => {
-                        for (commit_hash, _) in batch {
+                        for commit_hash in &e.commit_hashes {
                             all_errors.push(format!("Error inserting commit {}: {}", commit_hash, e));
                         }
                     }
                 }
             }
             
+            // Mark deletions after inserting, so messages removed later in the same run are covered
+            match PatchOps::mark_messages_deleted(&deleted_message_ids, &inserter_pool).await {
+                Ok(marked) if marked > 0 => println!("Marked {} messages as deleted", marked),
+                Ok(_) => {}
+                Err(e) => all_errors.push(format!("Error marking deleted messages: {}", e)),
+            }
+            
             (processed, inserted_authors, inserted_patches, all_errors)
         });
         
+        // Spawn a parser task per incoming chunk (each chunk belongs to a single epoch)
+        let mut parser_handles = Vec::new();
+        let mut total_received = 0usize;
+        let mut skipped_count = 0usize;
+        
+        while let Some(chunk) = chunks.recv().await {
+            // Stop handing out work; dropping the receiver also ends commit traversal
+            if self.population_cancelled() {
+                println!("Population cancelled, no further batches will be parsed");
+                break;
+            }
+            total_received += chunk.len();
+            
+            // Filter out commits that already exist in the database
+            let commit_hashes: Vec<String> = chunk.iter().map(|c| c.commit_hash.clone()).collect();
+            let existing_commits = match PatchOps::get_existing_commit_hashes(&commit_hashes, Some(list_id), &pool).await {
+                Ok(existing) => existing,
+                Err(e) => {
+                    errors.push(format!("Error checking existing commits: {}", e));
+                    HashSet::new()
+                }
+            };
+            
+            let new_hashes: Vec<String> = commit_hashes.into_iter()
+                .filter(|hash| !existing_commits.contains(hash))
+                .collect();
+            skipped_count += chunk.len() - new_hashes.len();
+            
+            let Some(epoch) = chunk.first().map(|c| c.epoch) else { continue };
+            if new_hashes.is_empty() {
+                continue;
+            }
+            
+            let batch_idx = parser_handles.len();
+            let tx_clone = tx.clone();
+            let repo_path = repo_path.to_string();
+            let parser_cancel = self.population_cancel.clone();
+            
+            let handle = tokio::spawn(async move {
+                if parser_cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                // Fetch commits
+                println!("Batch {} fetching {} commits from epoch {}", batch_idx + 1, new_hashes.len(), epoch);
+                let (batch, metadata_list) = match tokio::task::spawn_blocking(move || {
+                    // Fetch email contents (deletion and unreadable commits come back separately)
+                    let batch = crate::git_parser::get_multiple_email_content_in_epoch(&repo_path, epoch, &new_hashes)?;
+                    // Extract commit hashes for metadata lookup
+                    let commit_hashes: Vec<String> = batch.messages.iter().map(|(hash, _)| hash.clone()).collect();
+                    // Fetch commit metadata
+                    let metadata = crate::git_parser::get_commit_metadata_in_epoch(&repo_path, epoch, &commit_hashes)?;
+                    Ok::<_, crate::git_parser::ParseError>((batch, metadata))
+                }).await {
+                    Ok(Ok(result)) => result,
+                    Ok(Err(e)) => {
+                        let error = format!("Failed to fetch batch {} from epoch {}: {}", batch_idx + 1, epoch, e);
+                        eprintln!("{}", error);
+                        let _ = tx_clone.send((epoch, Vec::new(), vec![error], Vec::new(), Vec::new())).await;
+                        return;
+                    }
+                    Err(e) => {
+                        let error = format!("Task error fetching batch {}: {}", batch_idx + 1, e);
+                        eprintln!("{}", error);
+                        let _ = tx_clone.send((epoch, Vec::new(), vec![error], Vec::new(), Vec::new())).await;
+                        return;
+                    }
+                };
+                let crate::git_parser::EmailBatch { messages: email_contents, deleted, failed } = batch;
+                
+                // Missing or corrupt objects are reported per commit, the rest of the batch goes on
+                let mut fetch_errors: Vec<String> = failed.into_iter()
+                    .map(|(hash, e)| format!("Error reading commit {}: {}", hash, e))
+                    .collect();
+                if !fetch_errors.is_empty() {
+                    println!("Batch {} skipped {} unreadable commits", batch_idx + 1, fetch_errors.len());
+                }
+                
+                let deleted_message_ids: Vec<String> = deleted.iter()
+                    .filter_map(|d| d.removed_message.as_deref().and_then(extract_message_id))
+                    .collect();
+                if !deleted.is_empty() {
+                    println!("Batch {} skipped {} deletion commits", batch_idx + 1, deleted.len());
+                }
+                
+                // Combine email contents with metadata
+                let mut emails_with_metadata: Vec<(String, String, crate::git_parser::CommitMetadata)> = Vec::with_capacity(email_contents.len());
+                for ((hash, content), metadata) in email_contents.into_iter().zip(metadata_list) {
+                    match metadata {
+                        Ok(metadata) => emails_with_metadata.push((hash, content, metadata)),
+                        Err(e) => fetch_errors.push(format!("Error reading metadata for commit {}: {}", hash, e)),
+                    }
+                }
+                
+                // Parse emails
+                println!("Batch {} parsing {} emails", batch_idx + 1, emails_with_metadata.len());
+                let (parsed_emails, mut parse_errors, parse_failures) = parse_emails_parallel(emails_with_metadata).await;
+                parse_errors.extend(fetch_errors);
+                println!("Batch {} parsed: {} emails, {} errors", batch_idx + 1, parsed_emails.len(), parse_errors.len());
+                
+                // Send to DB inserter via channel
+                if tx_clone.send((epoch, parsed_emails, parse_errors, deleted_message_ids, parse_failures)).await.is_err() {
+                    eprintln!("Batch {}: Channel closed, DB inserter stopped", batch_idx + 1);
+                }
+            });
+            
+            parser_handles.push(handle);
+        }
+        
+        // Drop original sender so channel closes when all parsers finish
+        drop(tx);
+        
+        if skipped_count > 0 {
+            println!("Skipped {} existing commits, processing {} new commits",
+                     skipped_count, total_received - skipped_count);
+        }
+
+        if parser_handles.is_empty() {
+            println!("All commits already exist in database - nothing to process");
+            let _ = db_handle.await;
+            return DatabasePopulationResult {
+                success: errors.is_empty(),
+                total_processed: total_received as u32,
+                total_authors_inserted: 0,
+                total_emails_inserted: 0,
+                errors,
+                cancelled: self.population_cancelled(),
+            };
+        }
+        
         // Wait for all parsers to complete
         future::join_all(parser_handles).await;
         
@@ -228,12 +538,23 @@ impl DatabaseManager {
             errors.push(format!("Failed to refresh author patch counts: {}", e));
         }
 
+        // Group the new series patches
+        if let Err(e) = self.assign_series().await {
+            errors.push(format!("Failed to group patch series: {}", e));
+        }
+
+        // Link earlier versions of the new postings to them
+        if let Err(e) = self.link_superseded_patches().await {
+            errors.push(format!("Failed to link superseded patches: {}", e));
+        }
+
         DatabasePopulationResult {
             success: errors.is_empty(),
             total_processed: processed,
             total_authors_inserted: inserted_authors,
             total_emails_inserted: inserted_patches,
             errors,
+            cancelled: self.population_cancelled(),
         }
     }
 
@@ -249,7 +570,7 @@ impl DatabaseManager {
     /// Start a background progress reporter that polls the database for actual progress
     async fn start_progress_reporter<F>(
         &self,
-        total_commits: u32,
+        total_commits: Arc<AtomicU32>,
         initial_count: u32,
         pool: Pool<sqlx::Postgres>,
         callback: F
@@ -276,12 +597,13 @@ impl DatabaseManager {
 
                 // Calculate patches added since start
                 let patches_processed = current_count.saturating_sub(initial_count);
+                let total_commits = total_commits.load(Ordering::Relaxed);
 
                 // Report progress
                 callback(patches_processed, total_commits, format!("processing ({} patches)", current_count));
 
                 // Stop if we've processed all commits (with some buffer)
-                if patches_processed >= total_commits {
+                if total_commits > 0 && patches_processed >= total_commits {
                     break;
                 }
             }
@@ -289,3 +611,14 @@ impl DatabaseManager {
     }
 }
 
+/// Split commits into parse batches of at most `batch_size`, never mixing epochs in one batch
+fn chunk_by_epoch(commits: &[EpochCommit], batch_size: usize) -> Vec<Vec<EpochCommit>> {
+    let mut chunks: Vec<Vec<EpochCommit>> = Vec::new();
+    for commit in commits {
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() < batch_size && chunk[0].epoch == commit.epoch => chunk.push(commit.clone()),
+            _ => chunks.push(vec![commit.clone()]),
+        }
+    }
+    chunks
+}
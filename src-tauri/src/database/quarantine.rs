@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use sqlx::{Pool, Postgres, Row};
+use crate::database::config::DB_INSERT_BATCH_SIZE;
+use crate::database::patches::PatchOps;
+use crate::database::{DatabaseManager, ParseRetryResult};
+use crate::git_parser::CommitMetadata;
+use crate::mail_parser::{parse_email_from_content, EmailInfo, ParseFailure};
+
+/// Store emails that failed to parse in parse_failures
+/// A commit that is already quarantined gets its content and error refreshed and its attempt count bumped
+pub(crate) async fn record_parse_failures(
+    failures: &[ParseFailure],
+    list_id: Option<i32>,
+    pool: &Pool<Postgres>
+) -> Result<u64, Box<dyn std::error::Error>> {
+    if failures.is_empty() {
+        return Ok(0);
+    }
+
+    let mut commit_hashes = Vec::with_capacity(failures.len());
+    let mut raw_contents = Vec::with_capacity(failures.len());
+    let mut metadata = Vec::with_capacity(failures.len());
+    let mut errors = Vec::with_capacity(failures.len());
+    for failure in failures {
+        commit_hashes.push(failure.commit_hash.as_str());
+        // Postgres TEXT can't hold NUL bytes
+        raw_contents.push(failure.raw_content.replace('\0', ""));
+        metadata.push(serde_json::to_string(&failure.metadata)?);
+        errors.push(failure.error.as_str());
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO parse_failures (commit_hash, list_id, raw_content, commit_metadata, error)
+         SELECT f.commit_hash, $5, f.raw_content, f.commit_metadata::JSONB, f.error
+         FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[]) AS f(commit_hash, raw_content, commit_metadata, error)
+         ON CONFLICT (commit_hash) DO UPDATE
+         SET raw_content = EXCLUDED.raw_content,
+             commit_metadata = EXCLUDED.commit_metadata,
+             error = EXCLUDED.error,
+             attempts = parse_failures.attempts + 1,
+             last_attempt_at = NOW()"
+    )
+    .bind(&commit_hashes)
+    .bind(&raw_contents)
+    .bind(&metadata)
+    .bind(&errors)
+    .bind(list_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+impl DatabaseManager {
+    /// Re-parse quarantined emails with the current parser
+    /// Recovered emails are inserted under their original list and leave the quarantine;
+    /// the rest keep their row with the new error and one more attempt counted
+    pub async fn retry_failed_parses(&mut self) -> Result<ParseRetryResult, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?.clone();
+
+        let rows = sqlx::query(
+            "SELECT commit_hash, list_id, raw_content, commit_metadata
+             FROM parse_failures
+             ORDER BY failure_id"
+        )
+        .fetch_all(&pool)
+        .await?;
+
+        let retried = rows.len() as u32;
+        let mut recovered_by_list: HashMap<Option<i32>, Vec<(String, EmailInfo)>> = HashMap::new();
+        let mut failed_hashes = Vec::new();
+        let mut failed_errors = Vec::new();
+
+        for row in rows {
+            let commit_hash: String = row.get(0);
+            let list_id: Option<i32> = row.get(1);
+            let raw_content: String = row.get(2);
+            let metadata: sqlx::types::Json<CommitMetadata> = row.get(3);
+
+            match parse_email_from_content(&commit_hash, &raw_content, &metadata.0) {
+                Ok(email_info) => recovered_by_list.entry(list_id).or_default().push((commit_hash, email_info)),
+                Err(e) => {
+                    failed_hashes.push(commit_hash);
+                    failed_errors.push(e.to_string());
+                }
+            }
+        }
+
+        let mut recovered = 0u32;
+        let mut errors = Vec::new();
+
+        for (list_id, emails) in &recovered_by_list {
+            for batch in emails.chunks(DB_INSERT_BATCH_SIZE) {
+                match PatchOps::insert_batch_to_db(batch, *list_id, &HashMap::new(), self.config.compress_bodies, &pool).await {
+                    Ok(_) => {
+                        let hashes: Vec<&str> = batch.iter().map(|(hash, _)| hash.as_str()).collect();
+                        sqlx::query("DELETE FROM parse_failures WHERE commit_hash = ANY($1)")
+                            .bind(&hashes)
+                            .execute(&pool)
+                            .await?;
+                        recovered += batch.len() as u32;
+                    }
+                    Err(e) => {
+                        for commit_hash in &e.commit_hashes {
+                            errors.push(format!("Error inserting recovered commit {}: {}", commit_hash, e));
+                        }
+                    }
+                }
+            }
+        }
+
+        if !failed_hashes.is_empty() {
+            sqlx::query(
+                "UPDATE parse_failures pf
+                 SET error = f.error, attempts = pf.attempts + 1, last_attempt_at = NOW()
+                 FROM UNNEST($1::TEXT[], $2::TEXT[]) AS f(commit_hash, error)
+                 WHERE pf.commit_hash = f.commit_hash"
+            )
+            .bind(&failed_hashes)
+            .bind(&failed_errors)
+            .execute(&pool)
+            .await?;
+        }
+
+        if recovered > 0 {
+            if let Err(e) = self.refresh_author_patch_counts().await {
+                errors.push(format!("Failed to refresh author patch counts: {}", e));
+            }
+        }
+
+        println!("Retried {} quarantined emails: {} recovered, {} still failing",
+                 retried, recovered, retried - recovered);
+
+        Ok(ParseRetryResult {
+            retried,
+            recovered,
+            still_failing: retried - recovered,
+            errors,
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use crate::database::DatabaseManager;
+
+impl DatabaseManager {
+    /// Mark individual messages read or unread
+    /// Overrides the thread's read watermark for these messages; unknown ids are ignored
+    /// Returns the number of messages updated
+    pub async fn mark_patches_read(&mut self, patch_ids: &[i64], read: bool) -> Result<u64, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let updated = sqlx::query(
+            "INSERT INTO patch_read_state (patch_id, is_read)
+             SELECT patch_id, $2 FROM patches WHERE patch_id = ANY($1)
+             ON CONFLICT (patch_id) DO UPDATE SET is_read = EXCLUDED.is_read, updated_at = NOW()"
+        )
+        .bind(patch_ids)
+        .bind(read)
+        .execute(pool)
+        .await?;
+
+        Ok(updated.rows_affected())
+    }
+
+    /// Mark every message of a thread read or unread, clearing per-message overrides
+    /// Replies arriving after a thread was marked read show up as unread
+    /// Returns the thread's unread count afterwards
+    pub async fn mark_thread_read(&mut self, thread_id: i64, read: bool) -> Result<i64, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+        let mut tx = pool.begin().await?;
+
+        let newest: Option<chrono::DateTime<chrono::Utc>> = sqlx::query_scalar(
+            "SELECT MAX(p.sent_at)
+             FROM patch_replies pr
+             JOIN patches p ON pr.patch_id = p.patch_id
+             WHERE pr.thread_id = $1"
+        )
+        .bind(thread_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let newest = newest.ok_or_else(|| format!("Thread {} does not exist", thread_id))?;
+
+        sqlx::query(
+            "DELETE FROM patch_read_state rs
+             USING patch_replies pr
+             WHERE pr.patch_id = rs.patch_id AND pr.thread_id = $1"
+        )
+        .bind(thread_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if read {
+            sqlx::query(
+                "INSERT INTO thread_read_state (thread_id, read_through) VALUES ($1, $2)
+                 ON CONFLICT (thread_id) DO UPDATE SET read_through = EXCLUDED.read_through, updated_at = NOW()"
+            )
+            .bind(thread_id)
+            .bind(newest)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            sqlx::query("DELETE FROM thread_read_state WHERE thread_id = $1")
+                .bind(thread_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let unread: i64 = sqlx::query_scalar("SELECT unread_count FROM thread_summary WHERE thread_id = $1")
+            .bind(thread_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(unread)
+    }
+}
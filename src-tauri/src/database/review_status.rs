@@ -0,0 +1,57 @@
+use crate::database::{DatabaseManager, ReviewStatus};
+
+/// Statuses the automatic rules may move a thread out of
+const OPEN_STATUSES: [&str; 3] = ["new", "under_review", "changes_requested"];
+
+impl DatabaseManager {
+    /// Set a thread's review status by hand
+    pub async fn set_thread_review_status(&mut self, thread_id: i64, status: ReviewStatus) -> Result<(), Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        let updated = sqlx::query(
+            "UPDATE patch_threads SET review_status = $2, review_status_at = NOW() WHERE thread_id = $1"
+        )
+        .bind(thread_id)
+        .bind(status.as_str())
+        .execute(pool)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(format!("Thread {} does not exist", thread_id).into());
+        }
+        Ok(())
+    }
+
+    /// Move open threads on automatically: merged threads become accepted, threads whose
+    /// root patch was resubmitted as a newer version become superseded
+    /// Closed statuses (accepted, rejected, superseded) are left alone
+    /// Returns the number of threads changed
+    pub(crate) async fn apply_review_status_rules(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let pool = self.get_pool()?;
+
+        let accepted = sqlx::query(
+            "UPDATE patch_threads pt
+             SET review_status = 'accepted', review_status_at = NOW()
+             WHERE pt.review_status = ANY($1)
+               AND EXISTS (SELECT 1 FROM merged_threads mt WHERE mt.thread_id = pt.thread_id)"
+        )
+        .bind(&OPEN_STATUSES[..])
+        .execute(pool)
+        .await?;
+
+        let superseded = sqlx::query(
+            "UPDATE patch_threads pt
+             SET review_status = 'superseded', review_status_at = NOW()
+             FROM patches root
+             WHERE root.patch_id = pt.root_patch_id
+               AND root.superseded_by IS NOT NULL
+               AND pt.review_status = ANY($1)"
+        )
+        .bind(&OPEN_STATUSES[..])
+        .execute(pool)
+        .await?;
+
+        Ok(accepted.rows_affected() + superseded.rows_affected())
+    }
+}
@@ -1,7 +1,19 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use sqlx::migrate::{Migrate, Migrator};
 use sqlx::Row;
-use crate::database::{DatabaseManager, DatabaseSetupResult};
+use crate::database::{DatabaseManager, DatabaseSetupResult, MigrationStatus};
+
+/// Load the versioned migrations (migrations/<version>_<description>.sql)
+/// Schema changes go in a new migration file; applied files must not be edited
+pub(crate) async fn load_migrator() -> Result<Migrator, Box<dyn std::error::Error>> {
+    let migrations_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+    if !migrations_dir.exists() {
+        return Err(format!("Migrations directory not found: {}", migrations_dir.display()).into());
+    }
+    Ok(Migrator::new(migrations_dir).await?)
+}
 
 impl DatabaseManager {
     /// Execute SQL commands from a file
@@ -9,7 +21,7 @@ impl DatabaseManager {
         let sql_content = fs::read_to_string(file_path)?;
         self.ensure_connected().await?;
 
-        let pool = self.get_pool()?;
+        let pool = self.get_pool_unchecked()?;
         println!("Executing SQL file with batch execute...");
         sqlx::raw_sql(&sql_content).execute(pool).await?;
         println!("SQL file executed successfully");
@@ -21,7 +33,7 @@ impl DatabaseManager {
     pub async fn reset_database(&mut self) -> Result<String, Box<dyn std::error::Error>> {
         self.ensure_connected().await?;
 
-        let pool = self.get_pool()?;
+        let pool = self.get_pool_unchecked()?;
         println!("Dropping all tables...");
 
         // Get all user-defined tables in the current database
@@ -47,37 +59,64 @@ impl DatabaseManager {
         }
 
         println!("All tables dropped successfully");
+        self.check_schema_version().await?;
 
         Ok(format!("Database reset successful. Dropped {} tables.", table_count))
     }
 
-    /// Initialize database schema from SQL files
+    /// Initialize database schema by applying all pending migrations
     pub async fn setup_database(&mut self) -> Result<DatabaseSetupResult, Box<dyn std::error::Error>> {
         self.ensure_connected().await
             .map_err(|e| format!("Failed to connect to database during setup: {}", e))?;
 
-        let manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let sql_dir = Path::new(manifest_dir).join("sql");
-        let mut tables_created = Vec::new();
-
-        let sql_files = ["00_schema.sql"];
-
-        for sql_file in &sql_files {
-            let file_path = sql_dir.join(sql_file);
-            if file_path.exists() {
-                println!("Executing SQL file: {}", sql_file);
-                self.execute_sql_file(&file_path).await
-                    .map_err(|e| format!("Failed to execute SQL file '{}': {}", sql_file, e))?;
-                tables_created.push(sql_file.to_string());
-            } else {
-                return Err(format!("SQL schema file not found: {}", file_path.display()).into());
-            }
-        }
+        let status = self.migrate_database().await?;
 
         Ok(DatabaseSetupResult {
             success: true,
-            message: format!("Database setup completed successfully. Created {} tables/views.", tables_created.len()),
-            tables_created,
+            message: format!(
+                "Database setup completed successfully. Applied {} migrations (schema version {}).",
+                status.newly_applied.len(),
+                status.current_version.unwrap_or(0)
+            ),
+            tables_created: status.newly_applied,
+        })
+    }
+
+    /// Apply pending migrations and report the resulting schema version
+    pub async fn migrate_database(&mut self) -> Result<MigrationStatus, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool_unchecked()?;
+        let migrator = load_migrator().await?;
+
+        let applied_before: HashSet<i64> = {
+            let mut conn = pool.acquire().await?;
+            conn.ensure_migrations_table().await?;
+            conn.list_applied_migrations().await?
+                .into_iter()
+                .map(|m| m.version)
+                .collect()
+        };
+
+        println!("Applying database migrations...");
+        migrator.run(pool).await
+            .map_err(|e| format!("Failed to apply migrations: {}", e))?;
+
+        let migrations: Vec<_> = migrator.iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .collect();
+        let newly_applied: Vec<String> = migrations.iter()
+            .filter(|m| !applied_before.contains(&m.version))
+            .map(|m| format!("{:04}_{}", m.version, m.description.replace(' ', "_")))
+            .collect();
+        for migration in &newly_applied {
+            println!("Applied migration: {}", migration);
+        }
+        self.check_schema_version().await?;
+
+        Ok(MigrationStatus {
+            current_version: migrations.iter().map(|m| m.version).max(),
+            newly_applied,
+            total_migrations: migrations.len(),
         })
     }
 }
@@ -0,0 +1,135 @@
+use crate::database::{DatabaseManager, SeriesBuildStats};
+
+impl DatabaseManager {
+    /// Group series patches that have no series yet into the `series` table
+    ///
+    /// Each patch is walked up its In-Reply-To chain while the parent is a patch
+    /// (not a reply) of the same author, version and series length; the topmost
+    /// one is the series root. This follows both `git send-email` threading styles
+    /// and stops at a v2 sent in reply to its v1.
+    pub(crate) async fn assign_series(&self) -> Result<SeriesBuildStats, Box<dyn std::error::Error>> {
+        let pool = self.get_pool()?;
+        let mut tx = pool.begin().await?;
+
+        sqlx::query(
+            "CREATE TEMP TABLE series_roots ON COMMIT DROP AS
+             WITH RECURSIVE walk AS (
+                SELECT p.patch_id AS member_id, p.patch_id AS node_id, p.in_reply_to,
+                       p.author_id, p.patch_version, p.series_total, 0 AS depth
+                FROM patches p
+                WHERE p.is_series = TRUE AND p.is_reply = FALSE AND p.series_id IS NULL
+                UNION ALL
+                SELECT w.member_id, parent.patch_id, parent.in_reply_to,
+                       w.author_id, w.patch_version, w.series_total, w.depth + 1
+                FROM walk w
+                JOIN patches parent ON parent.message_id = w.in_reply_to
+                WHERE parent.is_series = TRUE
+                  AND parent.is_reply = FALSE
+                  AND parent.author_id = w.author_id
+                  AND parent.patch_version IS NOT DISTINCT FROM w.patch_version
+                  AND parent.series_total IS NOT DISTINCT FROM w.series_total
+                  AND w.depth < 1000
+             )
+             SELECT DISTINCT ON (member_id) member_id, node_id AS root_id
+             FROM walk
+             ORDER BY member_id, depth DESC"
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let created = sqlx::query(
+            "INSERT INTO series (root_patch_id, cover_letter_patch_id, version, total, target_tree, is_rfc, list_id)
+             SELECT root.patch_id,
+                    CASE WHEN root.is_cover_letter THEN root.patch_id END,
+                    root.patch_version, root.series_total, root.target_tree, root.is_rfc, root.list_id
+             FROM (SELECT DISTINCT root_id FROM series_roots) r
+             JOIN patches root ON root.patch_id = r.root_id
+             ORDER BY root.patch_id
+             ON CONFLICT (root_patch_id) DO NOTHING"
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let assigned = sqlx::query(
+            "UPDATE patches p
+             SET series_id = s.series_id
+             FROM series_roots r
+             JOIN series s ON s.root_patch_id = r.root_id
+             WHERE p.patch_id = r.member_id"
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(SeriesBuildStats {
+            series_created: created.rows_affected(),
+            patches_assigned: assigned.rows_affected(),
+        })
+    }
+
+    /// Link postings that have no newer version yet to the one replacing them
+    ///
+    /// A patch is superseded by the lowest newer version from the same author with
+    /// the same `git patch-id` or the same subject once its tags are stripped.
+    /// Returns the number of links set or moved
+    pub(crate) async fn link_superseded_patches(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let pool = self.get_pool()?;
+
+        let linked = sqlx::query(
+            "UPDATE patches p
+             SET superseded_by = n.next_id
+             FROM (
+                SELECT DISTINCT ON (old.patch_id) old.patch_id, newer.patch_id AS next_id
+                FROM patches old
+                JOIN patches newer ON newer.author_id = old.author_id
+                  AND newer.is_reply = FALSE
+                  AND newer.patch_version > old.patch_version
+                  AND newer.is_cover_letter IS NOT DISTINCT FROM old.is_cover_letter
+                  AND (newer.git_patch_id = old.git_patch_id
+                       OR patch_subject_key(newer.subject) = patch_subject_key(old.subject))
+                WHERE old.superseded_by IS NULL
+                  AND old.is_reply = FALSE
+                  AND old.patch_version IS NOT NULL
+                  AND patch_subject_key(old.subject) <> ''
+                ORDER BY old.patch_id, newer.patch_version, newer.sent_at
+             ) n
+             WHERE p.patch_id = n.patch_id"
+        )
+        .execute(pool)
+        .await?;
+
+        // A version stored after its successor: earlier postings linked past it now point to it
+        let relinked = sqlx::query(
+            "UPDATE patches p
+             SET superseded_by = n.next_id
+             FROM (
+                SELECT DISTINCT ON (old.patch_id) old.patch_id, mid.patch_id AS next_id
+                FROM patches old
+                JOIN patches mid ON mid.superseded_by = old.superseded_by
+                  AND mid.patch_version > old.patch_version
+                ORDER BY old.patch_id, mid.patch_version, mid.sent_at
+             ) n
+             WHERE p.patch_id = n.patch_id"
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(linked.rows_affected() + relinked.rows_affected())
+    }
+
+    /// Drop all series and group every series patch again
+    /// Use after changing how series are detected, or to pick up cover letters
+    /// that were stored after their patches
+    pub async fn rebuild_series(&mut self) -> Result<SeriesBuildStats, Box<dyn std::error::Error>> {
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        println!("Rebuilding patch series...");
+        sqlx::query("DELETE FROM series").execute(pool).await?;
+        let stats = self.assign_series().await?;
+        println!("Series rebuilt: {} series, {} patches", stats.series_created, stats.patches_assigned);
+
+        Ok(stats)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::database::{DatabaseManager, StatsRefreshResult};
+
+/// Materialized views behind the dashboard statistics (migration 0019)
+const STATS_VIEWS: [&str; 3] = [
+    "stats_overview",
+    "stats_contributors",
+    "stats_daily_activity",
+];
+
+impl DatabaseManager {
+    /// Recompute the dashboard statistics views, e.g. after a population run
+    /// Views are refreshed concurrently, so `get_enhanced_stats` keeps answering from the old numbers meanwhile
+    pub async fn refresh_stats(&mut self) -> Result<StatsRefreshResult, Box<dyn std::error::Error>> {
+        let start_time = std::time::Instant::now();
+
+        self.ensure_connected().await?;
+        let pool = self.get_pool()?;
+
+        println!("Refreshing {} statistics views...", STATS_VIEWS.len());
+        for view in STATS_VIEWS {
+            sqlx::query(&format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {}", view))
+                .execute(pool)
+                .await?;
+        }
+
+        let duration_ms = start_time.elapsed().as_millis() as u64;
+        println!("Statistics refreshed in {} ms", duration_ms);
+
+        Ok(StatsRefreshResult {
+            views: STATS_VIEWS.iter().map(|v| v.to_string()).collect(),
+            duration_ms,
+        })
+    }
+}
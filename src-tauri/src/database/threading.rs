@@ -1,8 +1,18 @@
 use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU32, Ordering};
+use rayon::prelude::*;
 use sqlx::{Pool, Postgres, Row};
-use crate::database::{DatabaseManager, ThreadBuildStats};
+use tokio::sync::Semaphore;
+use crate::database::config::{DB_INSERT_BATCH_SIZE, THREAD_INSERT_CONCURRENCY};
+use crate::database::{DatabaseManager, ThreadBuildProgress, ThreadBuildStats, ThreadingOptions};
 use regex::Regex;
 
+/// Patches (or thread roots) handled between two progress reports of the same phase
+const PROGRESS_INTERVAL: usize = 10_000;
+
+/// patch_replies row: thread, patch, parent, depth, position and path from the thread's top
+type ReplyRow = (i64, i64, Option<i64>, i32, i32, Vec<i64>);
+
 /// Metadata about a patch needed for threading
 #[allow(dead_code)]
 struct PatchThreadInfo {
@@ -11,10 +21,44 @@ struct PatchThreadInfo {
     subject: String,
     normalized_subject: String,
     sent_at: chrono::DateTime<chrono::Utc>,
+    author_id: i64,
     is_reply: bool,  // Based on "Re:" prefix in subject
     is_series: bool,
     series_number: Option<i32>,
     series_total: Option<i32>,
+    is_cover_letter: bool,  // [PATCH 0/N]
+}
+
+/// Series a message belongs to or replies to, see `extract_series_identifier`
+fn series_key(patch: &PatchThreadInfo) -> Option<String> {
+    extract_series_identifier(&patch.subject, patch.series_total?)
+}
+
+/// Whether `candidate` may serve as the subject-fallback parent of `patch` under `options`
+fn fallback_allows(options: &ThreadingOptions, patch: &PatchThreadInfo, candidate: &PatchThreadInfo) -> bool {
+    if let Some(days) = options.fallback_window_days {
+        if (patch.sent_at - candidate.sent_at).abs() > chrono::Duration::days(i64::from(days)) {
+            return false;
+        }
+    }
+    if options.fallback_same_author && patch.author_id != candidate.author_id {
+        return false;
+    }
+    if options.fallback_same_series {
+        let series = series_key(patch);
+        if series.is_none() || series != series_key(candidate) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Order of messages below the same parent: the members of a series by series_number
+/// (1/N, 2/N, ...) so the tree reads like the series, then everything else by send time
+fn sibling_order(patch: &PatchThreadInfo) -> (bool, i32, chrono::DateTime<chrono::Utc>, i64) {
+    let series_number = patch.series_number
+        .filter(|_| patch.is_series && !patch.is_reply && !patch.is_cover_letter);
+    (series_number.is_none(), series_number.unwrap_or(0), patch.sent_at, patch.patch_id)
 }
 
 /// Extract series identifier from subject line
@@ -32,22 +76,119 @@ fn extract_series_identifier(subject: &str, series_total: i32) -> Option<String>
     None
 }
 
+/// References chain of a message, oldest first and ending with its In-Reply-To
+fn reference_chain(in_reply_to: Option<String>, mut references: Vec<String>) -> Vec<String> {
+    if let Some(in_reply_to) = in_reply_to {
+        if references.last() != Some(&in_reply_to) {
+            references.retain(|id| *id != in_reply_to);
+            references.push(in_reply_to);
+        }
+    }
+    references
+}
+
+/// JWZ threading containers: the parent of every Message-ID, stored or not
+/// (https://www.jwz.org/doc/threading.html)
+#[derive(Default)]
+struct Containers {
+    parent: HashMap<String, String>,
+}
+
+impl Containers {
+    fn is_ancestor(&self, ancestor: &str, message_id: &str) -> bool {
+        let mut current = message_id;
+        while let Some(parent) = self.parent.get(current) {
+            if parent == ancestor {
+                return true;
+            }
+            current = parent;
+        }
+        false
+    }
+
+    /// Put `child` under `parent`, unless that would make a loop
+    /// Without `replace` an existing parent is kept
+    fn link(&mut self, parent: &str, child: &str, replace: bool) {
+        if parent == child || self.is_ancestor(child, parent) {
+            return;
+        }
+        if replace || !self.parent.contains_key(child) {
+            self.parent.insert(child.to_string(), parent.to_string());
+        }
+    }
+
+    fn unlink(&mut self, message_id: &str) {
+        self.parent.remove(message_id);
+    }
+
+    /// Topmost ancestor of a message (itself when it has no parent)
+    fn top<'a>(&'a self, message_id: &'a str) -> &'a str {
+        let mut current = message_id;
+        while let Some(parent) = self.parent.get(current) {
+            current = parent;
+        }
+        current
+    }
+
+    /// Nearest stored ancestor of a message, or else its topmost missing ancestor (the phantom)
+    fn resolve(&self, message_id: &str, stored: &HashMap<String, i64>) -> (Option<i64>, Option<String>) {
+        let mut phantom = None;
+        let mut current = message_id;
+        while let Some(parent) = self.parent.get(current) {
+            if let Some(&patch_id) = stored.get(parent) {
+                return (Some(patch_id), None);
+            }
+            phantom = Some(parent.clone());
+            current = parent;
+        }
+        (None, phantom)
+    }
+}
+
+/// Thread whose top-level messages reply to a message missing from the archive
+/// Its root patch is the earliest of `members`; the thread is rerooted on the real
+/// message once that is stored.
+struct PhantomRoot {
+    message_id: String,
+    members: Vec<i64>,
+}
+
 impl DatabaseManager {
     /// Build thread relationships for all patches in database
     /// Improved approach: Handles patch series and nested replies correctly
     /// Uses In-Reply-To and References headers to build complete thread hierarchy
     pub async fn build_thread_relationships(&mut self) -> Result<ThreadBuildStats, Box<dyn std::error::Error>> {
+        self.build_thread_relationships_with_progress(&ThreadingOptions::default(), None::<fn(ThreadBuildProgress)>).await
+    }
+    
+    /// Build thread relationships, reporting each phase through `progress_callback`
+    /// `options` restrict the subject fallback for messages whose references are not stored.
+    /// The callback receives the phase with its processed/total counts and the threads and replies written so far
+    pub async fn build_thread_relationships_with_progress<F>(
+        &mut self,
+        options: &ThreadingOptions,
+        progress_callback: Option<F>
+    ) -> Result<ThreadBuildStats, Box<dyn std::error::Error>>
+    where
+        F: Fn(ThreadBuildProgress) + Send + Sync,
+    {
         let start_time = std::time::Instant::now();
+        let report = |progress: ThreadBuildProgress| {
+            if let Some(callback) = &progress_callback {
+                callback(progress);
+            }
+        };
         
         self.ensure_connected().await?;
         let pool = self.get_pool()?;
         
         println!("Fetching all patches for thread building...");
+        report(ThreadBuildProgress { phase: "fetching".to_string(), ..Default::default() });
         
         // Step 1: Fetch all patches with threading info and series metadata
         let patch_rows = sqlx::query(
             "SELECT patch_id, message_id, subject, sent_at, in_reply_to, thread_references,
-                    is_series, series_number, series_total
+                    is_series, series_number, series_total, is_cover_letter, author_id
              FROM patches 
              ORDER BY sent_at ASC"
         )
@@ -68,6 +209,8 @@ impl DatabaseManager {
             let is_series: bool = row.try_get(6).unwrap_or(false);
             let series_number: Option<i32> = row.try_get(7).ok();
             let series_total: Option<i32> = row.try_get(8).ok();
+            let is_cover_letter: bool = row.try_get(9).unwrap_or(false);
+            let author_id: i64 = row.get(10);
             
             msg_id_to_patch_id.insert(message_id.clone(), patch_id);
             
@@ -80,10 +223,12 @@ impl DatabaseManager {
                 subject: subject.clone(),
                 normalized_subject: normalized_subject.clone(),
                 sent_at,
+                author_id,
                 is_reply,
                 is_series,
                 series_number,
                 series_total,
+                is_cover_letter,
             });
         }
         
@@ -98,7 +243,10 @@ impl DatabaseManager {
         
         // Step 3.5: Build series identifier mapping
         // Extract series identifier (e.g., "v3 net-next 12" from "[PATCH v3 net-next 03/12]")
-        // and map to the earliest patch in that series
+        // and map to the cover letter of that series, or its lowest-numbered patch without one
+        let patch_info_by_id: HashMap<i64, &PatchThreadInfo> = patches_info.iter()
+            .map(|p| (p.patch_id, p))
+            .collect();
         let mut series_to_root: HashMap<String, i64> = HashMap::new();
         for patch_info in &patches_info {
             if patch_info.is_series && patch_info.series_total.is_some() {
@@ -107,11 +255,15 @@ impl DatabaseManager {
                 if let Some(series_id) = extract_series_identifier(&patch_info.subject, patch_info.series_total.unwrap()) {
                     series_to_root.entry(series_id)
                         .and_modify(|root_id| {
-                            // Keep the patch with lowest series_number (or earliest if numbers are same)
-                            if let Some(existing_patch) = patches_info.iter().find(|p| p.patch_id == *root_id) {
-                                let should_replace = match (existing_patch.series_number, patch_info.series_number) {
-                                    (Some(existing_num), Some(new_num)) => new_num < existing_num,
-                                    _ => patch_info.sent_at < existing_patch.sent_at,
+                            // Prefer the cover letter, then the lowest series_number (or earliest if numbers are same)
+                            if let Some(existing_patch) = patch_info_by_id.get(root_id) {
+                                let should_replace = match (existing_patch.is_cover_letter, patch_info.is_cover_letter) {
+                                    (false, true) => true,
+                                    (true, false) => false,
+                                    _ => match (existing_patch.series_number, patch_info.series_number) {
+                                        (Some(existing_num), Some(new_num)) => new_num < existing_num,
+                                        _ => patch_info.sent_at < existing_patch.sent_at,
+                                    },
                                 };
                                 if should_replace {
                                     *root_id = patch_info.patch_id;
@@ -124,81 +276,164 @@ impl DatabaseManager {
         }
         println!("Found {} patch series", series_to_root.len());
         
+        // Step 3.6: JWZ containers: every Message-ID in a References chain gets one, whether
+        // or not the message is stored, so replies to a missing message still share a parent
+        let mut containers = Containers::default();
+        for row in &patch_rows {
+            let chain = reference_chain(row.get(4), row.try_get(5).unwrap_or_default());
+            for pair in chain.windows(2) {
+                containers.link(&pair[0], &pair[1], false);
+            }
+        }
+        // A message's own headers override what other chains said about its parent
+        for row in &patch_rows {
+            let message_id: String = row.get(1);
+            match reference_chain(row.get(4), row.try_get(5).unwrap_or_default()).last() {
+                Some(parent) => containers.link(parent, &message_id, true),
+                None => containers.unlink(&message_id),
+            }
+        }
+        
         // Step 4: Build parent-child relationships for ALL patches (not just "Re:" replies)
         // Patch series members also need to be linked to their parent
         let mut children_map: HashMap<i64, Vec<i64>> = HashMap::new();
         let mut patch_has_parent: HashMap<i64, bool> = HashMap::new();
         
-        for row in &patch_rows {
+        // Messages whose stored ancestors are all missing, by the topmost missing one (the phantom)
+        let mut unresolved: Vec<(i64, Option<String>)> = Vec::new();
+        let mut phantom_of: HashMap<i64, String> = HashMap::new();
+        
+        let total_patches = patch_rows.len() as u32;
+        for (index, row) in patch_rows.iter().enumerate() {
+            if index % PROGRESS_INTERVAL == 0 {
+                report(ThreadBuildProgress {
+                    phase: "linking".to_string(),
+                    processed: index as u32,
+                    total: total_patches,
+                    ..Default::default()
+                });
+            }
             let patch_id: i64 = row.get(0);
-            let subject: String = row.get(2);
+            let message_id: String = row.get(1);
             let in_reply_to: Option<String> = row.get(4);
             let references: Vec<String> = row.try_get(5).unwrap_or_default();
             
-            // Skip patches with no references (potential roots)
-            if in_reply_to.is_none() && references.is_empty() {
-                continue;
-            }
+            let has_references = in_reply_to.is_some() || !references.is_empty();
             
-            // Strategy 1: Try In-Reply-To header (most direct parent)
-            let mut parent_id = if let Some(parent_msg_id) = in_reply_to.as_ref() {
-                msg_id_to_patch_id.get(parent_msg_id).copied()
+            // Strategy 1: Nearest stored ancestor in the containers (In-Reply-To, then the
+            // References of this and every other message)
+            let (parent_id, phantom) = if has_references {
+                containers.resolve(&message_id, &msg_id_to_patch_id)
             } else {
-                None
+                (None, None)
             };
             
-            // Strategy 2: Walk backwards through References to find closest ancestor
-            if parent_id.is_none() && !references.is_empty() {
-                for ref_id in references.iter().rev() {
-                    if let Some(pid) = msg_id_to_patch_id.get(ref_id).copied() {
-                        parent_id = Some(pid);
-                        break;
+            match parent_id {
+                Some(parent) => {
+                    children_map.entry(parent).or_default().push(patch_id);
+                    patch_has_parent.insert(patch_id, true);
+                }
+                None => {
+                    if let Some(phantom) = phantom.clone() {
+                        phantom_of.insert(patch_id, phantom);
                     }
+                    unresolved.push((patch_id, phantom));
                 }
             }
+        }
+        
+        for (patch_id, phantom) in &unresolved {
+            let patch_id = *patch_id;
+            let Some(patch_info) = patch_info_by_id.get(&patch_id).copied() else {
+                continue;
+            };
+            let has_references = phantom.is_some();
+            let series_member_id = Some(patch_info)
+                .filter(|p| p.is_series && !p.is_cover_letter)
+                .and_then(|p| extract_series_identifier(&p.subject, p.series_total?));
+            // Fallbacks must not nest a message under another reply to the same missing
+            // message, or under its own replies
+            let top = containers.top(&patch_info.message_id);
+            let outside_group = |candidate: i64| {
+                patch_info_by_id.get(&candidate)
+                    .is_some_and(|other| containers.top(&other.message_id) != top)
+            };
+            
+            // Skip patches with no references (potential roots), unless they belong to
+            // a series whose cover letter we have: those were sent without threading headers
+            if !has_references {
+                let has_cover_letter = series_member_id.as_ref()
+                    .and_then(|series_id| series_to_root.get(series_id))
+                    .and_then(|root_id| patch_info_by_id.get(root_id))
+                    .is_some_and(|root| root.is_cover_letter);
+                if !has_cover_letter {
+                    continue;
+                }
+            }
+            
+            let mut parent_id = None;
             
-            // Strategy 3: Fall back to subject-based matching
+            // Strategy 2: Fall back to subject-based matching
             // For patches/replies that reference messages not in our database
-            if parent_id.is_none() {
-                let normalized = crate::mail_parser::normalize_subject(&subject);
-                if let Some(candidates) = subject_to_patches.get(&normalized) {
+            if has_references && options.subject_fallback {
+                if let Some(candidates) = subject_to_patches.get(&patch_info.normalized_subject) {
                     // Find the earliest patch with this subject (likely the root)
-                    // that is not the current patch itself
+                    // that is not the current patch itself and passes the options
                     parent_id = candidates.iter()
-                        .filter(|&&pid| pid != patch_id)
+                        .filter(|&&pid| pid != patch_id && outside_group(pid))
+                        .filter(|pid| fallback_allows(options, patch_info, patch_info_by_id[*pid]))
                         .min()
                         .copied();
                 }
             }
             
-            // Strategy 4: For patch series members, link to the series root
-            // This handles cases where the cover letter (00/N) is missing
+            // Strategy 3: For patch series members, link to the series root
+            // (the cover letter when present, otherwise the lowest-numbered patch)
             if parent_id.is_none() {
-                if let Some(patch_info) = patches_info.iter().find(|p| p.patch_id == patch_id) {
-                    if patch_info.is_series && patch_info.series_total.is_some() {
-                        if let Some(series_id) = extract_series_identifier(&subject, patch_info.series_total.unwrap()) {
-                            if let Some(&root_id) = series_to_root.get(&series_id) {
-                                // Don't link to ourselves
-                                if root_id != patch_id {
-                                    parent_id = Some(root_id);
-                                    println!("  Series: {} -> root {} (series: {})", patch_id, root_id, series_id);
-                                }
-                            }
+                if let Some(series_id) = series_member_id.as_ref() {
+                    if let Some(&root_id) = series_to_root.get(series_id) {
+                        // Don't link to ourselves
+                        if root_id != patch_id && outside_group(root_id) {
+                            parent_id = Some(root_id);
+                            println!("  Series: {} -> root {} (series: {})", patch_id, root_id, series_id);
                         }
                     }
                 }
             }
             
             if let Some(parent) = parent_id {
-                children_map.entry(parent).or_insert_with(Vec::new).push(patch_id);
+                children_map.entry(parent).or_default().push(patch_id);
                 patch_has_parent.insert(patch_id, true);
-            } else {
-                // Debug: log patches that couldn't find a parent
-                if in_reply_to.is_some() || !references.is_empty() {
-                    // Safe string truncation at char boundaries
-                    let truncated_subject = subject.chars().take(60).collect::<String>();
-                    println!("  Orphan: {} (has refs but no parent) - {}", patch_id, truncated_subject);
-                }
+                phantom_of.remove(&patch_id);
+            }
+        }
+        
+        // Step 4.5: Replies to the same missing message form one thread under a phantom root,
+        // represented by the earliest of them; a lone reply is simply a root (JWZ pruning)
+        let mut phantom_groups: HashMap<String, Vec<i64>> = HashMap::new();
+        for (&patch_id, phantom) in &phantom_of {
+            phantom_groups.entry(phantom.clone()).or_default().push(patch_id);
+        }
+        let mut phantom_roots: HashMap<i64, PhantomRoot> = HashMap::new();
+        for (message_id, mut members) in phantom_groups {
+            if members.len() < 2 {
+                continue;
+            }
+            members.sort_by_key(|id| (patch_info_by_id[id].sent_at, *id));
+            for &member in &members[1..] {
+                patch_has_parent.insert(member, true);
+            }
+            phantom_roots.insert(members[0], PhantomRoot { message_id, members });
+        }
+        for members in children_map.values_mut() {
+            members.sort_by_key(|id| sibling_order(patch_info_by_id[id]));
+        }
+        for (patch_id, phantom) in &unresolved {
+            // Debug: log patches that couldn't find a parent
+            if phantom.is_some() && !patch_has_parent.contains_key(patch_id) && !phantom_roots.contains_key(patch_id) {
+                // Safe string truncation at char boundaries
+                let truncated_subject = patch_info_by_id[patch_id].subject.chars().take(60).collect::<String>();
+                println!("  Orphan: {} (has refs but no parent) - {}", patch_id, truncated_subject);
             }
         }
         
@@ -229,64 +464,201 @@ impl DatabaseManager {
         let (total_threads, total_replies, max_depth) = self.build_all_threads_batched(
             &root_patches,
             &children_map,
-            pool
+            &phantom_roots,
+            pool,
+            &report
         ).await?;
         
+        // Step 7.5: Retire threads whose root now replies into another thread (an orphan whose
+        // parent arrived), carrying their labels and star over to that thread
+        let absorbed_threads = self.retire_absorbed_threads(pool).await?;
+        if absorbed_threads > 0 {
+            println!("Retired {} threads absorbed into others", absorbed_threads);
+        }
+        
         // Count orphaned patches (patches with references but no parent found)
         let orphaned = patches_info.len() - root_patches.len() - (total_replies as usize);
         
         println!("Thread building complete: {} threads, {} replies, {} orphaned", 
                  total_threads, total_replies, orphaned);
         
+        let built = |phase: &str| ThreadBuildProgress {
+            phase: phase.to_string(),
+            processed: total_threads,
+            total: total_threads,
+            threads: total_threads,
+            replies: total_replies,
+            ..Default::default()
+        };
+        
+        // Step 8: Link the threads of resubmitted versions (v1, v2, ...) into families
+        report(built("families"));
+        let thread_families = self.link_thread_families().await?;
+        
+        // Step 9: Move open threads on now that merges and resubmissions are attached
+        report(built("review-status"));
+        let review_status_changes = self.apply_review_status_rules().await?;
+        
+        // Step 10: Record which patches of each series the merge notifications applied
+        report(built("merge-status"));
+        let applied_patches = crate::database::merges::update_patch_merge_status(self.get_pool()?).await?;
+        
+        // Step 11: Thread statistics on the dashboard (a failure here leaves the threads intact)
+        if let Err(e) = self.refresh_stats().await {
+            println!("Failed to refresh statistics: {}", e);
+        }
+        
         let elapsed = start_time.elapsed();
+        report(built("complete"));
         
         Ok(ThreadBuildStats {
             total_threads,
             total_replies,
             orphaned_messages: orphaned as u32,
             max_depth,
+            phantom_roots: phantom_roots.len() as u32,
+            thread_families,
+            review_status_changes,
+            applied_patches,
             processing_time_ms: elapsed.as_millis() as u64,
         })
     }
     
+    /// Delete the threads whose root patch was placed in another thread by the last build
+    /// Labels and stars move to the thread now holding the root; returns the number deleted
+    async fn retire_absorbed_threads(&self, pool: &Pool<Postgres>) -> Result<u64, Box<dyn std::error::Error>> {
+        let mut tx = pool.begin().await?;
+        
+        sqlx::query(
+            "CREATE TEMP TABLE absorbed_threads ON COMMIT DROP AS
+             SELECT pt.thread_id AS old_thread_id, pr.thread_id AS new_thread_id
+             FROM patch_threads pt
+             JOIN patch_replies pr ON pr.patch_id = pt.root_patch_id
+             WHERE pr.thread_id <> pt.thread_id"
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
+            "INSERT INTO thread_labels (thread_id, label_id, added_at)
+             SELECT a.new_thread_id, tl.label_id, tl.added_at
+             FROM thread_labels tl
+             JOIN absorbed_threads a ON a.old_thread_id = tl.thread_id
+             ON CONFLICT DO NOTHING"
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        sqlx::query(
+            "INSERT INTO thread_bookmarks (thread_id, starred_at)
+             SELECT DISTINCT ON (a.new_thread_id) a.new_thread_id, b.starred_at
+             FROM thread_bookmarks b
+             JOIN absorbed_threads a ON a.old_thread_id = b.thread_id
+             ORDER BY a.new_thread_id, b.starred_at
+             ON CONFLICT DO NOTHING"
+        )
+        .execute(&mut *tx)
+        .await?;
+        
+        let retired = sqlx::query("DELETE FROM patch_threads WHERE thread_id IN (SELECT old_thread_id FROM absorbed_threads)")
+            .execute(&mut *tx)
+            .await?;
+        
+        tx.commit().await?;
+        
+        Ok(retired.rows_affected())
+    }
+    
     /// Build all threads at once with optimized batch inserts
     /// Much faster than building one thread at a time
     async fn build_all_threads_batched(
         &self,
         root_patches: &[&PatchThreadInfo],
         children_map: &HashMap<i64, Vec<i64>>,
-        pool: &Pool<Postgres>
+        phantom_roots: &HashMap<i64, PhantomRoot>,
+        pool: &Pool<Postgres>,
+        report: &(dyn Fn(ThreadBuildProgress) + Sync)
     ) -> Result<(u32, u32, i32), Box<dyn std::error::Error>> {
         if root_patches.is_empty() {
             return Ok((0, 0, 0));
         }
         
-        // Step 1: Batch insert all thread roots
+        // Step 1: Keep thread identity (labels, bookmarks, read state) across rebuilds: a phantom
+        // thread follows its missing message to a new earliest reply, or to the message itself once stored
+        let (carried_message_ids, carried_roots): (Vec<String>, Vec<i64>) = root_patches.iter()
+            .map(|root| {
+                let message_id = phantom_roots.get(&root.patch_id)
+                    .map_or(&root.message_id, |phantom| &phantom.message_id);
+                (message_id.clone(), root.patch_id)
+            })
+            .unzip();
+        sqlx::query(
+            "UPDATE patch_threads pt
+             SET root_patch_id = r.root_patch_id
+             FROM UNNEST($1::TEXT[], $2::BIGINT[]) AS r(message_id, root_patch_id)
+             WHERE pt.root_phantom_message_id = r.message_id
+               AND pt.root_patch_id <> r.root_patch_id
+               AND NOT EXISTS (SELECT 1 FROM patch_threads o WHERE o.root_patch_id = r.root_patch_id)"
+        )
+        .bind(&carried_message_ids)
+        .bind(&carried_roots)
+        .execute(pool)
+        .await?;
+        
+        // Step 2: Batch insert all thread roots
         println!("Inserting {} thread roots...", root_patches.len());
+        let total_threads = root_patches.len() as u32;
+        report(ThreadBuildProgress {
+            phase: "threads".to_string(),
+            total: total_threads,
+            ..Default::default()
+        });
         let mut thread_values = Vec::new();
         let mut param_count = 1;
-        let mut query_str = String::from("INSERT INTO patch_threads (root_patch_id, root_message_id, subject_base) VALUES ");
+        let mut query_str = String::from("INSERT INTO patch_threads (root_patch_id, root_message_id, subject_base, root_phantom_message_id, list_id) VALUES ");
         
         for (i, root) in root_patches.iter().enumerate() {
             if i > 0 {
                 query_str.push(',');
             }
-            query_str.push_str(&format!("(${}, ${}, ${})", param_count, param_count + 1, param_count + 2));
-            param_count += 3;
-            thread_values.push((root.patch_id, &root.message_id, &root.normalized_subject));
+            // Threads inherit the mailing list of their root patch
+            query_str.push_str(&format!("(${}, ${}, ${}, ${}, (SELECT list_id FROM patches WHERE patch_id = ${}))",
+                param_count, param_count + 1, param_count + 2, param_count + 3, param_count));
+            param_count += 4;
+            let phantom_message_id = phantom_roots.get(&root.patch_id).map(|phantom| &phantom.message_id);
+            thread_values.push((root.patch_id, &root.message_id, &root.normalized_subject, phantom_message_id));
         }
         
-        query_str.push_str(" ON CONFLICT (root_patch_id) DO UPDATE SET root_message_id = EXCLUDED.root_message_id, subject_base = EXCLUDED.subject_base");
+        query_str.push_str(" ON CONFLICT (root_patch_id) DO UPDATE SET root_message_id = EXCLUDED.root_message_id, subject_base = EXCLUDED.subject_base, root_phantom_message_id = EXCLUDED.root_phantom_message_id, list_id = EXCLUDED.list_id");
         
         let mut query = sqlx::query(&query_str);
-        for (patch_id, message_id, subject) in &thread_values {
-            query = query.bind(patch_id).bind(*message_id).bind(*subject);
+        for (patch_id, message_id, subject, phantom_message_id) in &thread_values {
+            query = query.bind(patch_id).bind(*message_id).bind(*subject).bind(*phantom_message_id);
         }
         query.execute(pool).await?;
         
-        // Step 2: Get all thread IDs
+        // Threads no longer rooted at a phantom
+        let phantom_root_ids: Vec<i64> = phantom_roots.keys().copied().collect();
+        sqlx::query(
+            "UPDATE patch_threads SET root_phantom_message_id = NULL
+             WHERE root_phantom_message_id IS NOT NULL AND root_patch_id <> ALL($1)"
+        )
+        .bind(&phantom_root_ids)
+        .execute(pool)
+        .await?;
+        
+        // Step 3: Get all thread IDs
         let mut root_to_thread_id: HashMap<i64, i64> = HashMap::new();
-        for root in root_patches {
+        for (index, root) in root_patches.iter().enumerate() {
+            if index > 0 && index % PROGRESS_INTERVAL == 0 {
+                report(ThreadBuildProgress {
+                    phase: "threads".to_string(),
+                    processed: index as u32,
+                    total: total_threads,
+                    threads: index as u32,
+                    ..Default::default()
+                });
+            }
             let row = sqlx::query("SELECT thread_id FROM patch_threads WHERE root_patch_id = $1")
                 .bind(root.patch_id)
                 .fetch_one(pool)
@@ -295,98 +667,146 @@ impl DatabaseManager {
             root_to_thread_id.insert(root.patch_id, thread_id);
         }
         
-        // Step 3: Build all patch_replies data in parallel
+        // Step 4: Build all patch_replies data in parallel, the roots partitioned across the rayon pool
         println!("Building thread hierarchies...");
-        let root_patch_ids: Vec<i64> = root_patches.iter().map(|r| r.patch_id).collect();
+        // Top-level messages of each thread: the root, or every reply to the phantom root
+        let thread_tops: Vec<(i64, Vec<i64>)> = root_patches.iter()
+            .map(|root| {
+                let tops = phantom_roots.get(&root.patch_id)
+                    .map_or_else(|| vec![root.patch_id], |phantom| phantom.members.clone());
+                (root.patch_id, tops)
+            })
+            .collect();
         
         let all_replies = tokio::task::spawn_blocking({
             let children_map = children_map.clone();
             let root_to_thread_id = root_to_thread_id.clone();
             
             move || {
-                let mut all_replies = Vec::new();
-                let mut max_depth = 0i32;
-                
-                for root_id in root_patch_ids {
-                    let thread_id = *root_to_thread_id.get(&root_id).unwrap();
-                    
-                    // Add root
-                    all_replies.push((thread_id, root_id, None, 0i32, vec![root_id]));
-                    
-                    // BFS through children
-                    let mut queue = VecDeque::new();
-                    queue.push_back((root_id, 0i32, vec![root_id]));
-                    
-                    while let Some((current_patch_id, depth, path)) = queue.pop_front() {
-                        if let Some(children) = children_map.get(&current_patch_id) {
-                            for &child_id in children {
-                                let new_depth = depth + 1;
-                                max_depth = max_depth.max(new_depth);
-                                let mut new_path = path.clone();
-                                new_path.push(child_id);
-                                
-                                all_replies.push((thread_id, child_id, Some(current_patch_id), new_depth, new_path.clone()));
-                                queue.push_back((child_id, new_depth, new_path));
+                let threads: Vec<(Vec<ReplyRow>, i32)> = thread_tops.into_par_iter()
+                    .map(|(root_id, tops)| {
+                        let thread_id = *root_to_thread_id.get(&root_id).unwrap();
+                        let mut replies = Vec::new();
+                        
+                        // Add the top-level messages; below a phantom root they are at depth 1
+                        let top_depth = if tops.len() > 1 { 1 } else { 0 };
+                        let mut max_depth = top_depth;
+                        let mut queue = VecDeque::new();
+                        // Positions follow the BFS, so siblings keep the order of children_map
+                        for top_id in tops {
+                            replies.push((thread_id, top_id, None, top_depth, replies.len() as i32, vec![top_id]));
+                            queue.push_back((top_id, top_depth, vec![top_id]));
+                        }
+                        
+                        // BFS through children
+                        
+                        while let Some((current_patch_id, depth, path)) = queue.pop_front() {
+                            if let Some(children) = children_map.get(&current_patch_id) {
+                                for &child_id in children {
+                                    let new_depth = depth + 1;
+                                    max_depth = max_depth.max(new_depth);
+                                    let mut new_path = path.clone();
+                                    new_path.push(child_id);
+                                    
+                                    replies.push((thread_id, child_id, Some(current_patch_id), new_depth, replies.len() as i32, new_path.clone()));
+                                    queue.push_back((child_id, new_depth, new_path));
+                                }
                             }
                         }
-                    }
-                }
+                        
+                        (replies, max_depth)
+                    })
+                    .collect();
                 
+                let max_depth = threads.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+                let all_replies: Vec<ReplyRow> = threads.into_iter().flat_map(|(replies, _)| replies).collect();
                 (all_replies, max_depth)
             }
         }).await?;
         
         let (all_replies, max_depth) = all_replies;
         
-        // Step 4: Batch insert all patch_replies
+        // Step 5: Batch insert all patch_replies, several batches at a time (bounded by a semaphore)
         println!("Inserting {} patch replies in batches...", all_replies.len());
-        const BATCH_SIZE: usize = 5000;
+        let total_rows = all_replies.len() as u32;
+        let rows_written = AtomicU32::new(0);
+        let permits = Semaphore::new(THREAD_INSERT_CONCURRENCY);
         
-        for batch in all_replies.chunks(BATCH_SIZE) {
-            let mut query_str = String::from("INSERT INTO patch_replies (thread_id, patch_id, parent_patch_id, depth_level, position_in_thread, thread_path) VALUES ");
-            let mut param_count = 1;
-            
-            for (i, _) in batch.iter().enumerate() {
-                if i > 0 {
-                    query_str.push(',');
+        let inserts = all_replies.chunks(DB_INSERT_BATCH_SIZE).enumerate().map(|(batch_index, batch)| {
+            let (rows_written, permits) = (&rows_written, &permits);
+            async move {
+                let _permit = permits.acquire().await.expect("insert semaphore is never closed");
+                let written = rows_written.load(Ordering::Relaxed);
+                report(ThreadBuildProgress {
+                    phase: "replies".to_string(),
+                    processed: written,
+                    total: total_rows,
+                    batch: Some(batch_index as u32 + 1),
+                    threads: total_threads,
+                    // Rows written so far include the thread roots
+                    replies: written.saturating_sub(total_threads),
+                });
+                let mut query_str = String::from("INSERT INTO patch_replies (thread_id, patch_id, parent_patch_id, depth_level, position_in_thread, thread_path) VALUES ");
+                let mut param_count = 1;
+                
+                for (i, _) in batch.iter().enumerate() {
+                    if i > 0 {
+                        query_str.push(',');
+                    }
+                    query_str.push_str(&format!("(${}, ${}, ${}, ${}, ${}, ${})", 
+                        param_count, param_count + 1, param_count + 2, param_count + 3, param_count + 4, param_count + 5));
+                    param_count += 6;
                 }
-                query_str.push_str(&format!("(${}, ${}, ${}, ${}, ${}, ${})", 
-                    param_count, param_count + 1, param_count + 2, param_count + 3, param_count + 4, param_count + 5));
-                param_count += 6;
-            }
-            
-            let mut query = sqlx::query(&query_str);
-            for (thread_id, patch_id, parent_patch_id, depth, path) in batch {
-                query = query
-                    .bind(thread_id)
-                    .bind(patch_id)
-                    .bind(parent_patch_id)
-                    .bind(depth)
-                    .bind(0i32) // position_in_thread - placeholder
-                    .bind(path);
+                
+                let mut query = sqlx::query(&query_str);
+                for (thread_id, patch_id, parent_patch_id, depth, position, path) in batch {
+                    query = query
+                        .bind(thread_id)
+                        .bind(patch_id)
+                        .bind(parent_patch_id)
+                        .bind(depth)
+                        .bind(position)
+                        .bind(path);
+                }
+                
+                query.execute(pool).await?;
+                rows_written.fetch_add(batch.len() as u32, Ordering::Relaxed);
+                Ok::<_, sqlx::Error>(())
             }
-            
-            query.execute(pool).await?;
-        }
+        });
+        futures::future::try_join_all(inserts).await?;
         
-        // Step 5: Calculate and update thread statistics in bulk
+        // Step 6: Calculate and update thread statistics in bulk
         println!("Calculating thread statistics...");
+        report(ThreadBuildProgress {
+            phase: "statistics".to_string(),
+            processed: total_threads,
+            total: total_threads,
+            threads: total_threads,
+            replies: total_rows - total_threads,
+            ..Default::default()
+        });
         
         // Use single query to update all thread statistics at once
         sqlx::query(
             "UPDATE patch_threads pt
              SET reply_count = subq.reply_count,
                  participant_count = subq.participant_count,
+                 has_rejection = subq.has_rejection,
                  updated_at = NOW(),
                  last_activity_at = subq.last_activity
              FROM (
                SELECT 
                  pr.thread_id,
-                 COUNT(*) FILTER (WHERE pr.parent_patch_id IS NOT NULL) as reply_count,
+                 COUNT(*) FILTER (WHERE pr.patch_id <> t.root_patch_id) as reply_count,
                  COUNT(DISTINCT p.author_id) as participant_count,
-                 MAX(p.sent_at) as last_activity
+                 MAX(p.sent_at) as last_activity,
+                 -- Rejections by the author themselves don't count
+                 COALESCE(BOOL_OR(p.is_rejection AND p.author_id <> rp.author_id), FALSE) as has_rejection
                FROM patch_replies pr
                JOIN patches p ON pr.patch_id = p.patch_id
+               JOIN patch_threads t ON pr.thread_id = t.thread_id
+               JOIN patches rp ON t.root_patch_id = rp.patch_id
                GROUP BY pr.thread_id
              ) subq
              WHERE pt.thread_id = subq.thread_id"
@@ -394,8 +814,7 @@ impl DatabaseManager {
         .execute(pool)
         .await?;
         
-        let total_threads = root_patches.len() as u32;
-        let total_replies = all_replies.len() as u32 - total_threads; // Subtract roots
+        let total_replies = total_rows - total_threads; // Subtract roots
         
         Ok((total_threads, total_replies, max_depth))
     }
@@ -414,11 +833,12 @@ impl DatabaseManager {
     ) -> Result<(u32, i32), Box<dyn std::error::Error>> {
         // Create thread entry
         let thread_row = sqlx::query(
-            "INSERT INTO patch_threads (root_patch_id, root_message_id, subject_base)
-             VALUES ($1, $2, $3)
+            "INSERT INTO patch_threads (root_patch_id, root_message_id, subject_base, list_id)
+             VALUES ($1, $2, $3, (SELECT list_id FROM patches WHERE patch_id = $1))
              ON CONFLICT (root_patch_id) DO UPDATE 
              SET root_message_id = EXCLUDED.root_message_id,
-                 subject_base = EXCLUDED.subject_base
+                 subject_base = EXCLUDED.subject_base,
+                 list_id = EXCLUDED.list_id
              RETURNING thread_id"
         )
         .bind(root_patch_id)
@@ -527,3 +947,113 @@ impl DatabaseManager {
         Ok((reply_count, max_depth))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_REFERENCES: &[&str] = &[];
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    /// Containers for (message_id, in_reply_to, references), linked the way thread building does
+    fn link_messages(messages: &[(&str, Option<&str>, &[&str])]) -> Containers {
+        let chains: Vec<_> = messages.iter()
+            .map(|(id, in_reply_to, references)| (*id, reference_chain(in_reply_to.map(str::to_string), ids(references))))
+            .collect();
+        let mut containers = Containers::default();
+        for (_, chain) in &chains {
+            for pair in chain.windows(2) {
+                containers.link(&pair[0], &pair[1], false);
+            }
+        }
+        for (id, chain) in &chains {
+            match chain.last() {
+                Some(parent) => containers.link(parent, id, true),
+                None => containers.unlink(id),
+            }
+        }
+        containers
+    }
+
+    #[test]
+    fn reference_chain_ends_with_in_reply_to() {
+        assert_eq!(reference_chain(Some("c".into()), ids(&["a", "b"])), ids(&["a", "b", "c"]));
+        assert_eq!(reference_chain(Some("b".into()), ids(&["a", "b"])), ids(&["a", "b"]));
+        // In-Reply-To wins over its position in a reordered References header
+        assert_eq!(reference_chain(Some("a".into()), ids(&["a", "b"])), ids(&["b", "a"]));
+        assert_eq!(reference_chain(None, ids(&["a", "b"])), ids(&["a", "b"]));
+        assert!(reference_chain(None, Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn missing_parent_becomes_phantom() {
+        // Two replies to "root", which isn't stored
+        let containers = link_messages(&[
+            ("r1", Some("root"), &["root"]),
+            ("r2", Some("root"), &["root"]),
+        ]);
+        let stored = HashMap::from([("r1".to_string(), 1), ("r2".to_string(), 2)]);
+        assert_eq!(containers.resolve("r1", &stored), (None, Some("root".to_string())));
+        assert_eq!(containers.resolve("r2", &stored), (None, Some("root".to_string())));
+        assert_eq!(containers.top("r1"), containers.top("r2"));
+    }
+
+    #[test]
+    fn several_phantom_levels_resolve_to_nearest_stored_or_topmost() {
+        // root (stored) <- m1 (missing) <- m2 (missing) <- leaf (stored)
+        let containers = link_messages(&[
+            ("root", None, NO_REFERENCES),
+            ("leaf", Some("m2"), &["root", "m1", "m2"]),
+        ]);
+        let stored = HashMap::from([("root".to_string(), 1), ("leaf".to_string(), 4)]);
+        assert_eq!(containers.resolve("leaf", &stored), (Some(1), None));
+        assert_eq!(containers.top("leaf"), "root");
+
+        // Without the root, the topmost missing message is the phantom
+        let stored = HashMap::from([("leaf".to_string(), 4)]);
+        assert_eq!(containers.resolve("leaf", &stored), (None, Some("root".to_string())));
+    }
+
+    #[test]
+    fn reference_cycles_are_not_linked() {
+        // a and b claim each other as parent
+        let containers = link_messages(&[
+            ("a", Some("b"), &["b"]),
+            ("b", Some("a"), &["a"]),
+        ]);
+        assert!(!(containers.is_ancestor("a", "b") && containers.is_ancestor("b", "a")));
+        // Walking up terminates
+        let top = containers.top("a");
+        assert!(top == "a" || top == "b");
+
+        let mut containers = Containers::default();
+        containers.link("a", "a", true);
+        assert!(containers.parent.is_empty());
+        containers.link("a", "b", false);
+        containers.link("b", "c", false);
+        containers.link("c", "a", true);
+        assert_eq!(containers.top("c"), "a");
+        assert!(!containers.parent.contains_key("a"));
+    }
+
+    #[test]
+    fn own_headers_override_other_chains() {
+        // "x" is listed under "a" in another message's References, but its own header says "b"
+        let containers = link_messages(&[
+            ("y", Some("x"), &["a", "x"]),
+            ("x", Some("b"), &["b"]),
+        ]);
+        assert_eq!(containers.parent.get("x").map(String::as_str), Some("b"));
+        assert_eq!(containers.top("y"), "b");
+
+        // A message without threading headers is a top, whatever others say
+        let containers = link_messages(&[
+            ("y", Some("x"), &["a", "x"]),
+            ("x", None, NO_REFERENCES),
+        ]);
+        assert_eq!(containers.top("y"), "x");
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+use serde::Serialize;
+use sqlx::migrate::Migrate;
+use thiserror::Error;
+use crate::database::schema::load_migrator;
+use crate::database::{DatabaseManager, SchemaVersion};
+
+/// Why the connected database's schema can't be used by this build
+/// Each message names the command that resolves it
+#[derive(Error, Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SchemaError {
+    #[error("Database schema is not initialized (this build expects version {expected}); run setup_database to create it")]
+    Uninitialized { expected: i64 },
+    #[error("Database schema version {current} is older than version {expected} required by this build; run migrate_database to apply {} pending migration(s)", pending.len())]
+    Outdated {
+        current: i64,
+        expected: i64,
+        pending: Vec<String>,
+    },
+    #[error("Database schema version {current} is newer than this build supports (version {supported}); upgrade the application")]
+    TooNew { current: i64, supported: i64 },
+    #[error("Migration {version} did not complete; restore a backup with import_database or repair the schema by hand")]
+    Dirty { version: i64 },
+}
+
+impl DatabaseManager {
+    /// Compare the applied migrations (the `_sqlx_migrations` table) with the ones
+    /// this build ships, and remember the outcome
+    ///
+    /// While the schema is incompatible, `get_pool` refuses with the stored
+    /// `SchemaError` instead of letting queries fail on missing columns.
+    /// Runs on every connect and after migrating.
+    pub async fn check_schema_version(&mut self) -> Result<SchemaVersion, Box<dyn std::error::Error>> {
+        let pool = self.get_pool_unchecked()?;
+        let migrator = load_migrator().await?;
+        let known: Vec<_> = migrator.iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .collect();
+        let expected = known.iter().map(|m| m.version).max().unwrap_or(0);
+
+        let has_history: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+        let (applied, dirty) = if has_history {
+            let mut conn = pool.acquire().await?;
+            let applied: HashSet<i64> = conn.list_applied_migrations().await?
+                .into_iter()
+                .map(|m| m.version)
+                .collect();
+            (applied, conn.dirty_version().await?)
+        } else {
+            (HashSet::new(), None)
+        };
+
+        let current = applied.iter().copied().max();
+        let pending: Vec<String> = known.iter()
+            .filter(|m| !applied.contains(&m.version))
+            .map(|m| format!("{:04}_{}", m.version, m.description.replace(' ', "_")))
+            .collect();
+
+        let error = if let Some(version) = dirty {
+            Some(SchemaError::Dirty { version })
+        } else if let Some(current) = current {
+            if applied.iter().any(|v| !known.iter().any(|m| m.version == *v)) {
+                Some(SchemaError::TooNew { current, supported: expected })
+            } else if !pending.is_empty() {
+                Some(SchemaError::Outdated { current, expected, pending })
+            } else {
+                None
+            }
+        } else {
+            Some(SchemaError::Uninitialized { expected })
+        };
+
+        if let Some(error) = &error {
+            eprintln!("Schema check failed: {}", error);
+        }
+        self.schema_error = error.clone();
+
+        Ok(SchemaVersion {
+            current_version: current,
+            expected_version: expected,
+            compatible: error.is_none(),
+            error,
+        })
+    }
+
+    /// Schema problem found by the last `check_schema_version`, if any
+    pub fn schema_error(&self) -> Option<&SchemaError> {
+        self.schema_error.as_ref()
+    }
+}
@@ -1,5 +1,6 @@
 /// Database API for frontend - handles translation between DB schema and frontend needs
-use serde::Serialize;
+use mailparse::MailHeaderMap;
+use serde::{Deserialize, Serialize};
 use sqlx::Row;
 use std::collections::HashMap;
 use crate::database::DatabaseManager;
@@ -38,49 +39,80 @@ pub struct DatabaseStats {
     pub total_emails: i64,
     pub unique_email_addresses: i64,
     pub patches_with_series: i64,
+    pub bot_messages: i64,
+    pub ping_messages: i64,
     pub top_contributors: Vec<TopContributor>,
     pub recent_activity: Vec<ActivityDay>,
+    pub thread_stats: ThreadStats,
+    pub refreshed_at: String,  // When the statistics views were last refreshed
 }
 
 #[derive(Debug, Serialize)]
 pub struct TopContributor {
+    pub author_id: i64,
     pub display_name: String,
-    pub patch_count: i32,
+    pub patch_count: i32,  // All messages, replies included
+    pub patches_sent: i32,
+    pub replies_sent: i32,
+    pub last_active: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ActivityDay {
     pub date: String,
-    pub patch_count: i64,
+    pub patch_count: i64,  // All messages, replies included
+    pub patches_sent: i64,
+    pub replies_sent: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThreadStats {
+    pub total_threads: i64,
+    pub answered_threads: i64,
+    pub merged_threads: i64,
+    pub avg_reply_count: f64,
+    pub avg_participant_count: f64,
 }
 
 /// Get all authors with their email addresses
-pub async fn get_authors_with_emails(db: &mut DatabaseManager) -> Result<Vec<AuthorInfo>, Box<dyn std::error::Error>> {
+/// Without `limit` the page holds every author
+pub async fn get_authors_with_emails(
+    db: &mut DatabaseManager,
+    limit: Option<usize>,
+    offset: Option<usize>
+) -> Result<crate::database::Page<AuthorInfo>, Box<dyn std::error::Error>> {
     db.ensure_connected().await?;
     let pool = db.get_pool()?;
+    let offset = offset.unwrap_or(0) as i64;
     
     // Use a single query with array_agg to get all authors and their emails at once
     // This is much faster than N+1 queries (one per author)
-    let rows = sqlx::query(
-        "SELECT 
-            a.author_id,
-            a.display_name,
-            a.first_name,
-            a.last_name,
-            a.first_seen,
-            a.patch_count,
-            COALESCE(
-                array_agg(ae.email ORDER BY ae.is_primary DESC, ae.email) 
-                FILTER (WHERE ae.email IS NOT NULL),
-                ARRAY[]::TEXT[]
-            ) as emails
-        FROM authors a
-        LEFT JOIN author_emails ae ON a.author_id = ae.author_id
-        GROUP BY a.author_id, a.display_name, a.first_name, a.last_name, a.first_seen, a.patch_count
-        ORDER BY a.patch_count DESC"
-    )
-    .fetch_all(pool)
-    .await?;
+    // The total is counted separately so pages past the end still report it
+    let (rows, total_count): (Vec<sqlx::postgres::PgRow>, i64) = futures::try_join!(
+        sqlx::query(
+            "SELECT 
+                a.author_id,
+                a.display_name,
+                a.first_name,
+                a.last_name,
+                a.first_seen,
+                a.patch_count,
+                COALESCE(
+                    array_agg(ae.email ORDER BY ae.is_primary DESC, ae.email) 
+                    FILTER (WHERE ae.email IS NOT NULL),
+                    ARRAY[]::TEXT[]
+                ) as emails
+            FROM authors a
+            LEFT JOIN author_emails ae ON a.author_id = ae.author_id
+            GROUP BY a.author_id, a.display_name, a.first_name, a.last_name, a.first_seen, a.patch_count
+            ORDER BY a.patch_count DESC, a.author_id
+            LIMIT $1 OFFSET $2"
+        )
+        .bind(limit.map(|limit| limit as i64))
+        .bind(offset)
+        .fetch_all(pool),
+        sqlx::query_scalar("SELECT COUNT(*) FROM authors").fetch_one(pool)
+    )?;
     
     let author_infos: Vec<AuthorInfo> = rows.iter().map(|row| {
         AuthorInfo {
@@ -94,16 +126,22 @@ pub async fn get_authors_with_emails(db: &mut DatabaseManager) -> Result<Vec<Aut
         }
     }).collect();
     
-    Ok(author_infos)
+    Ok(crate::database::Page {
+        has_more: offset + (author_infos.len() as i64) < total_count,
+        items: author_infos,
+        total_count,
+    })
 }
 
 /// Search patches by author and return frontend-friendly format
 pub async fn search_patches_for_frontend(
     db: &mut DatabaseManager,
     author_pattern: &str,
-    limit: Option<usize>
+    limit: Option<usize>,
+    list_id: Option<i32>,
+    dates: crate::database::DateRange
 ) -> Result<Vec<EmailInfo>, Box<dyn std::error::Error>> {
-    let results = db.search_patches_by_author(author_pattern, limit).await?;
+    let results = db.search_patches_by_author(author_pattern, limit, list_id, dates).await?;
     
     let mut emails = Vec::new();
     for (patch, author) in results {
@@ -134,6 +172,17 @@ pub async fn search_patches_for_frontend(
             to: "bpf@vger.kernel.org".to_string(),
             date: patch.sent_at.to_rfc3339(),
             message_id: patch.message_id,
+            diff: patch.body_text.as_deref().and_then(crate::diff_parser::parse_diff),
+            git_patch_id: patch.body_text.as_deref().and_then(crate::diff_parser::compute_patch_id),
+            trailers: patch.body_text.as_deref().map(crate::mail_parser::extract_trailers).unwrap_or_default(),
+            fixes: patch.body_text.as_deref().map(crate::mail_parser::extract_fixes_tags).unwrap_or_default(),
+            commit_refs: patch.body_text.as_deref().map(crate::mail_parser::extract_commit_refs).unwrap_or_default(),
+            links: patch.body_text.as_deref().map(crate::mail_parser::extract_message_links).unwrap_or_default(),
+            envelope_from: None,    // Not stored in legacy query
+            recipients: Vec::new(), // Not stored in legacy query
+            list_ids: Vec::new(),   // Not stored in legacy query
+            patchwork: Default::default(), // Not stored in legacy query
+            signature: patch.body_text.as_deref().and_then(|body| crate::mail_parser::split_signature(body).1).map(str::to_string),
             body: patch.body_text.unwrap_or_default(),
             headers: std::collections::HashMap::new(),
             in_reply_to: None,      // Not stored in legacy query
@@ -145,213 +194,1872 @@ pub async fn search_patches_for_frontend(
     Ok(emails)
 }
 
-/// Get comprehensive database statistics
-pub async fn get_enhanced_stats(db: &mut DatabaseManager) -> Result<DatabaseStats, Box<dyn std::error::Error>> {
+/// Patch posting matched by its subject tags
+#[derive(Debug, Serialize)]
+pub struct TaggedPatch {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    pub version: Option<i32>,
+    pub is_rfc: bool,
+    pub is_resend: bool,
+    pub target_tree: Option<String>,
+}
+
+/// Filters on parsed subject tags; unset fields match everything
+#[derive(Debug, Default, Deserialize)]
+pub struct SubjectTagFilter {
+    pub version: Option<i32>,
+    pub target_tree: Option<String>,
+    pub is_rfc: Option<bool>,
+    pub is_resend: Option<bool>,
+}
+
+/// Find patch postings by version, RFC/RESEND flags and target tree
+/// e.g. all v3 postings to bpf-next. Replies are excluded.
+pub async fn search_patches_by_subject_tags(
+    db: &mut DatabaseManager,
+    filter: &SubjectTagFilter,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<TaggedPatch>, Box<dyn std::error::Error>> {
     db.ensure_connected().await?;
     let pool = db.get_pool()?;
     
-    // Combine all COUNT queries into a single query for better performance
-    let stats_row = sqlx::query(
-        "SELECT 
-            (SELECT COUNT(*) FROM authors) as total_authors,
-            (SELECT COUNT(*) FROM patches) as total_patches,
-            (SELECT COUNT(*) FROM author_emails) as total_emails,
-            (SELECT COUNT(*) FROM patches WHERE is_series = true) as patches_with_series"
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at,
+                p.patch_version, p.is_rfc, p.is_resend, p.target_tree
+         FROM patches p
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE p.is_reply = FALSE
+           AND NOT p.is_deleted
+           AND p.patch_version IS NOT NULL
+           AND ($1::INT IS NULL OR p.patch_version = $1)
+           AND ($2::TEXT IS NULL OR p.target_tree = LOWER($2))
+           AND ($3::BOOLEAN IS NULL OR p.is_rfc = $3)
+           AND ($4::BOOLEAN IS NULL OR p.is_resend = $4)
+           AND ($5::INT IS NULL OR p.list_id = $5)
+         ORDER BY p.sent_at DESC
+         LIMIT $6"
     )
-    .fetch_one(pool)
+    .bind(filter.version)
+    .bind(&filter.target_tree)
+    .bind(filter.is_rfc)
+    .bind(filter.is_resend)
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
     .await?;
     
-    let total_authors: i64 = stats_row.get(0);
-    let total_patches: i64 = stats_row.get(1);
-    let total_emails: i64 = stats_row.get(2);
-    let patches_with_series: i64 = stats_row.get(3);
+    Ok(rows.iter().map(|row| TaggedPatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        version: row.get(4),
+        is_rfc: row.try_get(5).unwrap_or(false),
+        is_resend: row.try_get(6).unwrap_or(false),
+        target_tree: row.get(7),
+    }).collect())
+}
+
+/// Email matched by a full-text query
+#[derive(Debug, Serialize)]
+pub struct FulltextMatch {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    pub is_reply: bool,
+    pub thread_id: Option<i64>,
+    /// Subject of the thread's root message
+    pub thread_subject: Option<String>,
+    /// ts_rank score, subject hits weigh more than body hits
+    pub rank: f32,
+    /// Body excerpt with the matched terms wrapped in <b></b>
+    pub snippet: String,
+}
+
+/// Full-text search over subjects and bodies, best matches first
+/// `query` uses web search syntax: "quoted phrases", OR, and -excluded terms.
+/// `offset` skips that many matches, for fetching the following pages.
+pub async fn search_patches_fulltext(
+    db: &mut DatabaseManager,
+    query: &str,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<FulltextMatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
     
-    // Top 10 contributors
-    let top_rows = sqlx::query(
-        "SELECT display_name, patch_count FROM authors ORDER BY patch_count DESC LIMIT 10"
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    // Rank with the GIN index first, then build snippets for the page only
+    let rows = sqlx::query(
+        "WITH q AS (SELECT websearch_to_tsquery('english', $1) AS query),
+         ranked AS (
+             SELECT p.patch_id, ts_rank(p.search_vector, q.query) AS rank
+             FROM patches p, q
+             WHERE p.search_vector @@ q.query
+               AND NOT p.is_deleted
+               AND ($2::INT IS NULL OR p.list_id = $2)
+             ORDER BY rank DESC, p.sent_at DESC, p.patch_id
+             LIMIT $3 OFFSET $4
+         )
+         SELECT p.patch_id, p.subject, a.display_name, p.sent_at, p.is_reply, pr.thread_id, r.rank,
+                ts_headline('english', left(coalesce(p.body_text, ''), 200000), q.query,
+                            'MaxFragments=2, MaxWords=30, MinWords=10'),
+                rp.subject
+         FROM ranked r
+         CROSS JOIN q
+         JOIN patches p ON p.patch_id = r.patch_id
+         JOIN authors a ON p.author_id = a.author_id
+         LEFT JOIN patch_replies pr ON pr.patch_id = p.patch_id
+         LEFT JOIN patch_threads pt ON pt.thread_id = pr.thread_id
+         LEFT JOIN patches rp ON rp.patch_id = pt.root_patch_id
+         ORDER BY r.rank DESC, p.sent_at DESC, p.patch_id"
     )
+    .bind(query.trim())
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .bind(offset.unwrap_or(0) as i64)
     .fetch_all(pool)
     .await?;
     
-    let top_contributors: Vec<TopContributor> = top_rows.iter().map(|row| TopContributor {
-        display_name: row.get(0),
-        patch_count: row.get(1),
-    }).collect();
+    Ok(rows.iter().map(|row| FulltextMatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        is_reply: row.try_get(4).unwrap_or(false),
+        thread_id: row.get(5),
+        thread_subject: row.get(8),
+        rank: row.get(6),
+        snippet: row.get(7),
+    }).collect())
+}
+
+/// Author whose name is close to a fuzzy query
+#[derive(Debug, Serialize)]
+pub struct FuzzyAuthorMatch {
+    pub author_id: i64,
+    pub display_name: String,
+    pub patch_count: i32,
+    /// Trigram similarity between 0 and 1
+    pub similarity: f32,
+}
+
+/// Patch whose subject contains a near match of a fuzzy query
+#[derive(Debug, Serialize)]
+pub struct FuzzySubjectMatch {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    /// Trigram word similarity between 0 and 1
+    pub similarity: f32,
+}
+
+/// Default cut-offs, matching pg_trgm's own thresholds for each operator
+const DEFAULT_AUTHOR_SIMILARITY: f32 = 0.3;
+const DEFAULT_SUBJECT_SIMILARITY: f32 = 0.6;
+
+/// Find authors by approximate name, e.g. "Alexey Starovoytov" finds "Alexei Starovoitov"
+pub async fn fuzzy_search_authors(
+    db: &mut DatabaseManager,
+    query: &str,
+    min_similarity: Option<f32>,
+    limit: Option<usize>
+) -> Result<Vec<FuzzyAuthorMatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
     
-    // Recent activity (last 30 days)
-    let activity_rows = sqlx::query(
-        "SELECT DATE(sent_at) as day, COUNT(*) as count 
-         FROM patches 
-         WHERE sent_at > NOW() - INTERVAL '30 days'
-         GROUP BY DATE(sent_at)
-         ORDER BY day DESC
-         LIMIT 30"
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    // The % operator uses the trigram index; its threshold is a setting, scoped to this transaction
+    let mut tx = pool.begin().await?;
+    sqlx::query("SELECT set_config('pg_trgm.similarity_threshold', $1, true)")
+        .bind(min_similarity.unwrap_or(DEFAULT_AUTHOR_SIMILARITY).to_string())
+        .execute(&mut *tx)
+        .await?;
+    
+    let rows = sqlx::query(
+        "SELECT author_id, display_name, COALESCE(patch_count, 0), similarity(display_name, $1)
+         FROM authors
+         WHERE display_name % $1
+         ORDER BY similarity(display_name, $1) DESC, patch_count DESC
+         LIMIT $2"
     )
-    .fetch_all(pool)
+    .bind(query.trim())
+    .bind(limit.unwrap_or(20) as i64)
+    .fetch_all(&mut *tx)
     .await?;
+    tx.commit().await?;
     
-    let recent_activity: Vec<ActivityDay> = activity_rows.iter().map(|row| {
-        let date: chrono::NaiveDate = row.get(0);
-        ActivityDay {
-            date: date.to_string(),
-            patch_count: row.get(1),
-        }
-    }).collect();
-    
-    Ok(DatabaseStats {
-        total_authors,
-        total_patches,
-        total_emails,
-        unique_email_addresses: total_emails,
-        patches_with_series,
-        top_contributors,
-        recent_activity,
-    })
+    Ok(rows.iter().map(|row| FuzzyAuthorMatch {
+        author_id: row.get(0),
+        display_name: row.get(1),
+        patch_count: row.get(2),
+        similarity: row.get(3),
+    }).collect())
 }
 
-// Threading API
+/// Longest subject regex accepted
+const MAX_SUBJECT_REGEX_LEN: usize = 256;
+/// Statement timeout of regex searches; patterns without literal text can't use the trigram index
+const SUBJECT_REGEX_TIMEOUT_MS: u64 = 5000;
 
-#[derive(Debug, Serialize, Clone)]
-pub struct ThreadSummary {
-    pub thread_id: i64,
-    pub root_subject: String,
-    pub root_author: String,
-    pub reply_count: i32,
-    pub participant_count: i32,
-    pub created_at: String,
-    pub last_activity: String,
-    pub root_patch_id: i64,
-    pub merge_status: Option<MergeStatusInfo>,
+/// Check a case-insensitive subject regex (Postgres `~*` syntax) and limit how long
+/// the statements of `tx` may run
+async fn prepare_subject_regex(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    pattern: &str
+) -> Result<(), Box<dyn std::error::Error>> {
+    if pattern.len() > MAX_SUBJECT_REGEX_LEN {
+        return Err(format!("Regex is longer than {} characters", MAX_SUBJECT_REGEX_LEN).into());
+    }
+    sqlx::query("SELECT '' ~* $1")
+        .bind(pattern)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) => format!("Invalid regex: {}", db_error.message()),
+            None => e.to_string(),
+        })?;
+    sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+        .bind(SUBJECT_REGEX_TIMEOUT_MS.to_string())
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct MergeStatusInfo {
-    pub is_merged: bool,
-    pub merge_date: String,
-    pub repository: String,
-    pub branch: String,
-    pub applied_by: String,
-    pub commit_count: i32,
+/// Turn a statement timeout of a regex search into a readable error
+fn subject_regex_error(e: sqlx::Error) -> Box<dyn std::error::Error> {
+    match e.as_database_error().and_then(|db_error| db_error.code()) {
+        Some(code) if code == "57014" => format!(
+            "Regex search took longer than {}s, add literal text to the pattern",
+            SUBJECT_REGEX_TIMEOUT_MS / 1000
+        ).into(),
+        _ => e.into(),
+    }
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct ThreadNode {
+/// Find patch postings whose subject nearly contains `query`, tolerating typos
+/// With `regex` the query is a case-insensitive regular expression instead, and every
+/// match has similarity 1. Replies are excluded, their subjects repeat the original posting's
+pub async fn fuzzy_search_subjects(
+    db: &mut DatabaseManager,
+    query: &str,
+    min_similarity: Option<f32>,
+    limit: Option<usize>,
+    list_id: Option<i32>,
+    regex: bool
+) -> Result<Vec<FuzzySubjectMatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let mut tx = pool.begin().await?;
+    let sql = if regex {
+        prepare_subject_regex(&mut tx, query.trim()).await?;
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, 1::REAL AS score
+         FROM patches p
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE p.subject ~* $1
+           AND p.is_reply = FALSE
+           AND NOT p.is_deleted
+           AND ($2::INT IS NULL OR p.list_id = $2)
+         ORDER BY p.sent_at DESC
+         LIMIT $3"
+    } else {
+        sqlx::query("SELECT set_config('pg_trgm.word_similarity_threshold', $1, true)")
+            .bind(min_similarity.unwrap_or(DEFAULT_SUBJECT_SIMILARITY).to_string())
+            .execute(&mut *tx)
+            .await?;
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, word_similarity($1, p.subject) AS score
+         FROM patches p
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE $1 <% p.subject
+           AND p.is_reply = FALSE
+           AND NOT p.is_deleted
+           AND ($2::INT IS NULL OR p.list_id = $2)
+         ORDER BY score DESC, p.sent_at DESC
+         LIMIT $3"
+    };
+    
+    let rows = sqlx::query(sql)
+        .bind(query.trim())
+        .bind(list_id)
+        .bind(limit.unwrap_or(100) as i64)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(subject_regex_error)?;
+    tx.commit().await?;
+    
+    Ok(rows.iter().map(|row| FuzzySubjectMatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        similarity: row.get(4),
+    }).collect())
+}
+
+/// A syzbot crash report email
+#[derive(Debug, Serialize)]
+pub struct SyzbotReportSummary {
+    pub patch_id: i64,
+    pub sent_at: String,
+    pub thread_id: Option<i64>,
+    pub report: crate::mail_parser::SyzbotReport,
+}
+
+/// List syzbot reports, newest first, optionally matching `query` in the crash title
+pub async fn search_syzbot_reports(
+    db: &mut DatabaseManager,
+    query: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<SyzbotReportSummary>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT s.patch_id, p.sent_at, pr.thread_id,
+                s.title, s.extid, s.dashboard_url, s.head_commit, s.git_tree,
+                s.console_log_url, s.kernel_config_url, s.syz_repro_url, s.c_repro_url
+         FROM syzbot_reports s
+         JOIN patches p ON s.patch_id = p.patch_id
+         LEFT JOIN patch_replies pr ON pr.patch_id = s.patch_id
+         WHERE ($1::TEXT IS NULL OR s.title ILIKE '%' || $1 || '%')
+           AND ($2::INT IS NULL OR p.list_id = $2)
+         ORDER BY p.sent_at DESC
+         LIMIT $3"
+    )
+    .bind(query.filter(|q| !q.trim().is_empty()))
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| SyzbotReportSummary {
+        patch_id: row.get(0),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(1).to_rfc3339(),
+        thread_id: row.get(2),
+        report: crate::mail_parser::SyzbotReport {
+            title: row.get(3),
+            extid: row.get(4),
+            dashboard_url: row.get(5),
+            head_commit: row.get(6),
+            git_tree: row.get(7),
+            console_log_url: row.get(8),
+            kernel_config_url: row.get(9),
+            syz_repro_url: row.get(10),
+            c_repro_url: row.get(11),
+        },
+    }).collect())
+}
+
+/// A stable tree notification
+#[derive(Debug, Serialize)]
+pub struct StableBackportSummary {
     pub patch_id: i64,
     pub subject: String,
-    pub author_name: String,
-    pub author_email: String,
     pub sent_at: String,
-    pub depth: i32,
-    pub message_id: String,
-    pub body_preview: String,  // Smart preview of actual content
-    pub is_reply: bool,        // True if subject starts with "Re:"
-    pub is_series: bool,       // True if part of a patch series
-    pub series_info: Option<String>,  // e.g., "3/12" for patch series
-    pub has_diff: bool,        // True if body contains git diff/patch content
-    pub reply_count: i32,      // Direct reply count for this node
-    pub commit_hash: Option<String>,  // Git commit hash for debugging
-    pub children: Vec<ThreadNode>,
+    pub backport: crate::mail_parser::StableBackport,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub struct ThreadTree {
-    pub thread_id: i64,
-    pub summary: ThreadSummary,
-    pub root: ThreadNode,
+/// Stable versions that picked up a patch, with the notifications behind them
+#[derive(Debug, Serialize)]
+pub struct PatchStableStatus {
+    /// Versions whose stable tree the patch was added to, sorted
+    pub versions: Vec<String>,
+    /// Versions where the backport failed to apply (and wasn't added later)
+    pub failed_versions: Vec<String>,
+    pub notifications: Vec<StableBackportSummary>,
 }
 
-/// Get all thread summaries (for thread list view)
-pub async fn get_all_threads(
+/// Map stable_backports query rows to StableBackportSummary
+fn stable_backports_from_rows(rows: &[sqlx::postgres::PgRow]) -> Vec<StableBackportSummary> {
+    rows.iter().map(|row| StableBackportSummary {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(2).to_rfc3339(),
+        backport: crate::mail_parser::StableBackport {
+            kind: row.get(3),
+            stable_versions: row.get(4),
+            original_subject: row.get(5),
+            upstream_commit: row.get(6),
+        },
+    }).collect()
+}
+
+/// List stable tree notifications, newest first
+/// `version` (e.g. "6.6") and `kind` ("added", "autosel", "failed") narrow the list
+pub async fn search_stable_backports(
     db: &mut DatabaseManager,
+    version: Option<String>,
+    kind: Option<String>,
     limit: Option<usize>,
-    offset: Option<usize>,
-    sort_by: Option<String>,
-    merge_filter: Option<String>
-) -> Result<Vec<ThreadSummary>, Box<dyn std::error::Error>> {
+    list_id: Option<i32>
+) -> Result<Vec<StableBackportSummary>, Box<dyn std::error::Error>> {
     db.ensure_connected().await?;
     let pool = db.get_pool()?;
     
-    let limit_val = limit.unwrap_or(50) as i64;
-    let offset_val = offset.unwrap_or(0) as i64;
+    let rows = sqlx::query(
+        "SELECT b.patch_id, p.subject, p.sent_at, b.kind, b.stable_versions, b.original_subject, b.upstream_commit
+         FROM stable_backports b
+         JOIN patches p ON b.patch_id = p.patch_id
+         WHERE ($1::TEXT IS NULL OR $1 = ANY(b.stable_versions))
+           AND ($2::TEXT IS NULL OR b.kind = LOWER($2))
+           AND ($3::INT IS NULL OR p.list_id = $3)
+         ORDER BY p.sent_at DESC
+         LIMIT $4"
+    )
+    .bind(version.filter(|v| !v.trim().is_empty()))
+    .bind(kind)
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
     
-    // Determine sort order
-    let order_by = match sort_by.as_deref() {
-        Some("oldest") => "created_at ASC",
-        Some("newest") => "created_at DESC",
-        Some("most_replies") => "reply_count DESC",
-        Some("most_participants") => "participant_count DESC",
-        _ => "last_activity_at DESC", // Default: most recent activity
-    };
+    Ok(stable_backports_from_rows(&rows))
+}
+
+/// Find the stable notifications for a patch and the stable versions that picked it up
+/// Linked by the patch subject, or by the upstream commit when a merge notification
+/// in the patch's thread names it
+pub async fn get_patch_stable_status(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<PatchStableStatus, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
     
-    // Determine merge filter
-    let merge_filter_clause = match merge_filter.as_deref() {
-        Some("merged") => "WHERE mt.thread_id IS NOT NULL",
-        Some("unmerged") => "WHERE mt.thread_id IS NULL",
-        _ => "", // Default: show all
+    let subject: Option<(String,)> = sqlx::query_as(
+        "SELECT subject FROM patches WHERE patch_id = $1"
+    )
+    .bind(patch_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((subject,)) = subject else {
+        return Err(format!("Patch {} not found", patch_id).into());
     };
     
-    let query = format!(
-        "SELECT 
-            ts.thread_id,
-            ts.root_subject,
-            ts.root_author,
-            ts.reply_count,
-            ts.participant_count,
-            ts.created_at,
-            ts.last_activity_at,
-            ts.root_patch_id,
-            mt.merge_repository,
-            mt.merge_branch,
-            mt.merge_applied_by,
-            mt.merge_date,
-            mt.commit_count
-         FROM thread_summary ts
-         LEFT JOIN merged_threads mt ON ts.thread_id = mt.thread_id
-         {}
-         ORDER BY {}
-         LIMIT $1 OFFSET $2",
-        merge_filter_clause,
-        order_by
-    );
+    // Commit hashes from the merge notifications of the thread (".../c/<sha>" links or bare hashes)
+    let commit_links: Vec<String> = sqlx::query_scalar(
+        "SELECT UNNEST(mp.merge_commit_links)
+         FROM patch_replies own
+         JOIN patch_replies pr ON pr.thread_id = own.thread_id
+         JOIN patches mp ON pr.patch_id = mp.patch_id
+         WHERE own.patch_id = $1 AND mp.is_merge_notification = TRUE"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
+    let commits: Vec<String> = commit_links.iter()
+        .filter_map(|link| link.rsplit('/').next())
+        .map(|hash| hash.trim().to_lowercase())
+        .filter(|hash| hash.len() >= MIN_FIXES_HASH_LEN && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .collect();
     
-    let rows = sqlx::query(&query)
-    .bind(limit_val)
-    .bind(offset_val)
+    let rows = sqlx::query(
+        "SELECT b.patch_id, p.subject, p.sent_at, b.kind, b.stable_versions, b.original_subject, b.upstream_commit
+         FROM stable_backports b
+         JOIN patches p ON b.patch_id = p.patch_id
+         WHERE LOWER(b.original_subject) = LOWER($1)
+            OR EXISTS (SELECT 1 FROM UNNEST($2::TEXT[]) c
+                       WHERE b.upstream_commit LIKE c || '%' OR c LIKE b.upstream_commit || '%')
+         ORDER BY p.sent_at ASC"
+    )
+    .bind(strip_patch_prefixes(&subject))
+    .bind(&commits)
     .fetch_all(pool)
     .await?;
+    let notifications = stable_backports_from_rows(&rows);
     
-    let threads = rows.iter().map(|row| {
-        let merge_status = if let Ok(Some(repo)) = row.try_get::<Option<String>, _>(8) {
-            Some(MergeStatusInfo {
-                is_merged: true,
-                merge_date: row.get::<chrono::DateTime<chrono::Utc>, _>(11).to_rfc3339(),
-                repository: repo,
-                branch: row.get::<String, _>(9),
-                applied_by: row.get::<String, _>(10),
-                commit_count: row.get::<Option<i32>, _>(12).unwrap_or(0),
-            })
-        } else {
-            None
-        };
-        
-        ThreadSummary {
-            thread_id: row.get(0),
-            root_subject: row.get(1),
-            root_author: row.get(2),
-            reply_count: row.get(3),
-            participant_count: row.get(4),
-            created_at: row.get::<chrono::DateTime<chrono::Utc>, _>(5).to_rfc3339(),
-            last_activity: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
-            root_patch_id: row.get(7),
-            merge_status,
-        }
-    }).collect();
+    let versions_of = |kind: &str| -> Vec<String> {
+        let mut versions: Vec<String> = notifications.iter()
+            .filter(|n| n.backport.kind == kind)
+            .flat_map(|n| n.backport.stable_versions.iter().cloned())
+            .collect();
+        versions.sort();
+        versions.dedup();
+        versions
+    };
+    let versions = versions_of("added");
+    let failed_versions = versions_of("failed").into_iter()
+        .filter(|v| !versions.contains(v))
+        .collect();
     
-    Ok(threads)
+    Ok(PatchStableStatus {
+        versions,
+        failed_versions,
+        notifications,
+    })
 }
 
-fn remove_attribution_lines(text: &str) -> String {
-    let result = text.to_string();
+/// A `git request-pull` email
+#[derive(Debug, Serialize)]
+pub struct PullRequestSummary {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    pub pull_request: crate::mail_parser::PullRequestInfo,
+}
+
+/// List pull request emails, newest first
+pub async fn get_pull_requests(
+    db: &mut DatabaseManager,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<PullRequestSummary>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at,
+                p.pull_base_commit, p.pull_repository, p.pull_ref, p.pull_head_commit
+         FROM patches p
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE p.is_pull_request = TRUE
+           AND ($1::INT IS NULL OR p.list_id = $1)
+         ORDER BY p.sent_at DESC
+         LIMIT $2"
+    )
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| PullRequestSummary {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        pull_request: crate::mail_parser::PullRequestInfo {
+            base_commit: row.get::<Option<String>, _>(4).unwrap_or_default(),
+            repository: row.get::<Option<String>, _>(5).unwrap_or_default(),
+            git_ref: row.get(6),
+            head_commit: row.get(7),
+        },
+    }).collect())
+}
+
+/// An unmerged thread whose latest ping has had no reply from anyone but its author
+#[derive(Debug, Serialize)]
+pub struct StalledThread {
+    pub thread_id: i64,
+    pub root_patch_id: i64,
+    pub root_subject: String,
+    pub root_author: String,
+    pub created_at: String,
+    pub ping_count: i64,
+    pub last_ping_at: String,
+    /// Last human reply from someone other than the author that isn't a ping
+    pub last_review_at: Option<String>,
+}
+
+/// List stalled threads that have been pinged, most recently pinged first
+pub async fn get_stalled_threads_with_pings(
+    db: &mut DatabaseManager,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<StalledThread>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT ts.thread_id, ts.root_patch_id, ts.root_subject, ts.root_author, ts.created_at,
+                ts.ping_count, act.last_ping_at, act.last_review_at
+         FROM thread_summary ts
+         JOIN LATERAL (
+           SELECT MAX(tp.sent_at) FILTER (WHERE tp.is_ping) as last_ping_at,
+                  MAX(tp.sent_at) FILTER (
+                    WHERE tp.is_reply AND tp.is_ping IS NOT TRUE
+                      AND tp.sender_type = 'human'
+                      AND tp.author_id <> ts.root_author_id
+                  ) as last_review_at
+           FROM patch_replies pr
+           JOIN patches tp ON pr.patch_id = tp.patch_id
+           WHERE pr.thread_id = ts.thread_id
+         ) act ON TRUE
+         LEFT JOIN merged_threads mt ON ts.thread_id = mt.thread_id
+         WHERE ts.ping_count > 0
+           AND mt.thread_id IS NULL
+           AND (act.last_review_at IS NULL OR act.last_review_at < act.last_ping_at)
+           AND ($1::INT IS NULL OR ts.list_id = $1)
+         ORDER BY act.last_ping_at DESC
+         LIMIT $2"
+    )
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| StalledThread {
+        thread_id: row.get(0),
+        root_patch_id: row.get(1),
+        root_subject: row.get(2),
+        root_author: row.get(3),
+        created_at: row.get::<chrono::DateTime<chrono::Utc>, _>(4).to_rfc3339(),
+        ping_count: row.get(5),
+        last_ping_at: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
+        last_review_at: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>(7).map(|t| t.to_rfc3339()),
+    }).collect())
+}
+
+/// Patch that was sent To/Cc a given address
+#[derive(Debug, Serialize)]
+pub struct RecipientPatch {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    /// "to" or "cc"
+    pub kind: String,
+}
+
+/// Find patches sent to `email`, optionally only where it was in To or Cc (`kind`)
+pub async fn find_patches_by_recipient(
+    db: &mut DatabaseManager,
+    email: &str,
+    kind: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<RecipientPatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, r.kind
+         FROM patch_recipients r
+         JOIN patches p ON r.patch_id = p.patch_id
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE r.email = $1::CITEXT
+           AND ($2::TEXT IS NULL OR r.kind = LOWER($2))
+           AND ($3::INT IS NULL OR p.list_id = $3)
+         ORDER BY p.sent_at DESC
+         LIMIT $4"
+    )
+    .bind(email.trim())
+    .bind(kind)
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| RecipientPatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        kind: row.get(4),
+    }).collect())
+}
+
+/// Find patches an author was copied on at any of their addresses, excluding their own
+pub async fn get_patches_cc_author(
+    db: &mut DatabaseManager,
+    author_id: i64,
+    kind: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<RecipientPatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // DISTINCT ON: an author listed under two addresses still gets one row per patch
+    let rows = sqlx::query(
+        "SELECT * FROM (
+            SELECT DISTINCT ON (p.patch_id) p.patch_id, p.subject, a.display_name, p.sent_at, r.kind
+            FROM author_emails ae
+            JOIN patch_recipients r ON r.email = ae.email
+            JOIN patches p ON r.patch_id = p.patch_id
+            JOIN authors a ON p.author_id = a.author_id
+            WHERE ae.author_id = $1
+              AND p.author_id <> $1
+              AND ($2::TEXT IS NULL OR r.kind = LOWER($2))
+              AND ($3::INT IS NULL OR p.list_id = $3)
+            ORDER BY p.patch_id, r.kind DESC
+         ) copied
+         ORDER BY sent_at DESC
+         LIMIT $4"
+    )
+    .bind(author_id)
+    .bind(kind)
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| RecipientPatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        kind: row.get(4),
+    }).collect())
+}
+
+/// Get the To/Cc recipients of a patch
+pub async fn get_patch_recipients(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<Vec<crate::mail_parser::Recipient>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT kind, name, email::TEXT FROM patch_recipients
+         WHERE patch_id = $1
+         ORDER BY kind DESC, email"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| crate::mail_parser::Recipient {
+        kind: row.get(0),
+        name: row.get(1),
+        email: row.get(2),
+    }).collect())
+}
+
+/// Patch that received a trailer (e.g. Reviewed-by) from a given address
+#[derive(Debug, Serialize)]
+pub struct TrailerPatch {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    pub tag: String,
+    /// Message carrying the trailer: the patch itself, or a reply to it
+    pub given_in_patch_id: i64,
+}
+
+/// Find patches given a trailer (default "Reviewed-by") by `email`
+/// A trailer in a reply counts for the message it replies to
+pub async fn get_patches_reviewed_by(
+    db: &mut DatabaseManager,
+    email: &str,
+    tag: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<TrailerPatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, given.tag, given.given_in
+         FROM (
+            SELECT DISTINCT ON (target_id) target_id, tag, given_in
+            FROM (
+                SELECT CASE WHEN carrier.is_reply THEN pr.parent_patch_id ELSE carrier.patch_id END AS target_id,
+                       t.tag, t.patch_id AS given_in
+                FROM patch_trailers t
+                JOIN patches carrier ON t.patch_id = carrier.patch_id
+                LEFT JOIN patch_replies pr ON pr.patch_id = t.patch_id
+                WHERE t.email = $1::CITEXT
+                  AND LOWER(t.tag) = LOWER($2)
+            ) trailers
+            WHERE target_id IS NOT NULL
+            ORDER BY target_id, given_in
+         ) given
+         JOIN patches p ON p.patch_id = given.target_id
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE ($3::INT IS NULL OR p.list_id = $3)
+         ORDER BY p.sent_at DESC
+         LIMIT $4"
+    )
+    .bind(email.trim())
+    .bind(tag.as_deref().unwrap_or("Reviewed-by"))
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| TrailerPatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        tag: row.get(4),
+        given_in_patch_id: row.get(5),
+    }).collect())
+}
+
+/// How often one person gave one trailer within a thread
+#[derive(Debug, Serialize)]
+pub struct ThreadTrailerRollup {
+    pub tag: String,
+    pub name: String,
+    pub email: String,
+    /// Messages in the thread carrying this trailer
+    pub count: i64,
+}
+
+/// Roll up the Acked-by/Reviewed-by/Tested-by/... trailers given in a thread, per tag and person
+pub async fn get_thread_trailer_rollup(
+    db: &mut DatabaseManager,
+    thread_id: i64
+) -> Result<Vec<ThreadTrailerRollup>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT t.tag, MIN(t.name), t.email::TEXT, COUNT(*)
+         FROM patch_replies pr
+         JOIN patch_trailers t ON t.patch_id = pr.patch_id
+         WHERE pr.thread_id = $1
+         GROUP BY t.tag, t.email
+         ORDER BY t.tag, COUNT(*) DESC, t.email"
+    )
+    .bind(thread_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| ThreadTrailerRollup {
+        tag: row.get(0),
+        name: row.get(1),
+        email: row.get(2),
+        count: row.get(3),
+    }).collect())
+}
+
+/// Patch of a series, in series order
+#[derive(Debug, Serialize)]
+pub struct SeriesPatch {
+    pub patch_id: i64,
+    pub message_id: String,
+    pub subject: String,
+    pub series_number: Option<i32>,
+    pub sent_at: String,
+    pub is_cover_letter: bool,
+}
+
+/// A patch series with its patches ordered by series number
+#[derive(Debug, Serialize)]
+pub struct SeriesDetail {
+    pub series_id: i64,
+    /// Subject of the cover letter, or of the first patch without one
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    pub version: Option<i32>,
+    pub total: Option<i32>,
+    pub target_tree: Option<String>,
+    pub is_rfc: bool,
+    pub cover_letter_patch_id: Option<i64>,
+    pub patches: Vec<SeriesPatch>,
+}
+
+/// Get a series and its ordered patches (None if there is no such series)
+pub async fn get_series(
+    db: &mut DatabaseManager,
+    series_id: i64
+) -> Result<Option<SeriesDetail>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let row = sqlx::query(
+        "SELECT s.series_id, root.subject, a.display_name, root.sent_at, s.version, s.total,
+                s.target_tree, s.is_rfc, s.cover_letter_patch_id
+         FROM series s
+         JOIN patches root ON root.patch_id = s.root_patch_id
+         JOIN authors a ON a.author_id = root.author_id
+         WHERE s.series_id = $1"
+    )
+    .bind(series_id)
+    .fetch_optional(pool)
+    .await?;
+    
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    
+    let patch_rows = sqlx::query(
+        "SELECT patch_id, message_id, subject, series_number, sent_at, is_cover_letter
+         FROM patches
+         WHERE series_id = $1
+         ORDER BY series_number, sent_at"
+    )
+    .bind(series_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(Some(SeriesDetail {
+        series_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        version: row.get(4),
+        total: row.get(5),
+        target_tree: row.get(6),
+        is_rfc: row.get::<Option<bool>, _>(7).unwrap_or(false),
+        cover_letter_patch_id: row.get(8),
+        patches: patch_rows.iter().map(|row| SeriesPatch {
+            patch_id: row.get(0),
+            message_id: row.get(1),
+            subject: row.get(2),
+            series_number: row.get(3),
+            sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(4).to_rfc3339(),
+            is_cover_letter: row.get::<Option<bool>, _>(5).unwrap_or(false),
+        }).collect(),
+    }))
+}
+
+/// One thread of a family: the discussion of one version of a patch or series
+#[derive(Debug, Serialize)]
+pub struct FamilyThread {
+    pub thread_id: i64,
+    pub root_subject: String,
+    pub version: Option<i32>,
+    pub is_rfc: bool,
+    pub sent_at: String,
+    pub reply_count: i32,
+    pub review_status: crate::database::ReviewStatus,
+}
+
+/// Get the threads of every version of the patch or series discussed in `thread_id`,
+/// oldest version first
+/// Empty when no other version was posted
+pub async fn get_thread_family(
+    db: &mut DatabaseManager,
+    thread_id: i64
+) -> Result<Vec<FamilyThread>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT pt.thread_id, p.subject, p.patch_version, p.is_rfc, p.sent_at, pt.reply_count, pt.review_status
+         FROM patch_threads t
+         JOIN patch_threads pt ON pt.family_id = t.family_id
+         JOIN patches p ON p.patch_id = pt.root_patch_id
+         WHERE t.thread_id = $1
+         ORDER BY p.patch_version NULLS FIRST, p.sent_at"
+    )
+    .bind(thread_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| FamilyThread {
+        thread_id: row.get(0),
+        root_subject: row.get(1),
+        version: row.get(2),
+        is_rfc: row.get::<Option<bool>, _>(3).unwrap_or(false),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(4).to_rfc3339(),
+        reply_count: row.get::<Option<i32>, _>(5).unwrap_or(0),
+        review_status: crate::database::ReviewStatus::from_db(&row.get::<String, _>(6)),
+    }).collect())
+}
+
+/// One posting in the version history of a patch
+#[derive(Debug, Serialize)]
+pub struct PatchVersion {
+    pub patch_id: i64,
+    pub message_id: String,
+    pub subject: String,
+    pub version: Option<i32>,
+    pub is_rfc: bool,
+    pub is_resend: bool,
+    pub sent_at: String,
+    pub series_id: Option<i64>,
+    /// Next version replacing this posting (None for the latest)
+    pub superseded_by: Option<i64>,
+}
+
+/// Get every posting of the logical patch `patch_id` belongs to, oldest version first
+/// Empty when the patch does not exist
+pub async fn get_patch_versions(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<Vec<PatchVersion>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // Follow the links forward to the latest version, then collect everything it replaced
+    let rows = sqlx::query(
+        "WITH RECURSIVE newer AS (
+            SELECT patch_id, superseded_by, 0 AS depth FROM patches WHERE patch_id = $1
+            UNION ALL
+            SELECT p.patch_id, p.superseded_by, n.depth + 1
+            FROM newer n
+            JOIN patches p ON p.patch_id = n.superseded_by
+         ),
+         history AS (
+            SELECT patch_id FROM (SELECT patch_id FROM newer ORDER BY depth DESC LIMIT 1) latest
+            UNION ALL
+            SELECT p.patch_id
+            FROM history h
+            JOIN patches p ON p.superseded_by = h.patch_id
+         )
+         SELECT p.patch_id, p.message_id, p.subject, p.patch_version, p.is_rfc, p.is_resend,
+                p.sent_at, p.series_id, p.superseded_by
+         FROM history h
+         JOIN patches p ON p.patch_id = h.patch_id
+         ORDER BY p.patch_version, p.sent_at"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| PatchVersion {
+        patch_id: row.get(0),
+        message_id: row.get(1),
+        subject: row.get(2),
+        version: row.get(3),
+        is_rfc: row.get::<Option<bool>, _>(4).unwrap_or(false),
+        is_resend: row.get::<Option<bool>, _>(5).unwrap_or(false),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
+        series_id: row.get(7),
+        superseded_by: row.get(8),
+    }).collect())
+}
+
+/// One archive copy of a message: the commit it was read from and the list and epoch carrying it
+#[derive(Debug, Serialize)]
+pub struct MessageSource {
+    pub commit_hash: String,
+    pub list_id: Option<i32>,
+    pub list_name: Option<String>,
+    /// public-inbox epoch (None for mbox imports and messages stored before sources were tracked)
+    pub epoch: Option<i32>,
+    pub first_seen: Option<String>,
+}
+
+/// Get every copy of a message seen across epochs and lists, first seen first
+/// Empty when the patch does not exist
+pub async fn get_message_sources(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<Vec<MessageSource>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let rows = sqlx::query(
+        "SELECT ms.commit_hash, ms.list_id, ml.name, ms.epoch, ms.first_seen
+         FROM message_sources ms
+         LEFT JOIN mailing_lists ml ON ml.list_id = ms.list_id
+         WHERE ms.patch_id = $1
+         ORDER BY ms.source_id"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| MessageSource {
+        commit_hash: row.get(0),
+        list_id: row.get(1),
+        list_name: row.get(2),
+        epoch: row.get(3),
+        first_seen: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>(4).map(|t| t.to_rfc3339()),
+    }).collect())
+}
+
+/// Escape LIKE wildcards so `value` matches literally ('_' is common in kernel paths)
+fn escape_like(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Patch whose diff touches a given path
+#[derive(Debug, Serialize)]
+pub struct PathPatch {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    /// Matching file in the diff
+    pub path: String,
+    pub change_type: String,
+    pub lines_added: i32,
+    pub lines_removed: i32,
+}
+
+/// Find patches touching `path`: that file, or anything below it when it names a directory
+/// Renames match on either side
+pub async fn get_patches_touching_path(
+    db: &mut DatabaseManager,
+    path: &str,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<PathPatch>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let path = path.trim().trim_matches('/');
+    let below = format!("{}/%", escape_like(path));
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, f.path, f.change_type, f.lines_added, f.lines_removed
+         FROM (
+            SELECT DISTINCT ON (patch_id) *
+            FROM patch_files
+            WHERE path = $1 OR path LIKE $2 OR old_path = $1 OR old_path LIKE $2
+            ORDER BY patch_id, position
+         ) f
+         JOIN patches p ON f.patch_id = p.patch_id
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE ($3::INT IS NULL OR p.list_id = $3)
+         ORDER BY p.sent_at DESC
+         LIMIT $4"
+    )
+    .bind(path)
+    .bind(&below)
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| PathPatch {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        path: row.get(4),
+        change_type: row.get(5),
+        lines_added: row.get(6),
+        lines_removed: row.get(7),
+    }).collect())
+}
+
+/// Patch activity on one file
+#[derive(Debug, Serialize)]
+pub struct FileActivity {
+    pub path: String,
+    pub patch_count: i64,
+    pub author_count: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    pub last_touched: String,
+}
+
+/// Most patched files, optionally only below `path_prefix` (e.g. "kernel/bpf")
+/// Replies quoting a diff are not counted
+pub async fn get_file_activity(
+    db: &mut DatabaseManager,
+    path_prefix: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<FileActivity>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let below = path_prefix.as_deref()
+        .map(|prefix| prefix.trim().trim_matches('/'))
+        .filter(|prefix| !prefix.is_empty())
+        .map(|prefix| format!("{}/%", escape_like(prefix)));
+    let rows = sqlx::query(
+        "SELECT f.path, COUNT(DISTINCT p.patch_id), COUNT(DISTINCT p.author_id),
+                SUM(f.lines_added), SUM(f.lines_removed), MAX(p.sent_at)
+         FROM patch_files f
+         JOIN patches p ON f.patch_id = p.patch_id
+         WHERE p.is_reply = FALSE
+           AND ($1::TEXT IS NULL OR f.path LIKE $1)
+           AND ($2::INT IS NULL OR p.list_id = $2)
+         GROUP BY f.path
+         ORDER BY COUNT(DISTINCT p.patch_id) DESC, f.path
+         LIMIT $3"
+    )
+    .bind(below)
+    .bind(list_id)
+    .bind(limit.unwrap_or(50) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| FileActivity {
+        path: row.get(0),
+        patch_count: row.get(1),
+        author_count: row.get(2),
+        lines_added: row.get(3),
+        lines_removed: row.get(4),
+        last_touched: row.get::<chrono::DateTime<chrono::Utc>, _>(5).to_rfc3339(),
+    }).collect())
+}
+
+/// Number of patches whose size (lines added + removed) falls in a bucket
+#[derive(Debug, Serialize)]
+pub struct PatchSizeBucket {
+    pub label: String,
+    pub min_lines: i32,
+    pub max_lines: Option<i32>,
+    pub patch_count: i64,
+}
+
+/// Patch size distribution from the stored diffstat (emails without a diff are ignored)
+pub async fn get_patch_size_distribution(
+    db: &mut DatabaseManager,
+    list_id: Option<i32>
+) -> Result<Vec<PatchSizeBucket>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // Bucket bounds as (min, max inclusive)
+    const BUCKETS: [(i32, Option<i32>); 6] = [
+        (0, Some(10)),
+        (11, Some(50)),
+        (51, Some(200)),
+        (201, Some(500)),
+        (501, Some(2000)),
+        (2001, None),
+    ];
+    
+    let rows = sqlx::query(
+        "SELECT lines_added + lines_removed as size, COUNT(*)
+         FROM patches
+         WHERE files_changed IS NOT NULL
+           AND is_reply = FALSE
+           AND ($1::INT IS NULL OR list_id = $1)
+         GROUP BY size"
+    )
+    .bind(list_id)
+    .fetch_all(pool)
+    .await?;
+    
+    let mut buckets: Vec<PatchSizeBucket> = BUCKETS.iter().map(|&(min_lines, max_lines)| PatchSizeBucket {
+        label: match max_lines {
+            Some(max) => format!("{}-{}", min_lines, max),
+            None => format!("{}+", min_lines),
+        },
+        min_lines,
+        max_lines,
+        patch_count: 0,
+    }).collect();
+    
+    for row in &rows {
+        let size: i32 = row.try_get(0).unwrap_or(0);
+        let count: i64 = row.get(1);
+        if let Some(bucket) = buckets.iter_mut().find(|b| size >= b.min_lines && b.max_lines.is_none_or(|max| size <= max)) {
+            bucket.patch_count += count;
+        }
+    }
+    
+    Ok(buckets)
+}
+
+/// Get comprehensive database statistics
+/// Read from the statistics views, so numbers are as of the last `refresh_stats`
+pub async fn get_enhanced_stats(db: &mut DatabaseManager) -> Result<DatabaseStats, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let stats_row = sqlx::query(
+        "SELECT total_authors, total_patches, total_emails, patches_with_series, bot_messages, ping_messages,
+                total_threads, answered_threads, merged_threads, avg_reply_count, avg_participant_count,
+                refreshed_at
+         FROM stats_overview"
+    )
+    .fetch_one(pool)
+    .await?;
+    
+    let total_authors: i64 = stats_row.get(0);
+    let total_patches: i64 = stats_row.get(1);
+    let total_emails: i64 = stats_row.get(2);
+    let patches_with_series: i64 = stats_row.get(3);
+    let bot_messages: i64 = stats_row.get(4);
+    let ping_messages: i64 = stats_row.get(5);
+    let thread_stats = ThreadStats {
+        total_threads: stats_row.get(6),
+        answered_threads: stats_row.get(7),
+        merged_threads: stats_row.get(8),
+        avg_reply_count: stats_row.get(9),
+        avg_participant_count: stats_row.get(10),
+    };
+    let refreshed_at: chrono::DateTime<chrono::Utc> = stats_row.get(11);
+    
+    // Top 10 contributors
+    let top_rows = sqlx::query(
+        "SELECT author_id, display_name, message_count, patch_count, reply_count, last_active
+         FROM stats_contributors
+         ORDER BY message_count DESC
+         LIMIT 10"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let top_contributors: Vec<TopContributor> = top_rows.iter().map(|row| {
+        let last_active: Option<chrono::DateTime<chrono::Utc>> = row.get(5);
+        TopContributor {
+            author_id: row.get(0),
+            display_name: row.get(1),
+            patch_count: row.get(2),
+            patches_sent: row.get(3),
+            replies_sent: row.get(4),
+            last_active: last_active.map(|t| t.to_rfc3339()),
+        }
+    }).collect();
+    
+    // Recent activity (last 30 days)
+    let activity_rows = sqlx::query(
+        "SELECT day, message_count, patch_count, reply_count
+         FROM stats_daily_activity
+         WHERE day > CURRENT_DATE - 30
+         ORDER BY day DESC
+         LIMIT 30"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let recent_activity: Vec<ActivityDay> = activity_rows.iter().map(|row| {
+        let date: chrono::NaiveDate = row.get(0);
+        ActivityDay {
+            date: date.to_string(),
+            patch_count: row.get(1),
+            patches_sent: row.get(2),
+            replies_sent: row.get(3),
+        }
+    }).collect();
+    
+    Ok(DatabaseStats {
+        total_authors,
+        total_patches,
+        total_emails,
+        unique_email_addresses: total_emails,
+        patches_with_series,
+        bot_messages,
+        ping_messages,
+        top_contributors,
+        recent_activity,
+        thread_stats,
+        refreshed_at: refreshed_at.to_rfc3339(),
+    })
+}
+
+/// Size of a single table (including its indexes and TOAST data)
+#[derive(Debug, Serialize)]
+pub struct TableDiskUsage {
+    pub table_name: String,
+    pub total_bytes: i64,
+    pub table_bytes: i64,
+    pub index_bytes: i64,
+    pub estimated_rows: i64,
+}
+
+/// Storage used by the git archive and the database, plus a growth estimate
+#[derive(Debug, Serialize)]
+pub struct DiskUsageReport {
+    pub repository: Option<crate::git_parser::RepositoryDiskUsage>,
+    pub repository_error: Option<String>,
+    pub database_bytes: i64,
+    pub tables: Vec<TableDiskUsage>,
+    pub stored_messages: i64,
+    pub archive_messages: Option<u64>,
+    pub bytes_per_message: Option<f64>,
+    /// Extra database space needed to store the rest of the archive at the current bytes/message
+    pub estimated_growth_bytes: Option<u64>,
+}
+
+/// Get disk usage of the git archive and the database
+pub async fn get_disk_usage_report(db: &mut DatabaseManager) -> Result<DiskUsageReport, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let (database_bytes,): (i64,) = sqlx::query_as("SELECT pg_database_size(current_database())")
+        .fetch_one(pool)
+        .await?;
+    
+    let table_rows = sqlx::query(
+        "SELECT c.relname::TEXT,
+                pg_total_relation_size(c.oid),
+                pg_relation_size(c.oid),
+                pg_indexes_size(c.oid),
+                GREATEST(c.reltuples, 0)::BIGINT
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = 'public' AND c.relkind = 'r'
+         ORDER BY pg_total_relation_size(c.oid) DESC"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let tables: Vec<TableDiskUsage> = table_rows.iter().map(|row| TableDiskUsage {
+        table_name: row.get(0),
+        total_bytes: row.get(1),
+        table_bytes: row.get(2),
+        index_bytes: row.get(3),
+        estimated_rows: row.get(4),
+    }).collect();
+    
+    let (stored_messages,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM patches")
+        .fetch_one(pool)
+        .await?;
+    
+    // Directory walk and commit count hit the disk, keep them off the async runtime
+    let (repository, archive_messages) = tokio::task::spawn_blocking(|| {
+        let repository = crate::git_parser::get_repository_disk_usage();
+        let archive_messages = crate::git_parser::get_total_git_commits().ok().map(|n| n as u64);
+        (repository, archive_messages)
+    }).await?;
+    
+    let (repository, repository_error) = match repository {
+        Ok(usage) => (Some(usage), None),
+        Err(e) => (None, Some(e.message)),
+    };
+    
+    let bytes_per_message = (stored_messages > 0).then(|| database_bytes as f64 / stored_messages as f64);
+    let estimated_growth_bytes = match (archive_messages, bytes_per_message) {
+        (Some(total), Some(per_message)) => {
+            let remaining = total.saturating_sub(stored_messages as u64);
+            Some((remaining as f64 * per_message) as u64)
+        }
+        _ => None,
+    };
+    
+    Ok(DiskUsageReport {
+        repository,
+        repository_error,
+        database_bytes,
+        tables,
+        stored_messages,
+        archive_messages,
+        bytes_per_message,
+        estimated_growth_bytes,
+    })
+}
+
+/// Row counts, size and maintenance history of a table
+#[derive(Debug, Serialize)]
+pub struct TableHealth {
+    pub table_name: String,
+    /// Live and dead rows as tracked by the statistics collector (estimates)
+    pub live_rows: i64,
+    pub dead_rows: i64,
+    pub total_bytes: i64,
+    pub index_bytes: i64,
+    /// Latest manual or automatic VACUUM / ANALYZE
+    pub last_vacuum: Option<String>,
+    pub last_analyze: Option<String>,
+}
+
+/// Estimated wasted space in a B-tree index
+#[derive(Debug, Serialize)]
+pub struct IndexBloat {
+    pub index_name: String,
+    pub table_name: String,
+    pub index_bytes: i64,
+    pub estimated_bloat_bytes: i64,
+    /// Share of the index estimated to be bloat (0.0 - 1.0)
+    pub bloat_ratio: f64,
+}
+
+/// Query running longer than the threshold of `get_db_health`
+#[derive(Debug, Serialize)]
+pub struct LongRunningQuery {
+    pub pid: i32,
+    pub state: Option<String>,
+    pub duration_secs: f64,
+    pub wait_event: Option<String>,
+    pub query: String,
+}
+
+/// Database health overview
+#[derive(Debug, Serialize)]
+pub struct DatabaseHealth {
+    pub database_bytes: i64,
+    pub tables: Vec<TableHealth>,
+    pub bloated_indexes: Vec<IndexBloat>,
+    pub long_running_queries: Vec<LongRunningQuery>,
+}
+
+/// Get table statistics, index bloat and queries running longer than
+/// `long_query_secs` (default 30)
+/// Bloat is estimated from planner statistics for B-tree indexes on plain columns,
+/// so it is only as fresh as the last ANALYZE
+pub async fn get_db_health(
+    db: &mut DatabaseManager,
+    long_query_secs: Option<u32>
+) -> Result<DatabaseHealth, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let (database_bytes,): (i64,) = sqlx::query_as("SELECT pg_database_size(current_database())")
+        .fetch_one(pool)
+        .await?;
+    
+    let table_rows = sqlx::query(
+        "SELECT relname::TEXT, n_live_tup, n_dead_tup,
+                pg_total_relation_size(relid), pg_indexes_size(relid),
+                GREATEST(last_vacuum, last_autovacuum),
+                GREATEST(last_analyze, last_autoanalyze)
+         FROM pg_stat_user_tables
+         WHERE schemaname = 'public'
+         ORDER BY pg_total_relation_size(relid) DESC"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let tables = table_rows.iter().map(|row| TableHealth {
+        table_name: row.get(0),
+        live_rows: row.get(1),
+        dead_rows: row.get(2),
+        total_bytes: row.get(3),
+        index_bytes: row.get(4),
+        last_vacuum: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>(5).map(|t| t.to_rfc3339()),
+        last_analyze: row.get::<Option<chrono::DateTime<chrono::Utc>>, _>(6).map(|t| t.to_rfc3339()),
+    }).collect();
+    
+    // Expected size: one item pointer, tuple header and key per row at the default 90% fill
+    let index_rows = sqlx::query(
+        "WITH idx AS (
+            SELECT ci.relname::TEXT AS index_name, ct.relname::TEXT AS table_name,
+                   i.indexrelid, ci.relpages, ci.reltuples,
+                   current_setting('block_size')::NUMERIC AS block_size,
+                   SUM(COALESCE(st.avg_width, 8)) AS key_width
+            FROM pg_index i
+            JOIN pg_class ci ON ci.oid = i.indexrelid
+            JOIN pg_class ct ON ct.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = ct.relnamespace
+            JOIN pg_am am ON am.oid = ci.relam
+            JOIN pg_attribute a ON a.attrelid = i.indexrelid AND a.attnum > 0
+            LEFT JOIN pg_stats st ON st.schemaname = n.nspname AND st.tablename = ct.relname AND st.attname = a.attname
+            WHERE n.nspname = 'public' AND am.amname = 'btree' AND i.indexprs IS NULL AND ci.relpages > 10
+            GROUP BY ci.relname, ct.relname, i.indexrelid, ci.relpages, ci.reltuples
+         ),
+         estimate AS (
+            SELECT index_name, table_name, relpages, block_size,
+                   CEIL(GREATEST(reltuples, 0) * (12 + CEIL(key_width / 8) * 8) / ((block_size - 24) * 0.9)) AS expected_pages
+            FROM idx
+         )
+         SELECT index_name, table_name, (relpages * block_size)::BIGINT,
+                (GREATEST(relpages - expected_pages, 0) * block_size)::BIGINT,
+                (GREATEST(relpages - expected_pages, 0) / relpages)::FLOAT8
+         FROM estimate
+         WHERE relpages > expected_pages
+         ORDER BY 4 DESC"
+    )
+    .fetch_all(pool)
+    .await?;
+    
+    let bloated_indexes = index_rows.iter().map(|row| IndexBloat {
+        index_name: row.get(0),
+        table_name: row.get(1),
+        index_bytes: row.get(2),
+        estimated_bloat_bytes: row.get(3),
+        bloat_ratio: row.get(4),
+    }).collect();
+    
+    let query_rows = sqlx::query(
+        "SELECT pid, state, EXTRACT(EPOCH FROM NOW() - query_start)::FLOAT8,
+                wait_event_type || ':' || wait_event, LEFT(query, 500)
+         FROM pg_stat_activity
+         WHERE datname = current_database()
+           AND pid <> pg_backend_pid()
+           AND state <> 'idle'
+           AND query_start < NOW() - make_interval(secs => $1)
+         ORDER BY query_start"
+    )
+    .bind(long_query_secs.unwrap_or(30) as f64)
+    .fetch_all(pool)
+    .await?;
+    
+    let long_running_queries = query_rows.iter().map(|row| LongRunningQuery {
+        pid: row.get(0),
+        state: row.get(1),
+        duration_secs: row.get(2),
+        wait_event: row.get(3),
+        query: row.get(4),
+    }).collect();
+    
+    Ok(DatabaseHealth {
+        database_bytes,
+        tables,
+        bloated_indexes,
+        long_running_queries,
+    })
+}
+
+// Threading API
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ThreadSummary {
+    pub thread_id: i64,
+    pub root_subject: String,
+    pub root_author: String,
+    pub reply_count: i32,
+    pub participant_count: i32,
+    pub created_at: String,
+    pub last_activity: String,
+    pub root_patch_id: i64,
+    pub merge_status: Option<MergeStatusInfo>,
+    // Diffstat summed over the patches (not replies) in the thread
+    pub files_changed: i64,
+    pub lines_added: i64,
+    pub lines_removed: i64,
+    // "ping"/"any update?" nudges in the thread
+    pub ping_count: i64,
+    // A reply from someone other than the author rejected the patch (NAK, "please drop this")
+    pub has_rejection: bool,
+    // Messages not marked read locally
+    pub unread_count: i64,
+    // On the user's starred shortlist
+    pub is_starred: bool,
+    // Names of the labels attached to the thread
+    pub labels: Vec<String>,
+    pub review_status: crate::database::ReviewStatus,
+    // Thread of the earliest version when other versions were posted (see get_thread_family)
+    pub family_id: Option<i64>,
+}
+
+/// Position in the thread list ordered by latest activity: the last thread of a page
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThreadCursor {
+    pub last_activity: String,
+    pub thread_id: i64,
+}
+
+/// One page of the thread list
+#[derive(Debug, Serialize, Clone)]
+pub struct ThreadPage {
+    pub items: Vec<ThreadSummary>,
+    /// Threads passing the filters, on all pages
+    pub total_count: i64,
+    pub has_more: bool,
+    /// Where the next page starts; None on the last page and for orders other than latest activity
+    pub next_cursor: Option<ThreadCursor>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MergeStatusInfo {
+    pub is_merged: bool,
+    pub merge_date: String,
+    pub repository: String,
+    pub branch: String,
+    pub applied_by: String,
+    pub commit_count: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ThreadNode {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub sent_at: String,
+    pub depth: i32,
+    pub message_id: String,
+    pub body_preview: String,  // Smart preview of actual content
+    pub signature: Option<String>,  // Text after the "-- " separator, kept out of body_preview
+    pub is_reply: bool,        // True if subject starts with "Re:"
+    pub is_series: bool,       // True if part of a patch series
+    pub series_info: Option<String>,  // e.g., "3/12" for patch series
+    pub is_cover_letter: bool, // True for the [PATCH 0/N] introduction of a series
+    pub sender_type: crate::mail_parser::SenderType,  // Human or known bot
+    pub has_diff: bool,        // True if body contains git diff/patch content
+    pub reply_count: i32,      // Direct reply count for this node
+    pub is_read: bool,         // Marked read locally (per message or by thread)
+    pub labels: Vec<String>,   // Labels attached to this message
+    pub is_applied: Option<bool>,  // Applied or skipped per a merge notification, None if none mentions it
+    pub merge_commit: Option<String>,  // Commit link the patch was applied as
+    pub is_phantom: bool,      // Placeholder for a referenced message missing from the archive (patch_id 0)
+    pub commit_hash: Option<String>,  // Git commit hash for debugging
+    pub trailers: Vec<crate::mail_parser::Trailer>,  // Signed-off-by, Acked-by, Reviewed-by, ...
+    pub children: Vec<ThreadNode>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ThreadTree {
+    pub thread_id: i64,
+    pub summary: ThreadSummary,
+    pub root: ThreadNode,
+}
+
+/// Columns read by `thread_summary_from_row`
+const THREAD_SUMMARY_SELECT: &str = "SELECT 
+            ts.thread_id,
+            ts.root_subject,
+            ts.root_author,
+            ts.reply_count,
+            ts.participant_count,
+            ts.created_at,
+            ts.last_activity_at,
+            ts.root_patch_id,
+            mt.merge_repository,
+            mt.merge_branch,
+            mt.merge_applied_by,
+            mt.merge_date,
+            mt.commit_count,
+            ts.files_changed,
+            ts.lines_added,
+            ts.lines_removed,
+            ts.ping_count,
+            ts.has_rejection,
+            ts.unread_count,
+            EXISTS (SELECT 1 FROM thread_bookmarks b WHERE b.thread_id = ts.thread_id),
+            ARRAY(SELECT l.name FROM thread_labels tl JOIN labels l ON l.label_id = tl.label_id
+                  WHERE tl.thread_id = ts.thread_id ORDER BY LOWER(l.name)),
+            ts.review_status,
+            (SELECT pt.family_id FROM patch_threads pt WHERE pt.thread_id = ts.thread_id)
+         FROM thread_summary ts
+         LEFT JOIN merged_threads mt ON ts.thread_id = mt.thread_id";
+
+fn thread_summary_from_row(row: &sqlx::postgres::PgRow) -> ThreadSummary {
+    let merge_status = if let Ok(Some(repo)) = row.try_get::<Option<String>, _>(8) {
+        Some(MergeStatusInfo {
+            is_merged: true,
+            merge_date: row.get::<chrono::DateTime<chrono::Utc>, _>(11).to_rfc3339(),
+            repository: repo,
+            branch: row.get::<String, _>(9),
+            applied_by: row.get::<String, _>(10),
+            commit_count: row.get::<Option<i32>, _>(12).unwrap_or(0),
+        })
+    } else {
+        None
+    };
+    
+    ThreadSummary {
+        thread_id: row.get(0),
+        root_subject: row.get(1),
+        root_author: row.get(2),
+        reply_count: row.get(3),
+        participant_count: row.get(4),
+        created_at: row.get::<chrono::DateTime<chrono::Utc>, _>(5).to_rfc3339(),
+        last_activity: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
+        root_patch_id: row.get(7),
+        merge_status,
+        files_changed: row.try_get(13).unwrap_or(0),
+        lines_added: row.try_get(14).unwrap_or(0),
+        lines_removed: row.try_get(15).unwrap_or(0),
+        ping_count: row.try_get(16).unwrap_or(0),
+        has_rejection: row.try_get::<Option<bool>, _>(17).ok().flatten().unwrap_or(false),
+        unread_count: row.try_get(18).unwrap_or(0),
+        is_starred: row.try_get(19).unwrap_or(false),
+        labels: row.try_get(20).unwrap_or_default(),
+        review_status: row.try_get::<String, _>(21)
+            .map(|value| crate::database::ReviewStatus::from_db(&value))
+            .unwrap_or_default(),
+        family_id: row.try_get(22).unwrap_or(None),
+    }
+}
+
+/// Get all thread summaries (for thread list view)
+/// `label_id` keeps only threads carrying that label, `dates` only threads active within
+/// the window (started before its end and last active after its start)
+///
+/// In the default latest-activity order, pages are fetched by keyset: pass the `next_cursor`
+/// of the previous page as `cursor` (`offset` is then ignored). Other orders page by offset.
+#[allow(clippy::too_many_arguments)]
+pub async fn get_all_threads(
+    db: &mut DatabaseManager,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    cursor: Option<ThreadCursor>,
+    sort_by: Option<String>,
+    merge_filter: Option<String>,
+    list_id: Option<i32>,
+    label_id: Option<i32>,
+    dates: crate::database::DateRange
+) -> Result<ThreadPage, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let limit_val = limit.unwrap_or(50) as i64;
+    
+    // Determine sort order
+    let order_by = match sort_by.as_deref() {
+        Some("oldest") => "created_at ASC",
+        Some("newest") => "created_at DESC",
+        Some("most_replies") => "reply_count DESC",
+        Some("most_participants") => "participant_count DESC",
+        _ => "last_activity_at DESC, thread_id DESC", // Default: most recent activity
+    };
+    let by_activity = !matches!(sort_by.as_deref(), Some("oldest" | "newest" | "most_replies" | "most_participants"));
+    
+    let cursor = match cursor {
+        Some(_) if !by_activity => return Err("Cursor pagination needs the latest-activity order".into()),
+        Some(cursor) => Some((
+            chrono::DateTime::parse_from_rfc3339(&cursor.last_activity)?.with_timezone(&chrono::Utc),
+            cursor.thread_id,
+        )),
+        None => None,
+    };
+    let offset_val = if cursor.is_some() { 0 } else { offset.unwrap_or(0) as i64 };
+    
+    // Determine merge filter
+    let merge_filter_clause = match merge_filter.as_deref() {
+        Some("merged") => "AND mt.thread_id IS NOT NULL",
+        Some("unmerged") => "AND mt.thread_id IS NULL",
+        Some("rejected") => "AND mt.thread_id IS NULL AND ts.has_rejection = TRUE",
+        _ => "", // Default: show all
+    };
+    
+    // One row past the page tells whether another page follows
+    let query = format!(
+        "{}
+         WHERE ($3::INT IS NULL OR ts.list_id = $3)
+           AND ($4::INT IS NULL OR EXISTS (SELECT 1 FROM thread_labels tl WHERE tl.thread_id = ts.thread_id AND tl.label_id = $4))
+           AND ($5::TIMESTAMPTZ IS NULL OR (ts.last_activity_at, ts.thread_id) < ($5, $6))
+           AND ($7::TIMESTAMPTZ IS NULL OR ts.last_activity_at >= $7)
+           AND ($8::TIMESTAMPTZ IS NULL OR ts.created_at < $8)
+         {}
+         ORDER BY {}
+         LIMIT $1 + 1 OFFSET $2",
+        THREAD_SUMMARY_SELECT,
+        merge_filter_clause,
+        order_by
+    );
+    // The total ignores the cursor, and is counted on patch_threads rather than the view
+    // (whose created_at is the root patch's sent_at); both queries run at once
+    // Like thread_summary, threads rooted at a deleted message are left out
+    let count_query = format!(
+        "SELECT COUNT(*)
+         FROM patch_threads ts
+         JOIN patches rp ON rp.patch_id = ts.root_patch_id
+         LEFT JOIN merged_threads mt ON ts.thread_id = mt.thread_id
+         WHERE NOT rp.is_deleted
+           AND ($1::INT IS NULL OR ts.list_id = $1)
+           AND ($2::INT IS NULL OR EXISTS (SELECT 1 FROM thread_labels tl WHERE tl.thread_id = ts.thread_id AND tl.label_id = $2))
+           AND ($3::TIMESTAMPTZ IS NULL OR ts.last_activity_at >= $3)
+           AND ($4::TIMESTAMPTZ IS NULL OR rp.sent_at < $4)
+         {}",
+        merge_filter_clause
+    );
+    
+    let (rows, total_count): (Vec<sqlx::postgres::PgRow>, i64) = futures::try_join!(
+        sqlx::query(&query)
+            .bind(limit_val)
+            .bind(offset_val)
+            .bind(list_id)
+            .bind(label_id)
+            .bind(cursor.map(|(last_activity, _)| last_activity))
+            .bind(cursor.map(|(_, thread_id)| thread_id))
+            .bind(dates.after)
+            .bind(dates.before)
+            .fetch_all(pool),
+        sqlx::query_scalar(&count_query)
+            .bind(list_id)
+            .bind(label_id)
+            .bind(dates.after)
+            .bind(dates.before)
+            .fetch_one(pool)
+    )?;
+    
+    let has_more = rows.len() as i64 > limit_val;
+    let threads: Vec<ThreadSummary> = rows.iter()
+        .take(limit_val as usize)
+        .map(thread_summary_from_row)
+        .collect();
+    
+    let next_cursor = threads.last()
+        .filter(|_| by_activity && has_more)
+        .map(|last| ThreadCursor {
+            last_activity: last.last_activity.clone(),
+            thread_id: last.thread_id,
+        });
+    
+    Ok(ThreadPage { items: threads, total_count, has_more, next_cursor })
+}
+
+fn remove_attribution_lines(text: &str) -> String {
+    let result = text.to_string();
     
     // Remove email attribution patterns like "On Wed, Sep 24, 2025 at 1:43 AM ... wrote:"
     let result_lines: Vec<&str> = result.lines().collect();
@@ -366,426 +2074,1293 @@ fn remove_attribution_lines(text: &str) -> String {
             continue;
         }
         
-        // Skip email attribution lines (various patterns)
-        // Pattern 1: "On ... wrote:" (most common)
-        if trimmed.starts_with("On ") && trimmed.contains(" wrote:") {
-            continue;
+        // Skip email attribution lines (various patterns)
+        // Pattern 1: "On ... wrote:" (most common)
+        if trimmed.starts_with("On ") && trimmed.contains(" wrote:") {
+            continue;
+        }
+        
+        // Pattern 2: Contains date patterns with email addresses and "wrote:"
+        // Example: "On Wed, Sep 24, 2025 at 1:43 AM Brahmajit Das <...> wrote:"
+        if trimmed.starts_with("On ") 
+            && (trimmed.contains("@") || trimmed.contains('<'))
+            && trimmed.contains(" wrote:") {
+            continue;
+        }
+        
+        // Pattern 3: Date-based attribution patterns ending with colon
+        if (trimmed.starts_with("On ") || trimmed.starts_with("Am ")) 
+            && (trimmed.contains(", 20") || trimmed.contains(", 19"))
+            && trimmed.ends_with(':') {
+            continue;
+        }
+        
+        // Pattern 4: Lines that start with date and contain <email> and wrote
+        if trimmed.contains(", 20") && trimmed.contains('<') && trimmed.contains('>') 
+            && trimmed.to_lowercase().contains("wrote") {
+            continue;
+        }
+        
+        cleaned_lines.push(line);
+    }
+    
+    cleaned_lines.join("\n")
+}
+
+/// Check if body contains git diff/patch content (not quoted)
+/// This should return true only for actual patches, not replies quoting patches
+/// Improved: requires multiple consecutive diff lines to avoid false positives
+fn has_diff_content(body: &str) -> bool {
+    let mut consecutive_diff_lines = 0;
+    const MIN_DIFF_LINES: i32 = 3; // Require at least 3 consecutive diff lines
+    
+    for line in body.lines() {
+        let trimmed = line.trim();
+        
+        // Skip empty lines (don't reset counter)
+        if trimmed.is_empty() {
+            continue;
+        }
+        
+        // Skip quoted lines (these are not the actual patch, just quoted content)
+        if trimmed.starts_with('>') {
+            consecutive_diff_lines = 0; // Reset counter
+            continue;
+        }
+        
+        // Check for actual diff markers in non-quoted lines
+        let is_diff_line = trimmed.starts_with("diff --git") 
+            || trimmed.starts_with("--- a/")
+            || trimmed.starts_with("+++ b/")
+            || (trimmed.starts_with("@@") && trimmed.contains("@@"))
+            || (trimmed.starts_with("index ") && trimmed.len() > 10)
+            || trimmed.starts_with("new file mode")
+            || trimmed.starts_with("deleted file mode")
+            || (consecutive_diff_lines > 0 && (trimmed.starts_with('+') || trimmed.starts_with('-') || trimmed.starts_with(' ')));
+        
+        if is_diff_line {
+            consecutive_diff_lines += 1;
+            if consecutive_diff_lines >= MIN_DIFF_LINES {
+                return true;
+            }
+        } else {
+            // Non-diff line found, reset counter
+            consecutive_diff_lines = 0;
+        }
+    }
+    
+    false
+}
+
+/// Wrap text to fit within specified character width
+fn wrap_text_to_width(text: &str, max_width: usize) -> String {
+    let mut result = Vec::new();
+    
+    for line in text.lines() {
+        if line.len() <= max_width {
+            result.push(line.to_string());
+        } else {
+            // Split long lines at word boundaries
+            let mut current_line = String::new();
+            for word in line.split_whitespace() {
+                if current_line.is_empty() {
+                    current_line = word.to_string();
+                } else if current_line.len() + word.len() + 1 <= max_width {
+                    current_line.push(' ');
+                    current_line.push_str(word);
+                } else {
+                    result.push(current_line);
+                    current_line = word.to_string();
+                }
+            }
+            if !current_line.is_empty() {
+                result.push(current_line);
+            }
+        }
+    }
+    
+    result.join("\n")
+}
+
+/// A run of quoted lines at the same depth
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct QuoteBlock {
+    pub start_line: usize,  // 0-based, inclusive
+    pub end_line: usize,    // 0-based, inclusive
+    pub depth: usize,       // Number of ">" markers
+    pub attributed_to: Option<String>,  // Author named in the "... wrote:" line above, if any
+}
+
+/// A message body with the structure of its quoted parts
+#[derive(Debug, Serialize)]
+pub struct QuotedBody {
+    pub body: String,
+    pub quotes: Vec<QuoteBlock>,
+}
+
+/// Split a line into its quote depth and the text after the ">" markers
+fn quote_depth(line: &str) -> (usize, &str) {
+    let mut depth = 0;
+    let mut rest = line.trim_start();
+    while let Some(after) = rest.strip_prefix('>') {
+        depth += 1;
+        rest = after.trim_start_matches(' ');
+    }
+    (depth, rest)
+}
+
+/// Author name from an attribution line such as "On Mon, 1 Jan 2024, Jane Doe <jane@x.org> wrote:"
+fn attribution_author(line: &str) -> Option<String> {
+    let text = line.trim();
+    // ASCII-only lowercasing keeps byte offsets valid in `text`
+    let lower = text.to_ascii_lowercase();
+    let before = &text[..lower.rfind(" wrote:").or_else(|| lower.rfind(" writes:"))?];
+    // Drop the address and everything from the date part
+    let before = before.split('<').next().unwrap_or(before).trim();
+    let name = before.rsplit(", ").next().unwrap_or(before);
+    let name = [" AM ", " PM ", " am ", " pm "].iter()
+        .find_map(|marker| name.rsplit_once(marker).map(|(_, n)| n))
+        .unwrap_or(name)
+        .trim()
+        .trim_matches('"');
+    let name = name.strip_prefix("On ").unwrap_or(name).trim();
+    (!name.is_empty() && name.len() <= 80).then(|| name.to_string())
+}
+
+/// Find the quoted blocks of a body, with the author each one is attributed to
+fn extract_quote_blocks(body: &str) -> Vec<QuoteBlock> {
+    let lines: Vec<(usize, &str)> = body.lines().map(quote_depth).collect();
+    let mut blocks: Vec<QuoteBlock> = Vec::new();
+    
+    let mut i = 0;
+    while i < lines.len() {
+        let depth = lines[i].0;
+        if depth == 0 {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i + 1 < lines.len() && lines[i + 1].0 == depth {
+            i += 1;
+        }
+        
+        // The attribution is the closest non-blank line above, one quote level up
+        let attributed_to = lines[..start].iter()
+            .rev()
+            .find(|(_, text)| !text.trim().is_empty())
+            .filter(|(line_depth, _)| *line_depth + 1 == depth)
+            .and_then(|(_, text)| attribution_author(text))
+            // Deeper levels without their own attribution line inherit the enclosing one
+            .or_else(|| blocks.iter().rev()
+                .find(|b| b.depth == depth && b.end_line + 1 < start)
+                .filter(|_| lines[start - 1].0 > 0)
+                .and_then(|b| b.attributed_to.clone()));
+        
+        blocks.push(QuoteBlock {
+            start_line: start,
+            end_line: i,
+            depth,
+            attributed_to,
+        });
+        i += 1;
+    }
+    
+    blocks
+}
+
+/// Stages of reply cleanup for thread views; each can be turned off per call
+/// The defaults give the compact preview shown in the thread tree
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ReplyCleaning {
+    /// Drop "On ..., X wrote:" lines
+    pub strip_attribution: bool,
+    /// Drop lines starting with ">"
+    pub strip_quotes: bool,
+    /// Drop everything after the "-- " separator
+    pub strip_signature: bool,
+    /// Drop unquoted diff content
+    pub strip_diffs: bool,
+    /// Drop blank lines
+    pub strip_blank_lines: bool,
+    /// Wrap long lines at this width (None keeps lines as they are)
+    pub wrap_width: Option<usize>,
+}
+
+impl Default for ReplyCleaning {
+    fn default() -> Self {
+        Self {
+            strip_attribution: true,
+            strip_quotes: true,
+            strip_signature: true,
+            strip_diffs: true,
+            strip_blank_lines: true,
+            wrap_width: Some(80),
+        }
+    }
+}
+
+/// Extract the actual reply content, filtering out noise
+/// Remove quoted lines, email encoding artifacts, and unwanted formatting
+fn extract_reply_content(body: &str, cleaning: &ReplyCleaning) -> String {
+    // Content should already be decoded by mail-parser.rs based on Content-Transfer-Encoding header
+    // Don't try to guess/re-decode here - just use the raw text as-is
+    
+    // Remove attribution lines
+    let cleaned = if cleaning.strip_attribution {
+        remove_attribution_lines(body)
+    } else {
+        body.to_string()
+    };
+    
+    let mut result = Vec::new();
+    let mut in_signature = false;
+    let mut in_diff = false;
+    
+    for line in cleaned.lines() {
+        let trimmed = line.trim();
+        
+        // Skip empty lines
+        if trimmed.is_empty() {
+            if !cleaning.strip_blank_lines && !in_signature {
+                result.push(line);
+            }
+            continue;
+        }
+        
+        // Detect signature separator (-- or -- with space)
+        if cleaning.strip_signature && (trimmed == "--" || trimmed == "-- ") {
+            in_signature = true;
+            continue;
+        }
+        
+        // Skip lines after signature
+        if in_signature {
+            continue;
+        }
+        
+        // Skip quoted lines (starting with >)
+        if cleaning.strip_quotes && trimmed.starts_with('>') {
+            continue;
+        }
+        
+        // Detect diff/patch content (non-quoted)
+        if cleaning.strip_diffs && (trimmed.starts_with("diff --git") || 
+           trimmed.starts_with("--- a/") ||
+           trimmed.starts_with("+++ b/")) {
+            in_diff = true;
+        }
+        
+        // Skip lines in diff blocks
+        if in_diff {
+            // Exit diff mode if we see normal text (not starting with +, -, @, or space)
+            if !trimmed.starts_with('+') && 
+               !trimmed.starts_with('-') && 
+               !trimmed.starts_with('@') &&
+               !trimmed.starts_with(' ') &&
+               !trimmed.chars().next().unwrap_or(' ').is_ascii_punctuation() {
+                in_diff = false;
+                result.push(line); // Include this line as it's regular text
+            }
+            // Skip diff lines
+            continue;
+        }
+        
+        // Keep everything else
+        result.push(line);
+    }
+    
+    let joined = result.join("\n").trim().to_string();
+    
+    // Wrap (80 characters by default) for readability
+    match cleaning.wrap_width {
+        Some(width) => wrap_text_to_width(&joined, width),
+        None => joined,
+    }
+}
+
+/// Strip "RE:" and similar reply prefixes from subject for display
+/// This is different from normalize_subject which is for matching/comparison
+fn strip_reply_prefix(subject: &str) -> String {
+    let mut cleaned = subject.trim().to_string();
+    
+    // Remove reply prefixes but keep [PATCH] tags
+    let prefixes = ["re:", "Re:", "RE:", "fwd:", "Fwd:", "FWD:", "fw:", "Fw:", "FW:"];
+    loop {
+        let mut changed = false;
+        for prefix in &prefixes {
+            if cleaned.starts_with(prefix) {
+                cleaned = cleaned[prefix.len()..].trim_start().to_string();
+                changed = true;
+                break;
+            }
+        }
+        if !changed {
+            break;
         }
+    }
+    
+    cleaned.trim().to_string()
+}
+
+/// Get full thread tree with nested structure
+/// Previews are cleaned per `cleaning` (quotes, signatures and diffs stripped by default)
+pub async fn get_thread_tree(
+    db: &mut DatabaseManager,
+    thread_id: i64,
+    cleaning: &ReplyCleaning
+) -> Result<ThreadTree, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // Get all messages in thread with series and reply information
+    let messages = sqlx::query(
+        "SELECT 
+            pr.patch_id,
+            pr.parent_patch_id,
+            pr.depth_level,
+            p.subject,
+            p.message_id,
+            p.body_text,
+            p.sent_at,
+            a.display_name,
+            ae.email,
+            p.is_reply,
+            p.is_series,
+            p.series_number,
+            p.series_total,
+            p.commit_hash,
+            p.is_cover_letter,
+            p.sender_type,
+            p.body_compressed,
+            COALESCE(rs.is_read, p.sent_at <= trs.read_through, FALSE),
+            ARRAY(SELECT l.name FROM patch_labels pl JOIN labels l ON l.label_id = pl.label_id
+                  WHERE pl.patch_id = p.patch_id ORDER BY LOWER(l.name)),
+            pms.is_applied,
+            pms.commit_link
+         FROM patch_replies pr
+         JOIN patches p ON pr.patch_id = p.patch_id
+         JOIN authors a ON p.author_id = a.author_id
+         LEFT JOIN author_emails ae ON p.email_id = ae.email_id
+         LEFT JOIN patch_read_state rs ON rs.patch_id = p.patch_id
+         LEFT JOIN thread_read_state trs ON trs.thread_id = pr.thread_id
+         LEFT JOIN patch_merge_status pms ON pms.patch_id = p.patch_id
+         WHERE pr.thread_id = $1
+         ORDER BY pr.position_in_thread ASC"
+    )
+    .bind(thread_id)
+    .fetch_all(pool)
+    .await?;
+    
+    // Trailers of every message in the thread
+    let patch_ids: Vec<i64> = messages.iter().map(|row| row.get(0)).collect();
+    let trailer_rows = sqlx::query(
+        "SELECT patch_id, tag, name, email
+         FROM patch_trailers
+         WHERE patch_id = ANY($1)
+         ORDER BY patch_id, position"
+    )
+    .bind(&patch_ids)
+    .fetch_all(pool)
+    .await?;
+    
+    let mut trailers_by_patch: HashMap<i64, Vec<crate::mail_parser::Trailer>> = HashMap::new();
+    for row in trailer_rows {
+        trailers_by_patch.entry(row.get(0)).or_default().push(crate::mail_parser::Trailer {
+            tag: row.get(1),
+            name: row.get(2),
+            email: row.get(3),
+        });
+    }
+    
+    // Build node map
+    let mut nodes: HashMap<i64, ThreadNode> = HashMap::new();
+    let mut top_ids = Vec::new();
+    
+    for row in &messages {
+        let patch_id: i64 = row.get(0);
+        let parent_id: Option<i64> = row.get(1);
+        let body = crate::database::stored_body(row.get(5), row.get(16))?;
+        let is_reply: bool = row.get(9);
+        let is_series: bool = row.try_get(10).unwrap_or(false);
+        let series_number: Option<i32> = row.try_get(11).ok();
+        let series_total: Option<i32> = row.try_get(12).ok();
+        let commit_hash: Option<String> = row.try_get(13).ok();
+        let is_cover_letter: bool = row.try_get(14).unwrap_or(false);
+        let sender_type = row.try_get::<String, _>(15)
+            .map(|value| crate::mail_parser::SenderType::from_db(&value))
+            .unwrap_or_default();
         
-        // Pattern 2: Contains date patterns with email addresses and "wrote:"
-        // Example: "On Wed, Sep 24, 2025 at 1:43 AM Brahmajit Das <...> wrote:"
-        if trimmed.starts_with("On ") 
-            && (trimmed.contains("@") || trimmed.contains('<'))
-            && trimmed.contains(" wrote:") {
-            continue;
+        let body_text = body.unwrap_or_default();
+        // A "-- >8 --" scissors line separates commentary from the patch below it
+        let (commentary, scissors_patch) = crate::mail_parser::split_at_scissors(&body_text);
+        
+        // Check if body contains diff/patch content
+        // IMPORTANT: Replies (Re:) should never be marked as having patches,
+        // even if they quote patch content - unless the patch follows a scissors line
+        let has_diff = match scissors_patch {
+            Some(patch) => has_diff_content(patch),
+            None => !is_reply && has_diff_content(&body_text),
+        };
+        
+        // Extract actual reply content (removes quoted lines, signatures, diffs)
+        // Don't truncate here - let frontend handle display truncation
+        let (content, signature) = crate::mail_parser::split_signature(commentary);
+        let signature = signature.or_else(|| crate::mail_parser::split_signature(&body_text).1);
+        let cleaned_body = if cleaning.strip_signature {
+            extract_reply_content(content, cleaning)
+        } else {
+            extract_reply_content(commentary, cleaning)
+        };
+        let body_preview = if !cleaned_body.is_empty() {
+            cleaned_body
+        } else {
+            // Fallback: if extraction resulted in empty, show first few lines
+            body_text.lines()
+                .take(20)
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        
+        // Format series info
+        let series_info = if is_series {
+            match (series_number, series_total) {
+                (Some(num), Some(total)) => Some(format!("{}/{}", num, total)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        
+        // Clean subject: strip "RE:" from replies for display
+        let raw_subject: String = row.get(3);
+        let display_subject = strip_reply_prefix(&raw_subject);
+        
+        let node = ThreadNode {
+            patch_id,
+            subject: display_subject,
+            author_name: row.get(7),
+            author_email: row.get::<Option<String>, _>(8).unwrap_or_default(),
+            sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
+            depth: row.get(2),
+            message_id: row.get(4),
+            body_preview,
+            signature: signature.map(str::to_string),
+            is_reply,
+            is_series,
+            series_info,
+            is_cover_letter,
+            sender_type,
+            has_diff,
+            reply_count: 0,  // Will be populated when building tree
+            is_read: row.get(17),
+            labels: row.get(18),
+            is_applied: row.get(19),
+            merge_commit: row.get(20),
+            is_phantom: false,
+            commit_hash,
+            trailers: trailers_by_patch.remove(&patch_id).unwrap_or_default(),
+            children: Vec::new(),
+        };
+        
+        if parent_id.is_none() {
+            top_ids.push(patch_id);
         }
         
-        // Pattern 3: Date-based attribution patterns ending with colon
-        if (trimmed.starts_with("On ") || trimmed.starts_with("Am ")) 
-            && (trimmed.contains(", 20") || trimmed.contains(", 19"))
-            && trimmed.ends_with(':') {
-            continue;
+        nodes.insert(patch_id, node);
+    }
+    
+    // Build tree structure
+    let mut children_map: HashMap<i64, Vec<i64>> = HashMap::new();
+    for row in &messages {
+        let patch_id: i64 = row.get(0);
+        let parent_id: Option<i64> = row.get(1);
+        
+        if let Some(parent) = parent_id {
+            children_map.entry(parent).or_insert_with(Vec::new).push(patch_id);
         }
+    }
+    
+    // Recursive function to build tree
+    fn build_tree(
+        node_id: i64,
+        nodes: &mut HashMap<i64, ThreadNode>,
+        children_map: &HashMap<i64, Vec<i64>>
+    ) -> ThreadNode {
+        let mut node = nodes.remove(&node_id).unwrap();
         
-        // Pattern 4: Lines that start with date and contain <email> and wrote
-        if trimmed.contains(", 20") && trimmed.contains('<') && trimmed.contains('>') 
-            && trimmed.to_lowercase().contains("wrote") {
-            continue;
+        if let Some(child_ids) = children_map.get(&node_id) {
+            node.reply_count = child_ids.len() as i32;
+            for child_id in child_ids {
+                let child_node = build_tree(*child_id, nodes, children_map);
+                node.children.push(child_node);
+            }
         }
         
-        cleaned_lines.push(line);
+        node
+    }
+    
+    // Replies to a message missing from the archive hang below a placeholder for it
+    let phantom_message_id: Option<String> = sqlx::query_scalar(
+        "SELECT root_phantom_message_id FROM patch_threads WHERE thread_id = $1"
+    )
+    .bind(thread_id)
+    .fetch_one(pool)
+    .await?;
+    
+    let mut tops: Vec<ThreadNode> = top_ids.iter()
+        .map(|&top_id| build_tree(top_id, &mut nodes, &children_map))
+        .collect();
+    let root = match phantom_message_id {
+        Some(message_id) if tops.len() > 1 => phantom_node(message_id, tops),
+        _ if !tops.is_empty() => tops.remove(0),
+        _ => return Err("Thread has no messages".into()),
+    };
+    
+    // Get thread summary with merge status
+    let summary_row = sqlx::query(&format!(
+        "{}
+         WHERE ts.thread_id = $1",
+        THREAD_SUMMARY_SELECT
+    ))
+    .bind(thread_id)
+    .fetch_one(pool)
+    .await?;
+    
+    let summary = thread_summary_from_row(&summary_row);
+    
+    Ok(ThreadTree {
+        thread_id,
+        summary,
+        root,
+    })
+}
+
+/// Placeholder node for the missing message the top-level messages of a thread reply to
+fn phantom_node(message_id: String, children: Vec<ThreadNode>) -> ThreadNode {
+    ThreadNode {
+        patch_id: 0,
+        // The replies' subject without "Re:" is the best guess for the missing message's
+        subject: strip_reply_prefix(&children[0].subject),
+        author_name: String::new(),
+        author_email: String::new(),
+        sent_at: children[0].sent_at.clone(),
+        depth: 0,
+        message_id,
+        body_preview: String::new(),
+        signature: None,
+        is_reply: false,
+        is_series: false,
+        series_info: None,
+        is_cover_letter: false,
+        sender_type: Default::default(),
+        has_diff: false,
+        reply_count: children.len() as i32,
+        is_read: true,
+        labels: Vec::new(),
+        is_applied: None,
+        merge_commit: None,
+        is_phantom: true,
+        commit_hash: None,
+        trailers: Vec::new(),
+        children,
+    }
+}
+
+/// Get full patch body including diff
+pub async fn get_patch_body(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // Bodies may be stored compressed, see DatabaseConfig::compress_bodies
+    let row = sqlx::query(
+        "SELECT p.body_text, p.body_compressed, p.body_pruned, p.commit_hash, l.repo_path
+         FROM patches p
+         LEFT JOIN mailing_lists l ON l.list_id = p.list_id
+         WHERE p.patch_id = $1"
+    )
+    .bind(patch_id)
+    .fetch_optional(pool)
+    .await?;
+    
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let body_text: Option<String> = row.get("body_text");
+    let body_compressed: Option<Vec<u8>> = row.get("body_compressed");
+    let body_pruned: Option<bool> = row.get("body_pruned");
+    let commit_hash: Option<String> = row.get("commit_hash");
+    let repo_path: Option<String> = row.get("repo_path");
+    
+    // Bodies dropped by the retention policy are read back from the archive of the patch's own list
+    if let (Some(true), Some(commit_hash)) = (body_pruned, commit_hash) {
+        let body = tokio::task::spawn_blocking(move || {
+            let repo_path = match repo_path.filter(|path| !path.is_empty()) {
+                Some(path) => path,
+                None => crate::git_parser::default_mailing_list()?.repo_path,
+            };
+            crate::database::refetch_body_from_git(&repo_path, &commit_hash)
+        })
+        .await?
+        .map_err(|e| format!("Body was pruned and could not be re-read from git: {}", e))?;
+        return Ok(Some(body));
+    }
+    
+    Ok(crate::database::stored_body(body_text, body_compressed)?)
+}
+
+/// Result of resolving a Message-ID
+#[derive(Debug, Serialize)]
+pub struct MessageLookup {
+    pub message_id: String,
+    /// Where the message was found: "database" or "msgmap"
+    pub source: String,
+    pub patch_id: Option<i64>,
+    pub commit_hash: Option<String>,
+    pub subject: Option<String>,
+    /// public-inbox article number (msgmap only)
+    pub article_number: Option<i64>,
+    /// Raw email read through the msgmap blob, for messages not in the database yet
+    pub raw_email: Option<String>,
+    /// Thread holding the message (database only, once threads are built)
+    pub thread: Option<ThreadSummary>,
+}
+
+/// Message-ID typed or pasted by the user: bare, in angle brackets, or as a lore.kernel.org,
+/// lkml.kernel.org/r or patch.msgid.link URL (with or without the scheme)
+fn normalize_message_id_input(input: &str) -> String {
+    let input = input.trim();
+    let url = if input.starts_with("http://") || input.starts_with("https://") {
+        Some(input.to_string())
+    } else if ["lore.kernel.org/", "lkml.kernel.org/", "patch.msgid.link/"].iter().any(|host| input.starts_with(host)) {
+        Some(format!("https://{}", input))
+    } else {
+        None
+    };
+    
+    url.and_then(|url| crate::mail_parser::message_id_from_url(&url))
+        .unwrap_or_else(|| input.trim_start_matches('<').trim_end_matches('>').to_string())
+}
+
+/// Resolve a Message-ID to a patch and its thread
+/// `message_id` may also be a lore URL (see `normalize_message_id_input`).
+/// The database is checked first; messages that aren't populated yet are looked up in the
+/// inbox's msgmap (when the archive has one) instead of scanning commits.
+pub async fn get_patch_by_message_id(
+    db: &mut DatabaseManager,
+    message_id: &str
+) -> Result<Option<MessageLookup>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    let message_id = normalize_message_id_input(message_id);
+    
+    let row: Option<(i64, Option<String>, String)> = sqlx::query_as(
+        "SELECT patch_id, commit_hash, subject FROM patches WHERE message_id = $1"
+    )
+    .bind(&message_id)
+    .fetch_optional(pool)
+    .await?;
+    
+    if let Some((patch_id, commit_hash, subject)) = row {
+        let thread = sqlx::query(&format!(
+            "{} WHERE ts.thread_id = (SELECT thread_id FROM patch_replies WHERE patch_id = $1)",
+            THREAD_SUMMARY_SELECT
+        ))
+        .bind(patch_id)
+        .fetch_optional(pool)
+        .await?
+        .map(|row| thread_summary_from_row(&row));
+        
+        return Ok(Some(MessageLookup {
+            message_id,
+            source: "database".to_string(),
+            patch_id: Some(patch_id),
+            commit_hash,
+            subject: Some(subject),
+            article_number: None,
+            raw_email: None,
+            thread,
+        }));
     }
     
-    cleaned_lines.join("\n")
+    let Some(msgmap) = crate::msgmap::MsgMap::open_configured().await? else {
+        return Ok(None);
+    };
+    let Some(entry) = msgmap.lookup(&message_id).await? else {
+        return Ok(None);
+    };
+    
+    let raw_email = match entry.blob.clone() {
+        Some(blob) => tokio::task::spawn_blocking(move || crate::git_parser::get_email_content_by_blob(&blob))
+            .await?
+            .ok(),
+        None => None,
+    };
+    let subject = raw_email.as_deref()
+        .and_then(|raw| mailparse::parse_headers(raw.as_bytes()).ok())
+        .and_then(|(headers, _)| headers.get_first_value("Subject"));
+    
+    Ok(Some(MessageLookup {
+        message_id,
+        source: "msgmap".to_string(),
+        patch_id: None,
+        commit_hash: None,
+        subject,
+        article_number: Some(entry.num),
+        raw_email,
+        thread: None,
+    }))
 }
 
-/// Check if body contains git diff/patch content (not quoted)
-/// This should return true only for actual patches, not replies quoting patches
-/// Improved: requires multiple consecutive diff lines to avoid false positives
-fn has_diff_content(body: &str) -> bool {
-    let mut consecutive_diff_lines = 0;
-    const MIN_DIFF_LINES: i32 = 3; // Require at least 3 consecutive diff lines
-    
-    for line in body.lines() {
-        let trimmed = line.trim();
-        
-        // Skip empty lines (don't reset counter)
-        if trimmed.is_empty() {
-            continue;
-        }
-        
-        // Skip quoted lines (these are not the actual patch, just quoted content)
-        if trimmed.starts_with('>') {
-            consecutive_diff_lines = 0; // Reset counter
-            continue;
-        }
-        
-        // Check for actual diff markers in non-quoted lines
-        let is_diff_line = trimmed.starts_with("diff --git") 
-            || trimmed.starts_with("--- a/")
-            || trimmed.starts_with("+++ b/")
-            || (trimmed.starts_with("@@") && trimmed.contains("@@"))
-            || (trimmed.starts_with("index ") && trimmed.len() > 10)
-            || trimmed.starts_with("new file mode")
-            || trimmed.starts_with("deleted file mode")
-            || (consecutive_diff_lines > 0 && (trimmed.starts_with('+') || trimmed.starts_with('-') || trimmed.starts_with(' ')));
-        
-        if is_diff_line {
-            consecutive_diff_lines += 1;
-            if consecutive_diff_lines >= MIN_DIFF_LINES {
-                return true;
-            }
-        } else {
-            // Non-diff line found, reset counter
-            consecutive_diff_lines = 0;
+/// A patch linked to a commit through a "Fixes:" tag
+#[derive(Debug, Serialize)]
+pub struct FixesLink {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    pub fixes_commit: String,
+    pub fixes_subject: Option<String>,
+}
+
+/// Shortest abbreviated hash accepted for Fixes: lookups
+const MIN_FIXES_HASH_LEN: usize = 7;
+
+/// Subject without reply prefixes and leading [PATCH ...] tags, as quoted in Fixes: lines
+fn strip_patch_prefixes(subject: &str) -> String {
+    let mut cleaned = strip_reply_prefix(subject);
+    while cleaned.starts_with('[') {
+        match cleaned.find(']') {
+            Some(end) => cleaned = cleaned[end + 1..].trim_start().to_string(),
+            None => break,
         }
     }
-    
-    false
+    cleaned
 }
 
-/// Wrap text to fit within specified character width
-fn wrap_text_to_width(text: &str, max_width: usize) -> String {
-    let mut result = Vec::new();
+/// Map Fixes: query rows to FixesLink
+fn fixes_links_from_rows(rows: &[sqlx::postgres::PgRow]) -> Vec<FixesLink> {
+    rows.iter().map(|row| FixesLink {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        fixes_commit: row.get(4),
+        fixes_subject: row.get(5),
+    }).collect()
+}
+
+/// Find patches whose Fixes: tag points at `commit_hash` (full or abbreviated)
+pub async fn find_patches_fixing_commit(
+    db: &mut DatabaseManager,
+    commit_hash: &str
+) -> Result<Vec<FixesLink>, Box<dyn std::error::Error>> {
+    let commit_hash = commit_hash.trim().to_lowercase();
+    if commit_hash.len() < MIN_FIXES_HASH_LEN || !commit_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' is not a commit hash of at least {} hex digits", commit_hash, MIN_FIXES_HASH_LEN).into());
+    }
     
-    for line in text.lines() {
-        if line.len() <= max_width {
-            result.push(line.to_string());
-        } else {
-            // Split long lines at word boundaries
-            let mut current_line = String::new();
-            for word in line.split_whitespace() {
-                if current_line.is_empty() {
-                    current_line = word.to_string();
-                } else if current_line.len() + word.len() + 1 <= max_width {
-                    current_line.push(' ');
-                    current_line.push_str(word);
-                } else {
-                    result.push(current_line);
-                    current_line = word.to_string();
-                }
-            }
-            if !current_line.is_empty() {
-                result.push(current_line);
-            }
-        }
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // Tags use abbreviated hashes of varying length, so match prefixes both ways
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, f.fixes_commit, f.fixes_subject
+         FROM patch_fixes f
+         JOIN patches p ON f.patch_id = p.patch_id
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE f.fixes_commit LIKE $1 || '%' OR $1 LIKE f.fixes_commit || '%'
+         ORDER BY p.sent_at ASC"
+    )
+    .bind(&commit_hash)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(fixes_links_from_rows(&rows))
+}
+
+/// A message referring to a commit hash
+#[derive(Debug, Serialize)]
+pub struct CommitReference {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+    /// Hash as written in the message
+    pub commit_hash: String,
+    /// "fixes", "revert" or "mention"
+    pub kind: String,
+    pub thread_id: Option<i64>,
+}
+
+/// Find messages referring to `commit_hash` in a Fixes: tag, a revert or the text
+pub async fn find_patches_referencing_commit(
+    db: &mut DatabaseManager,
+    commit_hash: &str,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<CommitReference>, Box<dyn std::error::Error>> {
+    let commit_hash = commit_hash.trim().to_lowercase();
+    if commit_hash.len() < MIN_FIXES_HASH_LEN || !commit_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' is not a commit hash of at least {} hex digits", commit_hash, MIN_FIXES_HASH_LEN).into());
     }
+    // Shorter abbreviations of the hash, matched by equality so both directions use the index
+    let abbreviations: Vec<&str> = (MIN_FIXES_HASH_LEN..commit_hash.len())
+        .map(|len| &commit_hash[..len])
+        .collect();
     
-    result.join("\n")
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    // A message quoting the commit under several abbreviations is listed once, with its strongest kind
+    let rows = sqlx::query(
+        "SELECT * FROM (
+           SELECT DISTINCT ON (p.patch_id)
+                  p.patch_id, p.subject, a.display_name, p.sent_at, c.commit_hash, c.kind, r.thread_id
+           FROM patch_commit_refs c
+           JOIN patches p ON c.patch_id = p.patch_id
+           JOIN authors a ON p.author_id = a.author_id
+           LEFT JOIN patch_replies r ON r.patch_id = p.patch_id
+           WHERE (c.commit_hash LIKE $1 || '%' OR c.commit_hash = ANY($2))
+             AND ($3::INT IS NULL OR p.list_id = $3)
+           ORDER BY p.patch_id,
+                    CASE c.kind WHEN 'fixes' THEN 0 WHEN 'revert' THEN 1 ELSE 2 END,
+                    LENGTH(c.commit_hash) DESC
+         ) refs
+         ORDER BY sent_at ASC
+         LIMIT $4"
+    )
+    .bind(&commit_hash)
+    .bind(&abbreviations)
+    .bind(list_id)
+    .bind(limit.unwrap_or(100) as i64)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(rows.iter().map(|row| CommitReference {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+        commit_hash: row.get(4),
+        kind: row.get(5),
+        thread_id: row.get(6),
+    }).collect())
 }
 
-/// Extract the actual reply content, filtering out noise
-/// Remove quoted lines, email encoding artifacts, and unwanted formatting
-fn extract_reply_content(body: &str) -> String {
-    // Content should already be decoded by mail-parser.rs based on Content-Transfer-Encoding header
-    // Don't try to guess/re-decode here - just use the raw text as-is
+/// Find later patches whose Fixes: tag quotes the subject of `patch_id`
+/// Emails don't know their upstream commit hash, so the quoted subject is the link
+pub async fn find_fixes_for_patch(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<Vec<FixesLink>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
     
-    // Remove attribution lines
-    let cleaned = remove_attribution_lines(body);
+    let patch: Option<(String, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+        "SELECT subject, sent_at FROM patches WHERE patch_id = $1"
+    )
+    .bind(patch_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((subject, sent_at)) = patch else {
+        return Err(format!("Patch {} not found", patch_id).into());
+    };
     
-    let mut result = Vec::new();
-    let mut in_signature = false;
-    let mut in_diff = false;
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at, f.fixes_commit, f.fixes_subject
+         FROM patch_fixes f
+         JOIN patches p ON f.patch_id = p.patch_id
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE LOWER(f.fixes_subject) = LOWER($1)
+           AND p.patch_id <> $2
+           AND p.sent_at > $3
+         ORDER BY p.sent_at ASC"
+    )
+    .bind(strip_patch_prefixes(&subject))
+    .bind(patch_id)
+    .bind(sent_at)
+    .fetch_all(pool)
+    .await?;
     
-    for line in cleaned.lines() {
-        let trimmed = line.trim();
-        
-        // Skip empty lines
-        if trimmed.is_empty() {
-            continue;
-        }
-        
-        // Detect signature separator (-- or -- with space)
-        if trimmed == "--" || trimmed == "-- " {
-            in_signature = true;
-            continue;
-        }
-        
-        // Skip lines after signature
-        if in_signature {
-            continue;
-        }
-        
-        // Skip quoted lines (starting with >)
-        if trimmed.starts_with('>') {
-            continue;
-        }
-        
-        // Detect diff/patch content (non-quoted)
-        if trimmed.starts_with("diff --git") || 
-           trimmed.starts_with("--- a/") ||
-           trimmed.starts_with("+++ b/") {
-            in_diff = true;
-        }
-        
-        // Skip lines in diff blocks
-        if in_diff {
-            // Exit diff mode if we see normal text (not starting with +, -, @, or space)
-            if !trimmed.starts_with('+') && 
-               !trimmed.starts_with('-') && 
-               !trimmed.starts_with('@') &&
-               !trimmed.starts_with(' ') &&
-               !trimmed.chars().next().unwrap_or(' ').is_ascii_punctuation() {
-                in_diff = false;
-                result.push(line); // Include this line as it's regular text
-            }
-            // Skip diff lines
-            continue;
+    Ok(fixes_links_from_rows(&rows))
+}
+
+/// A patch on one side of a revert
+#[derive(Debug, Serialize)]
+pub struct RevertLink {
+    pub patch_id: i64,
+    pub subject: String,
+    pub author_name: String,
+    pub sent_at: String,
+}
+
+/// What a patch reverts and what reverts it
+#[derive(Debug, Serialize)]
+pub struct PatchReverts {
+    pub reverted_commit: Option<String>,
+    pub reverted_subject: Option<String>,
+    /// Earlier postings of the commit this patch reverts
+    pub reverts: Vec<RevertLink>,
+    /// Later patches reverting this one
+    pub reverted_by: Vec<RevertLink>,
+}
+
+/// Map revert query rows to RevertLink
+fn revert_links_from_rows(rows: &[sqlx::postgres::PgRow]) -> Vec<RevertLink> {
+    rows.iter().map(|row| RevertLink {
+        patch_id: row.get(0),
+        subject: row.get(1),
+        author_name: row.get(2),
+        sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(3).to_rfc3339(),
+    }).collect()
+}
+
+/// Link a patch to the patch it reverts and to patches reverting it
+/// Emails don't know their upstream commit hash, so the reverted subject is the link
+pub async fn get_patch_reverts(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<PatchReverts, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let patch = sqlx::query(
+        "SELECT subject, sent_at, reverted_commit, reverted_subject FROM patches WHERE patch_id = $1"
+    )
+    .bind(patch_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(patch) = patch else {
+        return Err(format!("Patch {} not found", patch_id).into());
+    };
+    let subject: String = patch.get(0);
+    let sent_at: chrono::DateTime<chrono::Utc> = patch.get(1);
+    let reverted_commit: Option<String> = patch.get(2);
+    let reverted_subject: Option<String> = patch.get(3);
+    
+    let reverts = match &reverted_subject {
+        Some(reverted_subject) => {
+            let rows = sqlx::query(
+                "SELECT p.patch_id, p.subject, a.display_name, p.sent_at
+                 FROM patches p
+                 JOIN authors a ON p.author_id = a.author_id
+                 WHERE LOWER(regexp_replace(p.subject, '^(\\[[^]]*\\]\\s*)+', '')) = LOWER($1)
+                   AND p.is_reply = FALSE
+                   AND p.sent_at < $2
+                 ORDER BY p.sent_at DESC"
+            )
+            .bind(reverted_subject)
+            .bind(sent_at)
+            .fetch_all(pool)
+            .await?;
+            revert_links_from_rows(&rows)
         }
-        
-        // Keep everything else
-        result.push(line);
-    }
+        None => Vec::new(),
+    };
     
-    let joined = result.join("\n").trim().to_string();
+    let rows = sqlx::query(
+        "SELECT p.patch_id, p.subject, a.display_name, p.sent_at
+         FROM patches p
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE p.is_revert = TRUE
+           AND LOWER(p.reverted_subject) = LOWER($1)
+           AND p.patch_id <> $2
+           AND p.sent_at > $3
+         ORDER BY p.sent_at ASC"
+    )
+    .bind(strip_patch_prefixes(&subject))
+    .bind(patch_id)
+    .bind(sent_at)
+    .fetch_all(pool)
+    .await?;
+    
+    Ok(PatchReverts {
+        reverted_commit,
+        reverted_subject,
+        reverts,
+        reverted_by: revert_links_from_rows(&rows),
+    })
+}
+
+/// A link between two messages (from a Link: trailer or lore URL)
+#[derive(Debug, Serialize)]
+pub struct MessageCrossReference {
+    pub url: String,
+    pub message_id: Option<String>,
+    pub is_trailer: bool,
+    /// The other end of the link, when it is in the database
+    pub patch_id: Option<i64>,
+    pub subject: Option<String>,
+    pub thread_id: Option<i64>,
+}
+
+/// Links out of a patch and links from other patches into it
+#[derive(Debug, Serialize)]
+pub struct PatchCrossReferences {
+    pub outgoing: Vec<MessageCrossReference>,
+    pub incoming: Vec<MessageCrossReference>,
+}
+
+/// Resolve the Link:/lore references of a patch against the database, in both directions
+pub async fn get_patch_cross_references(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<PatchCrossReferences, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let to_reference = |row: &sqlx::postgres::PgRow| MessageCrossReference {
+        url: row.get(0),
+        message_id: row.get(1),
+        is_trailer: row.try_get(2).unwrap_or(false),
+        patch_id: row.get(3),
+        subject: row.get(4),
+        thread_id: row.get(5),
+    };
+    
+    let outgoing_rows = sqlx::query(
+        "SELECT l.url, l.message_id, l.is_trailer, p.patch_id, p.subject, pr.thread_id
+         FROM patch_links l
+         LEFT JOIN patches p ON p.message_id = l.message_id
+         LEFT JOIN patch_replies pr ON pr.patch_id = p.patch_id
+         WHERE l.patch_id = $1
+         ORDER BY l.position"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
+    
+    // Patches whose links point at this patch's Message-ID
+    let incoming_rows = sqlx::query(
+        "SELECT l.url, l.message_id, l.is_trailer, src.patch_id, src.subject, pr.thread_id
+         FROM patches target
+         JOIN patch_links l ON l.message_id = target.message_id
+         JOIN patches src ON src.patch_id = l.patch_id
+         LEFT JOIN patch_replies pr ON pr.patch_id = src.patch_id
+         WHERE target.patch_id = $1 AND src.patch_id <> $1
+         ORDER BY src.sent_at ASC"
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
     
-    // Wrap to 80 characters for readability
-    wrap_text_to_width(&joined, 80)
+    Ok(PatchCrossReferences {
+        outgoing: outgoing_rows.iter().map(to_reference).collect(),
+        incoming: incoming_rows.iter().map(to_reference).collect(),
+    })
+}
+
+/// Get a message body along with its quoted blocks (for collapsible quotes)
+pub async fn get_patch_body_with_quotes(
+    db: &mut DatabaseManager,
+    patch_id: i64
+) -> Result<Option<QuotedBody>, Box<dyn std::error::Error>> {
+    let body = get_patch_body(db, patch_id).await?;
+    Ok(body.map(|body| QuotedBody {
+        quotes: extract_quote_blocks(&body),
+        body,
+    }))
+}
+
+/// A review comment anchored to the hunk of the patch it replies to
+#[derive(Debug, Serialize)]
+pub struct InlineComment {
+    pub patch_id: i64,      // The reply containing the comment
+    pub author_name: String,
+    pub sent_at: String,
+    pub file: String,
+    pub hunk_index: usize,  // Index into the file's hunks
+    pub hunk_section: Option<String>,
+    pub old_line: Option<u32>,  // Line the comment follows, on each side of the diff
+    pub new_line: Option<u32>,
+    pub quoted: Vec<String>,    // Quoted diff lines the comment follows
+    pub comment: String,
+}
+
+/// Where a comment sits in the parent diff: (file, hunk, line within hunk)
+type DiffAnchor = (usize, usize, usize);
+
+/// Like quote_depth, but only one space after each ">" is removed so diff prefixes survive
+fn unquote_diff_line(line: &str) -> (usize, &str) {
+    let mut depth = 0;
+    let mut rest = line;
+    while let Some(after) = rest.strip_prefix('>') {
+        depth += 1;
+        rest = after.strip_prefix(' ').unwrap_or(after);
+    }
+    (depth, rest)
 }
 
-/// Strip "RE:" and similar reply prefixes from subject for display
-/// This is different from normalize_subject which is for matching/comparison
-fn strip_reply_prefix(subject: &str) -> String {
-    let mut cleaned = subject.trim().to_string();
+/// Compare diff lines ignoring whitespace mangled by mail clients
+fn normalize_diff_line(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find a quoted line in the diff, preferring positions right after the previous match
+fn find_quoted_diff_line(
+    hunks: &[(usize, usize, Vec<String>)],
+    text: &str,
+    cursor: Option<DiffAnchor>,
+) -> Option<DiffAnchor> {
+    let wanted = normalize_diff_line(text);
+    if wanted.is_empty() {
+        return None;
+    }
+    // Lines like "}" or "+" match all over the place, so only follow on from the cursor
+    let distinctive = wanted.chars().filter(|c| c.is_alphanumeric()).count() >= 3;
     
-    // Remove reply prefixes but keep [PATCH] tags
-    let prefixes = ["re:", "Re:", "RE:", "fwd:", "Fwd:", "FWD:", "fw:", "Fw:", "FW:"];
-    loop {
-        let mut changed = false;
-        for prefix in &prefixes {
-            if cleaned.starts_with(prefix) {
-                cleaned = cleaned[prefix.len()..].trim_start().to_string();
-                changed = true;
-                break;
-            }
-        }
-        if !changed {
+    let start = cursor
+        .and_then(|(f, h, _)| hunks.iter().position(|(hf, hh, _)| (*hf, *hh) == (f, h)))
+        .unwrap_or(0);
+    let after_cursor = cursor.map(|(_, _, l)| l + 1).unwrap_or(0);
+    
+    for (i, (file, hunk, lines)) in hunks.iter().enumerate().cycle().skip(start).take(hunks.len()) {
+        let from = if i == start { after_cursor } else { 0 };
+        if !distinctive && (i != start || cursor.is_none()) {
             break;
         }
+        if let Some(offset) = lines.iter().skip(from).position(|l| *l == wanted) {
+            return Some((*file, *hunk, from + offset));
+        }
     }
-    
-    cleaned.trim().to_string()
+    None
 }
 
-/// Get full thread tree with nested structure
-pub async fn get_thread_tree(
-    db: &mut DatabaseManager,
-    thread_id: i64
-) -> Result<ThreadTree, Box<dyn std::error::Error>> {
-    db.ensure_connected().await?;
-    let pool = db.get_pool()?;
+/// Split a reply into comments, each anchored to the last diff line quoted above it
+fn anchor_review_comments(
+    diff: &crate::diff_parser::DiffSummary,
+    reply_body: &str,
+) -> Vec<(DiffAnchor, Vec<String>, String)> {
+    let hunks: Vec<(usize, usize, Vec<String>)> = diff.files.iter().enumerate()
+        .flat_map(|(f, file)| file.hunks.iter().enumerate().map(move |(h, hunk)| {
+            (f, h, hunk.lines.iter().map(|l| normalize_diff_line(l)).collect())
+        }))
+        .collect();
     
-    // Get all messages in thread with series and reply information
-    let messages = sqlx::query(
-        "SELECT 
-            pr.patch_id,
-            pr.parent_patch_id,
-            pr.depth_level,
-            p.subject,
-            p.message_id,
-            p.body_text,
-            p.sent_at,
-            a.display_name,
-            ae.email,
-            p.is_reply,
-            p.is_series,
-            p.series_number,
-            p.series_total,
-            p.commit_hash
-         FROM patch_replies pr
-         JOIN patches p ON pr.patch_id = p.patch_id
-         JOIN authors a ON p.author_id = a.author_id
-         LEFT JOIN author_emails ae ON p.email_id = ae.email_id
-         WHERE pr.thread_id = $1
-         ORDER BY pr.position_in_thread ASC"
-    )
-    .bind(thread_id)
-    .fetch_all(pool)
-    .await?;
+    let mut comments = Vec::new();
+    let mut cursor: Option<DiffAnchor> = None;
+    // Anchor and quoted lines of the current quote run; reset once a comment closes it
+    let mut anchor: Option<DiffAnchor> = None;
+    let mut quoted: Vec<String> = Vec::new();
+    let mut comment: Vec<&str> = Vec::new();
     
-    // Build node map
-    let mut nodes: HashMap<i64, ThreadNode> = HashMap::new();
-    let mut root_id = None;
+    let mut flush = |anchor: &mut Option<DiffAnchor>, quoted: &mut Vec<String>, comment: &mut Vec<&str>| {
+        let text = comment.join("\n").trim().to_string();
+        if let (Some(at), false) = (*anchor, text.is_empty()) {
+            comments.push((at, std::mem::take(quoted), text));
+        }
+        *anchor = None;
+        quoted.clear();
+        comment.clear();
+    };
     
-    for row in &messages {
-        let patch_id: i64 = row.get(0);
-        let parent_id: Option<i64> = row.get(1);
-        let body: Option<String> = row.get(5);
-        let is_reply: bool = row.get(9);
-        let is_series: bool = row.try_get(10).unwrap_or(false);
-        let series_number: Option<i32> = row.try_get(11).ok();
-        let series_total: Option<i32> = row.try_get(12).ok();
-        let commit_hash: Option<String> = row.try_get(13).ok();
-        
-        let body_text = body.unwrap_or_default();
-        
-        // Check if body contains diff/patch content
-        // IMPORTANT: Replies (Re:) should never be marked as having patches,
-        // even if they quote patch content
-        let has_diff = !is_reply && has_diff_content(&body_text);
-        
-        // Extract actual reply content (removes quoted lines, signatures, diffs)
-        // Don't truncate here - let frontend handle display truncation
-        let cleaned_body = extract_reply_content(&body_text);
-        let body_preview = if !cleaned_body.is_empty() {
-            cleaned_body
-        } else {
-            // Fallback: if extraction resulted in empty, show first few lines
-            body_text.lines()
-                .take(20)
-                .collect::<Vec<_>>()
-                .join("\n")
-        };
+    for line in reply_body.lines() {
+        let line = line.trim_end_matches('\r');
+        let (depth, text) = unquote_diff_line(line);
         
-        // Format series info
-        let series_info = if is_series {
-            match (series_number, series_total) {
-                (Some(num), Some(total)) => Some(format!("{}/{}", num, total)),
-                _ => None,
+        if depth == 0 {
+            if line == "-- " {
+                break;
             }
-        } else {
-            None
-        };
-        
-        // Clean subject: strip "RE:" from replies for display
-        let raw_subject: String = row.get(3);
-        let display_subject = strip_reply_prefix(&raw_subject);
-        
-        let node = ThreadNode {
-            patch_id,
-            subject: display_subject,
-            author_name: row.get(7),
-            author_email: row.get::<Option<String>, _>(8).unwrap_or_default(),
-            sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
-            depth: row.get(2),
-            message_id: row.get(4),
-            body_preview,
-            is_reply,
-            is_series,
-            series_info,
-            has_diff,
-            reply_count: 0,  // Will be populated when building tree
-            commit_hash,
-            children: Vec::new(),
-        };
-        
-        if parent_id.is_none() {
-            root_id = Some(patch_id);
+            if anchor.is_some() && (!comment.is_empty() || !line.trim().is_empty()) {
+                comment.push(line);
+            }
+            continue;
         }
         
-        nodes.insert(patch_id, node);
-    }
-    
-    // Build tree structure
-    let mut children_map: HashMap<i64, Vec<i64>> = HashMap::new();
-    for row in &messages {
-        let patch_id: i64 = row.get(0);
-        let parent_id: Option<i64> = row.get(1);
-        
-        if let Some(parent) = parent_id {
-            children_map.entry(parent).or_insert_with(Vec::new).push(patch_id);
+        if !comment.is_empty() {
+            flush(&mut anchor, &mut quoted, &mut comment);
         }
-    }
-    
-    // Recursive function to build tree
-    fn build_tree(
-        node_id: i64,
-        nodes: &mut HashMap<i64, ThreadNode>,
-        children_map: &HashMap<i64, Vec<i64>>
-    ) -> ThreadNode {
-        let mut node = nodes.remove(&node_id).unwrap();
-        
-        if let Some(child_ids) = children_map.get(&node_id) {
-            node.reply_count = child_ids.len() as i32;
-            for child_id in child_ids {
-                let child_node = build_tree(*child_id, nodes, children_map);
-                node.children.push(child_node);
+        if let Some(found) = find_quoted_diff_line(&hunks, text, cursor) {
+            // A jump to another hunk starts a new context
+            if anchor.is_some_and(|(f, h, _)| (f, h) != (found.0, found.1)) {
+                quoted.clear();
             }
+            cursor = Some(found);
+            anchor = Some(found);
+            quoted.push(text.to_string());
         }
-        
-        node
     }
+    flush(&mut anchor, &mut quoted, &mut comment);
     
-    let root = build_tree(root_id.unwrap(), &mut nodes, &children_map);
-    
-    // Get thread summary with merge status
-    let summary_row = sqlx::query(
-        "SELECT 
-            ts.thread_id,
-            ts.root_subject,
-            ts.root_author,
-            ts.reply_count,
-            ts.participant_count,
-            ts.created_at,
-            ts.last_activity_at,
-            ts.root_patch_id,
-            mt.merge_repository,
-            mt.merge_branch,
-            mt.merge_applied_by,
-            mt.merge_date,
-            mt.commit_count
-         FROM thread_summary ts
-         LEFT JOIN merged_threads mt ON ts.thread_id = mt.thread_id
-         WHERE ts.thread_id = $1"
-    )
-    .bind(thread_id)
-    .fetch_one(pool)
-    .await?;
-    
-    let merge_status = if let Ok(Some(repo)) = summary_row.try_get::<Option<String>, _>(8) {
-        Some(MergeStatusInfo {
-            is_merged: true,
-            merge_date: summary_row.get::<chrono::DateTime<chrono::Utc>, _>(11).to_rfc3339(),
-            repository: repo,
-            branch: summary_row.get::<String, _>(9),
-            applied_by: summary_row.get::<String, _>(10),
-            commit_count: summary_row.get::<Option<i32>, _>(12).unwrap_or(0),
-        })
-    } else {
-        None
-    };
-    
-    let summary = ThreadSummary {
-        thread_id: summary_row.get(0),
-        root_subject: summary_row.get(1),
-        root_author: summary_row.get(2),
-        reply_count: summary_row.get(3),
-        participant_count: summary_row.get(4),
-        created_at: summary_row.get::<chrono::DateTime<chrono::Utc>, _>(5).to_rfc3339(),
-        last_activity: summary_row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
-        root_patch_id: summary_row.get(7),
-        merge_status,
-    };
-    
-    Ok(ThreadTree {
-        thread_id,
-        summary,
-        root,
-    })
+    comments
 }
 
-/// Get full patch body including diff
-pub async fn get_patch_body(
+/// Get review comments on a patch, anchored to the files and hunks they quote
+pub async fn get_inline_comments(
     db: &mut DatabaseManager,
     patch_id: i64
-) -> Result<Option<String>, Box<dyn std::error::Error>> {
+) -> Result<Vec<InlineComment>, Box<dyn std::error::Error>> {
     db.ensure_connected().await?;
     let pool = db.get_pool()?;
     
-    let row: Option<(Option<String>,)> = sqlx::query_as(
-        "SELECT body_text FROM patches WHERE patch_id = $1"
+    let patch: Option<(String, Option<String>, Option<Vec<u8>>)> = sqlx::query_as(
+        "SELECT message_id, body_text, body_compressed FROM patches WHERE patch_id = $1"
     )
     .bind(patch_id)
     .fetch_optional(pool)
     .await?;
     
-    Ok(row.and_then(|(body,)| body))
+    let Some((message_id, body_text, body_compressed)) = patch else {
+        return Ok(Vec::new());
+    };
+    let Some(body) = crate::database::stored_body(body_text, body_compressed)? else {
+        return Ok(Vec::new());
+    };
+    let patch_body = crate::mail_parser::split_at_scissors(&body).1.unwrap_or(&body);
+    let Some(diff) = crate::diff_parser::parse_diff(patch_body) else {
+        return Ok(Vec::new());
+    };
+    
+    // Direct replies and deeper ones (which may still quote the diff)
+    let rows = sqlx::query(
+        "SELECT p.patch_id, a.display_name, p.sent_at, p.body_text, p.body_compressed
+         FROM patches p
+         JOIN authors a ON p.author_id = a.author_id
+         WHERE (p.in_reply_to = $1 OR $1 = ANY(p.thread_references))
+           AND p.patch_id <> $2
+           AND (p.body_text IS NOT NULL OR p.body_compressed IS NOT NULL)
+         ORDER BY p.sent_at"
+    )
+    .bind(&message_id)
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await?;
+    
+    let mut result = Vec::new();
+    for row in &rows {
+        let reply_body = crate::database::stored_body(row.get(3), row.get(4))?.unwrap_or_default();
+        for ((f, h, l), quoted, comment) in anchor_review_comments(&diff, &reply_body) {
+            let file = &diff.files[f];
+            let hunk = &file.hunks[h];
+            let (old_line, new_line) = hunk.line_numbers()[l];
+            result.push(InlineComment {
+                patch_id: row.get(0),
+                author_name: row.get(1),
+                sent_at: row.get::<chrono::DateTime<chrono::Utc>, _>(2).to_rfc3339(),
+                file: file.path().to_string(),
+                hunk_index: h,
+                hunk_section: hunk.section.clone(),
+                old_line,
+                new_line,
+                quoted,
+                comment,
+            });
+        }
+    }
+    
+    Ok(result)
 }
 
 /// Find thread containing a specific patch
 pub async fn get_thread_for_patch(
     db: &mut DatabaseManager,
-    patch_id: i64
+    patch_id: i64,
+    cleaning: &ReplyCleaning
 ) -> Result<Option<ThreadTree>, Box<dyn std::error::Error>> {
     db.ensure_connected().await?;
     let pool = db.get_pool()?;
@@ -799,76 +3374,123 @@ pub async fn get_thread_for_patch(
     .await?;
     
     if let Some((thread_id,)) = thread_row {
-        Ok(Some(get_thread_tree(db, thread_id).await?))
+        Ok(Some(get_thread_tree(db, thread_id, cleaning).await?))
     } else {
         Ok(None)
     }
 }
 
-/// Search threads by subject keyword
+/// Search threads by subject keyword, or by case-insensitive regex with `regex`
+/// Only threads active within `dates` (started before its end, last active after its start) match
 pub async fn search_threads(
     db: &mut DatabaseManager,
     keyword: &str,
-    limit: Option<usize>
+    limit: Option<usize>,
+    list_id: Option<i32>,
+    regex: bool,
+    dates: crate::database::DateRange
 ) -> Result<Vec<ThreadSummary>, Box<dyn std::error::Error>> {
     db.ensure_connected().await?;
     let pool = db.get_pool()?;
     
     let limit_val = limit.unwrap_or(50) as i64;
-    let pattern = format!("%{}%", keyword.to_lowercase());
+    let mut tx = pool.begin().await?;
+    let (condition, pattern) = if regex {
+        prepare_subject_regex(&mut tx, keyword).await?;
+        ("ts.root_subject ~* $1", keyword.to_string())
+    } else {
+        ("LOWER(ts.root_subject) LIKE $1", format!("%{}%", keyword.to_lowercase()))
+    };
     
-    let rows = sqlx::query(
-        "SELECT 
-            ts.thread_id,
-            ts.root_subject,
-            ts.root_author,
-            ts.reply_count,
-            ts.participant_count,
-            ts.created_at,
-            ts.last_activity_at,
-            ts.root_patch_id,
-            mt.merge_repository,
-            mt.merge_branch,
-            mt.merge_applied_by,
-            mt.merge_date,
-            mt.commit_count
-         FROM thread_summary ts
-         LEFT JOIN merged_threads mt ON ts.thread_id = mt.thread_id
-         WHERE LOWER(ts.root_subject) LIKE $1
+    let rows = sqlx::query(&format!(
+        "{}
+         WHERE {}
+           AND ($3::INT IS NULL OR ts.list_id = $3)
+           AND ($4::TIMESTAMPTZ IS NULL OR ts.last_activity_at >= $4)
+           AND ($5::TIMESTAMPTZ IS NULL OR ts.created_at < $5)
          ORDER BY ts.last_activity_at DESC
-         LIMIT $2"
-    )
+         LIMIT $2",
+        THREAD_SUMMARY_SELECT, condition
+    ))
     .bind(&pattern)
     .bind(limit_val)
+    .bind(list_id)
+    .bind(dates.after)
+    .bind(dates.before)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(subject_regex_error)?;
+    tx.commit().await?;
+    
+    let threads = rows.iter().map(thread_summary_from_row).collect();
+    
+    Ok(threads)
+}
+
+/// Get the starred threads, most recently starred first
+pub async fn get_starred_threads(
+    db: &mut DatabaseManager,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<ThreadSummary>, Box<dyn std::error::Error>> {
+    db.ensure_connected().await?;
+    let pool = db.get_pool()?;
+    
+    let limit_val = limit.unwrap_or(50) as i64;
+    
+    let rows = sqlx::query(&format!(
+        "{}
+         JOIN thread_bookmarks tb ON tb.thread_id = ts.thread_id
+         WHERE ($2::INT IS NULL OR ts.list_id = $2)
+         ORDER BY tb.starred_at DESC
+         LIMIT $1",
+        THREAD_SUMMARY_SELECT
+    ))
+    .bind(limit_val)
+    .bind(list_id)
     .fetch_all(pool)
     .await?;
     
-    let threads = rows.iter().map(|row| {
-        let merge_status = if let Ok(Some(repo)) = row.try_get::<Option<String>, _>(8) {
-            Some(MergeStatusInfo {
-                is_merged: true,
-                merge_date: row.get::<chrono::DateTime<chrono::Utc>, _>(11).to_rfc3339(),
-                repository: repo,
-                branch: row.get::<String, _>(9),
-                applied_by: row.get::<String, _>(10),
-                commit_count: row.get::<Option<i32>, _>(12).unwrap_or(0),
-            })
-        } else {
-            None
-        };
-        
-        ThreadSummary {
-            thread_id: row.get(0),
-            root_subject: row.get(1),
-            root_author: row.get(2),
-            reply_count: row.get(3),
-            participant_count: row.get(4),
-            created_at: row.get::<chrono::DateTime<chrono::Utc>, _>(5).to_rfc3339(),
-            last_activity: row.get::<chrono::DateTime<chrono::Utc>, _>(6).to_rfc3339(),
-            root_patch_id: row.get(7),
-            merge_status,
+    Ok(rows.iter().map(thread_summary_from_row).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attribution_author_forms() {
+        let cases = [
+            ("On Mon, 1 Jan 2024, Jane Doe <jane@x.org> wrote:", Some("Jane Doe")),
+            ("On Mon, Jan 1, 2024 at 10:00 AM Jane Doe <jane@x.org> wrote:", Some("Jane Doe")),
+            ("\"Jane Doe\" <jane@x.org> writes:", Some("Jane Doe")),
+            ("Jane Doe WROTE:", Some("Jane Doe")),
+            ("On Mon, İİİİİİİİ wrote:", Some("İİİİİİİİ")),
+            ("Grüße, Jürgen wrote:", Some("Jürgen")),
+            ("This is not an attribution", None),
+            (" wrote:", None),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(attribution_author(line).as_deref(), expected, "{:?}", line);
         }
-    }).collect();
-    
-    Ok(threads)
+    }
+
+    #[test]
+    fn quote_blocks_with_attribution() {
+        let body = "On Mon, 1 Jan 2024, Jane Doe <jane@x.org> wrote:\n\
+                    > On Sun, 31 Dec 2023, John Roe <john@x.org> wrote:\n\
+                    > > original text\n\
+                    > > more\n\
+                    > reply by Jane\n\
+                    \n\
+                    My answer.\n\
+                    >> unattributed\n";
+        assert_eq!(extract_quote_blocks(body), vec![
+            QuoteBlock { start_line: 1, end_line: 1, depth: 1, attributed_to: Some("Jane Doe".to_string()) },
+            QuoteBlock { start_line: 2, end_line: 3, depth: 2, attributed_to: Some("John Roe".to_string()) },
+            // Back at the first level after the nested quote: still Jane's text
+            QuoteBlock { start_line: 4, end_line: 4, depth: 1, attributed_to: Some("Jane Doe".to_string()) },
+            QuoteBlock { start_line: 7, end_line: 7, depth: 2, attributed_to: None },
+        ]);
+    }
 }
@@ -0,0 +1,613 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use once_cell::sync::Lazy;
+use sha1::{Digest, Sha1};
+
+// "@@ -old_start,old_lines +new_start,new_lines @@ section"
+static HUNK_HEADER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@ ?(.*)$").unwrap()
+});
+
+/// One hunk of a file diff
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DiffHunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Function/section context git prints after the hunk header
+    pub section: Option<String>,
+    pub added: u32,
+    pub removed: u32,
+    /// Raw hunk lines with their " "/"+"/"-" prefix (only kept in memory, not stored)
+    #[serde(skip)]
+    pub lines: Vec<String>,
+}
+
+impl DiffHunk {
+    /// Old/new line numbers of each hunk line (None on the side the line doesn't exist)
+    pub fn line_numbers(&self) -> Vec<(Option<u32>, Option<u32>)> {
+        let (mut old, mut new) = (self.old_start, self.new_start);
+        self.lines.iter().map(|line| match line.chars().next() {
+            Some('+') => {
+                new += 1;
+                (None, Some(new - 1))
+            }
+            Some('-') => {
+                old += 1;
+                (Some(old - 1), None)
+            }
+            Some('\\') => (None, None),
+            _ => {
+                old += 1;
+                new += 1;
+                (Some(old - 1), Some(new - 1))
+            }
+        }).collect()
+    }
+}
+
+/// Changes to a single file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    /// Path before the change (None for new files)
+    pub old_path: Option<String>,
+    /// Path after the change (None for deleted files)
+    pub new_path: Option<String>,
+    pub added: u32,
+    pub removed: u32,
+    pub is_new: bool,
+    pub is_deleted: bool,
+    pub is_rename: bool,
+    pub is_binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl FileDiff {
+    fn new() -> Self {
+        Self {
+            old_path: None,
+            new_path: None,
+            added: 0,
+            removed: 0,
+            is_new: false,
+            is_deleted: false,
+            is_rename: false,
+            is_binary: false,
+            hunks: Vec::new(),
+        }
+    }
+
+    /// The path to show for this file (new path, or old path for deletions)
+    pub fn path(&self) -> &str {
+        self.new_path.as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap_or("")
+    }
+
+    /// "added", "deleted", "renamed" or "modified"
+    pub fn change_type(&self) -> &'static str {
+        if self.is_new {
+            "added"
+        } else if self.is_deleted {
+            "deleted"
+        } else if self.is_rename {
+            "renamed"
+        } else {
+            "modified"
+        }
+    }
+}
+
+/// Structured form of the unified diff embedded in a patch email
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct DiffSummary {
+    pub files: Vec<FileDiff>,
+    pub total_added: u32,
+    pub total_removed: u32,
+}
+
+/// Strip the a/ or b/ prefix git puts on paths; /dev/null means "no file"
+fn clean_path(raw: &str) -> Option<String> {
+    // Some tools append a tab and timestamp after the path
+    let path = raw.split('\t').next().unwrap_or(raw).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Parse the "a/old b/new" part of a "diff --git" line
+fn parse_diff_git_paths(rest: &str) -> (Option<String>, Option<String>) {
+    match rest.find(" b/") {
+        Some(split) => (clean_path(&rest[..split]), clean_path(&rest[split + 1..])),
+        None => (clean_path(rest), None),
+    }
+}
+
+/// Parse a hunk header line
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let caps = HUNK_HEADER_REGEX.captures(line)?;
+    let number = |i: usize, default: u32| caps.get(i)
+        .and_then(|m| m.as_str().parse().ok())
+        .unwrap_or(default);
+
+    Some(DiffHunk {
+        old_start: number(1, 0),
+        old_lines: number(2, 1),
+        new_start: number(3, 0),
+        new_lines: number(4, 1),
+        section: caps.get(5)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|s| !s.is_empty()),
+        added: 0,
+        removed: 0,
+        lines: Vec::new(),
+    })
+}
+
+/// Parse the unified diff in a patch body into files and hunks
+/// Returns None when the body contains no diff (cover letters, replies, ...)
+pub fn parse_diff(body: &str) -> Option<DiffSummary> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut current: Option<FileDiff> = None;
+    // Lines still expected in the current hunk as (old, new)
+    let mut remaining = (0u32, 0u32);
+
+    for line in body.lines() {
+        let line = line.trim_end_matches('\r');
+
+        // Inside a hunk every line is content until the announced counts are used up
+        if remaining != (0, 0) {
+            if let Some(file) = current.as_mut() {
+                let hunk = file.hunks.last_mut().expect("hunk counts imply a hunk");
+                hunk.lines.push(line.to_string());
+                match line.chars().next() {
+                    Some('+') => {
+                        hunk.added += 1;
+                        file.added += 1;
+                        remaining.1 = remaining.1.saturating_sub(1);
+                    }
+                    Some('-') => {
+                        hunk.removed += 1;
+                        file.removed += 1;
+                        remaining.0 = remaining.0.saturating_sub(1);
+                    }
+                    Some('\\') => {}
+                    // Context line (mail clients may strip the leading space of blank lines)
+                    _ => {
+                        remaining.0 = remaining.0.saturating_sub(1);
+                        remaining.1 = remaining.1.saturating_sub(1);
+                    }
+                }
+                continue;
+            }
+        }
+
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            files.extend(current.take());
+            let (old_path, new_path) = parse_diff_git_paths(rest);
+            current = Some(FileDiff { old_path, new_path, ..FileDiff::new() });
+        } else if let Some(rest) = line.strip_prefix("--- ") {
+            // Plain unified diffs have no "diff --git" line; a new "---" after hunks starts a new file
+            if current.as_ref().is_none_or(|f| !f.hunks.is_empty()) {
+                files.extend(current.take());
+                current = Some(FileDiff::new());
+            }
+            if let Some(file) = current.as_mut() {
+                file.old_path = clean_path(rest);
+                file.is_new = file.is_new || file.old_path.is_none();
+            }
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(file) = current.as_mut() {
+                file.new_path = clean_path(rest);
+                file.is_deleted = file.is_deleted || file.new_path.is_none();
+            }
+        } else if let Some(hunk) = line.starts_with("@@ ").then(|| parse_hunk_header(line)).flatten() {
+            if let Some(file) = current.as_mut() {
+                remaining = (hunk.old_lines, hunk.new_lines);
+                file.hunks.push(hunk);
+            }
+        } else if let Some(file) = current.as_mut() {
+            if line.starts_with("new file mode") {
+                file.is_new = true;
+                file.old_path = None;
+            } else if line.starts_with("deleted file mode") {
+                file.is_deleted = true;
+                file.new_path = None;
+            } else if let Some(path) = line.strip_prefix("rename from ") {
+                file.is_rename = true;
+                file.old_path = Some(path.to_string());
+            } else if let Some(path) = line.strip_prefix("rename to ") {
+                file.is_rename = true;
+                file.new_path = Some(path.to_string());
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                file.is_binary = true;
+            } else if line == "-- " {
+                // Signature delimiter: the diff is over
+                break;
+            }
+        }
+    }
+    files.extend(current);
+
+    // A "---" separator line alone (e.g. before a diffstat) doesn't make a diff
+    files.retain(|f| !f.hunks.is_empty() || f.is_binary || f.is_rename || f.is_new || f.is_deleted);
+    if files.is_empty() {
+        return None;
+    }
+
+    Some(DiffSummary {
+        total_added: files.iter().map(|f| f.added).sum(),
+        total_removed: files.iter().map(|f| f.removed).sum(),
+        files,
+    })
+}
+
+/// Add a finished file digest into the patch-id (20-byte sum with carry, as git does)
+fn flush_patch_id_file(result: &mut [u8; 20], ctx: &mut Sha1) {
+    let digest = std::mem::take(ctx).finalize();
+    let mut carry = 0u16;
+    for (byte, digest_byte) in result.iter_mut().zip(digest.iter()) {
+        carry += *byte as u16 + *digest_byte as u16;
+        *byte = carry as u8;
+        carry >>= 8;
+    }
+}
+
+/// Compute the stable patch-id of the diff in a patch body (same value as `git patch-id --stable`)
+/// Whitespace, line numbers and file order don't affect it, so the same change reposted
+/// in another version, thread or list gets the same id. Returns None when there is no diff.
+pub fn compute_patch_id(body: &str) -> Option<String> {
+    let mut result = [0u8; 20];
+    let mut ctx = Sha1::new();
+    let mut patch_len = 0usize;
+    // Lines left in the current hunk; -1 while reading a file header
+    let (mut before, mut after): (i64, i64) = (-1, -1);
+    let mut is_binary = false;
+    let mut index_ids: Option<(String, String)> = None;
+
+    for line in body.lines() {
+        let line = line.trim_end_matches('\r');
+
+        // Ignore the commit message
+        if patch_len == 0 && !line.starts_with("diff ") {
+            continue;
+        }
+
+        if is_binary {
+            if !line.starts_with("diff ") {
+                continue;
+            }
+            is_binary = false;
+            before = -1;
+            after = -1;
+        }
+
+        // Parsing a file header
+        if before == -1 {
+            if line.starts_with("GIT binary patch") || line.starts_with("Binary files") {
+                // Binary contents are identified by the blob ids of the index line
+                if let Some((pre, post)) = index_ids.take() {
+                    ctx.update(pre.as_bytes());
+                    ctx.update(post.as_bytes());
+                }
+                flush_patch_id_file(&mut result, &mut ctx);
+                is_binary = true;
+                continue;
+            } else if let Some(rest) = line.strip_prefix("index ") {
+                index_ids = rest.split_whitespace().next()
+                    .and_then(|ids| ids.split_once(".."))
+                    .map(|(pre, post)| (pre.to_string(), post.to_string()));
+                continue;
+            } else if line.starts_with("--- ") {
+                before = 1;
+                after = 1;
+            } else if !line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                break;
+            }
+        }
+
+        // Between hunks: expect a hunk header or the next file
+        if before == 0 && after == 0 {
+            if line.starts_with("@@ -") {
+                if let Some(hunk) = parse_hunk_header(line) {
+                    before = hunk.old_lines as i64;
+                    after = hunk.new_lines as i64;
+                }
+                continue;
+            }
+            if !line.starts_with("diff ") {
+                break;
+            }
+            flush_patch_id_file(&mut result, &mut ctx);
+            before = -1;
+            after = -1;
+        }
+
+        if line.starts_with('-') || line.starts_with(' ') {
+            before -= 1;
+        }
+        if line.starts_with('+') || line.starts_with(' ') {
+            after -= 1;
+        }
+
+        let stripped: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+        patch_len += stripped.len();
+        ctx.update(stripped.as_bytes());
+    }
+
+    if patch_len == 0 {
+        return None;
+    }
+    flush_patch_id_file(&mut result, &mut ctx);
+    Some(result.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected ids come from `git patch-id --stable` (git 2.39) on the same patches
+
+    const ONE_FILE: &str = "\
+one: change b
+
+---
+ one.txt | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/one.txt b/one.txt
+index de98044..7be73ce 100644
+--- a/one.txt
++++ b/one.txt
+@@ -1,3 +1,3 @@
+ a
+-b
++B
+ c
+";
+
+    const TWO_FILES: &str = "\
+two files
+
+---
+ one.txt | 1 +
+ two.txt | 2 +-
+ 2 files changed, 2 insertions(+), 1 deletion(-)
+
+diff --git a/one.txt b/one.txt
+index 7be73ce..a7bc997 100644
+--- a/one.txt
++++ b/one.txt
+@@ -1,3 +1,4 @@
+ a
+ B
+ c
++d
+diff --git a/two.txt b/two.txt
+index b77b4eb..7061c57 100644
+--- a/two.txt
++++ b/two.txt
+@@ -1,2 +1,2 @@
+ x
+-y
++Y
+";
+
+    // TWO_FILES generated with `git format-patch -O` listing two.txt first
+    const TWO_FILES_REORDERED: &str = "\
+two files
+
+---
+ two.txt | 2 +-
+ one.txt | 1 +
+ 2 files changed, 2 insertions(+), 1 deletion(-)
+
+diff --git a/two.txt b/two.txt
+index b77b4eb..7061c57 100644
+--- a/two.txt
++++ b/two.txt
+@@ -1,2 +1,2 @@
+ x
+-y
++Y
+diff --git a/one.txt b/one.txt
+index 7be73ce..a7bc997 100644
+--- a/one.txt
++++ b/one.txt
+@@ -1,3 +1,4 @@
+ a
+ B
+ c
++d
+";
+
+    const BINARY: &str = "\
+binary
+
+---
+ blob.bin | Bin 3 -> 4 bytes
+ 1 file changed, 0 insertions(+), 0 deletions(-)
+
+diff --git a/blob.bin b/blob.bin
+index 8352675d67aed6625ece79af41c27fdb4ee2e867..ef2caffcda6e1bd757164a29c6f81be03d172fd5 100644
+GIT binary patch
+literal 4
+LcmZQzWM%;X01*HQ
+
+literal 3
+KcmZQzWC8#H2LJ>B
+
+";
+
+    #[test]
+    fn patch_id_single_file() {
+        assert_eq!(compute_patch_id(ONE_FILE).as_deref(), Some("11e1e5910a9180ef3f02451941132af89deafc53"));
+    }
+
+    #[test]
+    fn patch_id_ignores_file_order() {
+        let expected = Some("ae615d6384ad1f8aadae39641c8941a63ef970f3");
+        assert_eq!(compute_patch_id(TWO_FILES).as_deref(), expected);
+        assert_eq!(compute_patch_id(TWO_FILES_REORDERED).as_deref(), expected);
+    }
+
+    #[test]
+    fn patch_id_binary_file() {
+        assert_eq!(compute_patch_id(BINARY).as_deref(), Some("415aa3813fba9e748b0e639206ff60b4e6d9cd0d"));
+
+        // `--no-binary` output only has abbreviated blob ids, which git hashes as they are
+        let no_binary = "\
+diff --git a/blob.bin b/blob.bin
+index 8352675..ef2caff 100644
+Binary files a/blob.bin and b/blob.bin differ
+";
+        assert_eq!(compute_patch_id(no_binary).as_deref(), Some("39be5b1e8e77b0f40a47bfe9f3153edbed619a79"));
+    }
+
+    #[test]
+    fn patch_id_ignores_signature() {
+        let signed = format!("{}-- \n2.39.5\n\n", ONE_FILE);
+        assert_eq!(compute_patch_id(&signed), compute_patch_id(ONE_FILE));
+        assert_eq!(compute_patch_id(&signed).as_deref(), Some("11e1e5910a9180ef3f02451941132af89deafc53"));
+    }
+
+    #[test]
+    fn patch_id_none_without_diff() {
+        assert_eq!(compute_patch_id("Looks good to me.\n\nReviewed-by: A <a@b.c>\n"), None);
+    }
+
+    #[test]
+    fn parse_diff_counts_lines_and_hunks() {
+        let diff = parse_diff(TWO_FILES).unwrap();
+        assert_eq!(diff.files.len(), 2);
+        assert_eq!((diff.total_added, diff.total_removed), (2, 1));
+        let two = &diff.files[1];
+        assert_eq!((two.old_path.as_deref(), two.new_path.as_deref()), (Some("two.txt"), Some("two.txt")));
+        assert_eq!(two.change_type(), "modified");
+        assert_eq!((two.hunks[0].old_start, two.hunks[0].old_lines, two.hunks[0].new_lines), (1, 2, 2));
+    }
+
+    #[test]
+    fn parse_diff_rename() {
+        let body = "\
+diff --git a/two.txt b/three.txt
+similarity index 66%
+rename from two.txt
+rename to three.txt
+index 7061c57..20a747d 100644
+--- a/two.txt
++++ b/three.txt
+@@ -1,2 +1,3 @@
+ x
+ Y
++z
+";
+        let file = &parse_diff(body).unwrap().files[0];
+        assert!(file.is_rename);
+        assert_eq!(file.change_type(), "renamed");
+        assert_eq!((file.old_path.as_deref(), file.new_path.as_deref()), (Some("two.txt"), Some("three.txt")));
+        assert_eq!((file.added, file.removed), (1, 0));
+
+        // A pure rename has no hunks but is still a change
+        let pure = "\
+diff --git a/old.c b/new.c
+similarity index 100%
+rename from old.c
+rename to new.c
+";
+        let file = &parse_diff(pure).unwrap().files[0];
+        assert!(file.is_rename && file.hunks.is_empty());
+        assert_eq!(file.path(), "new.c");
+    }
+
+    #[test]
+    fn parse_diff_new_and_deleted_files() {
+        let body = "\
+diff --git a/added.txt b/added.txt
+new file mode 100644
+index 0000000..3e75765
+--- /dev/null
++++ b/added.txt
+@@ -0,0 +1 @@
++new
+diff --git a/one.txt b/one.txt
+deleted file mode 100644
+index a7bc997..0000000
+--- a/one.txt
++++ /dev/null
+@@ -1,4 +0,0 @@
+-a
+-B
+-c
+-d
+";
+        let diff = parse_diff(body).unwrap();
+        assert_eq!(diff.files.len(), 2);
+        let (added, deleted) = (&diff.files[0], &diff.files[1]);
+        assert!(added.is_new && added.old_path.is_none());
+        assert_eq!((added.path(), added.change_type(), added.added), ("added.txt", "added", 1));
+        assert!(deleted.is_deleted && deleted.new_path.is_none());
+        assert_eq!((deleted.path(), deleted.change_type(), deleted.removed), ("one.txt", "deleted", 4));
+    }
+
+    #[test]
+    fn parse_diff_binary_files() {
+        let file = &parse_diff(BINARY).unwrap().files[0];
+        assert!(file.is_binary && file.hunks.is_empty());
+        assert_eq!(file.path(), "blob.bin");
+
+        let no_binary = "\
+diff --git a/blob.bin b/blob.bin
+index 8352675..ef2caff 100644
+Binary files a/blob.bin and b/blob.bin differ
+";
+        assert!(parse_diff(no_binary).unwrap().files[0].is_binary);
+    }
+
+    #[test]
+    fn parse_diff_hunk_headers_without_counts() {
+        // A count of 1 may be left out: "@@ -3 +3 @@" and "@@ -0,0 +1 @@"
+        let body = "\
+diff --git a/f.c b/f.c
+--- a/f.c
++++ b/f.c
+@@ -3 +3 @@ int main(void)
+-	return 1;
++	return 0;
+@@ -10,0 +11 @@
++/* end */
+";
+        let file = &parse_diff(body).unwrap().files[0];
+        assert_eq!(file.hunks.len(), 2);
+        let first = &file.hunks[0];
+        assert_eq!((first.old_start, first.old_lines, first.new_start, first.new_lines), (3, 1, 3, 1));
+        assert_eq!(first.section.as_deref(), Some("int main(void)"));
+        let second = &file.hunks[1];
+        assert_eq!((second.old_lines, second.new_start, second.new_lines), (0, 11, 1));
+        assert_eq!((file.added, file.removed), (2, 1));
+    }
+
+    #[test]
+    fn parse_diff_separator_before_diffstat() {
+        // The "---" above the diffstat must not become a file, with or without a trailing space
+        for separator in ["---", "--- "] {
+            let body = ONE_FILE.replacen("---\n", &format!("{}\n", separator), 1);
+            let diff = parse_diff(&body).unwrap();
+            assert_eq!(diff.files.len(), 1, "separator {:?}", separator);
+            assert_eq!(diff.files[0].path(), "one.txt");
+        }
+        assert!(parse_diff("Commit message\n---\n foo.c | 2 +-\n").is_none());
+    }
+
+    #[test]
+    fn parse_diff_stops_at_signature() {
+        let signed = format!("{}-- \n2.39.5\n\n", ONE_FILE);
+        let diff = parse_diff(&signed).unwrap();
+        assert_eq!((diff.total_added, diff.total_removed), (1, 1));
+    }
+}
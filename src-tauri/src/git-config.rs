@@ -8,6 +8,36 @@ use std::io;
 pub struct GitConfig {
     pub repo_path: String,
     pub clone_url: String,
+    /// Additional mailing list archives tracked alongside the default one
+    #[serde(default)]
+    pub mailing_lists: Vec<MailingListRepo>,
+    /// Saved repository profiles (e.g. "bpf", "netdev-mirror", "local-test")
+    #[serde(default)]
+    pub profiles: Vec<GitProfile>,
+    /// Name of the profile the top-level fields were loaded from
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Local kernel git tree to verify merged commits against (optional)
+    #[serde(default)]
+    pub kernel_tree_path: Option<String>,
+}
+
+/// A named repository setup that can be switched to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitProfile {
+    pub name: String,
+    pub repo_path: String,
+    pub clone_url: String,
+    #[serde(default)]
+    pub mailing_lists: Vec<MailingListRepo>,
+}
+
+/// Archive location for a single mailing list (e.g. netdev, lkml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MailingListRepo {
+    pub name: String,
+    pub repo_path: String,
+    pub clone_url: String,
 }
 
 impl Default for GitConfig {
@@ -15,13 +45,32 @@ impl Default for GitConfig {
         Self {
             repo_path: String::new(),
             clone_url: "https://lore.kernel.org/bpf/0".to_string(),
+            mailing_lists: Vec::new(),
+            profiles: Vec::new(),
+            active_profile: None,
+            kernel_tree_path: None,
         }
     }
 }
 
+/// Derive a list name from a lore clone URL ("https://lore.kernel.org/bpf/0" -> "bpf")
+pub fn list_name_from_clone_url(clone_url: &str) -> String {
+    let segments: Vec<&str> = clone_url
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    
+    match segments.as_slice() {
+        [.., name, epoch] if epoch.trim_end_matches(".git").parse::<u32>().is_ok() => name.to_string(),
+        [.., name] => name.trim_end_matches(".git").to_string(),
+        [] => "default".to_string(),
+    }
+}
+
 impl GitConfig {
-    /// Get the path to the configuration file
-    fn get_config_file_path() -> Result<PathBuf, io::Error> {
+    /// Get the application config directory (created if missing)
+    pub fn get_config_dir() -> Result<PathBuf, io::Error> {
         // Use app data directory for config file
         let config_dir = if cfg!(windows) {
             std::env::var("APPDATA")
@@ -40,7 +89,12 @@ impl GitConfig {
             fs::create_dir_all(&app_config_dir)?;
         }
         
-        Ok(app_config_dir.join("git-config.json"))
+        Ok(app_config_dir)
+    }
+
+    /// Get the path to the configuration file
+    fn get_config_file_path() -> Result<PathBuf, io::Error> {
+        Ok(Self::get_config_dir()?.join("git-config.json"))
     }
 
     /// Load configuration from file, falling back to environment variables, then defaults
@@ -49,7 +103,8 @@ impl GitConfig {
         if let Ok(config_path) = Self::get_config_file_path() {
             if config_path.exists() {
                 if let Ok(contents) = fs::read_to_string(&config_path) {
-                    if let Ok(config) = serde_json::from_str::<GitConfig>(&contents) {
+                    if let Ok(mut config) = serde_json::from_str::<GitConfig>(&contents) {
+                        config.apply_active_profile();
                         return config;
                     }
                 }
@@ -67,15 +122,22 @@ impl GitConfig {
                 .unwrap_or_else(|_| Self::default().repo_path),
             clone_url: std::env::var("GIT_CLONE_URL")
                 .unwrap_or_else(|_| Self::default().clone_url),
+            mailing_lists: Vec::new(),
+            profiles: Vec::new(),
+            active_profile: None,
+            kernel_tree_path: std::env::var("KERNEL_TREE_PATH").ok(),
         }
     }
 
-    /// Save configuration to file
+    /// Save configuration to file (edits to the top-level fields are kept in the active profile)
     pub fn save(&self) -> Result<(), String> {
         let config_path = Self::get_config_file_path()
             .map_err(|e| format!("Failed to get config path: {}", e))?;
         
-        let json = serde_json::to_string_pretty(self)
+        let mut config = self.clone();
+        config.store_active_profile();
+        
+        let json = serde_json::to_string_pretty(&config)
             .map_err(|e| format!("Failed to serialize config: {}", e))?;
         
         fs::write(&config_path, json)
@@ -84,16 +146,199 @@ impl GitConfig {
         Ok(())
     }
 
+    /// The profile currently in use, if any
+    pub fn get_active_profile(&self) -> Option<&GitProfile> {
+        let name = self.active_profile.as_deref()?;
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Copy the active profile into the top-level fields so everything reading
+    /// repo_path/clone_url resolves through it
+    fn apply_active_profile(&mut self) {
+        if let Some(profile) = self.get_active_profile().cloned() {
+            self.repo_path = profile.repo_path;
+            self.clone_url = profile.clone_url;
+            self.mailing_lists = profile.mailing_lists;
+        }
+    }
+
+    /// Write the top-level fields back into the active profile
+    fn store_active_profile(&mut self) {
+        let Some(name) = self.active_profile.clone() else {
+            return;
+        };
+        let current = GitProfile {
+            name,
+            repo_path: self.repo_path.clone(),
+            clone_url: self.clone_url.clone(),
+            mailing_lists: self.mailing_lists.clone(),
+        };
+        if let Some(profile) = self.profiles.iter_mut().find(|p| p.name == current.name) {
+            *profile = current;
+        }
+    }
+
+    /// Add a profile, or update the repository of an existing one (its mailing lists are kept)
+    pub fn upsert_profile(&mut self, profile: GitProfile) {
+        let is_active = self.active_profile.as_deref() == Some(profile.name.as_str());
+        if let Some(existing) = self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            existing.repo_path = profile.repo_path;
+            existing.clone_url = profile.clone_url;
+        } else {
+            self.profiles.push(profile);
+        }
+        if is_active {
+            self.apply_active_profile();
+        }
+    }
+
+    /// Remove a profile; the active profile cannot be removed
+    pub fn remove_profile(&mut self, name: &str) -> Result<(), String> {
+        if self.active_profile.as_deref() == Some(name) {
+            return Err(format!("Profile '{}' is active, switch to another profile before removing it", name));
+        }
+        let before = self.profiles.len();
+        self.profiles.retain(|p| p.name != name);
+        if self.profiles.len() == before {
+            return Err(format!("Profile '{}' not found", name));
+        }
+        Ok(())
+    }
+
+    /// Make a profile active, keeping any edits made to the previously active one
+    /// When no profile was active yet, the current setup is saved as a "default" profile
+    /// first so it can be switched back to
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), String> {
+        if !self.profiles.iter().any(|p| p.name == name) {
+            return Err(format!("Profile '{}' not found", name));
+        }
+        if self.active_profile.is_none() {
+            let implicit_name = self.unused_profile_name("default");
+            self.profiles.push(GitProfile {
+                name: implicit_name.clone(),
+                repo_path: self.repo_path.clone(),
+                clone_url: self.clone_url.clone(),
+                mailing_lists: self.mailing_lists.clone(),
+            });
+            self.active_profile = Some(implicit_name);
+        }
+        self.store_active_profile();
+        self.active_profile = Some(name.to_string());
+        self.apply_active_profile();
+        Ok(())
+    }
+
+    /// `base`, or `base-2`, `base-3`, ... if a profile already has that name
+    fn unused_profile_name(&self, base: &str) -> String {
+        let taken = |name: &str| self.profiles.iter().any(|p| p.name == name);
+        if !taken(base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|name| !taken(name))
+            .unwrap()
+    }
+
     /// Check if the repository exists at the configured path
     pub fn repo_exists(&self) -> bool {
         let path = PathBuf::from(&self.repo_path);
         path.exists() && path.is_dir()
     }
 
+    /// Check that the configuration points at a usable archive
+    pub fn validate(&self) -> GitConfigValidation {
+        let mut problems = Vec::new();
+        
+        let repo_exists = !self.repo_path.trim().is_empty() && self.repo_exists();
+        if self.repo_path.trim().is_empty() {
+            problems.push("Repository path is empty".to_string());
+        } else if !repo_exists {
+            problems.push(format!("Repository path '{}' does not exist or is not a directory", self.repo_path));
+        }
+        
+        // Bare repos have HEAD at the top, work trees have .git, public-inbox archives have git/<epoch>.git
+        let path = self.get_path();
+        let is_git_repository = repo_exists
+            && (path.join("HEAD").is_file() || path.join(".git").exists() || path.join("git").is_dir());
+        if repo_exists && !is_git_repository {
+            problems.push(format!("'{}' does not look like a git repository or public-inbox archive", self.repo_path));
+        }
+        
+        let clone_url_valid = ["https://", "http://", "git://", "ssh://", "file://"]
+            .iter()
+            .any(|scheme| self.clone_url.starts_with(scheme));
+        if !clone_url_valid {
+            problems.push(format!("Clone URL '{}' is not a valid http(s), git, ssh or file URL", self.clone_url));
+        }
+        
+        for list in &self.mailing_lists {
+            let list_path = PathBuf::from(&list.repo_path);
+            if !list_path.is_dir() {
+                problems.push(format!("Mailing list '{}': repository path '{}' does not exist", list.name, list.repo_path));
+            }
+        }
+        
+        if let Some(tree) = self.kernel_tree_path.as_deref().filter(|p| !p.trim().is_empty()) {
+            if !PathBuf::from(tree).is_dir() {
+                problems.push(format!("Kernel tree path '{}' does not exist", tree));
+            }
+        }
+        
+        GitConfigValidation {
+            valid: problems.is_empty(),
+            repo_exists,
+            is_git_repository,
+            clone_url_valid,
+            problems,
+        }
+    }
+
     /// Get the repository path as a PathBuf
     pub fn get_path(&self) -> PathBuf {
         PathBuf::from(&self.repo_path)
     }
+
+    /// The default mailing list described by the top-level repo_path/clone_url
+    pub fn default_list(&self) -> MailingListRepo {
+        MailingListRepo {
+            name: list_name_from_clone_url(&self.clone_url),
+            repo_path: self.repo_path.clone(),
+            clone_url: self.clone_url.clone(),
+        }
+    }
+
+    /// All configured mailing lists, starting with the default one
+    pub fn all_lists(&self) -> Vec<MailingListRepo> {
+        let default_list = self.default_list();
+        let mut lists = vec![default_list.clone()];
+        lists.extend(self.mailing_lists.iter().filter(|l| l.name != default_list.name).cloned());
+        lists
+    }
+
+    /// Find a configured mailing list by name
+    pub fn find_list(&self, name: &str) -> Option<MailingListRepo> {
+        self.all_lists().into_iter().find(|l| l.name == name)
+    }
+
+    /// Add or replace a mailing list entry
+    pub fn upsert_list(&mut self, list: MailingListRepo) {
+        if let Some(existing) = self.mailing_lists.iter_mut().find(|l| l.name == list.name) {
+            *existing = list;
+        } else {
+            self.mailing_lists.push(list);
+        }
+    }
+}
+
+/// Result of checking a GitConfig before it is used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitConfigValidation {
+    pub valid: bool,
+    pub repo_exists: bool,
+    pub is_git_repository: bool,
+    pub clone_url_valid: bool,
+    pub problems: Vec<String>,
 }
 
 /// Result of a git operation with detailed output
@@ -133,3 +378,64 @@ impl GitOperationResult {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(name: &str) -> MailingListRepo {
+        MailingListRepo {
+            name: name.to_string(),
+            repo_path: format!("/archives/{}", name),
+            clone_url: format!("https://lore.kernel.org/{}/0", name),
+        }
+    }
+
+    fn profile(name: &str, mailing_lists: Vec<MailingListRepo>) -> GitProfile {
+        GitProfile {
+            name: name.to_string(),
+            repo_path: format!("/repos/{}", name),
+            clone_url: format!("https://lore.kernel.org/{}/0", name),
+            mailing_lists,
+        }
+    }
+
+    #[test]
+    fn updating_active_profile_keeps_its_mailing_lists() {
+        let mut config = GitConfig {
+            profiles: vec![profile("bpf", vec![list("netdev")])],
+            active_profile: Some("bpf".to_string()),
+            ..GitConfig::default()
+        };
+        config.apply_active_profile();
+
+        config.upsert_profile(GitProfile {
+            repo_path: "/elsewhere/bpf".to_string(),
+            ..profile("bpf", Vec::new())
+        });
+        assert_eq!(config.repo_path, "/elsewhere/bpf");
+        assert_eq!(config.mailing_lists, vec![list("netdev")]);
+        assert_eq!(config.get_active_profile().unwrap().mailing_lists, vec![list("netdev")]);
+    }
+
+    #[test]
+    fn switching_away_from_implicit_profile_saves_it() {
+        let mut config = GitConfig {
+            repo_path: "/repos/implicit".to_string(),
+            clone_url: "https://lore.kernel.org/bpf/0".to_string(),
+            mailing_lists: vec![list("netdev")],
+            profiles: vec![profile("default", Vec::new()), profile("mirror", Vec::new())],
+            ..GitConfig::default()
+        };
+
+        config.switch_profile("mirror").unwrap();
+        assert_eq!(config.repo_path, "/repos/mirror");
+        assert!(config.mailing_lists.is_empty());
+
+        config.switch_profile("default-2").unwrap();
+        assert_eq!(config.repo_path, "/repos/implicit");
+        assert_eq!(config.clone_url, "https://lore.kernel.org/bpf/0");
+        assert_eq!(config.mailing_lists, vec![list("netdev")]);
+        assert_eq!(config.profiles.len(), 3);
+    }
+}
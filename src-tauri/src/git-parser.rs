@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use gix::Repository;
+use std::num::NonZeroUsize;
+use std::path::Path;
 use std::process::Command;
+use std::sync::Mutex;
+use lru::LruCache;
+use once_cell::sync::Lazy;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CommitMetadata {
@@ -9,6 +14,12 @@ pub struct CommitMetadata {
     pub author_name: String,
     pub author_email: String,
     pub subject: String,
+    /// Git author timestamp (seconds since the unix epoch)
+    #[serde(default)]
+    pub author_time: Option<i64>,
+    /// Git committer timestamp (seconds since the unix epoch), i.e. when public-inbox imported the message
+    #[serde(default)]
+    pub committer_time: Option<i64>,
 }
 
 #[derive(Error, Debug, Serialize, Deserialize)]
@@ -29,8 +40,23 @@ impl From<gix::open::Error> for ParseError {
     }
 }
 
-/// Open the git repository at the configured path from environment variable
-fn open_repository() -> Result<Repository, ParseError> {
+/// A single public-inbox epoch repository (e.g. `git/0.git`, `git/1.git`)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpochRepo {
+    pub epoch: u32,
+    pub path: String,
+}
+
+/// A commit hash tagged with the epoch repository it was found in
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EpochCommit {
+    pub epoch: u32,
+    pub commit_hash: String,
+}
+
+/// Get the repository path of the active profile (GIT_REPO_PATH overrides the config file)
+fn configured_repo_path() -> Result<String, ParseError> {
+    // load() resolves the top-level fields through the active profile
     let config = crate::git_config::GitConfig::load();
     let repo_path = std::env::var("GIT_REPO_PATH")
         .unwrap_or_else(|_| config.repo_path.clone());
@@ -41,14 +67,19 @@ fn open_repository() -> Result<Repository, ParseError> {
         });
     }
     
-    let repo = gix::open(&repo_path).map_err(|e| ParseError {
-        message: format!("Failed to open repository at '{}': {}", repo_path, e),
-    })?;
-    Ok(repo)
+    Ok(repo_path)
+}
+
+/// The default mailing list, using the configured repository path
+pub fn default_mailing_list() -> Result<crate::git_config::MailingListRepo, ParseError> {
+    let config = crate::git_config::GitConfig::load();
+    Ok(crate::git_config::MailingListRepo {
+        repo_path: configured_repo_path()?,
+        ..config.default_list()
+    })
 }
 
 /// Open a repository at a specific path
-#[allow(dead_code)]
 fn open_repository_at_path(path: &str) -> Result<Repository, ParseError> {
     let repo = gix::open(path).map_err(|e| ParseError {
         message: format!("Failed to open repository at '{}': {}", path, e),
@@ -56,6 +87,115 @@ fn open_repository_at_path(path: &str) -> Result<Repository, ParseError> {
     Ok(repo)
 }
 
+/// Parse an epoch number from a directory name like "0.git", "12.git" or "3"
+fn parse_epoch_dir_name(name: &str) -> Option<u32> {
+    name.strip_suffix(".git").unwrap_or(name).parse().ok()
+}
+
+/// List the epoch repositories directly inside `dir`, sorted by epoch number
+fn list_epoch_dirs(dir: &Path) -> Vec<EpochRepo> {
+    let mut epochs: Vec<EpochRepo> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                parse_epoch_dir_name(&name).map(|epoch| EpochRepo {
+                    epoch,
+                    path: entry.path().to_string_lossy().to_string(),
+                })
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    epochs.sort_by_key(|e| e.epoch);
+    epochs
+}
+
+/// Enumerate the public-inbox epoch repositories for a configured path
+///
+/// Accepts any of the common layouts:
+/// - the inbox directory (`bpf/` containing `git/0.git`, `git/1.git`, ...)
+/// - the `git/` directory itself
+/// - a single epoch (`bpf/git/0.git`), in which case its sibling epochs are included
+///
+/// A plain repository that doesn't follow the epoch naming is treated as epoch 0.
+pub fn discover_epochs(repo_path: &str) -> Vec<EpochRepo> {
+    let path = Path::new(repo_path);
+    
+    let nested = list_epoch_dirs(&path.join("git"));
+    if !nested.is_empty() {
+        return nested;
+    }
+    
+    let direct = list_epoch_dirs(path);
+    if !direct.is_empty() {
+        return direct;
+    }
+    
+    let is_epoch_dir = path.file_name()
+        .and_then(|n| n.to_str())
+        .and_then(parse_epoch_dir_name)
+        .is_some();
+    if is_epoch_dir {
+        if let Some(parent) = path.parent() {
+            let siblings = list_epoch_dirs(parent);
+            if !siblings.is_empty() {
+                return siblings;
+            }
+        }
+    }
+    
+    vec![EpochRepo {
+        epoch: 0,
+        path: repo_path.to_string(),
+    }]
+}
+
+/// Get the epoch repositories for the configured repository path
+pub fn get_epochs() -> Result<Vec<EpochRepo>, ParseError> {
+    let repo_path = configured_repo_path()?;
+    Ok(discover_epochs(&repo_path))
+}
+
+/// Open the repository for a specific epoch of the archive at `repo_path`
+fn open_epoch_repository(repo_path: &str, epoch: u32) -> Result<Repository, ParseError> {
+    let epochs = discover_epochs(repo_path);
+    let epoch_repo = epochs.iter().find(|e| e.epoch == epoch).ok_or_else(|| ParseError {
+        message: format!("Epoch {} not found in repository '{}'", epoch, repo_path),
+    })?;
+    open_repository_at_path(&epoch_repo.path)
+}
+
+/// Open whichever epoch repository contains the given commit
+/// Single-commit lookups don't know their epoch, so probe newest epochs first
+fn open_repository_for_commit(commit_hash: &str) -> Result<Repository, ParseError> {
+    open_repository_for_commit_at(&configured_repo_path()?, commit_hash)
+}
+
+/// Like `open_repository_for_commit`, for the archive at `repo_path`
+fn open_repository_for_commit_at(repo_path: &str, commit_hash: &str) -> Result<Repository, ParseError> {
+    let epochs = discover_epochs(repo_path);
+    if epochs.len() == 1 {
+        return open_repository_at_path(&epochs[0].path);
+    }
+    
+    let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes()).map_err(|e| ParseError {
+        message: format!("Invalid commit hash {}: {}", commit_hash, e),
+    })?;
+    
+    for epoch_repo in epochs.iter().rev() {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        if repo.has_object(commit_id) {
+            return Ok(repo);
+        }
+    }
+    
+    Err(ParseError {
+        message: format!("Commit {} not found in any epoch repository", commit_hash),
+    })
+}
+
 /// Validate and sanitize email address
 /// Returns a valid email or generates a placeholder for invalid/empty emails
 fn validate_email(email: &str, commit_hash: &str) -> String {
@@ -79,26 +219,175 @@ fn validate_email(email: &str, commit_hash: &str) -> String {
 
 
 /// Get all commit hashes from the BPF mailing list repository
-/// Returns a vector of commit hashes in chronological order (oldest first)
+/// Returns a vector of commit hashes starting from HEAD of the newest epoch
 /// Limited to first `limit` commits (default: 10)
 pub fn get_all_commits_with_limit(limit: Option<usize>) -> Result<Vec<String>, ParseError> {
-    let repo = open_repository()?;
+    let commits = get_all_epoch_commits_with_limit(limit)?;
+    Ok(commits.into_iter().map(|c| c.commit_hash).collect())
+}
+
+/// Get commit hashes across all epoch repositories, tagged with their epoch
+/// Epochs are walked newest first so `limit` keeps the most recent messages,
+/// matching the HEAD-first traversal of a single repository
+pub fn get_all_epoch_commits_with_limit(limit: Option<usize>) -> Result<Vec<EpochCommit>, ParseError> {
+    get_epoch_commits_at(&configured_repo_path()?, limit)
+}
+
+/// Same as `get_all_epoch_commits_with_limit`, for the archive at `repo_path`
+/// Used to populate mailing lists other than the configured default
+pub fn get_epoch_commits_at(repo_path: &str, limit: Option<usize>) -> Result<Vec<EpochCommit>, ParseError> {
     let limit = limit.unwrap_or(10);
-    
-    let head = repo.head_id().map_err(|e| ParseError {
-        message: format!("Failed to get HEAD: {}", e),
+    let mut commits = Vec::new();
+    walk_epoch_commits(repo_path, limit, limit.max(1), |chunk| {
+        commits.extend(chunk);
+        true
     })?;
+    Ok(commits)
+}
+
+/// Walk up to `limit` commits of every epoch (newest first), handing them to `on_chunk`
+/// in chunks of at most `chunk_size`. A chunk never mixes commits from different epochs.
+/// Returning `false` from `on_chunk` stops the walk early.
+fn walk_epoch_commits<F>(repo_path: &str, limit: usize, chunk_size: usize, mut on_chunk: F) -> Result<usize, ParseError>
+where
+    F: FnMut(Vec<EpochCommit>) -> bool,
+{
+    let epochs = discover_epochs(repo_path);
+    let mut walked = 0;
+    
+    for epoch_repo in epochs.iter().rev() {
+        if walked >= limit {
+            break;
+        }
+        
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        let head = repo.head_id().map_err(|e| ParseError {
+            message: format!("Failed to get HEAD of epoch {}: {}", epoch_repo.epoch, e),
+        })?;
+        
+        let commit_iter = head.ancestors().all().map_err(|e| ParseError {
+            message: format!("Failed to create commit iterator: {}", e),
+        })?;
+        
+        let mut chunk = Vec::with_capacity(chunk_size);
+        for commit_result in commit_iter.take(limit - walked) {
+            let commit_info = commit_result.map_err(|e| ParseError {
+                message: format!("Failed to iterate commits: {}", e),
+            })?;
+            chunk.push(EpochCommit {
+                epoch: epoch_repo.epoch,
+                commit_hash: commit_info.id.to_string(),
+            });
+            walked += 1;
+            
+            if chunk.len() >= chunk_size && !on_chunk(std::mem::replace(&mut chunk, Vec::with_capacity(chunk_size))) {
+                return Ok(walked);
+            }
+        }
+        
+        if !chunk.is_empty() && !on_chunk(chunk) {
+            return Ok(walked);
+        }
+    }
+    
+    Ok(walked)
+}
+
+/// Streaming commit traversal: yields chunks of commit hashes while the walk continues
+/// on a background thread, so callers can start processing before traversal finishes
+pub struct EpochCommitStream {
+    receiver: std::sync::mpsc::Receiver<Result<Vec<EpochCommit>, ParseError>>,
+}
+
+impl Iterator for EpochCommitStream {
+    type Item = Result<Vec<EpochCommit>, ParseError>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// Stream up to `limit` commits (default: 10) of the archive at `repo_path`, newest epoch first
+/// Each item is a chunk of at most `chunk_size` commits from a single epoch.
+/// Dropping the stream stops the traversal.
+pub fn stream_epoch_commits(repo_path: &str, limit: Option<usize>, chunk_size: usize) -> EpochCommitStream {
+    let limit = limit.unwrap_or(10);
+    let chunk_size = chunk_size.max(1);
+    let repo_path = repo_path.to_string();
+    // Keep a few chunks buffered so traversal stays ahead of the consumer
+    let (sender, receiver) = std::sync::mpsc::sync_channel(4);
+    
+    std::thread::spawn(move || {
+        let result = walk_epoch_commits(&repo_path, limit, chunk_size, |chunk| {
+            sender.send(Ok(chunk)).is_ok()
+        });
+        if let Err(e) = result {
+            let _ = sender.send(Err(e));
+        }
+    });
+    
+    EpochCommitStream { receiver }
+}
+
+/// Get the commits of the configured archive whose commit time falls within [start, end]
+/// Newest epochs are walked first and traversal stops at `start`, so only the window is scanned
+pub fn get_commits_in_date_range(start: chrono::DateTime<chrono::Utc>, end: chrono::DateTime<chrono::Utc>) -> Result<Vec<EpochCommit>, ParseError> {
+    get_commits_in_date_range_at(&configured_repo_path()?, start, end)
+}
+
+/// Same as `get_commits_in_date_range`, for the archive at `repo_path`
+pub fn get_commits_in_date_range_at(
+    repo_path: &str,
+    start: chrono::DateTime<chrono::Utc>,
+    end: chrono::DateTime<chrono::Utc>
+) -> Result<Vec<EpochCommit>, ParseError> {
+    if start > end {
+        return Err(ParseError {
+            message: format!("Invalid date range: {} is after {}", start, end),
+        });
+    }
     
+    let start_secs = start.timestamp();
+    let end_secs = end.timestamp();
     let mut commits = Vec::new();
-    let commit_iter = head.ancestors().all().map_err(|e| ParseError {
-        message: format!("Failed to create commit iterator: {}", e),
-    })?;
     
-    for commit_result in commit_iter.take(limit) {
-        let commit_info = commit_result.map_err(|e| ParseError {
-            message: format!("Failed to iterate commits: {}", e),
+    for epoch_repo in discover_epochs(repo_path).iter().rev() {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        let head = repo.head_id().map_err(|e| ParseError {
+            message: format!("Failed to get HEAD of epoch {}: {}", epoch_repo.epoch, e),
         })?;
-        commits.push(commit_info.id.to_string());
+        
+        let commit_iter = head.ancestors()
+            .sorting(gix::revision::walk::Sorting::ByCommitTimeCutoff {
+                order: gix::traverse::commit::simple::CommitTimeOrder::NewestFirst,
+                seconds: start_secs,
+            })
+            .all()
+            .map_err(|e| ParseError {
+                message: format!("Failed to create commit iterator: {}", e),
+            })?;
+        
+        let mut newest_in_epoch = None;
+        for commit_result in commit_iter {
+            let commit_info = commit_result.map_err(|e| ParseError {
+                message: format!("Failed to iterate commits: {}", e),
+            })?;
+            let commit_time = commit_info.commit_time();
+            newest_in_epoch.get_or_insert(commit_time);
+            
+            if commit_time <= end_secs {
+                commits.push(EpochCommit {
+                    epoch: epoch_repo.epoch,
+                    commit_hash: commit_info.id.to_string(),
+                });
+            }
+        }
+        
+        // Older epochs only hold older messages; once an epoch has nothing
+        // newer than `start`, the rest of the archive is outside the window
+        if newest_in_epoch.is_none() {
+            break;
+        }
     }
     
     Ok(commits)
@@ -109,9 +398,106 @@ pub fn get_all_commits() -> Result<Vec<String>, ParseError> {
     get_all_commits_with_limit(None)
 }
 
+/// Open every epoch repository of the configured archive, newest first
+fn open_all_epoch_repositories() -> Result<Vec<Repository>, ParseError> {
+    get_epochs()?
+        .iter()
+        .rev()
+        .map(|e| open_repository_at_path(&e.path))
+        .collect()
+}
+
+/// Pick the repository that contains `commit_hash` out of a set of epoch repositories
+fn select_repository<'a>(repos: &'a [Repository], commit_hash: &str) -> Result<&'a Repository, ParseError> {
+    if repos.len() == 1 {
+        return Ok(&repos[0]);
+    }
+    
+    let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes()).map_err(|e| ParseError {
+        message: format!("Invalid commit hash {}: {}", commit_hash, e),
+    })?;
+    
+    repos.iter().find(|repo| repo.has_object(commit_id)).ok_or_else(|| ParseError {
+        message: format!("Commit {} not found in any epoch repository", commit_hash),
+    })
+}
+
+/// Default number of raw emails kept in memory (override with EMAIL_CACHE_SIZE)
+const DEFAULT_EMAIL_CACHE_SIZE: usize = 512;
+
+/// Recently read raw emails keyed by commit hash, so repeated thread views skip git
+static EMAIL_BLOB_CACHE: Lazy<Mutex<LruCache<String, String>>> = Lazy::new(|| {
+    let size = std::env::var("EMAIL_CACHE_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_EMAIL_CACHE_SIZE);
+    Mutex::new(LruCache::new(NonZeroUsize::new(size).unwrap_or(NonZeroUsize::MIN)))
+});
+
+/// Change how many raw emails the blob cache holds (least recently used are evicted)
+pub fn set_email_cache_capacity(capacity: usize) {
+    let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+    if let Ok(mut cache) = EMAIL_BLOB_CACHE.lock() {
+        cache.resize(capacity);
+    }
+}
+
+/// Drop all cached raw emails
+pub fn clear_email_cache() {
+    if let Ok(mut cache) = EMAIL_BLOB_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// Raw email of one commit, or why it couldn't be read
+pub type EmailContentResult = (String, Result<String, ParseError>);
+
 /// Get email content for multiple commit hashes using efficient batching
-/// This retrieves raw email content for multiple commits using git cat-file --batch
-pub fn get_multiple_email_content(commit_hashes: &[String]) -> Result<Vec<(String, String)>, ParseError> {
+/// Recently read emails are served from the in-memory LRU cache.
+/// Each commit gets its own result, so a missing or corrupt object doesn't fail the batch;
+/// deletion commits are skipped.
+pub fn get_multiple_email_content(commit_hashes: &[String]) -> Result<Vec<EmailContentResult>, ParseError> {
+    if commit_hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut cached: std::collections::HashMap<String, Result<String, ParseError>> = std::collections::HashMap::new();
+    if let Ok(mut cache) = EMAIL_BLOB_CACHE.lock() {
+        for commit_hash in commit_hashes {
+            if let Some(content) = cache.get(commit_hash) {
+                cached.insert(commit_hash.clone(), Ok(content.clone()));
+            }
+        }
+    }
+
+    let missing: Vec<String> = commit_hashes.iter()
+        .filter(|hash| !cached.contains_key(*hash))
+        .cloned()
+        .collect();
+    let fetched = fetch_email_content(&missing)?;
+
+    if let Ok(mut cache) = EMAIL_BLOB_CACHE.lock() {
+        for (commit_hash, content) in &fetched {
+            if let Ok(content) = content {
+                cache.put(commit_hash.clone(), content.clone());
+            }
+        }
+    }
+    cached.extend(fetched);
+
+    Ok(commit_hashes.iter()
+        .filter_map(|hash| cached.get(hash).map(|result| {
+            let result = match result {
+                Ok(content) => Ok(content.clone()),
+                Err(e) => Err(ParseError { message: e.message.clone() }),
+            };
+            (hash.clone(), result)
+        }))
+        .collect())
+}
+
+/// Read email content for commits straight from git (no caching)
+fn fetch_email_content(commit_hashes: &[String]) -> Result<Vec<EmailContentResult>, ParseError> {
     if commit_hashes.is_empty() {
         return Ok(Vec::new());
     }
@@ -123,72 +509,261 @@ pub fn get_multiple_email_content(commit_hashes: &[String]) -> Result<Vec<(Strin
 
     // For single commit, use direct call
     if let Some(commit_hash) = commit_hashes.first() {
-        match get_single_email_content(commit_hash) {
-            Ok(content) => Ok(vec![(commit_hash.clone(), content)]),
-            Err(e) => Err(e),
-        }
+        Ok(vec![(commit_hash.clone(), get_single_email_content(commit_hash))])
     } else {
         Ok(Vec::new())
     }
 }
 
+/// A public-inbox commit that removed a message instead of adding one
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeletedMessage {
+    pub commit_hash: String,
+    /// Raw content of the removed message (the "d" file), when the commit recorded it
+    pub removed_message: Option<String>,
+}
+
+/// Contents of a batch of public-inbox commits
+#[derive(Debug, Default)]
+pub struct EmailBatch {
+    /// Regular messages as (commit hash, raw email)
+    pub messages: Vec<(String, String)>,
+    /// Deletion commits found alongside them
+    pub deleted: Vec<DeletedMessage>,
+    /// Commits that couldn't be read (missing or corrupt objects)
+    pub failed: Vec<(String, ParseError)>,
+}
+
+/// What a public-inbox commit holds: a message ("m") or a deletion
+enum EmailEntry {
+    Message(String),
+    Deleted(Option<String>),
+}
+
+/// What one commit holds, or why it couldn't be read
+type EmailEntryResult = (String, Result<EmailEntry, ParseError>);
+
+/// Separate regular messages from deletion commits and unreadable ones
+fn split_entries(entries: Vec<EmailEntryResult>) -> EmailBatch {
+    let mut batch = EmailBatch {
+        messages: Vec::with_capacity(entries.len()),
+        ..Default::default()
+    };
+    for (commit_hash, entry) in entries {
+        match entry {
+            Ok(EmailEntry::Message(content)) => batch.messages.push((commit_hash, content)),
+            Ok(EmailEntry::Deleted(removed_message)) => batch.deleted.push(DeletedMessage { commit_hash, removed_message }),
+            Err(e) => batch.failed.push((commit_hash, e)),
+        }
+    }
+    batch
+}
+
+/// Get email content for commits that are all known to live in one epoch
+/// Avoids probing every epoch repository for each commit during population.
+/// Deletion commits and unreadable commits are returned separately instead of failing the batch.
+pub fn get_multiple_email_content_in_epoch(repo_path: &str, epoch: u32, commit_hashes: &[String]) -> Result<EmailBatch, ParseError> {
+    if commit_hashes.is_empty() {
+        return Ok(EmailBatch::default());
+    }
+    
+    let repo = open_epoch_repository(repo_path, epoch)?;
+    Ok(split_entries(read_email_entries_parallel(vec![repo], commit_hashes)?))
+}
+
 /// Efficiently retrieve email content for multiple commits using gix
-fn get_batch_email_content(commit_hashes: &[String]) -> Result<Vec<(String, String)>, ParseError> {
-    let repo = open_repository()?;
-    let mut results = Vec::new();
+/// Deletion commits are skipped, unreadable commits come back as errors
+fn get_batch_email_content(commit_hashes: &[String]) -> Result<Vec<EmailContentResult>, ParseError> {
+    let repos = open_all_epoch_repositories()?;
+    let entries = read_email_entries_parallel(repos, commit_hashes)?;
     
-    for commit_hash in commit_hashes {
-        // Parse the commit hash into an ObjectId
-        let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes()).map_err(|e| ParseError {
-            message: format!("Invalid commit hash {}: {}", commit_hash, e),
-        })?;
-        
-        // Get the commit object
-        let commit = repo.find_object(commit_id).map_err(|e| ParseError {
-            message: format!("Failed to find commit {}: {}", commit_hash, e),
-        })?;
-        
-        let commit = commit.try_into_commit().map_err(|e| ParseError {
-            message: format!("Object {} is not a commit: {}", commit_hash, e),
-        })?;
-        
-        // Get the tree from the commit
-        let tree_id = commit.tree_id().map_err(|e| ParseError {
-            message: format!("Failed to get tree for commit {}: {}", commit_hash, e),
-        })?;
-        
-        let tree = repo.find_object(tree_id).map_err(|e| ParseError {
-            message: format!("Failed to find tree for commit {}: {}", commit_hash, e),
-        })?;
-        
-        let tree = tree.try_into_tree().map_err(|e| ParseError {
-            message: format!("Object is not a tree for commit {}: {}", commit_hash, e),
-        })?;
-        
-        // Look for the "m" file in the tree
-        let tree_ref = tree.decode().map_err(|e| ParseError {
-            message: format!("Failed to decode tree for commit {}: {}", commit_hash, e),
-        })?;
-        
-        // Find the entry named "m"
-        let m_entry = tree_ref.entries.iter().find(|entry| {
-            entry.filename.as_ref() as &[u8] == b"m"
-        }).ok_or_else(|| ParseError {
-            message: format!("No 'm' file found in commit {}", commit_hash),
-        })?;
+    let mut results = Vec::with_capacity(entries.len());
+    let mut deleted_count = 0;
+    for (commit_hash, entry) in entries {
+        match entry {
+            Ok(EmailEntry::Message(content)) => results.push((commit_hash, Ok(content))),
+            Ok(EmailEntry::Deleted(_)) => deleted_count += 1,
+            Err(e) => results.push((commit_hash, Err(e))),
+        }
+    }
+    if deleted_count > 0 {
+        println!("Skipped {} deletion commits", deleted_count);
+    }
+    Ok(results)
+}
+
+/// Minimum number of blobs per worker thread; smaller batches aren't worth the thread startup
+const MIN_BLOBS_PER_WORKER: usize = 64;
+
+/// Read email blobs across a pool of scoped threads, one repository handle per worker
+/// Results keep the order of `commit_hashes`; a commit that can't be read only fails its own entry
+fn read_email_entries_parallel(repos: Vec<Repository>, commit_hashes: &[String]) -> Result<Vec<EmailEntryResult>, ParseError> {
+    let max_workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let workers = max_workers.min(commit_hashes.len().div_ceil(MIN_BLOBS_PER_WORKER)).max(1);
+    
+    let read_chunk = |repos: &[Repository], chunk: &[String]| -> Vec<EmailEntryResult> {
+        chunk.iter()
+            .map(|commit_hash| {
+                let entry = select_repository(repos, commit_hash)
+                    .and_then(|repo| read_email_entry(repo, commit_hash));
+                (commit_hash.clone(), entry)
+            })
+            .collect()
+    };
+    
+    if workers == 1 {
+        return Ok(read_chunk(&repos, commit_hashes));
+    }
+    
+    // gix::Repository isn't Sync; share the thread-safe handles and open a local one per worker
+    let shared: Vec<gix::ThreadSafeRepository> = repos.into_iter().map(|repo| repo.into_sync()).collect();
+    let chunk_size = commit_hashes.len().div_ceil(workers);
+    
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = commit_hashes.chunks(chunk_size)
+            .map(|chunk| {
+                let shared = &shared;
+                scope.spawn(move || {
+                    let local: Vec<Repository> = shared.iter().map(|repo| repo.to_thread_local()).collect();
+                    read_chunk(&local, chunk)
+                })
+            })
+            .collect();
         
-        // Get the blob content
-        let blob = repo.find_object(m_entry.oid).map_err(|e| ParseError {
-            message: format!("Failed to find blob 'm' for commit {}: {}", commit_hash, e),
+        let mut results = Vec::with_capacity(commit_hashes.len());
+        for handle in handles {
+            let chunk_results = handle.join().map_err(|_| ParseError {
+                message: "Blob reader thread panicked".to_string(),
+            })?;
+            results.extend(chunk_results);
+        }
+        Ok(results)
+    })
+}
+
+/// Read the raw email stored in the "m" file of a commit
+fn read_email_blob(repo: &Repository, commit_hash: &str) -> Result<String, ParseError> {
+    match read_email_entry(repo, commit_hash)? {
+        EmailEntry::Message(content) => Ok(content),
+        EmailEntry::Deleted(_) => Err(ParseError {
+            message: format!("No 'm' file found in commit {} (message was deleted)", commit_hash),
+        }),
+    }
+}
+
+/// Blob ids of the "m" (message) and "d" (deleted message) files of a public-inbox commit
+fn find_message_blobs(repo: &Repository, commit_hash: &str) -> Result<(Option<gix::ObjectId>, Option<gix::ObjectId>), ParseError> {
+    // Parse the commit hash into an ObjectId
+    let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes()).map_err(|e| ParseError {
+        message: format!("Invalid commit hash {}: {}", commit_hash, e),
+    })?;
+    
+    // Get the commit object
+    let commit = repo.find_object(commit_id).map_err(|e| ParseError {
+        message: format!("Failed to find commit {}: {}", commit_hash, e),
+    })?;
+    
+    let commit = commit.try_into_commit().map_err(|e| ParseError {
+        message: format!("Object {} is not a commit: {}", commit_hash, e),
+    })?;
+    
+    // Get the tree from the commit
+    let tree_id = commit.tree_id().map_err(|e| ParseError {
+        message: format!("Failed to get tree for commit {}: {}", commit_hash, e),
+    })?;
+    
+    let tree = repo.find_object(tree_id).map_err(|e| ParseError {
+        message: format!("Failed to find tree for commit {}: {}", commit_hash, e),
+    })?;
+    
+    let tree = tree.try_into_tree().map_err(|e| ParseError {
+        message: format!("Object is not a tree for commit {}: {}", commit_hash, e),
+    })?;
+    
+    // Look for the "m" file in the tree
+    let tree_ref = tree.decode().map_err(|e| ParseError {
+        message: format!("Failed to decode tree for commit {}: {}", commit_hash, e),
+    })?;
+    
+    // Find the entry named "m" (or "d" for deletions)
+    let find_entry = |name: &[u8]| tree_ref.entries.iter()
+        .find(|entry| entry.filename.as_ref() as &[u8] == name)
+        .map(|entry| entry.oid.to_owned());
+    
+    Ok((find_entry(b"m"), find_entry(b"d")))
+}
+
+/// Read a public-inbox commit: the "m" file for a message, or a deletion
+/// Deletion commits drop "m" and may keep the removed message as "d"
+fn read_email_entry(repo: &Repository, commit_hash: &str) -> Result<EmailEntry, ParseError> {
+    let read_blob = |oid: gix::ObjectId, name: &str| -> Result<String, ParseError> {
+        let blob = repo.find_object(oid).map_err(|e| ParseError {
+            message: format!("Failed to find blob '{}' for commit {}: {}", name, commit_hash, e),
         })?;
-        
-        let blob_data = blob.data.clone();
-        
         // Convert to string and sanitize
-        let content = String::from_utf8_lossy(&blob_data).to_string();
-        let sanitized_content = content.replace('\0', "");
-        
-        results.push((commit_hash.clone(), sanitized_content));
+        let content = String::from_utf8_lossy(&blob.data).to_string();
+        Ok(content.replace('\0', ""))
+    };
+    
+    match find_message_blobs(repo, commit_hash)? {
+        (Some(m_oid), _) => Ok(EmailEntry::Message(read_blob(m_oid, "m")?)),
+        (None, Some(d_oid)) => Ok(EmailEntry::Deleted(Some(read_blob(d_oid, "d")?))),
+        (None, None) => Ok(EmailEntry::Deleted(None)),
+    }
+}
+
+/// Default cap on how much of a message is kept when only headers are read
+pub const DEFAULT_HEADER_READ_LIMIT: usize = 64 * 1024;
+
+/// The header block of a raw email: everything up to the first blank line, at most `max_bytes`
+fn header_section(data: &[u8], max_bytes: usize) -> &[u8] {
+    let window = &data[..data.len().min(max_bytes)];
+    let lf_end = window.windows(2).position(|w| w == b"\n\n").map(|pos| pos + 2);
+    let crlf_end = window.windows(4).position(|w| w == b"\r\n\r\n").map(|pos| pos + 4);
+    
+    match (lf_end, crlf_end) {
+        (Some(a), Some(b)) => &window[..a.min(b)],
+        (Some(end), None) | (None, Some(end)) => &window[..end],
+        (None, None) => window,
+    }
+}
+
+/// Read the headers of the "m" blob of a commit (None for deletion commits)
+/// gix has no bounded reads for packed objects, so the whole blob is still inflated;
+/// only the header slice is converted to a String and parsed
+fn read_email_headers(repo: &Repository, commit_hash: &str, max_bytes: usize) -> Result<Option<String>, ParseError> {
+    let (Some(m_oid), _) = find_message_blobs(repo, commit_hash)? else {
+        return Ok(None);
+    };
+    
+    let blob = repo.find_object(m_oid).map_err(|e| ParseError {
+        message: format!("Failed to find blob 'm' for commit {}: {}", commit_hash, e),
+    })?;
+    Ok(Some(String::from_utf8_lossy(header_section(&blob.data, max_bytes)).replace('\0', "")))
+}
+
+/// Get just the headers of the emails in `commit_hashes`, for metadata-only scans and threading backfills
+/// The headers end at the blank line or after `max_bytes` (DEFAULT_HEADER_READ_LIMIT by default).
+/// Each blob is still read in full, so this saves the string conversion and MIME parsing of
+/// the body rather than memory or I/O.
+/// Each commit gets its own result; deletion commits are skipped.
+pub fn get_email_headers(commit_hashes: &[String], max_bytes: Option<usize>) -> Result<Vec<EmailContentResult>, ParseError> {
+    if commit_hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_HEADER_READ_LIMIT);
+    let repos = open_all_epoch_repositories()?;
+    
+    let mut results = Vec::with_capacity(commit_hashes.len());
+    for commit_hash in commit_hashes {
+        let headers = select_repository(&repos, commit_hash)
+            .and_then(|repo| read_email_headers(repo, commit_hash, max_bytes));
+        match headers {
+            Ok(Some(headers)) => results.push((commit_hash.clone(), Ok(headers))),
+            Ok(None) => {}
+            Err(e) => results.push((commit_hash.clone(), Err(e))),
+        }
     }
     
     Ok(results)
@@ -196,43 +771,164 @@ fn get_batch_email_content(commit_hashes: &[String]) -> Result<Vec<(String, Stri
 
 /// Get email content for a single commit hash
 fn get_single_email_content(commit_hash: &str) -> Result<String, ParseError> {
-    // Reuse the batch function for consistency
-    let results = get_batch_email_content(&[commit_hash.to_string()])?;
-    results.into_iter().next()
-        .map(|(_, content)| content)
-        .ok_or_else(|| ParseError {
-            message: format!("Failed to get email content for commit {}", commit_hash),
-        })
+    let repo = open_repository_for_commit(commit_hash)?;
+    read_email_blob(&repo, commit_hash)
 }
 
 /// Get email content for a specific commit hash
 /// This retrieves the raw email content stored in the "m" file of the commit
 pub fn get_email_content(commit_hash: &str) -> Result<String, ParseError> {
     let results = get_multiple_email_content(&[commit_hash.to_string()])?;
-    Ok(results.into_iter().next().unwrap().1)
+    match results.into_iter().next() {
+        Some((_, content)) => content,
+        None => Err(ParseError {
+            message: format!("Commit {} is a deletion commit and holds no message", commit_hash),
+        }),
+    }
 }
 
 
+/// Read a raw email directly by the OID of its "m" blob (as recorded in public-inbox's over.sqlite3)
+/// Newest epochs are checked first
+pub fn get_email_content_by_blob(blob_oid: &str) -> Result<String, ParseError> {
+    let oid = gix::ObjectId::from_hex(blob_oid.as_bytes()).map_err(|e| ParseError {
+        message: format!("Invalid blob id {}: {}", blob_oid, e),
+    })?;
+    
+    for repo in &open_all_epoch_repositories()? {
+        if !repo.has_object(oid) {
+            continue;
+        }
+        let blob = repo.find_object(oid).map_err(|e| ParseError {
+            message: format!("Failed to read blob {}: {}", blob_oid, e),
+        })?;
+        return Ok(String::from_utf8_lossy(&blob.data).replace('\0', ""));
+    }
+    
+    Err(ParseError {
+        message: format!("Blob {} not found in any epoch repository", blob_oid),
+    })
+}
+
 /// Get the total number of emails in the repository
 pub fn get_email_count() -> Result<usize, ParseError> {
     let commits = get_all_commits()?;
     Ok(commits.len())
 }
 
-/// Get the total count of all commits in the git repository
+/// Cached commit count of one epoch repository, valid while HEAD and the shallow boundary are unchanged
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct CachedCommitCount {
+    head: String,
+    count: usize,
+    /// Shallow boundary commits (comma separated), None for a full clone
+    /// Deepening a shallow clone adds history without moving HEAD
+    #[serde(default)]
+    shallow: Option<String>,
+}
+
+/// The shallow boundary of a repository as stored in CachedCommitCount
+fn shallow_boundary(repo: &Repository) -> Option<String> {
+    let commits = repo.shallow_commits().ok().flatten()?;
+    let mut hashes: Vec<String> = commits.iter().map(|id| id.to_string()).collect();
+    hashes.sort();
+    Some(hashes.join(","))
+}
+
+/// Path of the persisted commit count cache (keyed by epoch repository path)
+fn commit_count_cache_path() -> Option<std::path::PathBuf> {
+    crate::git_config::GitConfig::get_config_dir()
+        .ok()
+        .map(|dir| dir.join("commit-count-cache.json"))
+}
+
+fn load_commit_count_cache() -> std::collections::HashMap<String, CachedCommitCount> {
+    commit_count_cache_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_commit_count_cache(cache: &std::collections::HashMap<String, CachedCommitCount>) {
+    let Some(path) = commit_count_cache_path() else { return };
+    match serde_json::to_string_pretty(cache) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                eprintln!("Failed to write commit count cache: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Failed to serialize commit count cache: {}", e),
+    }
+}
+
+/// Count the commits reachable from HEAD of one epoch, reusing a cached count when possible
+/// - HEAD and shallow boundary unchanged: the cached count is returned without walking
+/// - HEAD fast-forwarded from the cached HEAD: only the new commits are walked
+/// - otherwise (rewritten history, deepened or unshallowed clone): the cache is dropped and
+///   everything is walked again
+fn count_epoch_commits(repo: &Repository, cached: Option<&CachedCommitCount>) -> Result<Option<CachedCommitCount>, ParseError> {
+    let Some(head) = read_head_id(repo) else {
+        return Ok(None);
+    };
+    let shallow = shallow_boundary(repo);
+    
+    let cached_head = cached
+        .filter(|c| c.shallow == shallow)
+        .and_then(|c| gix::ObjectId::from_hex(c.head.as_bytes()).ok().map(|id| (id, c.count)));
+    
+    let count = match cached_head {
+        Some((cached_id, count)) if cached_id == head => count,
+        Some((cached_id, count)) if is_ancestor(repo, cached_id, head) => {
+            count + count_commits_since(repo, Some(cached_id))?
+        }
+        _ => count_commits_since(repo, None)?,
+    };
+    
+    Ok(Some(CachedCommitCount {
+        head: head.to_string(),
+        count,
+        shallow,
+    }))
+}
+
+/// Whether `ancestor` is reachable from `head`
+fn is_ancestor(repo: &Repository, ancestor: gix::ObjectId, head: gix::ObjectId) -> bool {
+    repo.has_object(ancestor)
+        && repo.merge_base(ancestor, head).is_ok_and(|base| base.detach() == ancestor)
+}
+
+/// Get the total count of all commits in the git repository (summed over all epochs)
+/// Counts are persisted per epoch keyed by HEAD, so repeated calls return without a full walk
 pub fn get_total_git_commits() -> Result<usize, ParseError> {
-    let repo = open_repository()?;
+    let mut cache = load_commit_count_cache();
+    let mut total = 0;
+    let mut cache_changed = false;
     
-    let head = repo.head_id().map_err(|e| ParseError {
-        message: format!("Failed to get HEAD: {}", e),
-    })?;
+    for epoch_repo in get_epochs()? {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        let cached = cache.get(&epoch_repo.path);
+        
+        match count_epoch_commits(&repo, cached)? {
+            Some(entry) => {
+                total += entry.count;
+                if cached != Some(&entry) {
+                    cache.insert(epoch_repo.path.clone(), entry);
+                    cache_changed = true;
+                }
+            }
+            None => {
+                return Err(ParseError {
+                    message: format!("Failed to get HEAD of epoch {}", epoch_repo.epoch),
+                });
+            }
+        }
+    }
     
-    let commit_iter = head.ancestors().all().map_err(|e| ParseError {
-        message: format!("Failed to create commit iterator: {}", e),
-    })?;
+    if cache_changed {
+        save_commit_count_cache(&cache);
+    }
     
-    let count = commit_iter.count();
-    Ok(count)
+    Ok(total)
 }
 
 /// Get commit metadata (author name, email, subject) for multiple commits
@@ -257,57 +953,98 @@ pub fn get_commit_metadata(commit_hashes: &[String]) -> Result<Vec<CommitMetadat
     Ok(all_results)
 }
 
+/// Get commit metadata for commits that are all known to live in one epoch
+/// Each commit gets its own result so one unreadable commit doesn't fail the batch
+pub fn get_commit_metadata_in_epoch(repo_path: &str, epoch: u32, commit_hashes: &[String]) -> Result<Vec<Result<CommitMetadata, ParseError>>, ParseError> {
+    if commit_hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let repo = open_epoch_repository(repo_path, epoch)?;
+    Ok(commit_hashes.iter()
+        .map(|commit_hash| read_commit_metadata(&repo, commit_hash))
+        .collect())
+}
+
 /// Internal function to get metadata for a batch of commits
 fn get_commit_metadata_batch(commit_hashes: &[String]) -> Result<Vec<CommitMetadata>, ParseError> {
     if commit_hashes.is_empty() {
         return Ok(Vec::new());
     }
 
-    let repo = open_repository()?;
-    let mut results = Vec::new();
-    
-    for commit_hash in commit_hashes {
-        // Parse the commit hash into an ObjectId
-        let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes()).map_err(|e| ParseError {
-            message: format!("Invalid commit hash {}: {}", commit_hash, e),
-        })?;
-        
-        // Get the commit object
-        let commit = repo.find_object(commit_id).map_err(|e| ParseError {
-            message: format!("Failed to find commit {}: {}", commit_hash, e),
-        })?;
-        
-        let commit = commit.try_into_commit().map_err(|e| ParseError {
-            message: format!("Object {} is not a commit: {}", commit_hash, e),
-        })?;
-        
-        let commit_ref = commit.decode().map_err(|e| ParseError {
-            message: format!("Failed to decode commit {}: {}", commit_hash, e),
-        })?;
-        
-        // Extract metadata
-        let author = &commit_ref.author;
-        let author_name = String::from_utf8_lossy(author.name.as_ref()).to_string();
-        let raw_email = String::from_utf8_lossy(author.email.as_ref());
-        
-        // Validate and sanitize email (handles empty/invalid emails)
-        let author_email = validate_email(&raw_email, commit_hash);
-        
-        // Get subject (first line of message)
-        let message = String::from_utf8_lossy(commit_ref.message.as_ref());
-        let subject = message.lines().next().unwrap_or("").to_string();
-        
-        results.push(CommitMetadata {
-            commit_hash: commit_hash.clone(),
-            author_name,
-            author_email,
-            subject,
-        });
+    let repos = open_all_epoch_repositories()?;
+    let mut results = Vec::new();
+    
+    for commit_hash in commit_hashes {
+        let repo = select_repository(&repos, commit_hash)?;
+        results.push(read_commit_metadata(repo, commit_hash)?);
     }
     
     Ok(results)
 }
 
+/// Read author and subject information from a commit object
+fn read_commit_metadata(repo: &Repository, commit_hash: &str) -> Result<CommitMetadata, ParseError> {
+    // Parse the commit hash into an ObjectId
+    let commit_id = gix::ObjectId::from_hex(commit_hash.as_bytes()).map_err(|e| ParseError {
+        message: format!("Invalid commit hash {}: {}", commit_hash, e),
+    })?;
+    
+    // Get the commit object
+    let commit = repo.find_object(commit_id).map_err(|e| ParseError {
+        message: format!("Failed to find commit {}: {}", commit_hash, e),
+    })?;
+    
+    let commit = commit.try_into_commit().map_err(|e| ParseError {
+        message: format!("Object {} is not a commit: {}", commit_hash, e),
+    })?;
+    
+    let commit_ref = commit.decode().map_err(|e| ParseError {
+        message: format!("Failed to decode commit {}: {}", commit_hash, e),
+    })?;
+    
+    // Extract metadata
+    let author = &commit_ref.author;
+    let author_name = String::from_utf8_lossy(author.name.as_ref()).to_string();
+    let raw_email = String::from_utf8_lossy(author.email.as_ref());
+    
+    // Validate and sanitize email (handles empty/invalid emails)
+    let author_email = validate_email(&raw_email, commit_hash);
+    
+    // Get subject (first line of message)
+    let message = String::from_utf8_lossy(commit_ref.message.as_ref());
+    let subject = message.lines().next().unwrap_or("").to_string();
+    
+    // seconds() falls back to 0 for malformed signatures
+    let timestamp = |seconds: i64| (seconds > 0).then_some(seconds);
+    
+    Ok(CommitMetadata {
+        commit_hash: commit_hash.to_string(),
+        author_name,
+        author_email,
+        subject,
+        author_time: timestamp(author.seconds()),
+        committer_time: timestamp(commit_ref.committer.seconds()),
+    })
+}
+
+/// Read the raw email and commit metadata of a single commit from the archive at `repo_path`
+/// Used for lists other than the default one, whose epochs aren't known up front
+pub fn get_email_with_metadata_at(repo_path: &str, commit_hash: &str) -> Result<(String, CommitMetadata), ParseError> {
+    let repo = open_repository_for_commit_at(repo_path, commit_hash)?;
+    let content = read_email_blob(&repo, commit_hash)?;
+    let metadata = read_commit_metadata(&repo, commit_hash)?;
+    Ok((content, metadata))
+}
+
+/// Whether every epoch of the archive at `repo_path` can be opened
+pub fn archive_is_readable(repo_path: &str) -> bool {
+    !repo_path.is_empty()
+        && discover_epochs(repo_path)
+            .iter()
+            .all(|epoch| open_repository_at_path(&epoch.path).is_ok())
+}
+
 /// Get commit metadata for a single commit
 pub fn get_single_commit_metadata(commit_hash: &str) -> Result<CommitMetadata, ParseError> {
     let results = get_commit_metadata(&[commit_hash.to_string()])?;
@@ -339,13 +1076,15 @@ pub fn sync_repository(repo_path: Option<&str>) -> Result<GitSyncResult, ParseEr
     }
     
     // For bare repositories, use git fetch instead of git pull
-    // Bare repos (like 0.git) don't have working trees
+    // Bare repos (like 0.git) don't have working trees, and a bare clone has no
+    // remote.origin.fetch refspec, so the branches are mapped explicitly to move local refs
     let output = Command::new("git")
         .arg("-C")
         .arg(repo_path)
         .arg("fetch")
-        .arg("--all")
         .arg("--verbose")
+        .arg("origin")
+        .arg("+refs/heads/*:refs/heads/*")
         .output()
         .map_err(|e| ParseError {
             message: format!("Failed to execute git fetch: {}", e),
@@ -383,6 +1122,358 @@ pub fn sync_repository(repo_path: Option<&str>) -> Result<GitSyncResult, ParseEr
     }
 }
 
+/// Result of fetching new messages into the configured archive
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FetchUpdatesResult {
+    pub success: bool,
+    pub new_commits: Vec<EpochCommit>,
+    pub combined_output: String,
+}
+
+/// Read the current HEAD of a repository, or None for an empty repository
+fn read_head_id(repo: &Repository) -> Option<gix::ObjectId> {
+    repo.head_id().ok().map(|id| id.detach())
+}
+
+/// Walk HEAD ancestry and return every commit not reachable from `stop_at`
+/// With `stop_at = None` this returns the complete history
+fn walk_commits_since(repo: &Repository, stop_at: Option<gix::ObjectId>) -> Result<Vec<String>, ParseError> {
+    let head = repo.head_id().map_err(|e| ParseError {
+        message: format!("Failed to get HEAD: {}", e),
+    })?;
+    
+    let commit_iter = head.ancestors().with_hidden(stop_at).all().map_err(|e| ParseError {
+        message: format!("Failed to create commit iterator: {}", e),
+    })?;
+    
+    let mut commits = Vec::new();
+    for commit_result in commit_iter {
+        let commit_info = commit_result.map_err(|e| ParseError {
+            message: format!("Failed to iterate commits: {}", e),
+        })?;
+        commits.push(commit_info.id.to_string());
+    }
+    
+    Ok(commits)
+}
+
+/// Count the HEAD ancestry not reachable from `stop_at` without collecting hashes
+fn count_commits_since(repo: &Repository, stop_at: Option<gix::ObjectId>) -> Result<usize, ParseError> {
+    let head = repo.head_id().map_err(|e| ParseError {
+        message: format!("Failed to get HEAD: {}", e),
+    })?;
+    
+    let commit_iter = head.ancestors().with_hidden(stop_at).all().map_err(|e| ParseError {
+        message: format!("Failed to create commit iterator: {}", e),
+    })?;
+    
+    let mut count = 0;
+    for commit_result in commit_iter {
+        commit_result.map_err(|e| ParseError {
+            message: format!("Failed to iterate commits: {}", e),
+        })?;
+        count += 1;
+    }
+    
+    Ok(count)
+}
+
+/// Get the commits added to the configured archive after `last_seen_hash`
+/// Epochs newer than the one holding `last_seen_hash` are entirely new; in that epoch
+/// HEAD ancestry is walked until the known commit. Nothing is checked against the database.
+pub fn get_commits_since(last_seen_hash: &str) -> Result<Vec<EpochCommit>, ParseError> {
+    get_commits_since_at(&configured_repo_path()?, last_seen_hash)
+}
+
+/// Same as `get_commits_since`, for the archive at `repo_path`
+pub fn get_commits_since_at(repo_path: &str, last_seen_hash: &str) -> Result<Vec<EpochCommit>, ParseError> {
+    let last_seen = gix::ObjectId::from_hex(last_seen_hash.as_bytes()).map_err(|e| ParseError {
+        message: format!("Invalid commit hash {}: {}", last_seen_hash, e),
+    })?;
+    
+    let mut commits = Vec::new();
+    for epoch_repo in discover_epochs(repo_path).iter().rev() {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        let found = repo.has_object(last_seen);
+        
+        let epoch_commits = walk_commits_since(&repo, found.then_some(last_seen))?;
+        commits.extend(epoch_commits.into_iter().map(|commit_hash| EpochCommit {
+            epoch: epoch_repo.epoch,
+            commit_hash,
+        }));
+        
+        if found {
+            return Ok(commits);
+        }
+    }
+    
+    Err(ParseError {
+        message: format!("Commit {} not found in any epoch repository", last_seen_hash),
+    })
+}
+
+/// Current HEAD of every epoch of the archive at `repo_path` (empty epochs are left out)
+pub fn epoch_heads_at(repo_path: &str) -> Result<Vec<EpochCommit>, ParseError> {
+    let mut heads = Vec::new();
+    for epoch_repo in discover_epochs(repo_path) {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        if let Some(head) = read_head_id(&repo) {
+            heads.push(EpochCommit {
+                epoch: epoch_repo.epoch,
+                commit_hash: head.to_string(),
+            });
+        }
+    }
+    Ok(heads)
+}
+
+/// Fetch every epoch of the configured archive and report the commits that are new
+/// `populated_heads` maps each epoch to the HEAD of the last population; everything after
+/// it is new. Epochs without one (or whose recorded HEAD is gone after a history rewrite)
+/// fall back to the HEAD from before the fetch.
+pub fn fetch_updates(populated_heads: &std::collections::HashMap<u32, String>) -> Result<FetchUpdatesResult, ParseError> {
+    let epochs = get_epochs()?;
+    let mut new_commits = Vec::new();
+    let mut outputs = Vec::new();
+    
+    for epoch_repo in epochs.iter().rev() {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        let known_head = populated_heads.get(&epoch_repo.epoch)
+            .and_then(|hash| gix::ObjectId::from_hex(hash.as_bytes()).ok())
+            .filter(|id| repo.has_object(id))
+            .or_else(|| read_head_id(&repo));
+        
+        let sync = sync_repository(Some(&epoch_repo.path))?;
+        outputs.push(format!("[epoch {}] {}", epoch_repo.epoch, sync.combined_output));
+        
+        // Reopen so the refs updated by the fetch are visible
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        if read_head_id(&repo) == known_head {
+            continue;
+        }
+        
+        let epoch_commits = walk_commits_since(&repo, known_head)?;
+        println!("Epoch {}: {} new commits", epoch_repo.epoch, epoch_commits.len());
+        new_commits.extend(epoch_commits.into_iter().map(|commit_hash| EpochCommit {
+            epoch: epoch_repo.epoch,
+            commit_hash,
+        }));
+    }
+    
+    Ok(FetchUpdatesResult {
+        success: true,
+        new_commits,
+        combined_output: outputs.join("\n"),
+    })
+}
+
+/// Verification report for one epoch repository
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpochVerification {
+    pub epoch: u32,
+    pub path: String,
+    pub head: Option<String>,
+    pub commits_walked: usize,
+    pub error: Option<String>,
+}
+
+/// Result of `verify_repository`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RepositoryVerification {
+    pub success: bool,
+    pub repo_path: String,
+    pub epochs: Vec<EpochVerification>,
+    pub commits_walked: usize,
+    pub commits_checked: usize,
+    pub deletion_commits: usize,
+    pub problems: Vec<String>,
+}
+
+/// Default number of random commits to spot-check for an "m" blob
+pub const DEFAULT_VERIFY_SAMPLE_SIZE: usize = 100;
+
+/// Small xorshift generator for sampling; no need for cryptographic randomness here
+struct SampleRng(u64);
+
+impl SampleRng {
+    fn from_clock() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x2545_f491_4f6c_dd1d);
+        SampleRng(nanos | 1)
+    }
+    
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+/// Check that the configured archive is usable before populating from it
+/// - every epoch repository opens and has a reachable HEAD
+/// - the full ancestry can be walked (missing/corrupt commit objects are reported)
+/// - `sample_size` random commits have a readable "m" blob (deletion commits are counted separately)
+pub fn verify_repository(sample_size: Option<usize>) -> Result<RepositoryVerification, ParseError> {
+    let repo_path = configured_repo_path()?;
+    let sample_size = sample_size.unwrap_or(DEFAULT_VERIFY_SAMPLE_SIZE);
+    let mut rng = SampleRng::from_clock();
+    let mut problems = Vec::new();
+    let mut epochs = Vec::new();
+    let mut repos = Vec::new();
+    // Reservoir sample of (index into repos, commit id) across all epochs
+    let mut sample: Vec<(usize, gix::ObjectId)> = Vec::with_capacity(sample_size);
+    let mut commits_walked = 0;
+    
+    if !Path::new(&repo_path).exists() {
+        return Err(ParseError {
+            message: format!("Repository path '{}' does not exist", repo_path),
+        });
+    }
+    
+    for epoch_repo in discover_epochs(&repo_path) {
+        let mut report = EpochVerification {
+            epoch: epoch_repo.epoch,
+            path: epoch_repo.path.clone(),
+            head: None,
+            commits_walked: 0,
+            error: None,
+        };
+        
+        let repo = match open_repository_at_path(&epoch_repo.path) {
+            Ok(repo) => repo,
+            Err(e) => {
+                problems.push(format!("Epoch {}: {}", epoch_repo.epoch, e));
+                report.error = Some(e.message);
+                epochs.push(report);
+                continue;
+            }
+        };
+        
+        let head = match repo.head_id() {
+            Ok(head) => head,
+            Err(e) => {
+                let message = format!("HEAD is not reachable: {}", e);
+                problems.push(format!("Epoch {}: {}", epoch_repo.epoch, message));
+                report.error = Some(message);
+                epochs.push(report);
+                continue;
+            }
+        };
+        report.head = Some(head.to_string());
+        
+        match head.ancestors().all() {
+            Ok(commit_iter) => {
+                for commit_result in commit_iter {
+                    match commit_result {
+                        Ok(info) => {
+                            if sample.len() < sample_size {
+                                sample.push((repos.len(), info.id));
+                            } else {
+                                let slot = rng.below(commits_walked + 1);
+                                if slot < sample_size {
+                                    sample[slot] = (repos.len(), info.id);
+                                }
+                            }
+                            commits_walked += 1;
+                            report.commits_walked += 1;
+                        }
+                        Err(e) => {
+                            let message = format!("History walk stopped after {} commits: {}", report.commits_walked, e);
+                            problems.push(format!("Epoch {}: {}", epoch_repo.epoch, message));
+                            report.error = Some(message);
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                let message = format!("Failed to create commit iterator: {}", e);
+                problems.push(format!("Epoch {}: {}", epoch_repo.epoch, message));
+                report.error = Some(message);
+            }
+        }
+        
+        repos.push(repo);
+        epochs.push(report);
+    }
+    
+    let mut commits_checked = 0;
+    let mut deletion_commits = 0;
+    for (repo_index, commit_id) in &sample {
+        let commit_hash = commit_id.to_string();
+        match read_email_entry(&repos[*repo_index], &commit_hash) {
+            Ok(EmailEntry::Message(_)) => {}
+            Ok(EmailEntry::Deleted(_)) => deletion_commits += 1,
+            Err(e) => problems.push(e.message),
+        }
+        commits_checked += 1;
+    }
+    
+    Ok(RepositoryVerification {
+        success: problems.is_empty(),
+        repo_path,
+        epochs,
+        commits_walked,
+        commits_checked,
+        deletion_commits,
+        problems,
+    })
+}
+
+/// On-disk size of one epoch repository
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EpochDiskUsage {
+    pub epoch: u32,
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// On-disk size of the configured archive
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RepositoryDiskUsage {
+    pub repo_path: String,
+    pub total_bytes: u64,
+    pub epochs: Vec<EpochDiskUsage>,
+}
+
+/// Total size of all files below `path` (symlinks are not followed)
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => directory_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Get the on-disk size of the configured archive, per epoch
+pub fn get_repository_disk_usage() -> Result<RepositoryDiskUsage, ParseError> {
+    let repo_path = configured_repo_path()?;
+    let epochs: Vec<EpochDiskUsage> = discover_epochs(&repo_path)
+        .into_iter()
+        .map(|epoch_repo| EpochDiskUsage {
+            bytes: directory_size(Path::new(&epoch_repo.path)),
+            epoch: epoch_repo.epoch,
+            path: epoch_repo.path,
+        })
+        .collect();
+    
+    Ok(RepositoryDiskUsage {
+        total_bytes: epochs.iter().map(|e| e.bytes).sum(),
+        repo_path,
+        epochs,
+    })
+}
+
 /// Check if a git repository exists at the given path
 pub fn check_repository_exists(path: &str) -> bool {
     std::path::Path::new(path).exists()
@@ -440,3 +1531,408 @@ pub fn clone_repository(clone_url: &str, target_path: &str, bare: bool) -> Resul
         })
     }
 }
+
+/// Run a git command, returning its combined output or an error mentioning `action`
+fn run_git_command(args: &[&str], action: &str) -> Result<GitSyncResult, ParseError> {
+    let output = Command::new("git").args(args).output().map_err(|e| ParseError {
+        message: format!("Failed to execute git {}: {}", action, e),
+    })?;
+    
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let combined = [stderr.as_str(), stdout.as_str()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n");
+    
+    if output.status.success() {
+        Ok(GitSyncResult {
+            success: true,
+            stdout,
+            stderr,
+            combined_output: combined,
+        })
+    } else {
+        Err(ParseError {
+            message: format!("Git {} failed: {}", action, combined),
+        })
+    }
+}
+
+/// Base URL of a public-inbox archive ("https://lore.kernel.org/bpf/0" -> "https://lore.kernel.org/bpf")
+pub fn epoch_base_url(clone_url: &str) -> String {
+    let trimmed = clone_url.trim_end_matches('/');
+    match trimmed.rsplit_once('/') {
+        Some((base, last)) if parse_epoch_dir_name(last).is_some() => base.to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+/// Upper bound when probing a remote archive for epochs
+const MAX_REMOTE_EPOCHS: u32 = 128;
+
+/// Find the epochs a remote public-inbox archive serves by probing `<base>/<n>` with ls-remote
+pub fn list_remote_epochs(clone_url: &str) -> Result<Vec<u32>, ParseError> {
+    let base = epoch_base_url(clone_url);
+    let mut epochs = Vec::new();
+    
+    for epoch in 0..MAX_REMOTE_EPOCHS {
+        let url = format!("{}/{}", base, epoch);
+        if run_git_command(&["ls-remote", "--heads", &url], "ls-remote").is_err() {
+            break;
+        }
+        epochs.push(epoch);
+    }
+    
+    if epochs.is_empty() {
+        return Err(ParseError {
+            message: format!("No epochs found at {}", base),
+        });
+    }
+    Ok(epochs)
+}
+
+/// Bare-clone one epoch of the archive at `base` into `<epoch_dir>/<epoch>.git`
+fn clone_epoch(base: &str, epoch: u32, epoch_dir: &Path, depth: Option<u32>) -> Result<GitSyncResult, ParseError> {
+    let url = format!("{}/{}", base, epoch);
+    let epoch_path = epoch_dir.join(format!("{}.git", epoch));
+    let epoch_path = epoch_path.to_string_lossy();
+    
+    let depth_arg = depth.map(|d| format!("--depth={}", d.max(1)));
+    let mut args = vec!["clone", "--bare", "--verbose"];
+    if let Some(depth_arg) = depth_arg.as_deref() {
+        args.push(depth_arg);
+    }
+    args.push(&url);
+    args.push(&epoch_path);
+    
+    run_git_command(&args, "clone")
+}
+
+/// Quick-start clone: only the newest `recent_epochs` epochs, optionally with shallow history
+/// Epochs are cloned bare into `<target_path>/git/<epoch>.git`, the layout `discover_epochs` expects,
+/// so `deepen_repository` can fetch older history and epochs later.
+pub fn clone_recent_epochs(
+    clone_url: &str,
+    target_path: &str,
+    recent_epochs: u32,
+    depth: Option<u32>,
+) -> Result<GitSyncResult, ParseError> {
+    let base = epoch_base_url(clone_url);
+    let remote_epochs = list_remote_epochs(clone_url)?;
+    let skip = remote_epochs.len().saturating_sub(recent_epochs.max(1) as usize);
+    
+    let git_dir = Path::new(target_path).join("git");
+    std::fs::create_dir_all(&git_dir).map_err(|e| ParseError {
+        message: format!("Failed to create directory '{}': {}", git_dir.display(), e),
+    })?;
+    
+    let mut outputs = Vec::new();
+    for &epoch in &remote_epochs[skip..] {
+        let result = clone_epoch(&base, epoch, &git_dir, depth)?;
+        outputs.push(format!("[epoch {}] {}", epoch, result.combined_output));
+    }
+    
+    let summary = format!(
+        "Cloned {} of {} epochs from {}{}",
+        remote_epochs.len() - skip,
+        remote_epochs.len(),
+        base,
+        depth.map(|d| format!(" (depth {})", d)).unwrap_or_default()
+    );
+    outputs.insert(0, summary.clone());
+    
+    Ok(GitSyncResult {
+        success: true,
+        stdout: summary,
+        stderr: String::new(),
+        combined_output: outputs.join("\n"),
+    })
+}
+
+/// Result of `deepen_repository`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeepenResult {
+    pub success: bool,
+    /// Commits that became reachable through the deepening, to backfill the database with
+    pub new_commits: Vec<EpochCommit>,
+    pub combined_output: String,
+}
+
+/// Fetch older history for a quick-start clone
+/// Shallow epochs are deepened by `depth` commits (fully unshallowed with `None`), and with
+/// `include_older_epochs` the epochs older than the oldest local one are cloned as well.
+/// The commits that arrived are returned so the caller can populate exactly those.
+pub fn deepen_repository(depth: Option<u32>, include_older_epochs: bool) -> Result<DeepenResult, ParseError> {
+    let repo_path = configured_repo_path()?;
+    let config = crate::git_config::GitConfig::load();
+    let epochs = discover_epochs(&repo_path);
+    let mut outputs = Vec::new();
+    let mut new_commits = Vec::new();
+    
+    for epoch_repo in &epochs {
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        if !repo.is_shallow() {
+            continue;
+        }
+        let known: std::collections::HashSet<String> = walk_commits_since(&repo, None)?.into_iter().collect();
+        
+        let deepen_arg = match depth {
+            Some(d) => format!("--deepen={}", d.max(1)),
+            None => "--unshallow".to_string(),
+        };
+        // Explicit refspec for the same reason as in `sync_repository`
+        let result = run_git_command(
+            &["-C", &epoch_repo.path, "fetch", "--verbose", &deepen_arg, "origin", "+refs/heads/*:refs/heads/*"],
+            "fetch"
+        )?;
+        outputs.push(format!("[epoch {}] {}", epoch_repo.epoch, result.combined_output));
+        
+        // Reopen so the new shallow boundary is visible
+        let repo = open_repository_at_path(&epoch_repo.path)?;
+        new_commits.extend(walk_commits_since(&repo, None)?
+            .into_iter()
+            .filter(|commit_hash| !known.contains(commit_hash))
+            .map(|commit_hash| EpochCommit { epoch: epoch_repo.epoch, commit_hash }));
+    }
+    
+    let oldest_local = epochs.iter().map(|e| e.epoch).min().unwrap_or(0);
+    if include_older_epochs && oldest_local > 0 {
+        let base = epoch_base_url(&config.clone_url);
+        // Older epochs sit next to the existing ones (the archive root or its git/ directory)
+        let epoch_dir = epochs.first()
+            .and_then(|e| Path::new(&e.path).parent().map(|p| p.to_path_buf()))
+            .unwrap_or_else(|| Path::new(&repo_path).join("git"));
+        
+        for epoch in 0..oldest_local {
+            let result = clone_epoch(&base, epoch, &epoch_dir, depth)?;
+            outputs.push(format!("[epoch {}] {}", epoch, result.combined_output));
+            
+            let repo = open_repository_at_path(&epoch_dir.join(format!("{}.git", epoch)).to_string_lossy())?;
+            new_commits.extend(walk_commits_since(&repo, None)?
+                .into_iter()
+                .map(|commit_hash| EpochCommit { epoch, commit_hash }));
+        }
+    }
+    
+    let combined_output = if outputs.is_empty() {
+        "Repository already has full history".to_string()
+    } else {
+        outputs.join("\n")
+    };
+    
+    Ok(DeepenResult {
+        success: true,
+        new_commits,
+        combined_output,
+    })
+}
+
+/// Progress snapshot emitted while cloning with gix
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloneProgress {
+    pub phase: String,
+    pub objects_received: usize,
+    pub objects_total: Option<usize>,
+    pub bytes_received: usize,
+    pub done: bool,
+}
+
+/// Progress ids used by gix-pack while writing a received pack
+const PROGRESS_ID_READ_PACK_BYTES: gix::progress::Id = *b"BWRB";
+const PROGRESS_ID_INDEX_OBJECTS: gix::progress::Id = *b"IWIO";
+
+/// Fold a progress tree snapshot into the previous CloneProgress
+/// gix removes finished tasks from the tree, so counters only ever grow here
+fn update_clone_progress(
+    previous: &CloneProgress,
+    snapshot: &[(gix::progress::prodash::progress::Key, gix::progress::Task)],
+) -> CloneProgress {
+    let mut progress = previous.clone();
+    
+    for (_, task) in snapshot {
+        let Some(value) = task.progress.as_ref() else {
+            continue;
+        };
+        let step = value.step.load(std::sync::atomic::Ordering::Relaxed);
+        
+        if task.id == PROGRESS_ID_READ_PACK_BYTES {
+            progress.bytes_received = progress.bytes_received.max(step);
+        } else if task.id == PROGRESS_ID_INDEX_OBJECTS {
+            progress.objects_received = progress.objects_received.max(step);
+            progress.objects_total = value.done_at.or(progress.objects_total);
+        }
+        
+        // The most recently started task with activity is the current phase
+        if step > 0 {
+            progress.phase = task.name.clone();
+        }
+    }
+    
+    progress
+}
+
+/// Clone a repository in-process with gix, reporting progress through a callback
+/// The callback is polled from a background thread and receives a final snapshot with `done = true`
+pub fn clone_repository_with_progress<F>(
+    clone_url: &str,
+    target_path: &str,
+    bare: bool,
+    progress_callback: F,
+) -> Result<GitSyncResult, ParseError>
+where
+    F: Fn(CloneProgress) + Send + Sync + 'static,
+{
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    
+    // Create parent directory if it doesn't exist
+    if let Some(parent) = Path::new(target_path).parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ParseError {
+            message: format!("Failed to create directory '{}': {}", parent.display(), e),
+        })?;
+    }
+    
+    let root = gix::progress::tree::Root::new();
+    let callback = Arc::new(progress_callback);
+    let finished = Arc::new(AtomicBool::new(false));
+    
+    // Poll the progress tree in the background while the clone blocks this thread
+    let reporter = {
+        let root = Arc::clone(&root);
+        let callback = Arc::clone(&callback);
+        let finished = Arc::clone(&finished);
+        std::thread::spawn(move || {
+            let mut snapshot = Vec::new();
+            let mut progress = CloneProgress {
+                phase: "connecting".to_string(),
+                objects_received: 0,
+                objects_total: None,
+                bytes_received: 0,
+                done: false,
+            };
+            while !finished.load(Ordering::Relaxed) {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                root.sorted_snapshot(&mut snapshot);
+                progress = update_clone_progress(&progress, &snapshot);
+                callback(progress.clone());
+            }
+            progress
+        })
+    };
+    
+    let should_interrupt = AtomicBool::new(false);
+    let clone_result = run_gix_clone(clone_url, target_path, bare, &root, &should_interrupt);
+    
+    finished.store(true, Ordering::Relaxed);
+    let mut final_progress = reporter.join().map_err(|_| ParseError {
+        message: "Clone progress reporter panicked".to_string(),
+    })?;
+    final_progress.phase = if clone_result.is_ok() { "done" } else { "failed" }.to_string();
+    final_progress.done = true;
+    callback(final_progress.clone());
+    
+    let repo = clone_result?;
+    let summary = format!(
+        "Cloned {} into {} ({} objects, {} bytes received)",
+        clone_url,
+        repo.path().display(),
+        final_progress.objects_received,
+        final_progress.bytes_received
+    );
+    
+    Ok(GitSyncResult {
+        success: true,
+        stdout: summary.clone(),
+        stderr: String::new(),
+        combined_output: summary,
+    })
+}
+
+/// Run the actual gix clone, attaching fetch/checkout progress to `root`
+fn run_gix_clone(
+    clone_url: &str,
+    target_path: &str,
+    bare: bool,
+    root: &gix::progress::tree::Root,
+    should_interrupt: &std::sync::atomic::AtomicBool,
+) -> Result<Repository, ParseError> {
+    let prepare = if bare {
+        gix::prepare_clone_bare(clone_url, target_path)
+    } else {
+        gix::prepare_clone(clone_url, target_path)
+    };
+    let mut prepare = prepare.map_err(|e| ParseError {
+        message: format!("Failed to prepare clone of '{}': {}", clone_url, e),
+    })?;
+    
+    if bare {
+        let (repo, _outcome) = prepare
+            .fetch_only(root.add_child("fetch"), should_interrupt)
+            .map_err(|e| ParseError {
+                message: format!("Clone fetch failed: {}", e),
+            })?;
+        return Ok(repo);
+    }
+    
+    let (mut checkout, _outcome) = prepare
+        .fetch_then_checkout(root.add_child("fetch"), should_interrupt)
+        .map_err(|e| ParseError {
+            message: format!("Clone fetch failed: {}", e),
+        })?;
+    let (repo, _outcome) = checkout
+        .main_worktree(root.add_child("checkout"), should_interrupt)
+        .map_err(|e| ParseError {
+            message: format!("Clone checkout failed: {}", e),
+        })?;
+    Ok(repo)
+}
+
+/// A merged commit looked up in a local kernel tree
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KernelCommit {
+    /// Commit hash as given, possibly abbreviated
+    pub requested: String,
+    /// Full commit hash, None when the tree has no such commit
+    pub commit_hash: Option<String>,
+    /// First release tag containing the commit (e.g. "v6.9-rc1"), None while unreleased
+    pub release_tag: Option<String>,
+}
+
+/// Commits named per `git name-rev` call, keeping the command line short
+const NAME_REV_BATCH_SIZE: usize = 500;
+
+/// Resolve (possibly abbreviated) commit hashes in a kernel tree and find the release each landed in
+pub fn resolve_kernel_commits(tree_path: &str, commit_hashes: &[String]) -> Result<Vec<KernelCommit>, ParseError> {
+    let repo = open_repository_at_path(tree_path)?;
+    let mut commits: Vec<KernelCommit> = commit_hashes.iter()
+        .map(|hash| KernelCommit {
+            requested: hash.clone(),
+            commit_hash: repo.rev_parse_single(format!("{}^{{commit}}", hash).as_str())
+                .ok()
+                .map(|id| id.detach().to_string()),
+            release_tag: None,
+        })
+        .collect();
+    
+    let mut found: Vec<&mut KernelCommit> = commits.iter_mut().filter(|c| c.commit_hash.is_some()).collect();
+    for batch in found.chunks_mut(NAME_REV_BATCH_SIZE) {
+        // Names each commit after the nearest release tag containing it, e.g. "v6.9-rc1~12^2~3"
+        let mut args = vec!["-C", tree_path, "name-rev", "--name-only", "--tags", "--refs=refs/tags/v[0-9]*"];
+        let hashes: Vec<String> = batch.iter().filter_map(|c| c.commit_hash.clone()).collect();
+        args.extend(hashes.iter().map(String::as_str));
+        let output = run_git_command(&args, "name-rev")?;
+        
+        for (commit, name) in batch.iter_mut().zip(output.stdout.lines()) {
+            let tag = name.trim().trim_start_matches("tags/");
+            let tag = tag.split(['~', '^']).next().unwrap_or(tag);
+            if !tag.is_empty() && tag != "undefined" {
+                commit.release_tag = Some(tag.to_string());
+            }
+        }
+    }
+    
+    Ok(commits)
+}
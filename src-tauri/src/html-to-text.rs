@@ -0,0 +1,213 @@
+use regex::Regex;
+use once_cell::sync::Lazy;
+
+// Tags and comments; group 1 is "/" for closing tags, group 2 the tag name
+static HTML_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<!--.*?-->|<(/?)([a-zA-Z][a-zA-Z0-9]*)[^>]*>").unwrap()
+});
+static HTML_ENTITY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap()
+});
+static BLANK_LINES_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n{3,}").unwrap());
+
+/// Decode character references and the common named entities
+pub fn decode_html_entities(text: &str) -> String {
+    HTML_ENTITY_REGEX.replace_all(text, |caps: &regex::Captures| {
+        let entity = &caps[1];
+        let decoded = if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+            u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+        } else if let Some(dec) = entity.strip_prefix('#') {
+            dec.parse().ok().and_then(char::from_u32)
+        } else {
+            match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => None,
+            }
+        };
+        decoded.map(String::from).unwrap_or_else(|| caps[0].to_string())
+    }).into_owned()
+}
+
+/// Accumulates plain text, prefixing lines inside <blockquote> with "> "
+struct PlainTextWriter {
+    out: String,
+    quote_depth: usize,
+    at_line_start: bool,
+    pending_space: bool,
+}
+
+impl PlainTextWriter {
+    fn write_str(&mut self, s: &str) {
+        if self.at_line_start {
+            self.out.push_str(&"> ".repeat(self.quote_depth));
+            self.at_line_start = false;
+            self.pending_space = false;
+        }
+        if self.pending_space {
+            self.out.push(' ');
+            self.pending_space = false;
+        }
+        self.out.push_str(s);
+    }
+
+    fn newline(&mut self) {
+        if self.at_line_start && self.quote_depth > 0 {
+            self.out.push_str(&"> ".repeat(self.quote_depth));
+        }
+        self.out.push('\n');
+        self.at_line_start = true;
+        self.pending_space = false;
+    }
+
+    /// Start a new line unless already at the start of one
+    fn break_line(&mut self) {
+        if !self.at_line_start {
+            self.newline();
+        }
+    }
+
+    /// Text outside <pre>: runs of whitespace collapse to one space
+    fn text(&mut self, text: &str) {
+        if text.starts_with(char::is_whitespace) && !self.at_line_start {
+            self.pending_space = true;
+        }
+        let mut words = text.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            self.write_str(word);
+            self.pending_space = words.peek().is_some();
+        }
+        if text.ends_with(char::is_whitespace) && !self.at_line_start {
+            self.pending_space = true;
+        }
+    }
+
+    /// Text inside <pre>: kept verbatim
+    fn preformatted(&mut self, text: &str) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.newline();
+            }
+            if !line.is_empty() {
+                self.write_str(line);
+            }
+        }
+    }
+}
+
+/// Convert an HTML email body to readable plain text
+/// Tags are stripped, entities decoded, block elements become line breaks and
+/// <blockquote> content is quoted with "> " so reply detection keeps working
+pub fn html_to_text(html: &str) -> String {
+    let mut writer = PlainTextWriter {
+        out: String::new(),
+        quote_depth: 0,
+        at_line_start: true,
+        pending_space: false,
+    };
+    let mut pre_depth = 0usize;
+    // Inside <head>, <script> or <style>: nothing to show
+    let mut hidden_depth = 0usize;
+    let mut last = 0;
+
+    let emit = |writer: &mut PlainTextWriter, raw: &str, pre: bool, hidden: bool| {
+        if hidden || raw.is_empty() {
+            return;
+        }
+        let text = decode_html_entities(raw);
+        if pre {
+            writer.preformatted(&text);
+        } else {
+            writer.text(&text);
+        }
+    };
+
+    for caps in HTML_TAG_REGEX.captures_iter(html) {
+        let tag = caps.get(0).unwrap();
+        emit(&mut writer, &html[last..tag.start()], pre_depth > 0, hidden_depth > 0);
+        last = tag.end();
+
+        let Some(name) = caps.get(2) else {
+            continue; // comment
+        };
+        let closing = caps.get(1).is_some_and(|m| !m.as_str().is_empty());
+        match name.as_str().to_lowercase().as_str() {
+            "head" | "script" | "style" | "title" => {
+                hidden_depth = if closing { hidden_depth.saturating_sub(1) } else { hidden_depth + 1 };
+            }
+            // Markup inside hidden content (e.g. in a script string) has no layout effect
+            _ if hidden_depth > 0 => {}
+            "br" => writer.newline(),
+            "blockquote" => {
+                writer.break_line();
+                writer.quote_depth = if closing { writer.quote_depth.saturating_sub(1) } else { writer.quote_depth + 1 };
+            }
+            "pre" => {
+                writer.break_line();
+                pre_depth = if closing { pre_depth.saturating_sub(1) } else { pre_depth + 1 };
+            }
+            "li" if !closing => {
+                writer.break_line();
+                writer.write_str("* ");
+            }
+            "p" | "div" | "tr" | "ul" | "ol" | "li" | "table" | "hr"
+            | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                writer.break_line();
+                if name.as_str().eq_ignore_ascii_case("p") && closing {
+                    writer.newline();
+                }
+            }
+            "td" | "th" if closing => writer.pending_space = true,
+            _ => {}
+        }
+    }
+    emit(&mut writer, &html[last..], pre_depth > 0, hidden_depth > 0);
+
+    let text: String = writer.out.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    BLANK_LINES_REGEX.replace_all(text.trim(), "\n\n").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_named_and_numeric_entities() {
+        assert_eq!(decode_html_entities("a &lt;b&gt; &amp;&amp; &quot;c&quot; &apos;d&apos;"), "a <b> && \"c\" 'd'");
+        assert_eq!(decode_html_entities("&#65;&#x42;&#X43;&nbsp;"), "ABC ");
+        // Unknown or invalid references are left as they are
+        assert_eq!(decode_html_entities("&bogus; &#xD800; & alone"), "&bogus; &#xD800; & alone");
+        assert_eq!(html_to_text("<p>x &lt; y &amp;&amp; y &gt; z</p>"), "x < y && y > z");
+    }
+
+    #[test]
+    fn line_breaks_and_paragraphs() {
+        assert_eq!(html_to_text("first<br>second<BR/>third"), "first\nsecond\nthird");
+        assert_eq!(html_to_text("<p>one\n  two</p><p>three</p>"), "one two\n\nthree");
+        assert_eq!(html_to_text("<div>a</div><div>b</div>"), "a\nb");
+        assert_eq!(html_to_text("<ul><li>x</li><li>y</li></ul>"), "* x\n* y");
+        assert_eq!(html_to_text("<p>a</p><br><br><br><br><p>b</p>"), "a\n\nb");
+    }
+
+    #[test]
+    fn drops_script_style_and_head() {
+        let html = "<html><head><title>Title</title><style>p { color: red; }</style></head>\
+                    <body><script type=\"text/javascript\">document.write(\"<p>hidden</p>\");</script>\
+                    <p>visible</p><!-- comment --></body></html>";
+        assert_eq!(html_to_text(html), "visible");
+    }
+
+    #[test]
+    fn blockquote_and_pre() {
+        let html = "<p>On Tue, Jane wrote:</p><blockquote><p>quoted</p><blockquote>deeper</blockquote></blockquote>\
+                    <pre>  keep\n    spacing</pre>";
+        assert_eq!(html_to_text(html), "On Tue, Jane wrote:\n\n> quoted\n>\n> > deeper\n  keep\n    spacing");
+    }
+}
@@ -10,6 +10,25 @@ pub mod git_config;
 #[path = "mail-parser.rs"]
 pub mod mail_parser;
 
+// Include the diff parser module
+#[path = "diff-parser.rs"]
+pub mod diff_parser;
+
+// Include the HTML to plain text conversion module
+#[path = "html-to-text.rs"]
+pub mod html_to_text;
+
+// Include the mbox/Maildir importer module
+#[path = "mbox-importer.rs"]
+pub mod mbox_importer;
+
+// Include the public-inbox msgmap lookup module
+#[path = "msgmap.rs"]
+pub mod msgmap;
+
+// Include the .mailmap parser module
+pub mod mailmap;
+
 // Include the database module
 pub mod database;
 
@@ -30,8 +49,11 @@ pub mod test_threading_full;
 #[path = "test_threading_db.rs"]
 pub mod test_threading_db;
 
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 // Import the Emitter trait for window.emit()
 use tauri::Emitter;
+use tauri::Manager;
 use tauri::State;
 use tokio::sync::Mutex;
 
@@ -45,16 +67,95 @@ pub use database::{DatabaseConfig, DatabaseSetupResult, DatabasePopulationResult
 // Global database state
 pub struct DatabaseState {
     manager: Mutex<Option<database::DatabaseManager>>,
+    // Kept outside the manager lock, which a running population holds
+    population_cancel: Arc<AtomicBool>,
+    // Task forwarding database change events to the frontend
+    change_forwarder: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
 }
 
 impl DatabaseState {
     pub fn new() -> Self {
         Self {
             manager: Mutex::new(None),
+            population_cancel: Arc::new(AtomicBool::new(false)),
+            change_forwarder: Mutex::new(None),
+        }
+    }
+
+    /// Stop background work and close the connection pool before the app exits
+    /// A running population stops after its current batch, keeping what it inserted;
+    /// a thread rebuild is waited for, up to SHUTDOWN_TIMEOUT_SECS
+    async fn shutdown(&self) {
+        self.population_cancel.store(true, Ordering::Relaxed);
+        if let Some(forwarder) = self.change_forwarder.lock().await.take() {
+            forwarder.abort();
+        }
+
+        let timeout = std::time::Duration::from_secs(SHUTDOWN_TIMEOUT_SECS);
+        match tokio::time::timeout(timeout, self.manager.lock()).await {
+            Ok(mut manager_guard) => {
+                if let Some(mut manager) = manager_guard.take() {
+                    manager.close().await;
+                }
+            }
+            Err(_) => eprintln!(
+                "Database still busy after {}s, exiting without closing the connection pool",
+                SHUTDOWN_TIMEOUT_SECS
+            ),
         }
     }
 }
 
+/// How long shutdown waits for a running database operation to finish
+const SHUTDOWN_TIMEOUT_SECS: u64 = 10;
+
+/// How long to collect a burst of change events (e.g. from a thread rebuild) before emitting
+const CHANGE_EVENT_COALESCE_MS: u64 = 250;
+
+/// Emit database change events (see database::ChangeEvent) as frontend events
+/// Events arriving together are coalesced so each is emitted once
+fn spawn_change_forwarder(app: tauri::AppHandle, mut listener: sqlx::postgres::PgListener) -> tauri::async_runtime::JoinHandle<()> {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<database::ChangeEvent>();
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = receiver.recv().await {
+            let mut burst = vec![event];
+            let deadline = tokio::time::Instant::now() + std::time::Duration::from_millis(CHANGE_EVENT_COALESCE_MS);
+            while let Ok(Some(event)) = tokio::time::timeout_at(deadline, receiver.recv()).await {
+                burst.push(event);
+            }
+
+            let mut emitted = std::collections::HashSet::new();
+            for event in burst {
+                if emitted.insert(event.clone()) {
+                    let _ = app.emit(event.name(), &event);
+                }
+            }
+        }
+    });
+
+    // Reads notifications until the pool closes or the task is aborted, which drops
+    // the sender and ends the emitting task above
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match listener.recv().await {
+                Ok(notification) => {
+                    if let Some(event) = database::ChangeEvent::from_payload(notification.payload()) {
+                        if sender.send(event).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(sqlx::Error::PoolClosed) => break,
+                Err(e) => {
+                    eprintln!("Change event listener error: {}", e);
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+            }
+        }
+    })
+}
+
 // Tauri command to get BPF commit hashes (first 10 by default)
 #[tauri::command]
 fn get_bpf_commits() -> Result<Vec<String>, ParseError> {
@@ -99,6 +200,12 @@ fn get_total_git_commits() -> Result<usize, ParseError> {
     git_parser::get_total_git_commits()
 }
 
+// Tauri command to list the public-inbox epoch repositories of the configured archive
+#[tauri::command]
+fn get_repository_epochs() -> Result<Vec<git_parser::EpochRepo>, ParseError> {
+    git_parser::get_epochs()
+}
+
 // Tauri command to get recent commits (same as get_bpf_commits for backward compatibility)
 #[tauri::command]
 fn get_recent_bpf_commits() -> Result<Vec<String>, ParseError> {
@@ -157,45 +264,67 @@ async fn is_database_connected(state: State<'_, DatabaseState>) -> Result<bool,
 }
 
 /// Connect to database with provided configuration
+///
+/// Failures are returned as a structured `ConnectionError` so the UI can
+/// tell an unreachable server apart from bad credentials or a missing database.
 #[tauri::command]
 async fn connect_database(
+    app: tauri::AppHandle,
     state: State<'_, DatabaseState>,
     host: String,
     port: u16,
     user: String,
     password: String,
     database: String
-) -> Result<String, String> {
+) -> Result<String, database::ConnectionError> {
     let config = DatabaseConfig {
         host,
         port,
         user,
         password,
         database,
+        ..DatabaseConfig::from_env()
     };
 
     let mut db_manager = database::DatabaseManager::new(config.clone());
+    db_manager.set_population_cancel_flag(state.population_cancel.clone());
     
-    // Try to connect
-    match db_manager.connect().await {
-        Ok(_) => {
-            // Test the connection
-            match db_manager.test_connection().await {
-                Ok(true) => {
-                    // Store in global state
-                    let mut manager_guard = state.manager.lock().await;
-                    *manager_guard = Some(db_manager);
-                    Ok("Successfully connected to database".to_string())
-                },
-                Ok(false) => Err("Connection test failed".to_string()),
-                Err(e) => Err(format!("Connection test failed: {}", e)),
+    // Try to connect (retries with backoff while the server is unreachable)
+    db_manager.connect().await?;
+
+    // Test the connection
+    match db_manager.test_connection().await {
+        Ok(true) => {
+            // An uninitialized schema is expected before setup_database; any other
+            // mismatch is reported, keeping the connection so it can be migrated
+            let schema_error = db_manager.schema_error()
+                .filter(|e| !matches!(e, database::SchemaError::Uninitialized { .. }))
+                .cloned();
+
+            // Live updates are optional; the app falls back to refreshing on demand
+            let forwarder = match db_manager.listen_for_changes().await {
+                Ok(listener) => Some(spawn_change_forwarder(app, listener)),
+                Err(e) => {
+                    eprintln!("Not listening for database changes: {}", e);
+                    None
+                }
+            };
+            if let Some(previous) = std::mem::replace(&mut *state.change_forwarder.lock().await, forwarder) {
+                previous.abort();
+            }
+
+            // Store in global state
+            let mut manager_guard = state.manager.lock().await;
+            *manager_guard = Some(db_manager);
+            match schema_error {
+                Some(schema) => Err(database::ConnectionError::IncompatibleSchema { schema }),
+                None => Ok("Successfully connected to database".to_string()),
             }
         },
-        Err(e) => {
-            // Provide detailed error information
-            let error_msg = format!("Failed to connect to database: {}", e);
-            Err(error_msg)
-        }
+        Ok(false) => Err(database::ConnectionError::Other {
+            message: "Connection test failed".to_string(),
+        }),
+        Err(e) => Err(database::ConnectionError::classify(&e, &config, 1)),
     }
 }
 
@@ -204,6 +333,9 @@ async fn connect_database(
 async fn disconnect_database(state: State<'_, DatabaseState>) -> Result<String, String> {
     let mut manager_guard = state.manager.lock().await;
     
+    if let Some(forwarder) = state.change_forwarder.lock().await.take() {
+        forwarder.abort();
+    }
     if let Some(mut manager) = manager_guard.take() {
         manager.close().await;
         Ok("Disconnected from database".to_string())
@@ -213,329 +345,1827 @@ async fn disconnect_database(state: State<'_, DatabaseState>) -> Result<String,
 }
 
 // Tauri command to search emails by author (database-based)
+// `after`/`before` are RFC 3339 timestamps or plain dates; `before` is exclusive
 #[tauri::command]
 async fn search_emails_by_author(
     state: State<'_, DatabaseState>,
     author_pattern: String,
-    limit: Option<usize>
+    limit: Option<usize>,
+    list_id: Option<i32>,
+    after: Option<String>,
+    before: Option<String>
 ) -> Result<Vec<EmailInfo>, String> {
+    let dates = database::DateRange::parse(after.as_deref(), before.as_deref())?;
+
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::search_patches_for_frontend(db_manager, &author_pattern, limit).await {
+    match database_api::search_patches_for_frontend(db_manager, &author_pattern, limit, list_id, dates).await {
         Ok(emails) => Ok(emails),
         Err(e) => Err(format!("Failed to search by author: {}", e)),
     }
 }
 
-// Database setup command (async)
+// Search patch postings by version, RFC/RESEND flags and target tree (async)
 #[tauri::command]
-async fn setup_database(state: State<'_, DatabaseState>) -> Result<DatabaseSetupResult, String> {
+async fn search_patches_by_subject_tags(
+    state: State<'_, DatabaseState>,
+    filter: database_api::SubjectTagFilter,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::TaggedPatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match db_manager.setup_database().await {
-        Ok(result) => Ok(result),
-        Err(e) => Err(format!("Database setup failed: {}", e)),
+    match database_api::search_patches_by_subject_tags(db_manager, &filter, limit, list_id).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to search by subject tags: {}", e)),
     }
 }
 
-// Database population command with progress callback (async)
+// Ranked full-text search over subjects and bodies, with highlighted snippets (async)
 #[tauri::command]
-async fn populate_database(
+async fn search_fulltext(
     state: State<'_, DatabaseState>,
+    query: String,
     limit: Option<usize>,
-    window: tauri::Window
-) -> Result<DatabasePopulationResult, String> {
+    offset: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::FulltextMatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    // Use Tauri event system for progress tracking
-    let progress_fn = move |current: u32, total: u32, commit_hash: String| {
-        let payload = serde_json::json!({
-            "current": current,
-            "total": total,
-            "commit_hash": commit_hash
-        });
-        // Emit event to the window
-        let _ = window.emit("populate-progress", payload);
-    };
-
-    match db_manager.populate_database(limit, Some(progress_fn)).await {
-        Ok(result) => Ok(result),
-        Err(e) => Err(format!("Database population failed: {}", e)),
+    match database_api::search_patches_fulltext(db_manager, &query, limit, offset, list_id).await {
+        Ok(matches) => Ok(matches),
+        Err(e) => Err(format!("Failed to run full-text search: {}", e)),
     }
 }
 
-// Test database connection (async)
+// Typo tolerant author search by trigram similarity (async)
 #[tauri::command]
-async fn test_database_connection(state: State<'_, DatabaseState>) -> Result<bool, String> {
+async fn fuzzy_search_authors(
+    state: State<'_, DatabaseState>,
+    query: String,
+    min_similarity: Option<f32>,
+    limit: Option<usize>
+) -> Result<Vec<database_api::FuzzyAuthorMatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match db_manager.test_connection().await {
-        Ok(success) => Ok(success),
-        Err(e) => Err(format!("Database connection test failed: {}", e)),
+    match database_api::fuzzy_search_authors(db_manager, &query, min_similarity, limit).await {
+        Ok(authors) => Ok(authors),
+        Err(e) => Err(format!("Failed to fuzzy search authors: {}", e)),
     }
 }
 
-// Get database statistics (async) - returns simple stats for compatibility
+// Typo tolerant subject search by trigram word similarity, or regex search with `regex` (async)
 #[tauri::command]
-async fn get_database_stats(state: State<'_, DatabaseState>) -> Result<serde_json::Value, String> {
+async fn fuzzy_search_subjects(
+    state: State<'_, DatabaseState>,
+    query: String,
+    min_similarity: Option<f32>,
+    limit: Option<usize>,
+    list_id: Option<i32>,
+    regex: Option<bool>
+) -> Result<Vec<database_api::FuzzySubjectMatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    // Get enhanced stats and convert to simple format for compatibility
-    match database_api::get_enhanced_stats(db_manager).await {
-        Ok(stats) => {
-            let simple_stats = serde_json::json!({
-                "total_authors": stats.total_authors,
-                "total_patches": stats.total_patches,
-                "total_emails": stats.total_emails,
-                "unique_authors": stats.total_authors,
-                "unique_threads": 0  // Not tracked in new schema
-            });
-            Ok(simple_stats)
-        },
-        Err(e) => Err(format!("Failed to get database stats: {}", e)),
+    match database_api::fuzzy_search_subjects(db_manager, &query, min_similarity, limit, list_id, regex.unwrap_or(false)).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to fuzzy search subjects: {}", e)),
     }
 }
 
-// Reset database (drop all tables) (async)
+// Search syzbot crash reports by title (async)
 #[tauri::command]
-async fn reset_database(state: State<'_, DatabaseState>) -> Result<String, String> {
+async fn search_syzbot_reports(
+    state: State<'_, DatabaseState>,
+    query: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::SyzbotReportSummary>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match db_manager.reset_database().await {
-        Ok(message) => Ok(message),
-        Err(e) => Err(format!("Database reset failed: {}", e)),
+    match database_api::search_syzbot_reports(db_manager, query, limit, list_id).await {
+        Ok(reports) => Ok(reports),
+        Err(e) => Err(format!("Failed to search syzbot reports: {}", e)),
     }
 }
 
-// Get all authors with their emails (async)
+// List stable tree notifications (async)
 #[tauri::command]
-async fn get_authors(state: State<'_, DatabaseState>) -> Result<Vec<database_api::AuthorInfo>, String> {
+async fn search_stable_backports(
+    state: State<'_, DatabaseState>,
+    version: Option<String>,
+    kind: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::StableBackportSummary>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::get_authors_with_emails(db_manager).await {
-        Ok(authors) => Ok(authors),
-        Err(e) => Err(format!("Failed to get authors: {}", e)),
+    match database_api::search_stable_backports(db_manager, version, kind, limit, list_id).await {
+        Ok(backports) => Ok(backports),
+        Err(e) => Err(format!("Failed to search stable backports: {}", e)),
     }
 }
 
-// Get enhanced database statistics (async)
+// Get the stable versions that picked up a patch (async)
 #[tauri::command]
-async fn get_enhanced_database_stats(state: State<'_, DatabaseState>) -> Result<database_api::DatabaseStats, String> {
+async fn get_patch_stable_status(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<database_api::PatchStableStatus, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::get_enhanced_stats(db_manager).await {
-        Ok(stats) => Ok(stats),
-        Err(e) => Err(format!("Failed to get enhanced stats: {}", e)),
+    match database_api::get_patch_stable_status(db_manager, patch_id).await {
+        Ok(status) => Ok(status),
+        Err(e) => Err(format!("Failed to get stable status: {}", e)),
     }
 }
 
-// Get patches by author (async)
+// List `git request-pull` emails (async)
 #[tauri::command]
-async fn get_patches_by_author(
+async fn get_pull_requests(
     state: State<'_, DatabaseState>,
-    author_id: i64
-) -> Result<Vec<Patch>, String> {
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::PullRequestSummary>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match db_manager.get_patches_by_author(author_id).await {
-        Ok(patches) => Ok(patches),
-        Err(e) => Err(format!("Failed to get patches: {}", e)),
+    match database_api::get_pull_requests(db_manager, limit, list_id).await {
+        Ok(pull_requests) => Ok(pull_requests),
+        Err(e) => Err(format!("Failed to get pull requests: {}", e)),
     }
 }
 
-// Threading commands
-
-/// Build thread relationships for all patches
+// List unmerged threads whose pings are still unanswered (async)
 #[tauri::command]
-async fn build_threads(state: State<'_, DatabaseState>) -> Result<database::ThreadBuildStats, String> {
+async fn get_stalled_threads_with_pings(
+    state: State<'_, DatabaseState>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::StalledThread>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match db_manager.build_thread_relationships().await {
-        Ok(stats) => Ok(stats),
-        Err(e) => Err(format!("Failed to build threads: {}", e)),
+    match database_api::get_stalled_threads_with_pings(db_manager, limit, list_id).await {
+        Ok(threads) => Ok(threads),
+        Err(e) => Err(format!("Failed to get stalled threads: {}", e)),
     }
 }
 
-/// Get all threads (paginated with sorting and filtering)
+// Find patches where an address was in To or Cc (async)
 #[tauri::command]
-async fn get_threads(
+async fn search_patches_by_recipient(
     state: State<'_, DatabaseState>,
+    email: String,
+    kind: Option<String>,
     limit: Option<usize>,
-    offset: Option<usize>,
-    sort_by: Option<String>,
-    merge_filter: Option<String>
-) -> Result<Vec<database_api::ThreadSummary>, String> {
+    list_id: Option<i32>
+) -> Result<Vec<database_api::RecipientPatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::get_all_threads(db_manager, limit, offset, sort_by, merge_filter).await {
-        Ok(threads) => Ok(threads),
-        Err(e) => Err(format!("Failed to get threads: {}", e)),
+    match database_api::find_patches_by_recipient(db_manager, &email, kind, limit, list_id).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to search by recipient: {}", e)),
     }
 }
 
-/// Get full thread tree by thread ID
+// Find patches an author was in To or Cc of, at any of their addresses (async)
 #[tauri::command]
-async fn get_thread_tree(
+async fn get_patches_cc_author(
     state: State<'_, DatabaseState>,
-    thread_id: i64
-) -> Result<database_api::ThreadTree, String> {
+    author_id: i64,
+    kind: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::RecipientPatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::get_thread_tree(db_manager, thread_id).await {
-        Ok(tree) => Ok(tree),
-        Err(e) => Err(format!("Failed to get thread tree: {}", e)),
+    match database_api::get_patches_cc_author(db_manager, author_id, kind, limit, list_id).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to get patches the author was copied on: {}", e)),
     }
 }
 
-/// Find thread for a specific patch
+// Find patches an address gave a trailer to, Reviewed-by unless another tag is given (async)
 #[tauri::command]
-async fn get_thread_for_patch(
+async fn get_patches_reviewed_by(
     state: State<'_, DatabaseState>,
-    patch_id: i64
-) -> Result<Option<database_api::ThreadTree>, String> {
+    email: String,
+    tag: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::TrailerPatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::get_thread_for_patch(db_manager, patch_id).await {
-        Ok(thread) => Ok(thread),
-        Err(e) => Err(format!("Failed to find thread for patch: {}", e)),
+    match database_api::get_patches_reviewed_by(db_manager, &email, tag, limit, list_id).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to get reviewed patches: {}", e)),
     }
 }
 
-/// Search threads by keyword
+// Per-person ack/review/test trailer counts for a thread (async)
 #[tauri::command]
-async fn search_threads(
+async fn get_thread_trailer_rollup(
     state: State<'_, DatabaseState>,
-    keyword: String,
-    limit: Option<usize>
-) -> Result<Vec<database_api::ThreadSummary>, String> {
+    thread_id: i64
+) -> Result<Vec<database_api::ThreadTrailerRollup>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::search_threads(db_manager, &keyword, limit).await {
-        Ok(threads) => Ok(threads),
-        Err(e) => Err(format!("Failed to search threads: {}", e)),
+    match database_api::get_thread_trailer_rollup(db_manager, thread_id).await {
+        Ok(rollup) => Ok(rollup),
+        Err(e) => Err(format!("Failed to get thread trailer rollup: {}", e)),
     }
 }
 
-/// Get full patch body with diff
+// Find patches whose diff touches a file or directory (async)
 #[tauri::command]
-async fn get_patch_body(
+async fn get_patches_touching_path(
     state: State<'_, DatabaseState>,
-    patch_id: i64
-) -> Result<Option<String>, String> {
+    path: String,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::PathPatch>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
 
-    match database_api::get_patch_body(db_manager, patch_id).await {
-        Ok(body) => Ok(body),
-        Err(e) => Err(format!("Failed to get patch body: {}", e)),
+    match database_api::get_patches_touching_path(db_manager, &path, limit, list_id).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to get patches touching path: {}", e)),
     }
 }
 
-/// Reprocess all patches to identify and mark merge notifications
+// Most patched files, optionally below a directory (async)
 #[tauri::command]
-async fn reprocess_merge_notifications(state: State<'_, DatabaseState>) -> Result<database::merges::ReprocessResult, String> {
+async fn get_file_activity(
+    state: State<'_, DatabaseState>,
+    path_prefix: Option<String>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::FileActivity>, String> {
     let mut manager_guard = state.manager.lock().await;
     let db_manager = manager_guard.as_mut()
         .ok_or("Not connected to database")?;
-    
-    db_manager.ensure_connected().await
-        .map_err(|e| format!("Database connection error: {}", e))?;
-    
-    let pool = db_manager.get_pool()
-        .map_err(|e| format!("Failed to get pool: {}", e))?;
-    
-    match database::merges::reprocess_merge_notifications(pool).await {
-        Ok(result) => Ok(result),
-        Err(e) => Err(format!("Failed to reprocess merge notifications: {}", e)),
+
+    match database_api::get_file_activity(db_manager, path_prefix, limit, list_id).await {
+        Ok(activity) => Ok(activity),
+        Err(e) => Err(format!("Failed to get file activity: {}", e)),
     }
 }
 
-/// Get current git configuration
+// Get a patch series with its patches in order (async)
 #[tauri::command]
-fn get_git_config() -> git_config::GitConfig {
-    git_config::GitConfig::load()
+async fn get_series(
+    state: State<'_, DatabaseState>,
+    series_id: i64
+) -> Result<Option<database_api::SeriesDetail>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_series(db_manager, series_id).await {
+        Ok(series) => Ok(series),
+        Err(e) => Err(format!("Failed to get series: {}", e)),
+    }
 }
 
-/// Save git configuration
+// Get the version history (v1, v2, ...) of a patch (async)
 #[tauri::command]
-fn save_git_config(config: git_config::GitConfig) -> Result<(), String> {
-    config.save()
+async fn get_patch_versions(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Vec<database_api::PatchVersion>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_versions(db_manager, patch_id).await {
+        Ok(versions) => Ok(versions),
+        Err(e) => Err(format!("Failed to get patch versions: {}", e)),
+    }
 }
 
-/// Update git configuration (save and return updated config)
+// Get the threads of every version (v1, v2, ...) of a thread's patch or series (async)
 #[tauri::command]
-fn update_git_config(repo_path: String, clone_url: String) -> Result<git_config::GitConfig, String> {
-    let config = git_config::GitConfig {
-        repo_path,
-        clone_url,
-    };
-    config.save()?;
-    Ok(config)
+async fn get_thread_family(
+    state: State<'_, DatabaseState>,
+    thread_id: i64
+) -> Result<Vec<database_api::FamilyThread>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_thread_family(db_manager, thread_id).await {
+        Ok(threads) => Ok(threads),
+        Err(e) => Err(format!("Failed to get thread family: {}", e)),
+    }
 }
 
-/// Check if git repository exists at configured path
+// Get the lists and epochs that carried a message (async)
 #[tauri::command]
-fn check_git_repo_exists(path: Option<String>) -> bool {
-    let config = git_config::GitConfig::load();
-    let check_path = path.unwrap_or(config.repo_path);
-    git_parser::check_repository_exists(&check_path)
+async fn get_message_sources(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Vec<database_api::MessageSource>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_message_sources(db_manager, patch_id).await {
+        Ok(sources) => Ok(sources),
+        Err(e) => Err(format!("Failed to get message sources: {}", e)),
+    }
 }
 
-/// Clone git repository
+// Get the To/Cc recipients of a patch (async)
 #[tauri::command]
-async fn clone_git_repository(
-    clone_url: String,
-    target_path: String,
-    bare: bool
-) -> Result<git_parser::GitSyncResult, String> {
-    match git_parser::clone_repository(&clone_url, &target_path, bare) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(format!("Failed to clone repository: {}", e)),
+async fn get_patch_recipients(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Vec<mail_parser::Recipient>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_recipients(db_manager, patch_id).await {
+        Ok(recipients) => Ok(recipients),
+        Err(e) => Err(format!("Failed to get patch recipients: {}", e)),
     }
 }
 
-/// Sync the git repository by running git fetch
+// Get the distribution of patch sizes from stored diffstats (async)
 #[tauri::command]
-fn sync_git_repository(repo_path: Option<String>) -> Result<git_parser::GitSyncResult, String> {
-    match git_parser::sync_repository(repo_path.as_deref()) {
-        Ok(result) => Ok(result),
-        Err(e) => Err(format!("Failed to sync repository: {}", e)),
+async fn get_patch_size_distribution(
+    state: State<'_, DatabaseState>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::PatchSizeBucket>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_size_distribution(db_manager, list_id).await {
+        Ok(buckets) => Ok(buckets),
+        Err(e) => Err(format!("Failed to get patch size distribution: {}", e)),
     }
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
+// Get disk usage of the git archive and database, with a growth estimate (async)
+#[tauri::command]
+async fn get_disk_usage(state: State<'_, DatabaseState>) -> Result<database_api::DiskUsageReport, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_disk_usage_report(db_manager).await {
+        Ok(report) => Ok(report),
+        Err(e) => Err(format!("Failed to get disk usage: {}", e)),
+    }
+}
+
+// Table statistics, index bloat and long-running queries (async)
+#[tauri::command]
+async fn get_db_health(
+    state: State<'_, DatabaseState>,
+    long_query_secs: Option<u32>
+) -> Result<database_api::DatabaseHealth, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_db_health(db_manager, long_query_secs).await {
+        Ok(health) => Ok(health),
+        Err(e) => Err(format!("Failed to get database health: {}", e)),
+    }
+}
+
+// ANALYZE (optionally VACUUM) the main tables (async)
+#[tauri::command]
+async fn run_maintenance(
+    state: State<'_, DatabaseState>,
+    vacuum: Option<bool>
+) -> Result<database::MaintenanceResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.run_maintenance(vacuum.unwrap_or(false)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Maintenance failed: {}", e)),
+    }
+}
+
+// Report query patterns lacking an index, optionally creating the recommended ones (async)
+#[tauri::command]
+async fn advise_indexes(
+    state: State<'_, DatabaseState>,
+    create: Option<bool>
+) -> Result<database::IndexAdvisorReport, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.advise_indexes(create.unwrap_or(false)).await {
+        Ok(report) => Ok(report),
+        Err(e) => Err(format!("Index advisor failed: {}", e)),
+    }
+}
+
+// Database setup command (async)
+#[tauri::command]
+async fn setup_database(state: State<'_, DatabaseState>) -> Result<DatabaseSetupResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.setup_database().await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Database setup failed: {}", e)),
+    }
+}
+
+// Apply pending schema migrations and report the schema version (async)
+#[tauri::command]
+async fn migrate_database(state: State<'_, DatabaseState>) -> Result<database::MigrationStatus, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.migrate_database().await {
+        Ok(status) => Ok(status),
+        Err(e) => Err(format!("Database migration failed: {}", e)),
+    }
+}
+
+// Database population command with progress callback (async)
+#[tauri::command]
+async fn populate_database(
+    state: State<'_, DatabaseState>,
+    limit: Option<usize>,
+    window: tauri::Window
+) -> Result<DatabasePopulationResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    // Use Tauri event system for progress tracking
+    let progress_fn = move |current: u32, total: u32, commit_hash: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "commit_hash": commit_hash
+        });
+        // Emit event to the window
+        let _ = window.emit("populate-progress", payload);
+    };
+
+    match db_manager.populate_database(limit, Some(progress_fn)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Database population failed: {}", e)),
+    }
+}
+
+/// Ask a running population or import to stop after its current batch
+/// The running command then returns its partial counts with `cancelled` set
+#[tauri::command]
+async fn cancel_population(state: State<'_, DatabaseState>) -> Result<String, String> {
+    state.population_cancel.store(true, Ordering::Relaxed);
+    Ok("Cancellation requested".to_string())
+}
+
+// Re-parse emails quarantined by earlier population runs (async)
+#[tauri::command]
+async fn retry_failed_parses(state: State<'_, DatabaseState>) -> Result<database::ParseRetryResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.retry_failed_parses().await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to retry quarantined emails: {}", e)),
+    }
+}
+
+// Compress the plain text bodies already in the database (async)
+#[tauri::command]
+async fn compress_patch_bodies(state: State<'_, DatabaseState>) -> Result<database::BodyCompressionResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.compress_existing_bodies().await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to compress bodies: {}", e)),
+    }
+}
+
+// Compare the database schema version with the one this build expects (async)
+#[tauri::command]
+async fn check_schema_version(state: State<'_, DatabaseState>) -> Result<database::SchemaVersion, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.check_schema_version().await {
+        Ok(version) => Ok(version),
+        Err(e) => Err(format!("Failed to check schema version: {}", e)),
+    }
+}
+
+// Fold author identities listed in a .mailmap file, or a kernel checkout's mailmap (async)
+#[tauri::command]
+async fn import_mailmap(
+    state: State<'_, DatabaseState>,
+    path: String
+) -> Result<database::MailmapImportResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.import_mailmap(&path).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to import mailmap: {}", e)),
+    }
+}
+
+// Merge one author record into another, re-pointing its addresses and patches; later imports under the merged name follow (async)
+#[tauri::command]
+async fn merge_authors(
+    state: State<'_, DatabaseState>,
+    target_author_id: i64,
+    source_author_id: i64
+) -> Result<database::Author, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.merge_authors(target_author_id, source_author_id).await {
+        Ok(author) => Ok(author),
+        Err(e) => Err(format!("Failed to merge authors: {}", e)),
+    }
+}
+
+// Move some addresses of a wrongly merged author, with their patches, to another name (async)
+#[tauri::command]
+async fn split_author(
+    state: State<'_, DatabaseState>,
+    author_id: i64,
+    emails: Vec<String>,
+    name: String
+) -> Result<database::Author, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.split_author(author_id, &emails, &name).await {
+        Ok(author) => Ok(author),
+        Err(e) => Err(format!("Failed to split author: {}", e)),
+    }
+}
+
+// Set the canonical display name of an author (async)
+#[tauri::command]
+async fn set_author_display_name(
+    state: State<'_, DatabaseState>,
+    author_id: i64,
+    display_name: String
+) -> Result<database::Author, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.set_author_display_name(author_id, &display_name).await {
+        Ok(author) => Ok(author),
+        Err(e) => Err(format!("Failed to set display name: {}", e)),
+    }
+}
+
+// Report authors that are likely the same person, optionally merging the high-confidence pairs (async)
+#[tauri::command]
+async fn audit_duplicate_authors(
+    state: State<'_, DatabaseState>,
+    auto_merge: Option<bool>
+) -> Result<database::DuplicateAuthorReport, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.find_duplicate_authors(auto_merge.unwrap_or(false)).await {
+        Ok(report) => Ok(report),
+        Err(e) => Err(format!("Failed to audit duplicate authors: {}", e)),
+    }
+}
+
+// Back up schema and data into a single pg_dump archive (async)
+#[tauri::command]
+async fn export_database(
+    state: State<'_, DatabaseState>,
+    path: String
+) -> Result<database::DatabaseExportResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.export_database(&path).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to export database: {}", e)),
+    }
+}
+
+// Restore an archive made by export_database into an empty database (async)
+#[tauri::command]
+async fn import_database(
+    state: State<'_, DatabaseState>,
+    path: String
+) -> Result<database::DatabaseImportResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.import_database(&path).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to import database: {}", e)),
+    }
+}
+
+// Drop bodies older than the given (or configured) number of years, keeping metadata (async)
+// Prunes one list when list_id is given, otherwise every list
+#[tauri::command]
+async fn prune_bodies(
+    state: State<'_, DatabaseState>,
+    list_id: Option<i32>,
+    older_than_years: Option<u32>
+) -> Result<database::BodyPruneResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.prune_bodies(list_id, older_than_years).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to prune bodies: {}", e)),
+    }
+}
+
+// Incremental population of an explicit commit list, e.g. the new commits from fetch_updates (async)
+#[tauri::command]
+async fn populate_new_commits(
+    state: State<'_, DatabaseState>,
+    commits: Vec<git_parser::EpochCommit>,
+    window: tauri::Window
+) -> Result<DatabasePopulationResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    let progress_fn = move |current: u32, total: u32, commit_hash: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "commit_hash": commit_hash
+        });
+        let _ = window.emit("populate-progress", payload);
+    };
+
+    match db_manager.populate_commits(commits, Some(progress_fn)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Incremental population failed: {}", e)),
+    }
+}
+
+// Populate the database with commits from a time window, e.g. the last 6 months (async)
+// Dates are RFC 3339 strings
+#[tauri::command]
+async fn populate_date_range(
+    state: State<'_, DatabaseState>,
+    start: String,
+    end: String,
+    window: tauri::Window
+) -> Result<DatabasePopulationResult, String> {
+    let start = chrono::DateTime::parse_from_rfc3339(&start)
+        .map_err(|e| format!("Invalid start date '{}': {}", start, e))?
+        .with_timezone(&chrono::Utc);
+    let end = chrono::DateTime::parse_from_rfc3339(&end)
+        .map_err(|e| format!("Invalid end date '{}': {}", end, e))?
+        .with_timezone(&chrono::Utc);
+
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    let progress_fn = move |current: u32, total: u32, commit_hash: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "commit_hash": commit_hash
+        });
+        let _ = window.emit("populate-progress", payload);
+    };
+
+    match db_manager.populate_date_range(start, end, Some(progress_fn)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Date range population failed: {}", e)),
+    }
+}
+
+// Populate the database from one of the configured mailing lists (async)
+#[tauri::command]
+async fn populate_mailing_list(
+    state: State<'_, DatabaseState>,
+    list_name: String,
+    limit: Option<usize>,
+    window: tauri::Window
+) -> Result<DatabasePopulationResult, String> {
+    let list = git_config::GitConfig::load()
+        .find_list(&list_name)
+        .ok_or(format!("Mailing list '{}' is not configured", list_name))?;
+
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    let progress_fn = move |current: u32, total: u32, commit_hash: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "commit_hash": commit_hash
+        });
+        let _ = window.emit("populate-progress", payload);
+    };
+
+    match db_manager.populate_list(&list, limit, Some(progress_fn)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Database population failed: {}", e)),
+    }
+}
+
+// Import a standard mbox file into the database (async)
+// Emits `mbox-import-progress` events with the current phase
+#[tauri::command]
+async fn import_mbox(
+    state: State<'_, DatabaseState>,
+    path: String,
+    list_name: Option<String>,
+    window: tauri::Window
+) -> Result<mbox_importer::MailImportResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    let progress_fn = move |current: u32, total: u32, phase: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "phase": phase
+        });
+        let _ = window.emit("mbox-import-progress", payload);
+    };
+
+    match mbox_importer::import_mbox(db_manager, &path, list_name, Some(progress_fn)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to import mbox: {}", e)),
+    }
+}
+
+// Import a Maildir directory (one message per file) into the database (async)
+// Emits `maildir-import-progress` events with the current phase
+#[tauri::command]
+async fn import_maildir(
+    state: State<'_, DatabaseState>,
+    path: String,
+    list_name: Option<String>,
+    window: tauri::Window
+) -> Result<mbox_importer::MailImportResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    let progress_fn = move |current: u32, total: u32, phase: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "phase": phase
+        });
+        let _ = window.emit("maildir-import-progress", payload);
+    };
+
+    match mbox_importer::import_maildir(db_manager, &path, list_name, Some(progress_fn)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to import Maildir: {}", e)),
+    }
+}
+
+// Get the mailing lists stored in the database (async)
+#[tauri::command]
+async fn get_mailing_lists(state: State<'_, DatabaseState>) -> Result<Vec<database::MailingList>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.get_mailing_lists().await {
+        Ok(lists) => Ok(lists),
+        Err(e) => Err(format!("Failed to get mailing lists: {}", e)),
+    }
+}
+
+// Test database connection (async)
+#[tauri::command]
+async fn test_database_connection(state: State<'_, DatabaseState>) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.test_connection().await {
+        Ok(success) => Ok(success),
+        Err(e) => Err(format!("Database connection test failed: {}", e)),
+    }
+}
+
+// Get database statistics (async) - returns simple stats for compatibility
+#[tauri::command]
+async fn get_database_stats(state: State<'_, DatabaseState>) -> Result<serde_json::Value, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    // Get enhanced stats and convert to simple format for compatibility
+    match database_api::get_enhanced_stats(db_manager).await {
+        Ok(stats) => {
+            let simple_stats = serde_json::json!({
+                "total_authors": stats.total_authors,
+                "total_patches": stats.total_patches,
+                "total_emails": stats.total_emails,
+                "unique_authors": stats.total_authors,
+                "unique_threads": 0  // Not tracked in new schema
+            });
+            Ok(simple_stats)
+        },
+        Err(e) => Err(format!("Failed to get database stats: {}", e)),
+    }
+}
+
+// Reset database (drop all tables) (async)
+#[tauri::command]
+async fn reset_database(state: State<'_, DatabaseState>) -> Result<String, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.reset_database().await {
+        Ok(message) => Ok(message),
+        Err(e) => Err(format!("Database reset failed: {}", e)),
+    }
+}
+
+// Get authors with their emails, a page at a time when `limit` is given (async)
+#[tauri::command]
+async fn get_authors(
+    state: State<'_, DatabaseState>,
+    limit: Option<usize>,
+    offset: Option<usize>
+) -> Result<database::Page<database_api::AuthorInfo>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_authors_with_emails(db_manager, limit, offset).await {
+        Ok(authors) => Ok(authors),
+        Err(e) => Err(format!("Failed to get authors: {}", e)),
+    }
+}
+
+// Get enhanced database statistics (async)
+#[tauri::command]
+async fn get_enhanced_database_stats(state: State<'_, DatabaseState>) -> Result<database_api::DatabaseStats, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_enhanced_stats(db_manager).await {
+        Ok(stats) => Ok(stats),
+        Err(e) => Err(format!("Failed to get enhanced stats: {}", e)),
+    }
+}
+
+// Recompute the statistics views behind the dashboard (async)
+#[tauri::command]
+async fn refresh_stats(state: State<'_, DatabaseState>) -> Result<database::StatsRefreshResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.refresh_stats().await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to refresh statistics: {}", e)),
+    }
+}
+
+// Get patches by author, a page at a time when `limit` is given (async)
+// `after`/`before` are RFC 3339 timestamps or plain dates; `before` is exclusive
+#[tauri::command]
+async fn get_patches_by_author(
+    state: State<'_, DatabaseState>,
+    author_id: i64,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    after: Option<String>,
+    before: Option<String>
+) -> Result<database::Page<Patch>, String> {
+    let dates = database::DateRange::parse(after.as_deref(), before.as_deref())?;
+
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.get_patches_by_author(author_id, limit, offset, dates).await {
+        Ok(patches) => Ok(patches),
+        Err(e) => Err(format!("Failed to get patches: {}", e)),
+    }
+}
+
+// Threading commands
+
+/// Build thread relationships for all patches
+/// Emits `thread-build-progress` events with the current phase and interim counts
+/// `options` restrict the subject fallback (defaults when omitted)
+#[tauri::command]
+async fn build_threads(
+    state: State<'_, DatabaseState>,
+    options: Option<database::ThreadingOptions>,
+    window: tauri::Window
+) -> Result<database::ThreadBuildStats, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    let progress_fn = move |progress: database::ThreadBuildProgress| {
+        let _ = window.emit("thread-build-progress", progress);
+    };
+
+    match db_manager.build_thread_relationships_with_progress(&options.unwrap_or_default(), Some(progress_fn)).await {
+        Ok(stats) => Ok(stats),
+        Err(e) => Err(format!("Failed to build threads: {}", e)),
+    }
+}
+
+/// Re-examine orphaned messages and attach them to parents stored since
+/// `options` are the threading options of the rebuild (defaults when omitted)
+#[tauri::command]
+async fn reprocess_orphans(
+    state: State<'_, DatabaseState>,
+    options: Option<database::ThreadingOptions>
+) -> Result<database::OrphanReprocessResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.reprocess_orphans(&options.unwrap_or_default()).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to reprocess orphans: {}", e)),
+    }
+}
+
+/// Regroup every series patch into the series table
+#[tauri::command]
+async fn rebuild_series(state: State<'_, DatabaseState>) -> Result<database::SeriesBuildStats, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.rebuild_series().await {
+        Ok(stats) => Ok(stats),
+        Err(e) => Err(format!("Failed to rebuild series: {}", e)),
+    }
+}
+
+/// Get all threads (paginated with sorting and filtering)
+/// In the default order, pass the `next_cursor` of the previous page as `cursor` to fetch the next one
+/// `after`/`before` (RFC 3339 timestamps or plain dates) keep threads active within that window
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn get_threads(
+    state: State<'_, DatabaseState>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    cursor: Option<database_api::ThreadCursor>,
+    sort_by: Option<String>,
+    merge_filter: Option<String>,
+    list_id: Option<i32>,
+    label_id: Option<i32>,
+    after: Option<String>,
+    before: Option<String>
+) -> Result<database_api::ThreadPage, String> {
+    let dates = database::DateRange::parse(after.as_deref(), before.as_deref())?;
+
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_all_threads(db_manager, limit, offset, cursor, sort_by, merge_filter, list_id, label_id, dates).await {
+        Ok(threads) => Ok(threads),
+        Err(e) => Err(format!("Failed to get threads: {}", e)),
+    }
+}
+
+/// Get full thread tree by thread ID
+/// `cleaning` selects which reply cleanup stages run (defaults when omitted)
+#[tauri::command]
+async fn get_thread_tree(
+    state: State<'_, DatabaseState>,
+    thread_id: i64,
+    cleaning: Option<database_api::ReplyCleaning>
+) -> Result<database_api::ThreadTree, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_thread_tree(db_manager, thread_id, &cleaning.unwrap_or_default()).await {
+        Ok(tree) => Ok(tree),
+        Err(e) => Err(format!("Failed to get thread tree: {}", e)),
+    }
+}
+
+/// Find thread for a specific patch
+#[tauri::command]
+async fn get_thread_for_patch(
+    state: State<'_, DatabaseState>,
+    patch_id: i64,
+    cleaning: Option<database_api::ReplyCleaning>
+) -> Result<Option<database_api::ThreadTree>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_thread_for_patch(db_manager, patch_id, &cleaning.unwrap_or_default()).await {
+        Ok(thread) => Ok(thread),
+        Err(e) => Err(format!("Failed to find thread for patch: {}", e)),
+    }
+}
+
+/// Resolve a Message-ID or lore URL to a patch and its thread (database first, then the archive's msgmap if present)
+#[tauri::command]
+async fn get_patch_by_message_id(
+    state: State<'_, DatabaseState>,
+    message_id: String
+) -> Result<Option<database_api::MessageLookup>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_by_message_id(db_manager, &message_id).await {
+        Ok(lookup) => Ok(lookup),
+        Err(e) => Err(format!("Failed to look up message: {}", e)),
+    }
+}
+
+/// Find patches carrying a "Fixes:" tag for the given commit hash
+#[tauri::command]
+async fn get_patches_fixing_commit(
+    state: State<'_, DatabaseState>,
+    commit_hash: String
+) -> Result<Vec<database_api::FixesLink>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::find_patches_fixing_commit(db_manager, &commit_hash).await {
+        Ok(links) => Ok(links),
+        Err(e) => Err(format!("Failed to find fixing patches: {}", e)),
+    }
+}
+
+/// Find messages referring to a commit hash (Fixes: tags, reverts and mentions in the text)
+#[tauri::command]
+async fn get_patches_referencing_commit(
+    state: State<'_, DatabaseState>,
+    commit_hash: String,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::CommitReference>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::find_patches_referencing_commit(db_manager, &commit_hash, limit, list_id).await {
+        Ok(references) => Ok(references),
+        Err(e) => Err(format!("Failed to find patches referencing commit: {}", e)),
+    }
+}
+
+/// Find later patches that fix the given patch (matched on the subject quoted in "Fixes:")
+#[tauri::command]
+async fn get_fixes_for_patch(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Vec<database_api::FixesLink>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::find_fixes_for_patch(db_manager, patch_id).await {
+        Ok(links) => Ok(links),
+        Err(e) => Err(format!("Failed to find fixes for patch: {}", e)),
+    }
+}
+
+/// Get the patch a revert reverts and the patches reverting a patch
+#[tauri::command]
+async fn get_patch_reverts(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<database_api::PatchReverts, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_reverts(db_manager, patch_id).await {
+        Ok(reverts) => Ok(reverts),
+        Err(e) => Err(format!("Failed to get reverts for patch: {}", e)),
+    }
+}
+
+/// Get the Link:/lore cross-references of a patch, resolved against the database
+#[tauri::command]
+async fn get_patch_cross_references(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<database_api::PatchCrossReferences, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_cross_references(db_manager, patch_id).await {
+        Ok(references) => Ok(references),
+        Err(e) => Err(format!("Failed to get cross-references: {}", e)),
+    }
+}
+
+/// Search threads by keyword, or by regex over the subject when `regex` is set
+/// `after`/`before` keep threads active within that window
+#[tauri::command]
+async fn search_threads(
+    state: State<'_, DatabaseState>,
+    keyword: String,
+    limit: Option<usize>,
+    list_id: Option<i32>,
+    regex: Option<bool>,
+    after: Option<String>,
+    before: Option<String>
+) -> Result<Vec<database_api::ThreadSummary>, String> {
+    let dates = database::DateRange::parse(after.as_deref(), before.as_deref())?;
+
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::search_threads(db_manager, &keyword, limit, list_id, regex.unwrap_or(false), dates).await {
+        Ok(threads) => Ok(threads),
+        Err(e) => Err(format!("Failed to search threads: {}", e)),
+    }
+}
+
+/// Star a thread; false when it was already starred
+#[tauri::command]
+async fn star_thread(
+    state: State<'_, DatabaseState>,
+    thread_id: i64
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.star_thread(thread_id).await {
+        Ok(starred) => Ok(starred),
+        Err(e) => Err(format!("Failed to star thread: {}", e)),
+    }
+}
+
+/// Unstar a thread; false when it was not starred
+#[tauri::command]
+async fn unstar_thread(
+    state: State<'_, DatabaseState>,
+    thread_id: i64
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.unstar_thread(thread_id).await {
+        Ok(removed) => Ok(removed),
+        Err(e) => Err(format!("Failed to unstar thread: {}", e)),
+    }
+}
+
+/// Get starred threads, most recently starred first
+#[tauri::command]
+async fn get_starred_threads(
+    state: State<'_, DatabaseState>,
+    limit: Option<usize>,
+    list_id: Option<i32>
+) -> Result<Vec<database_api::ThreadSummary>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_starred_threads(db_manager, limit, list_id).await {
+        Ok(threads) => Ok(threads),
+        Err(e) => Err(format!("Failed to get starred threads: {}", e)),
+    }
+}
+
+/// Set the review status of a thread by hand
+#[tauri::command]
+async fn set_thread_review_status(
+    state: State<'_, DatabaseState>,
+    thread_id: i64,
+    status: database::ReviewStatus
+) -> Result<(), String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.set_thread_review_status(thread_id, status).await {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to set review status: {}", e)),
+    }
+}
+
+/// Create a label for threads and patches
+#[tauri::command]
+async fn create_label(
+    state: State<'_, DatabaseState>,
+    name: String,
+    color: Option<String>
+) -> Result<database::Label, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.create_label(&name, color.as_deref()).await {
+        Ok(label) => Ok(label),
+        Err(e) => Err(format!("Failed to create label: {}", e)),
+    }
+}
+
+/// Get all labels
+#[tauri::command]
+async fn get_labels(state: State<'_, DatabaseState>) -> Result<Vec<database::Label>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.get_labels().await {
+        Ok(labels) => Ok(labels),
+        Err(e) => Err(format!("Failed to get labels: {}", e)),
+    }
+}
+
+/// Delete a label; false when there was no such label
+#[tauri::command]
+async fn delete_label(
+    state: State<'_, DatabaseState>,
+    label_id: i32
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.delete_label(label_id).await {
+        Ok(deleted) => Ok(deleted),
+        Err(e) => Err(format!("Failed to delete label: {}", e)),
+    }
+}
+
+/// Attach a label to a thread; false when it already had it
+#[tauri::command]
+async fn add_thread_label(
+    state: State<'_, DatabaseState>,
+    thread_id: i64,
+    label_id: i32
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.add_thread_label(thread_id, label_id).await {
+        Ok(added) => Ok(added),
+        Err(e) => Err(format!("Failed to label thread: {}", e)),
+    }
+}
+
+/// Detach a label from a thread; false when it did not have it
+#[tauri::command]
+async fn remove_thread_label(
+    state: State<'_, DatabaseState>,
+    thread_id: i64,
+    label_id: i32
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.remove_thread_label(thread_id, label_id).await {
+        Ok(removed) => Ok(removed),
+        Err(e) => Err(format!("Failed to remove thread label: {}", e)),
+    }
+}
+
+/// Attach a label to a patch; false when it already had it
+#[tauri::command]
+async fn add_patch_label(
+    state: State<'_, DatabaseState>,
+    patch_id: i64,
+    label_id: i32
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.add_patch_label(patch_id, label_id).await {
+        Ok(added) => Ok(added),
+        Err(e) => Err(format!("Failed to label patch: {}", e)),
+    }
+}
+
+/// Detach a label from a patch; false when it did not have it
+#[tauri::command]
+async fn remove_patch_label(
+    state: State<'_, DatabaseState>,
+    patch_id: i64,
+    label_id: i32
+) -> Result<bool, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.remove_patch_label(patch_id, label_id).await {
+        Ok(removed) => Ok(removed),
+        Err(e) => Err(format!("Failed to remove patch label: {}", e)),
+    }
+}
+
+/// Mark messages read or unread
+#[tauri::command]
+async fn mark_patches_read(
+    state: State<'_, DatabaseState>,
+    patch_ids: Vec<i64>,
+    read: bool
+) -> Result<u64, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.mark_patches_read(&patch_ids, read).await {
+        Ok(updated) => Ok(updated),
+        Err(e) => Err(format!("Failed to update read state: {}", e)),
+    }
+}
+
+/// Mark a whole thread read or unread, returning its unread count
+#[tauri::command]
+async fn mark_thread_read(
+    state: State<'_, DatabaseState>,
+    thread_id: i64,
+    read: bool
+) -> Result<i64, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match db_manager.mark_thread_read(thread_id, read).await {
+        Ok(unread_count) => Ok(unread_count),
+        Err(e) => Err(format!("Failed to update thread read state: {}", e)),
+    }
+}
+
+/// Get full patch body with diff
+#[tauri::command]
+async fn get_patch_body(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Option<String>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_body(db_manager, patch_id).await {
+        Ok(body) => Ok(body),
+        Err(e) => Err(format!("Failed to get patch body: {}", e)),
+    }
+}
+
+/// Get a message body with its quoted blocks (line ranges, depth, attribution)
+#[tauri::command]
+async fn get_patch_body_with_quotes(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Option<database_api::QuotedBody>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_patch_body_with_quotes(db_manager, patch_id).await {
+        Ok(body) => Ok(body),
+        Err(e) => Err(format!("Failed to get patch body: {}", e)),
+    }
+}
+
+/// Get review comments on a patch, anchored to the diff hunks they quote
+#[tauri::command]
+async fn get_inline_comments(
+    state: State<'_, DatabaseState>,
+    patch_id: i64
+) -> Result<Vec<database_api::InlineComment>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+
+    match database_api::get_inline_comments(db_manager, patch_id).await {
+        Ok(comments) => Ok(comments),
+        Err(e) => Err(format!("Failed to get inline comments: {}", e)),
+    }
+}
+
+/// Reprocess all patches to identify and mark merge notifications
+#[tauri::command]
+async fn reprocess_merge_notifications(state: State<'_, DatabaseState>) -> Result<database::merges::ReprocessResult, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+    
+    db_manager.ensure_connected().await
+        .map_err(|e| format!("Database connection error: {}", e))?;
+    
+    let pool = db_manager.get_pool()
+        .map_err(|e| format!("Failed to get pool: {}", e))?;
+    
+    match database::merges::reprocess_merge_notifications(pool).await {
+        Ok(mut result) => {
+            // Newly found merges accept their threads
+            if let Err(e) = db_manager.apply_review_status_rules().await {
+                result.errors.push(format!("Failed to update review statuses: {}", e));
+            }
+            if let Err(e) = database::merges::update_patch_merge_status(pool).await {
+                result.errors.push(format!("Failed to update patch merge status: {}", e));
+            }
+            Ok(result)
+        }
+        Err(e) => Err(format!("Failed to reprocess merge notifications: {}", e)),
+    }
+}
+
+/// Check merged commit links against the configured kernel tree, storing full SHAs and release tags
+#[tauri::command]
+async fn verify_merge_commits(state: State<'_, DatabaseState>) -> Result<database::merges::MergeVerificationResult, String> {
+    let kernel_tree_path = git_config::GitConfig::load().kernel_tree_path
+        .filter(|path| !path.trim().is_empty())
+        .ok_or("Kernel tree path not configured")?;
+
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+    
+    db_manager.ensure_connected().await
+        .map_err(|e| format!("Database connection error: {}", e))?;
+    
+    let pool = db_manager.get_pool()
+        .map_err(|e| format!("Failed to get pool: {}", e))?;
+    
+    match database::merges::verify_merge_commits(pool, &kernel_tree_path).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to verify merge commits: {}", e)),
+    }
+}
+
+/// Get the verified commits of a merge notification
+#[tauri::command]
+async fn get_merge_commits(state: State<'_, DatabaseState>, patch_id: i64) -> Result<Vec<database::merges::MergeCommit>, String> {
+    let mut manager_guard = state.manager.lock().await;
+    let db_manager = manager_guard.as_mut()
+        .ok_or("Not connected to database")?;
+    
+    db_manager.ensure_connected().await
+        .map_err(|e| format!("Database connection error: {}", e))?;
+    
+    let pool = db_manager.get_pool()
+        .map_err(|e| format!("Failed to get pool: {}", e))?;
+    
+    match database::merges::get_merge_commits(pool, patch_id).await {
+        Ok(commits) => Ok(commits),
+        Err(e) => Err(format!("Failed to get merge commits: {}", e)),
+    }
+}
+
+/// Get current git configuration
+#[tauri::command]
+fn get_git_config() -> git_config::GitConfig {
+    git_config::GitConfig::load()
+}
+
+/// Save git configuration
+#[tauri::command]
+fn save_git_config(config: git_config::GitConfig) -> Result<(), String> {
+    config.save()
+}
+
+/// Replace the git configuration, persist it and return what was saved
+#[tauri::command]
+fn set_git_config(config: git_config::GitConfig) -> Result<git_config::GitConfig, String> {
+    save_git_config(config)?;
+    Ok(git_config::GitConfig::load())
+}
+
+/// Validate a git configuration (the saved one if none is given) without saving it
+#[tauri::command]
+fn validate_git_config(config: Option<git_config::GitConfig>) -> git_config::GitConfigValidation {
+    config.unwrap_or_else(git_config::GitConfig::load).validate()
+}
+
+/// Update git configuration (save and return updated config)
+#[tauri::command]
+fn update_git_config(repo_path: String, clone_url: String) -> Result<git_config::GitConfig, String> {
+    let config = git_config::GitConfig {
+        repo_path,
+        clone_url,
+        ..git_config::GitConfig::load()
+    };
+    config.save()?;
+    Ok(config)
+}
+
+/// Set (or clear) the local kernel tree merged commits are verified against
+#[tauri::command]
+fn set_kernel_tree_path(path: Option<String>) -> Result<git_config::GitConfig, String> {
+    let config = git_config::GitConfig {
+        kernel_tree_path: path.filter(|p| !p.trim().is_empty()),
+        ..git_config::GitConfig::load()
+    };
+    config.save()?;
+    Ok(config)
+}
+
+/// Add or update a mailing list archive in the git configuration
+#[tauri::command]
+fn add_mailing_list(name: String, repo_path: String, clone_url: String) -> Result<git_config::GitConfig, String> {
+    let mut config = git_config::GitConfig::load();
+    config.upsert_list(git_config::MailingListRepo {
+        name,
+        repo_path,
+        clone_url,
+    });
+    config.save()?;
+    Ok(config)
+}
+
+/// List saved repository profiles
+#[tauri::command]
+fn list_git_profiles() -> Vec<git_config::GitProfile> {
+    git_config::GitConfig::load().profiles
+}
+
+/// Add a repository profile, or point an existing one at another repository (its mailing lists are kept)
+#[tauri::command]
+fn add_git_profile(name: String, repo_path: String, clone_url: String) -> Result<git_config::GitConfig, String> {
+    let mut config = git_config::GitConfig::load();
+    config.upsert_profile(git_config::GitProfile {
+        name,
+        repo_path,
+        clone_url,
+        mailing_lists: Vec::new(),
+    });
+    config.save()?;
+    Ok(config)
+}
+
+/// Remove a repository profile
+#[tauri::command]
+fn remove_git_profile(name: String) -> Result<git_config::GitConfig, String> {
+    let mut config = git_config::GitConfig::load();
+    config.remove_profile(&name)?;
+    config.save()?;
+    Ok(config)
+}
+
+/// Switch the active repository profile
+#[tauri::command]
+fn switch_git_profile(name: String) -> Result<git_config::GitConfig, String> {
+    let mut config = git_config::GitConfig::load();
+    config.switch_profile(&name)?;
+    config.save()?;
+    // Cached blobs belong to the previous repository
+    git_parser::clear_email_cache();
+    Ok(config)
+}
+
+/// Change how many raw emails are kept in memory for thread views (defaults to EMAIL_CACHE_SIZE)
+#[tauri::command]
+fn set_email_cache_size(capacity: usize) -> Result<(), String> {
+    if capacity == 0 {
+        return Err("Email cache size must be at least 1".to_string());
+    }
+    git_parser::set_email_cache_capacity(capacity);
+    Ok(())
+}
+
+/// Check if git repository exists at configured path
+#[tauri::command]
+fn check_git_repo_exists(path: Option<String>) -> bool {
+    let config = git_config::GitConfig::load();
+    let check_path = path.unwrap_or(config.repo_path);
+    git_parser::check_repository_exists(&check_path)
+}
+
+/// Clone git repository
+#[tauri::command]
+async fn clone_git_repository(
+    clone_url: String,
+    target_path: String,
+    bare: bool
+) -> Result<git_parser::GitSyncResult, String> {
+    match git_parser::clone_repository(&clone_url, &target_path, bare) {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to clone repository: {}", e)),
+    }
+}
+
+/// Quick-start clone of the configured archive: only the newest epochs, optionally shallow
+#[tauri::command]
+async fn clone_recent_epochs(
+    target_path: String,
+    recent_epochs: Option<u32>,
+    depth: Option<u32>
+) -> Result<git_parser::GitSyncResult, String> {
+    let clone_url = git_config::GitConfig::load().clone_url;
+
+    let result = tokio::task::spawn_blocking(move || {
+        git_parser::clone_recent_epochs(&clone_url, &target_path, recent_epochs.unwrap_or(1), depth)
+    })
+    .await
+    .map_err(|e| format!("Clone task failed: {}", e))?;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to clone repository: {}", e)),
+    }
+}
+
+/// Fetch older history for a quick-start clone, then backfill the database with it
+/// Backfilling only runs when connected and populates exactly the commits the deepening added
+#[tauri::command]
+async fn deepen_repository(
+    state: State<'_, DatabaseState>,
+    depth: Option<u32>,
+    include_older_epochs: Option<bool>,
+    window: tauri::Window
+) -> Result<git_parser::DeepenResult, String> {
+    let include_older_epochs = include_older_epochs.unwrap_or(false);
+    let mut result = tokio::task::spawn_blocking(move || git_parser::deepen_repository(depth, include_older_epochs))
+        .await
+        .map_err(|e| format!("Deepen task failed: {}", e))?
+        .map_err(|e| format!("Failed to deepen repository: {}", e))?;
+
+    if result.new_commits.is_empty() {
+        return Ok(result);
+    }
+    let mut manager_guard = state.manager.lock().await;
+    let Some(db_manager) = manager_guard.as_mut() else {
+        return Ok(result);
+    };
+
+    let progress_fn = move |current: u32, total: u32, commit_hash: String| {
+        let payload = serde_json::json!({
+            "current": current,
+            "total": total,
+            "commit_hash": commit_hash
+        });
+        let _ = window.emit("populate-progress", payload);
+    };
+
+    match db_manager.populate_commits(result.new_commits.clone(), Some(progress_fn)).await {
+        Ok(population) => {
+            result.combined_output.push_str(&format!(
+                "\nBackfilled {} messages from {} new commits ({} errors)",
+                population.total_emails_inserted,
+                result.new_commits.len(),
+                population.errors.len()
+            ));
+            Ok(result)
+        }
+        Err(e) => Err(format!("Backfill after deepen failed: {}", e)),
+    }
+}
+
+/// Clone the configured mailing list archive (GitConfig.clone_url) in-process with gix
+/// Emits `clone-progress` events with the current phase, objects and bytes received
+#[tauri::command]
+async fn clone_repository(
+    target_path: String,
+    bare: Option<bool>,
+    window: tauri::Window
+) -> Result<git_parser::GitSyncResult, String> {
+    let config = git_config::GitConfig::load();
+    let clone_url = config.clone_url.clone();
+    let bare = bare.unwrap_or(true);
+
+    let progress_fn = move |progress: git_parser::CloneProgress| {
+        let _ = window.emit("clone-progress", progress);
+    };
+
+    let result = tokio::task::spawn_blocking(move || {
+        git_parser::clone_repository_with_progress(&clone_url, &target_path, bare, progress_fn)
+    })
+    .await
+    .map_err(|e| format!("Clone task failed: {}", e))?;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to clone repository: {}", e)),
+    }
+}
+
+/// Verify the configured repository: epochs open, HEAD is reachable and a random
+/// sample of commits has a readable message blob
+#[tauri::command]
+async fn verify_repository(sample_size: Option<usize>) -> Result<git_parser::RepositoryVerification, String> {
+    let result = tokio::task::spawn_blocking(move || git_parser::verify_repository(sample_size))
+        .await
+        .map_err(|e| format!("Verification task failed: {}", e))?;
+
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to verify repository: {}", e)),
+    }
+}
+
+/// Sync the git repository by running git fetch
+#[tauri::command]
+fn sync_git_repository(repo_path: Option<String>) -> Result<git_parser::GitSyncResult, String> {
+    match git_parser::sync_repository(repo_path.as_deref()) {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to sync repository: {}", e)),
+    }
+}
+
+/// Fetch new messages into the archive and return the commits after the last populated HEAD
+#[tauri::command]
+async fn fetch_updates(state: State<'_, DatabaseState>) -> Result<git_parser::FetchUpdatesResult, String> {
+    // Read the recorded heads, then release the database before the (slow) fetch
+    let populated_heads = {
+        let mut manager_guard = state.manager.lock().await;
+        let db_manager = manager_guard.as_mut()
+            .ok_or("Not connected to database")?;
+        let list = git_parser::default_mailing_list()
+            .map_err(|e| format!("Failed to fetch updates: {}", e))?;
+        let list_id = db_manager.ensure_mailing_list(&list).await
+            .map_err(|e| format!("Failed to fetch updates: {}", e))?;
+        db_manager.get_populated_heads(list_id).await
+            .map_err(|e| format!("Failed to read populated heads: {}", e))?
+    };
+
+    match tokio::task::spawn_blocking(move || git_parser::fetch_updates(&populated_heads)).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(e)) => Err(format!("Failed to fetch updates: {}", e)),
+        Err(e) => Err(format!("Fetch task failed: {}", e)),
+    }
+}
+
+/// Get the commits that arrived after a known commit, newest first
+/// The result can be passed straight to `populate_new_commits`
+#[tauri::command]
+fn get_commits_since(last_seen_hash: String) -> Result<Vec<git_parser::EpochCommit>, String> {
+    match git_parser::get_commits_since(&last_seen_hash) {
+        Ok(commits) => Ok(commits),
+        Err(e) => Err(format!("Failed to get commits since {}: {}", last_seen_hash, e)),
+    }
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(DatabaseState::new())
         .invoke_handler(tauri::generate_handler![
@@ -544,6 +2174,7 @@ pub fn run() {
             get_bpf_email,
             get_bpf_email_count,
             get_total_git_commits,
+            get_repository_epochs,
             get_recent_bpf_commits,
             search_bpf_emails,
             // Database connection management
@@ -554,28 +2185,119 @@ pub fn run() {
             // Database operations
             search_emails_by_author,
             setup_database,
+            migrate_database,
+            check_schema_version,
             populate_database,
+            cancel_population,
+            retry_failed_parses,
+            compress_patch_bodies,
+            prune_bodies,
+            export_database,
+            import_database,
+            import_mailmap,
+            merge_authors,
+            split_author,
+            set_author_display_name,
+            audit_duplicate_authors,
+            populate_new_commits,
+            populate_date_range,
+            populate_mailing_list,
+            import_mbox,
+            import_maildir,
+            get_mailing_lists,
             test_database_connection,
             get_database_stats,
             get_enhanced_database_stats,
+            refresh_stats,
+            get_disk_usage,
+            get_db_health,
+            run_maintenance,
+            advise_indexes,
+            get_patch_size_distribution,
             reset_database,
             get_authors,
             get_patches_by_author,
             build_threads,
+            reprocess_orphans,
             get_threads,
             get_thread_tree,
             get_thread_for_patch,
+            get_patch_by_message_id,
+            get_patches_fixing_commit,
+            get_patches_referencing_commit,
+            search_patches_by_subject_tags,
+            search_fulltext,
+            fuzzy_search_authors,
+            fuzzy_search_subjects,
+            search_patches_by_recipient,
+            get_pull_requests,
+            get_stalled_threads_with_pings,
+            search_syzbot_reports,
+            search_stable_backports,
+            get_patch_stable_status,
+            get_patch_recipients,
+            get_patches_cc_author,
+            get_patches_reviewed_by,
+            get_thread_trailer_rollup,
+            get_patches_touching_path,
+            get_file_activity,
+            get_series,
+            get_patch_versions,
+            get_thread_family,
+            get_message_sources,
+            rebuild_series,
+            get_fixes_for_patch,
+            get_patch_reverts,
+            get_patch_cross_references,
             search_threads,
+            mark_patches_read,
+            mark_thread_read,
+            star_thread,
+            unstar_thread,
+            get_starred_threads,
+            set_thread_review_status,
+            create_label,
+            get_labels,
+            delete_label,
+            add_thread_label,
+            remove_thread_label,
+            add_patch_label,
+            remove_patch_label,
             get_patch_body,
+            get_patch_body_with_quotes,
+            get_inline_comments,
             reprocess_merge_notifications,
+            verify_merge_commits,
+            get_merge_commits,
             // Git configuration
             get_git_config,
             save_git_config,
+            set_git_config,
+            validate_git_config,
             update_git_config,
+            set_kernel_tree_path,
+            add_mailing_list,
+            list_git_profiles,
+            add_git_profile,
+            remove_git_profile,
+            switch_git_profile,
+            set_email_cache_size,
             check_git_repo_exists,
             clone_git_repository,
-            sync_git_repository
+            clone_repository,
+            clone_recent_epochs,
+            deepen_repository,
+            sync_git_repository,
+            verify_repository,
+            fetch_updates,
+            get_commits_since
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<DatabaseState>();
+                tauri::async_runtime::block_on(state.shutdown());
+            }
+        });
 }
\ No newline at end of file
@@ -3,11 +3,15 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use once_cell::sync::Lazy;
-use mailparse::parse_mail;
+use mailparse::{parse_mail, MailHeaderMap};
 use crate::git_parser::CommitMetadata;
 
 // Lazy-compiled regexes for performance
 static WHITESPACE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s+").unwrap());
+// ANSI escape sequences: CSI ("ESC [ ... m"), OSC ("ESC ] ... BEL") and two-character escapes
+static ANSI_ESCAPE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)|[@-Z\\-_])").unwrap()
+});
 static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<([^>]+)>").unwrap());
 
 // Merge notification parsing regexes
@@ -20,6 +24,102 @@ static MERGE_BY_REGEX: Lazy<Regex> = Lazy::new(|| {
 static MERGE_COMMIT_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?m)^\s*-\s+\[([^\]]+)\]\s+([^\n]+)\n\s+(https?://[^\s]+/c/([a-f0-9]+))").unwrap()
 });
+// Summary entry of a patchwork notification, followed by a commit link or "(no matching commit)"
+static MERGE_ENTRY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*-\s+\[([^\]]+)\]\s+([^\n]+)\n\s+(https?://\S+/c/[a-f0-9]+|\(no matching commit\))").unwrap()
+});
+static SERIES_POSITION_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)/(\d+)").unwrap());
+
+// `git request-pull` message parts (anchored at line start so quoted copies don't match)
+static PULL_BASE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)^The following changes since commit ([0-9a-f]{7,40})").unwrap()
+});
+static PULL_URL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)^are available in the Git repository at:\s*\n(?:[ \t]*\n)*[ \t]+(\S+)(?:[ \t]+(\S+))?").unwrap()
+});
+static PULL_HEAD_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)^for you to fetch changes up to ([0-9a-f]{7,40})").unwrap()
+});
+
+// "Fixes: 0123456789ab ("subject of the fixed commit")"
+static FIXES_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)^Fixes:\s*([0-9a-f]{7,40})\b(?:\s*\(\s*"(.*)"\s*\))?"#).unwrap()
+});
+
+// Commits named in prose: "commit 0123456789ab" or "0123456789ab ("subject")"
+static COMMIT_MENTION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)\bcommit\s+([0-9a-f]{7,40})\b|\b([0-9a-f]{12,40})\s*\(""#).unwrap()
+});
+
+// URLs in message bodies, and the Message-ID embedded in lore/msgid.link URLs
+static URL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"https?://[^\s<>()\[\]"']+"#).unwrap());
+static LORE_URL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^https?://(?:lore\.kernel\.org/[^/]+|lkml\.kernel\.org/r|patch\.msgid\.link)/([^/?#]+@[^/?#]+)").unwrap()
+});
+
+// Trailer lines such as "Acked-by: Jane Doe <jane@example.com>"
+static TRAILER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    // ASCII-only tag, so case folding can't match look-alikes such as "ſ" (U+017F)
+    Regex::new(r"(?i)^((?-u:[a-z][a-z-]*-by)):\s*(.+)$").unwrap()
+});
+
+// "-- >8 --" scissors line (git am --scissors); quoted lines never match
+static SCISSORS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*-+\s*(?:>8|8<)\s*-+").unwrap());
+
+// Subject tag tokens: "v3" (also "PATCHv3") and "3/12"
+// "This reverts commit <sha>." line git revert puts in the commit message
+static REVERT_COMMIT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)this reverts commit ([0-9a-f]{7,40})\b").unwrap()
+});
+
+// Maintainer "applied" replies: "Applied to bpf-next, thanks!", "Queued for 6.9", "Thanks, applied."
+// Only counts with a target tree or a "thanks", see is_applied_notification
+static APPLIED_REPLY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?P<lead>thanks[,!.]?\s+)?(?:applied|queued(?: up)?|pushed|merged)(?:\s+(?:it|this|the series|the patch|the patches|the whole series|patches|them|both|all))?(?P<thanks>,?\s+thanks[!.]?)?(?:\s+(?:to|into|in|for)\s+(?:the\s+)?(?:my\s+)?(?P<target>[\w.+/:-]+))?(?:\s+(?:branch|tree)(?:\s+[\w.+/-]+)?)?(?:\s*[,.!]\s*(?P<tail>.*)|\s+(?P<closing>thanks.*))?$").unwrap()
+});
+// What follows "Applied," when the maintainer goes on about a problem or their own testing
+static APPLIED_CONTINUATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:but|and|compiled|tested)\b").unwrap()
+});
+// "... branch <name>" (or "<name> branch") in the applied line
+static APPLIED_BRANCH_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bbranch\s+([\w.+/-]+)").unwrap());
+static APPLIED_BRANCH_BEFORE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b([\w.+-]+)\s+branch\b").unwrap());
+// b4 ty style "      commit: <sha>" lines under each applied patch
+static APPLIED_COMMIT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?mi)^\s*commit:\s*([0-9a-f]{7,40})\b|(https?://\S+/c/[0-9a-f]{7,40})").unwrap()
+});
+/// Longer lines are discussion that happens to start with "Applied"
+const MAX_APPLIED_LINE_WORDS: usize = 12;
+
+// Stable tree bot subjects: 'Patch "<subject>" has been added to the 6.6-stable tree'
+// and 'FAILED: patch "<subject>" failed to apply to 6.1-stable tree'
+static STABLE_ADDED_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)^patch "(.+)" has been added to the (\S+?)-stable tree"#).unwrap()
+});
+static STABLE_FAILED_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)^failed: patch "(.+)" failed to apply to (?:the )?(\S+?)-stable tree"#).unwrap()
+});
+// "[ Upstream commit <sha> ]" (AUTOSEL) or "commit <sha> upstream." (stable queue)
+static UPSTREAM_COMMIT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?im)\[\s*upstream commit ([0-9a-f]{12,40})\s*\]|^commit ([0-9a-f]{12,40}) upstream\.?\s*$").unwrap()
+});
+
+// Nudges on a stalled series ("ping", "Any update on this?", "Gentle reminder")
+static PING_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:ping|pinging|bump|any (?:updates?|news|comments?|feedback|thoughts)|(?:gentle|friendly|kind) (?:reminder|nudge)|status of this|is there anything (?:else )?(?:i|we) (?:should|need|can))\b").unwrap()
+});
+/// Replies longer than this (in words, quotes excluded) are real discussion, not a ping
+const MAX_PING_WORDS: usize = 30;
+
+// Rejections in a reviewer's own words ("NAK", "please drop this patch")
+// A leading NAK must stand on its own ("NAK.", "Nak, this breaks ...") so names such as
+// "Nak Kim" don't count; at the end of a sentence only the capitalized "NAK" does
+static REJECTION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^nak(?:ed)?(?:[.!,:;]|$)|^nacked-by:|(?-i:\bNAK(?:ed)?)[.!]?$|\bi (?:don'?t|do not) think we (?:should|want)|\bplease (?:drop|revert) (?:(?:this|these|the) (?:patch|series|commit|change)(?:es|s)?\b|this[.!]?$)|\b(?:i'?m|i am) not (?:going to|gonna) (?:apply|take|merge)|\bthis is not (?:acceptable|the right (?:approach|way|fix))").unwrap()
+});
+
+static VERSION_TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^(?:patch)?v(\d+)$").unwrap());
+static SERIES_TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d+/\d+$").unwrap());
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EmailInfo {
@@ -42,6 +142,105 @@ pub struct EmailInfo {
     pub in_reply_to: Option<String>,    // Message-ID of parent
     pub references: Vec<String>,        // Full thread chain
     pub is_reply: bool,                 // Quick flag
+    // Structured form of the embedded diff (None when the email carries no diff)
+    #[serde(default)]
+    pub diff: Option<crate::diff_parser::DiffSummary>,
+    // Stable git patch-id of the diff, identical for reposts of the same change
+    #[serde(default)]
+    pub git_patch_id: Option<String>,
+    // Signed-off-by/Acked-by/Reviewed-by/Tested-by/... trailers in the message
+    #[serde(default)]
+    pub trailers: Vec<Trailer>,
+    // Commits this patch fixes ("Fixes:" tags)
+    #[serde(default)]
+    pub fixes: Vec<FixesTag>,
+    // Commit hashes the message refers to (Fixes: tags, reverts and mentions in the text)
+    #[serde(default)]
+    pub commit_refs: Vec<CommitRef>,
+    // "Link:" trailers and lore URLs referencing other messages
+    #[serde(default)]
+    pub links: Vec<MessageLink>,
+    // Sender of the email ("Name <email>") when an in-body "From:" names a different author
+    #[serde(default)]
+    pub envelope_from: Option<String>,
+    // Parsed To/Cc recipients
+    #[serde(default)]
+    pub recipients: Vec<Recipient>,
+    // Lists named by List-Id/X-Mailing-List/Mailing-List headers, e.g. "bpf.vger.kernel.org"
+    #[serde(default)]
+    pub list_ids: Vec<String>,
+    // X-Patchwork-* headers added by patchwork instances
+    #[serde(default)]
+    pub patchwork: PatchworkHeaders,
+    // Text after the "-- " signature separator (None when there is no signature)
+    #[serde(default)]
+    pub signature: Option<String>,
+}
+
+/// A "Fixes: <sha> ("subject")" tag
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FixesTag {
+    /// Abbreviated commit hash, lowercased
+    pub commit: String,
+    /// Subject of the fixed commit, when given
+    pub subject: Option<String>,
+}
+
+/// A commit hash referenced in a message body
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommitRef {
+    /// Full or abbreviated commit hash, lowercased
+    pub commit: String,
+    /// "fixes", "revert" or "mention"
+    pub kind: String,
+}
+
+/// Patchwork automation headers (X-Patchwork-Id, X-Patchwork-Delegate, X-Patchwork-State)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PatchworkHeaders {
+    /// Patch ID in the patchwork REST API
+    pub id: Option<i64>,
+    pub delegate: Option<String>,
+    pub state: Option<String>,
+}
+
+/// An address from the To or Cc header
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Recipient {
+    /// "to" or "cc"
+    pub kind: String,
+    pub name: String,
+    /// Lowercased address
+    pub email: String,
+}
+
+/// A "Link:" trailer or inline lore URL pointing at another message
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessageLink {
+    pub url: String,
+    /// Message-ID embedded in lore.kernel.org / patch.msgid.link URLs
+    pub message_id: Option<String>,
+    /// True for "Link:" trailers, false for URLs found in the text
+    pub is_trailer: bool,
+}
+
+/// A "Tag-by: Name <email>" trailer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Trailer {
+    pub tag: String,
+    pub name: String,
+    pub email: String,
+}
+
+/// Tags in the leading "[...]" groups of a subject, e.g. "[RFC PATCH bpf-next v3 2/5]"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SubjectTags {
+    /// Posting version (1 when the subject has tags but no "vN")
+    pub version: Option<i32>,
+    pub is_rfc: bool,
+    pub is_resend: bool,
+    /// Tree the patch targets, e.g. "net-next" or "bpf-next"
+    pub target_tree: Option<String>,
 }
 
 #[derive(Error, Debug)]
@@ -83,6 +282,187 @@ pub fn normalize_subject(subject: &str) -> String {
     normalized.trim().to_string()
 }
 
+/// Split a body at its first "-- >8 --" scissors line into (commentary, patch)
+/// Without a scissors line the whole body is commentary and the patch part is None
+pub fn split_at_scissors(body: &str) -> (&str, Option<&str>) {
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        if SCISSORS_REGEX.is_match(line) {
+            return (&body[..offset], Some(&body[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    (body, None)
+}
+
+/// Split a body at its last "-- " signature separator into (content, signature)
+/// The last separator wins so "-- " lines inside quoted replies don't cut the message short
+pub fn split_signature(body: &str) -> (&str, Option<&str>) {
+    let mut offset = 0;
+    let mut split = None;
+    for line in body.split_inclusive('\n') {
+        if line.trim_end_matches(['\n', '\r']).trim_end() == "--" {
+            split = Some((offset, offset + line.len()));
+        }
+        offset += line.len();
+    }
+    match split {
+        Some((start, end)) => {
+            let signature = body[end..].trim();
+            (&body[..start], (!signature.is_empty()).then_some(signature))
+        }
+        None => (body, None),
+    }
+}
+
+/// Parse the version, RFC/RESEND flags and target tree out of a subject's "[...]" tags
+/// Reply prefixes are skipped, so "Re: [PATCH v2 1/3] ..." yields version 2
+pub fn parse_subject_tags(subject: &str) -> SubjectTags {
+    let mut tags = SubjectTags::default();
+    let mut rest = subject.trim();
+    let mut has_tags = false;
+
+    loop {
+        let lower = rest.to_lowercase();
+        if let Some(prefix) = ["re:", "fwd:", "fw:", "aw:"].iter().find(|p| lower.starts_with(*p)) {
+            rest = rest[prefix.len()..].trim_start();
+            continue;
+        }
+        let Some(group) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) else {
+            break;
+        };
+        let (inside, after) = group;
+        let tokens: Vec<String> = inside.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_lowercase())
+            .collect();
+
+        // Only patch tags count: "[syzbot] ..." or "[ANNOUNCE] ..." carry no version or tree
+        let is_patch_group = tokens.iter().any(|t| {
+            matches!(t.as_str(), "patch" | "patches" | "rfc" | "resend" | "pull" | "rfc/patch" | "patch/rfc")
+                || VERSION_TAG_REGEX.is_match(t)
+        });
+        if !is_patch_group {
+            break;
+        }
+        has_tags = true;
+
+        for token in tokens {
+            if let Some(caps) = VERSION_TAG_REGEX.captures(&token) {
+                tags.version = caps[1].parse().ok().or(tags.version);
+            } else if SERIES_TAG_REGEX.is_match(&token) {
+                continue;
+            } else if matches!(token.as_str(), "rfc" | "rfc/patch" | "patch/rfc") {
+                tags.is_rfc = true;
+            } else if token == "resend" {
+                tags.is_resend = true;
+            } else if matches!(token.as_str(), "patch" | "patches" | "pull" | "git") {
+                continue;
+            } else if tags.target_tree.is_none() {
+                tags.target_tree = Some(token);
+            }
+        }
+        rest = after.trim_start();
+    }
+
+    if has_tags && tags.version.is_none() {
+        tags.version = Some(1);
+    }
+    tags
+}
+
+/// Parse every To and Cc header into (name, email) recipients, deduplicated per kind
+/// Unparseable headers are skipped
+pub fn parse_recipients(headers: &[mailparse::MailHeader]) -> Vec<Recipient> {
+    let mut recipients: Vec<Recipient> = Vec::new();
+    for header in headers {
+        let kind = header.get_key().to_lowercase();
+        if kind != "to" && kind != "cc" {
+            continue;
+        }
+        let Ok(addresses) = mailparse::addrparse_header(header) else {
+            continue;
+        };
+        let singles = addresses.iter().flat_map(|addr| match addr {
+            mailparse::MailAddr::Single(single) => vec![single.clone()],
+            mailparse::MailAddr::Group(group) => group.addrs.clone(),
+        });
+        for single in singles {
+            let email = single.addr.trim().to_lowercase();
+            if email.is_empty() || recipients.iter().any(|r| r.kind == kind && r.email == email) {
+                continue;
+            }
+            recipients.push(Recipient {
+                kind: kind.clone(),
+                name: single.display_name.map(|n| normalize_name(&n)).unwrap_or_default(),
+                email,
+            });
+        }
+    }
+    recipients
+}
+
+/// Collect list identifiers from List-Id, X-Mailing-List, Mailing-List and List-Post headers
+/// Address forms ("bpf@vger.kernel.org") are converted to List-Id form ("bpf.vger.kernel.org")
+pub fn extract_list_ids(headers: &[mailparse::MailHeader]) -> Vec<String> {
+    let mut list_ids: Vec<String> = Vec::new();
+    for header in headers {
+        let key = header.get_key().to_lowercase();
+        if !matches!(key.as_str(), "list-id" | "x-mailing-list" | "mailing-list" | "list-post") {
+            continue;
+        }
+        let value = header.get_value();
+        // Prefer the bracketed part: "BPF list <bpf.vger.kernel.org>", "<mailto:bpf@vger.kernel.org>"
+        let raw = EMAIL_REGEX.captures(&value)
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str())
+            .unwrap_or(&value);
+        // "Mailing-List: contact bpf-help@vger.kernel.org; run by ezmlm" style values
+        let raw = raw.split([';', ' ', ',']).find(|part| part.contains('.')).unwrap_or(raw);
+        let address = raw.trim().trim_start_matches("mailto:");
+        // ezmlm names the "-help" contact address rather than the list itself
+        let list_id = match address.split_once('@') {
+            Some((local, domain)) => format!("{}.{}", local.trim_end_matches("-help"), domain),
+            None => address.to_string(),
+        }.to_lowercase();
+        if !list_id.is_empty() && list_id.contains('.') && !list_ids.contains(&list_id) {
+            list_ids.push(list_id);
+        }
+    }
+    list_ids
+}
+
+/// Read the X-Patchwork-* headers (keys of `headers` are lowercase)
+pub fn extract_patchwork_headers(headers: &HashMap<String, String>) -> PatchworkHeaders {
+    let value = |key: &str| headers.get(key)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    PatchworkHeaders {
+        id: value("x-patchwork-id").and_then(|id| id.parse().ok()),
+        delegate: value("x-patchwork-delegate"),
+        state: value("x-patchwork-state").map(|state| state.to_lowercase()),
+    }
+}
+
+/// Find an in-body "From: Name <email>" override at the top of a patch body
+/// Used when someone sends a patch on behalf of its author; returns (name, email)
+/// Only bodies that carry a patch (a "---" separator or a diff below) qualify, so
+/// forwarded mails and replies quoting headers are left alone
+pub fn extract_in_body_from(body: &str) -> Option<(String, String)> {
+    let has_patch = body.lines().any(|line| line.trim_end() == "---" || line.starts_with("diff --git "));
+    if !has_patch {
+        return None;
+    }
+    let first_line = body.lines().find(|line| !line.trim().is_empty())?;
+    let value = first_line.trim().strip_prefix("From:")?.trim();
+    let email = extract_email(value);
+    if !email.contains('@') || email.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((extract_name(value), email))
+}
+
 /// Extract email address from From/To header and normalize to lowercase
 pub fn extract_email(from_header: &str) -> String {
     let email = if let Some(captures) = EMAIL_REGEX.captures(from_header) {
@@ -140,12 +520,20 @@ pub fn parse_name_components(full_name: &str) -> (String, Option<String>, String
     }
 }
 
-/// Sanitize string for PostgreSQL - remove null bytes and invalid UTF-8
+/// Sanitize string for PostgreSQL - remove null bytes, control characters and invalid UTF-8
 fn sanitize_string(s: &str) -> String {
-    s.chars()
-        .filter(|c| *c != '\0') // Remove null bytes
-        .collect::<String>()
-        .replace('\u{FFFD}', "") // Remove replacement characters
+    ANSI_ESCAPE_REGEX.replace_all(s, "")
+        .chars()
+        // Null bytes, terminal control codes, etc.; only newlines and tabs are kept
+        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+        .filter(|c| *c != '\u{FFFD}') // Remove replacement characters left by lossy decoding
+        .collect()
+}
+
+/// Sanitize a message body: normalize line endings to LF, then sanitize_string
+/// Runs before any parsing so diffs, trailers and the stored body all see the same text
+fn sanitize_body(body: &str) -> String {
+    sanitize_string(&body.replace("\r\n", "\n").replace('\r', "\n"))
 }
 
 /// Clean up Message-ID (remove < > brackets)
@@ -156,22 +544,279 @@ fn sanitize_message_id(msg_id: &str) -> String {
         .to_string()
 }
 
+/// Remove RFC 5322 comments ("(...)", possibly nested) from a header value
+fn strip_header_comments(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for c in value.chars() {
+        if escaped {
+            escaped = false;
+            if depth == 0 {
+                result.push(c);
+            }
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            _ if depth == 0 => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Parse a Message-ID list header (Message-ID, In-Reply-To, References)
+/// Handles comments, missing separators ("<a><b>") and bare IDs without angle brackets
+pub fn parse_message_id_list(value: &str) -> Vec<String> {
+    let cleaned = strip_header_comments(value);
+    let ids: Vec<String> = match mailparse::msgidparse(&cleaned) {
+        Ok(ids) => ids.iter().map(|id| sanitize_message_id(id)).collect(),
+        // Stray text around the IDs: take every <...> token, or bare words containing '@'
+        Err(_) if cleaned.contains('<') => cleaned.split('<')
+            .skip(1)
+            .filter_map(|part| part.split_once('>').map(|(id, _)| sanitize_message_id(id)))
+            .collect(),
+        Err(_) => cleaned.split_whitespace()
+            .filter(|word| word.contains('@') || cleaned.split_whitespace().count() == 1)
+            .map(sanitize_message_id)
+            .collect(),
+    };
+    ids.into_iter()
+        .filter(|id| !id.is_empty() && !id.contains(char::is_whitespace))
+        .collect()
+}
+
+/// Parse email date with multiple format support
+pub fn parse_email_date(date_str: &str) -> Result<chrono::DateTime<chrono::Utc>, chrono::ParseError> {
+    chrono::DateTime::parse_from_rfc2822(date_str)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(date_str))
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S")
+                .map(|dt| dt.and_utc())
+        })
+}
+
+/// Pick the date to store for an email
+/// The Date header is used when it parses; otherwise fall back to the git author
+/// (then committer) timestamp, which public-inbox always records
+fn resolve_email_date(date_header: Option<&String>, metadata: &CommitMetadata) -> String {
+    if let Some(date) = date_header {
+        if parse_email_date(date).is_ok() {
+            return date.clone();
+        }
+    }
+    
+    metadata.author_time
+        .or(metadata.committer_time)
+        .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+        .map(|dt| dt.to_rfc2822())
+        .or_else(|| date_header.cloned())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Extract the normalized Message-ID from a raw email (as stored in patches.message_id)
+pub fn extract_message_id(raw_email: &str) -> Option<String> {
+    let (headers, _) = mailparse::parse_headers(raw_email.as_bytes()).ok()?;
+    headers.iter()
+        .find(|h| h.get_key().eq_ignore_ascii_case("message-id"))
+        .and_then(|h| parse_message_id_list(&h.get_value()).into_iter().next())
+}
+
+/// Extract the trailers of a message body
+/// Trailers are read from the message text only: quoted lines are skipped and parsing
+/// stops at the "---" separator, the diff or the signature. Replies count as well,
+/// since reviewers usually send their Acked-by/Reviewed-by in a reply.
+/// A trailer wrapped onto indented continuation lines is read as one line.
+pub fn extract_trailers(body: &str) -> Vec<Trailer> {
+    let mut trailers = Vec::new();
+    let mut lines = body.lines().map(str::trim_end).peekable();
+    
+    while let Some(line) = lines.next() {
+        if line == "---" || line == "--" || line.starts_with("diff --git ") {
+            break;
+        }
+        
+        let Some(caps) = TRAILER_REGEX.captures(line) else {
+            continue;
+        };
+        let mut value = caps[2].trim().to_string();
+        while let Some(next) = lines.next_if(|l| l.starts_with([' ', '\t']) && !l.trim().is_empty()) {
+            value.push(' ');
+            value.push_str(next.trim());
+        }
+        let value = value.as_str();
+        let email = extract_email(value);
+        if !email.contains('@') {
+            continue;
+        }
+        
+        // Tags are case-insensitive; store the canonical "Signed-off-by" form
+        let lower = caps[1].to_lowercase();
+        let mut chars = lower.chars();
+        let tag: String = chars.next().into_iter().flat_map(char::to_uppercase).chain(chars).collect();
+        let name = match extract_name(value) {
+            name if name.is_empty() => email.clone(),
+            name => name,
+        };
+        
+        let trailer = Trailer { tag, name, email };
+        if !trailers.contains(&trailer) {
+            trailers.push(trailer);
+        }
+    }
+    
+    trailers
+}
+
+/// Extract the "Fixes:" tags of a message body (same scope rules as `extract_trailers`)
+pub fn extract_fixes_tags(body: &str) -> Vec<FixesTag> {
+    let mut fixes: Vec<FixesTag> = Vec::new();
+    
+    for line in body.lines() {
+        let line = line.trim_end();
+        if line == "---" || line == "--" || line.starts_with("diff --git ") {
+            break;
+        }
+        
+        let Some(caps) = FIXES_REGEX.captures(line) else {
+            continue;
+        };
+        let commit = caps[1].to_lowercase();
+        if fixes.iter().any(|f| f.commit == commit) {
+            continue;
+        }
+        fixes.push(FixesTag {
+            commit,
+            subject: caps.get(2)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|s| !s.is_empty()),
+        });
+    }
+    
+    fixes
+}
+
+/// Extract the commit hashes a message body refers to: Fixes: tags, "This reverts commit"
+/// lines and commits named in the text (quoted lines, the diff and the signature are skipped)
+/// Each hash is reported once, with the strongest kind (fixes, then revert, then mention)
+pub fn extract_commit_refs(body: &str) -> Vec<CommitRef> {
+    let mut refs: Vec<CommitRef> = Vec::new();
+    let rank = |kind: &str| ["fixes", "revert", "mention"].iter().position(|k| *k == kind);
+    let mut add = |commit: &str, kind: &str| {
+        let commit = commit.to_lowercase();
+        match refs.iter_mut().find(|r| r.commit == commit) {
+            Some(existing) if rank(kind) < rank(&existing.kind) => existing.kind = kind.to_string(),
+            Some(_) => {}
+            None => refs.push(CommitRef { commit, kind: kind.to_string() }),
+        }
+    };
+    
+    for line in body.lines() {
+        let line = line.trim_end();
+        if line.starts_with("diff --git ") || line == "--" {
+            break;
+        }
+        if line.trim_start().starts_with('>') {
+            continue;
+        }
+        
+        if let Some(caps) = FIXES_REGEX.captures(line) {
+            add(&caps[1], "fixes");
+            continue;
+        }
+        if let Some(caps) = REVERT_COMMIT_REGEX.captures(line) {
+            add(&caps[1], "revert");
+        }
+        for caps in COMMIT_MENTION_REGEX.captures_iter(line) {
+            if let Some(m) = caps.get(1).or_else(|| caps.get(2)) {
+                add(m.as_str(), "mention");
+            }
+        }
+    }
+    
+    refs
+}
+
+/// Decode %XX escapes (lore URLs sometimes encode the "@" of a Message-ID)
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Message-ID embedded in a lore.kernel.org, lkml.kernel.org/r or patch.msgid.link URL
+pub fn message_id_from_url(url: &str) -> Option<String> {
+    let decoded = percent_decode(url);
+    LORE_URL_REGEX.captures(&decoded)
+        .map(|caps| sanitize_message_id(&caps[1]))
+}
+
+/// Extract "Link:" trailers and inline lore URLs (quoted lines and the diff are skipped)
+pub fn extract_message_links(body: &str) -> Vec<MessageLink> {
+    let mut links: Vec<MessageLink> = Vec::new();
+    
+    for line in body.lines() {
+        let line = line.trim_end();
+        if line.starts_with("diff --git ") || line == "--" {
+            break;
+        }
+        if line.trim_start().starts_with('>') {
+            continue;
+        }
+        
+        let is_trailer = line.len() > 5 && line.get(..5).is_some_and(|p| p.eq_ignore_ascii_case("link:"));
+        for url in URL_REGEX.find_iter(line) {
+            let url = url.as_str().trim_end_matches(['.', ',', ';', ':']);
+            let message_id = message_id_from_url(url);
+            // Outside Link: trailers only message URLs are cross-references
+            if (!is_trailer && message_id.is_none()) || links.iter().any(|l| l.url == url) {
+                continue;
+            }
+            links.push(MessageLink {
+                url: url.to_string(),
+                message_id,
+                is_trailer,
+            });
+        }
+    }
+    
+    links
+}
+
 /// Parse threading headers from email
 /// Returns (in_reply_to, references, is_reply)
-fn parse_threading_info(headers: &HashMap<String, String>, subject: &str) -> (Option<String>, Vec<String>, bool) {
-    // Get In-Reply-To header
-    let in_reply_to = headers.get("in-reply-to")
-        .map(|s| sanitize_message_id(s));
-    
-    // Parse References header (space-separated Message-IDs)
-    let references: Vec<String> = headers.get("references")
-        .map(|refs| {
-            refs.split_whitespace()
-                .map(|id| sanitize_message_id(id))
-                .filter(|id| !id.is_empty())
-                .collect::<Vec<String>>()
-        })
-        .unwrap_or_default();
+fn parse_threading_info(headers: &[mailparse::MailHeader], subject: &str) -> (Option<String>, Vec<String>, bool) {
+    // Get In-Reply-To header (the first ID; some mailers append the grandparent or a comment)
+    let in_reply_to = headers.get_first_value("In-Reply-To")
+        .and_then(|value| parse_message_id_list(&value).into_iter().next());
+    
+    // Parse References header (folded, possibly without separators or with comments)
+    let references: Vec<String> = headers.get_all_values("References")
+        .iter()
+        .flat_map(|value| parse_message_id_list(value))
+        .fold(Vec::new(), |mut ids, id| {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+            ids
+        });
     
     // Determine is_reply based on Subject header (Re: prefix)
     // Patch series members have In-Reply-To but are NOT replies
@@ -180,6 +825,27 @@ fn parse_threading_info(headers: &HashMap<String, String>, subject: &str) -> (Op
     (in_reply_to, references, is_reply)
 }
 
+/// First part (depth-first) with the given MIME type
+fn find_mime_part<'a, 'b>(mail: &'b mailparse::ParsedMail<'a>, mimetype: &str) -> Option<&'b mailparse::ParsedMail<'a>> {
+    if mail.ctype.mimetype.eq_ignore_ascii_case(mimetype) {
+        return Some(mail);
+    }
+    mail.subparts.iter().find_map(|part| find_mime_part(part, mimetype))
+}
+
+/// Decoded body text of an email
+/// HTML-only messages (no text/plain part anywhere) are converted to plain text
+fn extract_body_text(parsed: &mailparse::ParsedMail) -> String {
+    if find_mime_part(parsed, "text/plain").is_none() {
+        if let Some(html_part) = find_mime_part(parsed, "text/html") {
+            if let Ok(html) = html_part.get_body() {
+                return crate::html_to_text::html_to_text(&html);
+            }
+        }
+    }
+    parsed.get_body().unwrap_or_default()
+}
+
 /// Parse complete email information from commit hash and email content
 /// Uses commit metadata for author and subject information (much more reliable)
 /// Now uses mailparse crate for proper email parsing and decoding
@@ -194,23 +860,40 @@ pub fn parse_email_from_content(commit_hash: &str, email_content: &str, metadata
         .collect();
     
     // Get body - mailparse automatically decodes based on Content-Transfer-Encoding!
-    let body = parsed.get_body().unwrap_or_default();
+    // Normalized up front: stored bodies and everything parsed from them stay consistent
+    let body = sanitize_body(&extract_body_text(&parsed));
+    // With a "-- >8 --" scissors line, only the part below it is the patch
+    let patch_body = split_at_scissors(&body).1.unwrap_or(&body);
 
     // Use commit metadata for subject (much more reliable than email headers)
     let subject = &metadata.subject;
     let normalized_subject = normalize_subject(subject);
 
     // Use commit metadata for author information (most reliable source)
-    let author_email = metadata.author_email.to_lowercase(); // Already normalized in CommitMetadata
-    let author_name = &metadata.author_name;
-    let (author_first_name, author_last_name, author_display_name) = parse_name_components(author_name);
+    let mut author_email = metadata.author_email.to_lowercase(); // Already normalized in CommitMetadata
+    let mut author_name = metadata.author_name.clone();
+    
+    // Parse threading information
+    let (in_reply_to, references, is_reply) = parse_threading_info(&parsed.headers, subject);
+
+    // An in-body "From:" on a posted patch names the real author; the sender is kept as envelope_from
+    // Replies never override: their first line is the replier's own text or a quoted header
+    let mut envelope_from = None;
+    let in_body_from = if is_reply { None } else { extract_in_body_from(patch_body) };
+    if let Some((in_body_name, in_body_email)) = in_body_from {
+        if in_body_email != author_email {
+            envelope_from = Some(format!("{} <{}>", author_name, author_email));
+            author_email = in_body_email;
+            if !in_body_name.is_empty() {
+                author_name = in_body_name;
+            }
+        }
+    }
+    let (author_first_name, author_last_name, author_display_name) = parse_name_components(&author_name);
     
     // Format from_header for compatibility
     let from_header = format!("{} <{}>", author_name, author_email);
 
-    // Parse threading information
-    let (in_reply_to, references, is_reply) = parse_threading_info(&headers, subject);
-
     let email_info = EmailInfo {
         commit_hash: commit_hash.to_string(),
         subject: sanitize_string(subject),
@@ -223,9 +906,22 @@ pub fn parse_email_from_content(commit_hash: &str, email_content: &str, metadata
         author_display_name,
         // Other fields from email headers
         to: sanitize_string(&headers.get("to").cloned().unwrap_or_else(|| "Unknown".to_string())),
-        date: sanitize_string(&headers.get("date").cloned().unwrap_or_else(|| "Unknown".to_string())),
-        message_id: sanitize_message_id(&headers.get("message-id").cloned().unwrap_or_else(|| format!("commit-{}", commit_hash))),
-        body: sanitize_string(&body),
+        date: sanitize_string(&resolve_email_date(headers.get("date"), metadata)),
+        message_id: parsed.headers.get_first_value("Message-ID")
+            .and_then(|value| parse_message_id_list(&value).into_iter().next())
+            .unwrap_or_else(|| format!("commit-{}", commit_hash)),
+        diff: crate::diff_parser::parse_diff(patch_body),
+        git_patch_id: crate::diff_parser::compute_patch_id(patch_body),
+        trailers: extract_trailers(patch_body),
+        fixes: extract_fixes_tags(patch_body),
+        commit_refs: extract_commit_refs(&body),
+        links: extract_message_links(&body),
+        envelope_from: envelope_from.map(|sender| sanitize_string(&sender)),
+        recipients: parse_recipients(&parsed.headers),
+        list_ids: extract_list_ids(&parsed.headers),
+        patchwork: extract_patchwork_headers(&headers),
+        signature: split_signature(&body).1.map(str::to_string),
+        body,
         headers: headers.clone(),
         // Threading fields
         in_reply_to,
@@ -237,8 +933,8 @@ pub fn parse_email_from_content(commit_hash: &str, email_content: &str, metadata
 }
 
 /// Parse multiple emails in parallel from commit hash/content/metadata tuples
-/// Returns (successful_emails, errors)
-pub async fn parse_emails_parallel(emails: Vec<(String, String, CommitMetadata)>) -> (Vec<(String, EmailInfo)>, Vec<String>) {
+/// Returns (successful_emails, errors, failures), failures carry the raw email for quarantine
+pub async fn parse_emails_parallel(emails: Vec<(String, String, CommitMetadata)>) -> (Vec<(String, EmailInfo)>, Vec<String>, Vec<ParseFailure>) {
     use futures::future;
     
     let mut parse_handles = Vec::new();
@@ -247,7 +943,12 @@ pub async fn parse_emails_parallel(emails: Vec<(String, String, CommitMetadata)>
         let handle = tokio::spawn(async move {
             match parse_email_from_content(&commit_hash, &email_content, &metadata) {
                 Ok(email_info) => Ok((commit_hash, email_info)),
-                Err(e) => Err(format!("Error parsing commit {}: {}", commit_hash, e)),
+                Err(e) => Err(ParseFailure {
+                    commit_hash,
+                    raw_content: email_content,
+                    metadata,
+                    error: e.to_string(),
+                }),
             }
         });
         parse_handles.push(handle);
@@ -256,16 +957,29 @@ pub async fn parse_emails_parallel(emails: Vec<(String, String, CommitMetadata)>
     let results = future::join_all(parse_handles).await;
     let mut parsed_emails = Vec::new();
     let mut errors = Vec::new();
+    let mut failures = Vec::new();
     
     for result in results {
         match result {
             Ok(Ok(email)) => parsed_emails.push(email),
-            Ok(Err(e)) => errors.push(e),
+            Ok(Err(failure)) => {
+                errors.push(format!("Error parsing commit {}: {}", failure.commit_hash, failure.error));
+                failures.push(failure);
+            }
             Err(e) => errors.push(format!("Task error: {}", e)),
         }
     }
     
-    (parsed_emails, errors)
+    (parsed_emails, errors, failures)
+}
+
+/// An email that could not be parsed, kept whole so it can be retried later
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    pub commit_hash: String,
+    pub raw_content: String,
+    pub metadata: CommitMetadata,
+    pub error: String,
 }
 
 // ============================================================================
@@ -273,6 +987,98 @@ pub async fn parse_emails_parallel(emails: Vec<(String, String, CommitMetadata)>
 // ============================================================================
 
 /// Information extracted from patchwork bot merge notification
+/// Who sent a message: a person or one of the known automation accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SenderType {
+    #[default]
+    Human,
+    PatchworkBot,
+    KernelTestRobot,
+    BpfCi,
+    Syzbot,
+    Coverity,
+}
+
+impl SenderType {
+    /// Value stored in the patches.sender_type column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SenderType::Human => "human",
+            SenderType::PatchworkBot => "patchwork_bot",
+            SenderType::KernelTestRobot => "kernel_test_robot",
+            SenderType::BpfCi => "bpf_ci",
+            SenderType::Syzbot => "syzbot",
+            SenderType::Coverity => "coverity",
+        }
+    }
+
+    /// Parse a patches.sender_type value (unknown values are treated as human)
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "patchwork_bot" => SenderType::PatchworkBot,
+            "kernel_test_robot" => SenderType::KernelTestRobot,
+            "bpf_ci" => SenderType::BpfCi,
+            "syzbot" => SenderType::Syzbot,
+            "coverity" => SenderType::Coverity,
+            _ => SenderType::Human,
+        }
+    }
+
+    pub fn is_bot(&self) -> bool {
+        *self != SenderType::Human
+    }
+}
+
+/// Classify the sender of an email by its author address and name
+pub fn classify_sender(email_info: &EmailInfo) -> SenderType {
+    let email = email_info.author_email.to_lowercase();
+    let name = email_info.author_display_name.to_lowercase();
+    
+    if email.contains("patchwork") {
+        SenderType::PatchworkBot
+    } else if email == "lkp@intel.com" || name.contains("kernel test robot") {
+        SenderType::KernelTestRobot
+    } else if email.contains("bpf-ci") || email.starts_with("kernel-patches-bot") || name.contains("bpf ci") {
+        SenderType::BpfCi
+    } else if email.starts_with("syzbot") || email.ends_with("@syzkaller.appspotmail.com") {
+        SenderType::Syzbot
+    } else if email.contains("coverity") {
+        SenderType::Coverity
+    } else {
+        SenderType::Human
+    }
+}
+
+/// Detect short replies that only nudge for review ("ping", "Any update on this?")
+pub fn is_ping(email_info: &EmailInfo) -> bool {
+    if !email_info.is_reply || email_info.diff.is_some() {
+        return false;
+    }
+    let (content, _) = split_signature(&email_info.body);
+    // Own words only: no quoted text or "On ..., X wrote:" attribution
+    let own_text: Vec<&str> = content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('>'))
+        .filter(|line| !line.ends_with("wrote:") && !line.ends_with("writes:"))
+        .collect();
+    let word_count: usize = own_text.iter().map(|line| line.split_whitespace().count()).sum();
+    
+    word_count > 0 && word_count <= MAX_PING_WORDS && own_text.iter().any(|line| PING_REGEX.is_match(line))
+}
+
+/// Detect replies rejecting the patch ("NAK", "I don't think we should", "please drop this patch")
+pub fn is_rejection(email_info: &EmailInfo) -> bool {
+    if !email_info.is_reply {
+        return false;
+    }
+    let (content, _) = split_signature(&email_info.body);
+    content.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('>'))
+        .any(|line| REJECTION_REGEX.is_match(line))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeInfo {
     pub repository: String,          // e.g., "bpf/bpf-next.git"
@@ -281,6 +1087,35 @@ pub struct MergeInfo {
     pub commit_links: Vec<String>,   // URLs/hashes of merged commits
 }
 
+/// One patch listed in the summary of a patchwork merge notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedPatchEntry {
+    /// Position in the series ("1/3" in "[bpf-next,v2,1/3]"), None for single patches
+    pub series_number: Option<i32>,
+    pub series_total: Option<i32>,
+    /// Subject without the bracketed tags
+    pub subject: String,
+    /// Commit it was applied as, None for "(no matching commit)"
+    pub commit_link: Option<String>,
+}
+
+/// Parse the per-patch summary of a patchwork notification ("Here is the summary with links:")
+/// A series applied in part lists the skipped patches with "(no matching commit)"
+pub fn parse_merged_patch_entries(body: &str) -> Vec<MergedPatchEntry> {
+    MERGE_ENTRY_REGEX.captures_iter(body)
+        .map(|caps| {
+            let position = SERIES_POSITION_REGEX.captures(&caps[1]);
+            let link = &caps[3];
+            MergedPatchEntry {
+                series_number: position.as_ref().and_then(|p| p[1].parse().ok()),
+                series_total: position.as_ref().and_then(|p| p[2].parse().ok()),
+                subject: caps[2].trim().to_string(),
+                commit_link: link.starts_with("http").then(|| link.to_string()),
+            }
+        })
+        .collect()
+}
+
 /// Detect if an email is a patchwork bot merge notification
 /// Checks author email and body content patterns
 pub fn is_patchwork_merge_notification(email_info: &EmailInfo) -> bool {
@@ -354,13 +1189,717 @@ pub fn parse_merge_metadata(email_info: &EmailInfo) -> Option<MergeInfo> {
     })
 }
 
+/// Whether a line matched by APPLIED_REPLY_REGEX really announces the patch was applied
+/// It has to name a target ("to bpf-next") or say thanks, and must not go on with
+/// "but ...", "and ...", "compiled ..." or "tested ..."
+fn is_applied_notification(caps: &regex::Captures) -> bool {
+    let tail = caps.name("tail").map_or("", |m| m.as_str());
+    if APPLIED_CONTINUATION_REGEX.is_match(tail) {
+        return false;
+    }
+    let thanked = caps.name("lead").is_some()
+        || caps.name("thanks").is_some()
+        || caps.name("closing").is_some()
+        || tail.to_lowercase().starts_with("thank");
+    caps.name("target").is_some() || thanked
+}
+
+/// Detect a maintainer reply saying the patch was applied ("Applied to bpf-next, thanks!")
+/// The tree/branch are taken from the reply when stated; the sender is recorded as applied_by
+pub fn parse_applied_reply(email_info: &EmailInfo) -> Option<MergeInfo> {
+    if !email_info.is_reply || classify_sender(email_info).is_bot() {
+        return None;
+    }
+    let (content, _) = split_signature(&email_info.body);
+    
+    // Own text only; the applied line is usually the first thing in the reply
+    let (applied_line, caps) = content.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('>'))
+        .filter(|line| !line.ends_with("wrote:") && !line.ends_with("writes:"))
+        .filter(|line| line.split_whitespace().count() <= MAX_APPLIED_LINE_WORDS)
+        .find_map(|line| {
+            APPLIED_REPLY_REGEX.captures(line)
+                .filter(is_applied_notification)
+                .map(|caps| (line, caps))
+        })?;
+    
+    let target = caps.name("target")
+        .map(|m| m.as_str().trim_end_matches(['.', ',', ':', '!']).to_string());
+    let explicit_branch = APPLIED_BRANCH_REGEX.captures(applied_line)
+        .or_else(|| APPLIED_BRANCH_BEFORE_REGEX.captures(applied_line))
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim_end_matches(['.', ',', '!']).to_string());
+    
+    // "bpf-next/master" names both the tree and the branch
+    let (repository, branch) = match target {
+        Some(target) => match target.split_once('/') {
+            Some((tree, branch)) if !target.contains("://") => (tree.to_string(), branch.to_string()),
+            _ => (target, String::new()),
+        },
+        None => ("Unknown".to_string(), String::new()),
+    };
+    let branch = explicit_branch.filter(|b| *b != repository).unwrap_or(branch);
+    
+    let commit_links = APPLIED_COMMIT_REGEX.captures_iter(content)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .map(|m| m.as_str().to_string())
+        .collect();
+    
+    Some(MergeInfo {
+        repository,
+        branch,
+        applied_by: format!("{} <{}>", email_info.author_display_name, email_info.author_email),
+        commit_links,
+    })
+}
+
 /// Check if email is merge notification and extract metadata in one call
+/// Patchwork bot notifications first, then maintainer "applied" replies
 /// Returns (is_merge, Option<MergeInfo>)
 pub fn detect_and_parse_merge(email_info: &EmailInfo) -> (bool, Option<MergeInfo>) {
-    if !is_patchwork_merge_notification(email_info) {
-        return (false, None);
+    if is_patchwork_merge_notification(email_info) {
+        let merge_info = parse_merge_metadata(email_info);
+        return (true, merge_info);
+    }
+    
+    match parse_applied_reply(email_info) {
+        Some(merge_info) => (true, Some(merge_info)),
+        None => (false, None),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestInfo {
+    pub base_commit: String,         // "The following changes since commit ..."
+    pub repository: String,          // e.g., "git://git.kernel.org/pub/scm/linux/kernel/git/bpf/bpf-next.git"
+    pub git_ref: Option<String>,     // e.g., "tags/for-netdev" or a branch name
+    pub head_commit: Option<String>, // "for you to fetch changes up to ..."
+}
+
+/// Detect a `git request-pull` email and extract the requested repository, ref and range
+/// Returns None for anything else, including replies that quote a pull request
+pub fn parse_pull_request(email_info: &EmailInfo) -> Option<PullRequestInfo> {
+    if email_info.is_reply {
+        return None;
+    }
+    let body = &email_info.body;
+    
+    let base_commit = PULL_BASE_REGEX.captures(body)?.get(1)?.as_str().to_lowercase();
+    let url_caps = PULL_URL_REGEX.captures(body)?;
+    let repository = url_caps.get(1)?.as_str().to_string();
+    let git_ref = url_caps.get(2).map(|m| m.as_str().to_string());
+    let head_commit = PULL_HEAD_REGEX.captures(body)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_lowercase());
+    
+    Some(PullRequestInfo {
+        base_commit,
+        repository,
+        git_ref,
+        head_commit,
+    })
+}
+
+/// A patch reverting an earlier commit
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RevertInfo {
+    pub commit: Option<String>,   // From "This reverts commit <sha>.", lowercased
+    pub subject: Option<String>,  // From 'Revert "<subject>"'
+}
+
+/// Detect a revert patch by its 'Revert "..."' subject or "This reverts commit" line
+/// Replies return None
+pub fn parse_revert(email_info: &EmailInfo) -> Option<RevertInfo> {
+    if email_info.is_reply {
+        return None;
+    }
+    
+    // Skip [PATCH ...] tags
+    let mut subject = email_info.subject.trim();
+    while subject.starts_with('[') {
+        match subject.find(']') {
+            Some(end) => subject = subject[end + 1..].trim_start(),
+            None => break,
+        }
+    }
+    
+    let reverted_subject = subject.strip_prefix("Revert ")
+        .or_else(|| subject.strip_prefix("revert "))
+        .and_then(|rest| {
+            // Outermost quotes, so 'Revert "Revert "foo""' yields 'Revert "foo"'
+            let start = rest.find('"')?;
+            let end = rest.rfind('"')?;
+            (end > start + 1).then(|| rest[start + 1..end].trim().to_string())
+        });
+    let commit = REVERT_COMMIT_REGEX.captures(&email_info.body)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_lowercase());
+    
+    if reverted_subject.is_none() && commit.is_none() {
+        return None;
+    }
+    Some(RevertInfo {
+        commit,
+        subject: reverted_subject,
+    })
+}
+
+/// A stable tree notification: patch queued, AUTOSEL pick or failed backport
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StableBackport {
+    pub kind: String,                      // "added", "autosel" or "failed"
+    pub stable_versions: Vec<String>,      // e.g., ["6.6"] or ["6.1", "5.15"]
+    pub original_subject: Option<String>,  // Subject of the upstream patch
+    pub upstream_commit: Option<String>,   // Upstream commit hash, lowercased
+}
+
+/// Detect stable tree notifications and AUTOSEL backport postings
+pub fn parse_stable_backport(email_info: &EmailInfo) -> Option<StableBackport> {
+    let subject = email_info.subject.trim();
+    
+    let (kind, stable_versions, original_subject) = if let Some(caps) = STABLE_ADDED_REGEX.captures(subject) {
+        ("added", vec![caps[2].to_string()], Some(caps[1].to_string()))
+    } else if let Some(caps) = STABLE_FAILED_REGEX.captures(subject) {
+        ("failed", vec![caps[2].to_string()], Some(caps[1].to_string()))
+    } else {
+        // "[PATCH AUTOSEL 6.6 03/12] bpf: ..." (several versions may be listed)
+        if email_info.is_reply {
+            return None;
+        }
+        let mut rest = subject;
+        let mut versions = None;
+        while let Some((tag, after)) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            let words: Vec<&str> = tag.split_whitespace().collect();
+            if words.iter().any(|w| w.eq_ignore_ascii_case("AUTOSEL")) {
+                versions = Some(words.iter()
+                    .filter(|w| w.split('.').all(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())) && w.contains('.'))
+                    .map(|w| w.to_string())
+                    .collect::<Vec<_>>());
+            }
+            rest = after.trim_start();
+        }
+        ("autosel", versions?, Some(rest.to_string()).filter(|s| !s.is_empty()))
+    };
+    
+    // The queued patch may carry "[PATCH]" tags in its quoted subject
+    let original_subject = original_subject.map(|s| {
+        let mut s = s.trim();
+        while let Some((_, after)) = s.strip_prefix('[').and_then(|r| r.split_once(']')) {
+            s = after.trim_start();
+        }
+        s.to_string()
+    });
+    let upstream_commit = UPSTREAM_COMMIT_REGEX.captures(&email_info.body)
+        .and_then(|caps| caps.get(1).or_else(|| caps.get(2)))
+        .map(|m| m.as_str().to_lowercase());
+    
+    Some(StableBackport {
+        kind: kind.to_string(),
+        stable_versions,
+        original_subject,
+        upstream_commit,
+    })
+}
+
+/// Crash report sent by syzbot (syzkaller's continuous fuzzing)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SyzbotReport {
+    pub title: String,                     // e.g., "KASAN: slab-use-after-free Read in bpf_prog_put"
+    pub extid: Option<String>,             // Bug ID from the dashboard link / syzbot+<extid>@ address
+    pub dashboard_url: Option<String>,
+    pub head_commit: Option<String>,
+    pub git_tree: Option<String>,
+    pub console_log_url: Option<String>,
+    pub kernel_config_url: Option<String>,
+    pub syz_repro_url: Option<String>,
+    pub c_repro_url: Option<String>,
+}
+
+/// Detect a syzbot report and extract its crash title and links
+/// Replies (including syzbot's own test results) return None
+pub fn parse_syzbot_report(email_info: &EmailInfo) -> Option<SyzbotReport> {
+    if email_info.is_reply {
+        return None;
+    }
+    let body = &email_info.body;
+    let from_syzbot = email_info.author_email.starts_with("syzbot");
+    if !from_syzbot && !body.contains("syzbot found the following") && !body.contains("syzbot has found a reproducer") {
+        return None;
+    }
+    
+    // "[syzbot] [bpf?] KASAN: ..." -> "KASAN: ..."
+    let mut title = email_info.subject.trim();
+    while let Some(rest) = title.strip_prefix('[').and_then(|r| r.split_once(']')) {
+        title = rest.1.trim_start();
+    }
+    
+    let mut report = SyzbotReport {
+        title: title.to_string(),
+        // syzbot+<extid>@syzkaller.appspotmail.com
+        extid: email_info.author_email.split_once('@')
+            .and_then(|(local, _)| local.strip_prefix("syzbot+"))
+            .map(str::to_string),
+        ..Default::default()
+    };
+    
+    for line in body.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let value = Some(value.to_string());
+        match key.trim().to_lowercase().as_str() {
+            "head commit" => report.head_commit = value.as_deref().and_then(|v| v.split_whitespace().next()).map(str::to_string),
+            "git tree" => report.git_tree = value,
+            "console output" => report.console_log_url = value,
+            "kernel config" => report.kernel_config_url = value,
+            "dashboard link" => report.dashboard_url = value,
+            "syz repro" => report.syz_repro_url = value,
+            "c reproducer" => report.c_repro_url = value,
+            _ => {}
+        }
+    }
+    
+    if report.extid.is_none() {
+        report.extid = report.dashboard_url.as_deref()
+            .and_then(|url| url.split_once("extid="))
+            .map(|(_, extid)| extid.split('&').next().unwrap_or(extid).to_string());
     }
     
-    let merge_info = parse_merge_metadata(email_info);
-    (true, merge_info)
+    // Without a dashboard link this is just a mention of syzbot, not a report
+    report.dashboard_url.as_ref()?;
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Email from a maintainer with the given subject and body
+    fn email(subject: &str, in_reply_to: Option<&str>, body: &str) -> EmailInfo {
+        let in_reply_to = in_reply_to.map_or(String::new(), |id| format!("In-Reply-To: <{}>\n", id));
+        let content = format!(
+            "From: Alexei Starovoitov <ast@kernel.org>\n\
+             Subject: {}\n\
+             Message-ID: <msg@kernel.org>\n\
+             {}\
+             Date: Tue, 2 Jan 2024 10:00:00 +0000\n\
+             \n\
+             {}\n",
+            subject, in_reply_to, body
+        );
+        let metadata = CommitMetadata {
+            commit_hash: "0".repeat(40),
+            author_name: "Alexei Starovoitov".to_string(),
+            author_email: "ast@kernel.org".to_string(),
+            subject: subject.to_string(),
+            author_time: None,
+            committer_time: None,
+        };
+        parse_email_from_content(&metadata.commit_hash, &content, &metadata).unwrap()
+    }
+
+    /// Reply from a maintainer with the given body
+    fn reply(body: &str) -> EmailInfo {
+        email("Re: [PATCH bpf-next] bpf: Fix verifier", Some("patch@example.com"), body)
+    }
+
+    const PATCH_BELOW: &str = "Fix the thing.\n\n\
+                               Signed-off-by: Jane Doe <jane@example.com>\n\
+                               ---\n \
+                               kernel/bpf/verifier.c | 1 +\n";
+
+    #[test]
+    fn in_body_from_attributes_posted_patch() {
+        let body = format!("From: Jane Doe <jane@example.com>\n\n{}", PATCH_BELOW);
+        let patch = email("[PATCH bpf-next] bpf: Fix verifier", None, &body);
+        assert_eq!(patch.author_email, "jane@example.com");
+        assert_eq!(patch.author_display_name, "Jane Doe");
+        assert_eq!(patch.envelope_from.as_deref(), Some("Alexei Starovoitov <ast@kernel.org>"));
+    }
+
+    #[test]
+    fn in_body_from_ignored_in_replies_and_plain_mail() {
+        let body = format!("From: Jane Doe <jane@example.com>\n\n{}", PATCH_BELOW);
+        let quoted = reply(&body);
+        assert_eq!(quoted.author_email, "ast@kernel.org");
+        assert!(quoted.envelope_from.is_none());
+
+        let forwarded = email("Fwd: build report", None, "From: Jane Doe <jane@example.com>\n\nThe build is broken.");
+        assert_eq!(forwarded.author_email, "ast@kernel.org");
+    }
+
+    #[test]
+    fn applied_reply_with_target_or_thanks() {
+        let merge = parse_applied_reply(&reply("Applied to bpf-next, thanks!")).unwrap();
+        assert_eq!(merge.repository, "bpf-next");
+        assert_eq!(merge.applied_by, "Alexei Starovoitov <ast@kernel.org>");
+
+        let merge = parse_applied_reply(&reply("Applied into bpf-next/master.")).unwrap();
+        assert_eq!((merge.repository.as_str(), merge.branch.as_str()), ("bpf-next", "master"));
+
+        let merge = parse_applied_reply(&reply("Queued for 6.9")).unwrap();
+        assert_eq!(merge.repository, "6.9");
+
+        for body in ["Thanks, applied.", "Applied, thanks!", "Applied the series. Thanks"] {
+            let merge = parse_applied_reply(&reply(body)).unwrap_or_else(|| panic!("{:?} not detected", body));
+            assert_eq!(merge.repository, "Unknown");
+        }
+    }
+
+    #[test]
+    fn applied_reply_skips_quoted_attribution() {
+        let body = "On Mon, Jan 1, 2024 at 10:00 AM Jane Doe <jane@example.com> wrote:\n\
+                    > Applied, but please check.\n\
+                    \n\
+                    Applied to bpf, thanks.";
+        assert_eq!(parse_applied_reply(&reply(body)).unwrap().repository, "bpf");
+    }
+
+    #[test]
+    fn applied_reply_rejects_discussion() {
+        for body in [
+            "Applied, but it fails to build on arm64.",
+            "Applied, compiled and booted on my test box.",
+            "Applied and tested, looks good.",
+            "Applied, tested on x86.",
+            "Applied, and then the verifier rejects the program.",
+            "Applied.",
+            "Applied locally to reproduce the crash you reported last week here.",
+        ] {
+            assert!(parse_applied_reply(&reply(body)).is_none(), "{:?} detected as applied", body);
+        }
+    }
+
+    #[test]
+    fn applied_reply_ignores_patches() {
+        let mut email = reply("Applied to bpf-next, thanks!");
+        email.is_reply = false;
+        assert!(parse_applied_reply(&email).is_none());
+    }
+
+    #[test]
+    fn trailers_wrapped_onto_a_second_line() {
+        let body = "Fix the thing.\n\n\
+                    Reviewed-by: Maximilian Alexander Very-Long-Surname-Holder\n \
+                    <max@example.com>\n\
+                    Signed-off-by: Jane Doe <jane@example.com>\n";
+        let trailers = extract_trailers(body);
+        assert_eq!(trailers, vec![
+            Trailer {
+                tag: "Reviewed-by".to_string(),
+                name: "Maximilian Alexander Very-Long-Surname-Holder".to_string(),
+                email: "max@example.com".to_string(),
+            },
+            Trailer {
+                tag: "Signed-off-by".to_string(),
+                name: "Jane Doe".to_string(),
+                email: "jane@example.com".to_string(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn trailers_in_quoted_text_are_skipped() {
+        let body = "On Tue, Jan 2, 2024 Jane Doe wrote:\n\
+                    > Fix the thing.\n\
+                    >\n\
+                    > Signed-off-by: Jane Doe <jane@example.com>\n\
+                    >> Acked-by: Someone Else <else@example.com>\n\
+                    \n\
+                    acked-BY: Alexei Starovoitov <ast@kernel.org>\n";
+        let trailers = extract_trailers(body);
+        assert_eq!(trailers.len(), 1);
+        assert_eq!(trailers[0].tag, "Acked-by");
+        assert_eq!(trailers[0].email, "ast@kernel.org");
+    }
+
+    #[test]
+    fn trailer_tags_are_ascii() {
+        let body = "ſigned-off-by: Long S <longs@example.com>\n\
+                    SIGNED-OFF-BY: Jane Doe <jane@example.com>\n";
+        let trailers = extract_trailers(body);
+        assert_eq!(trailers.len(), 1);
+        assert_eq!(trailers[0].tag, "Signed-off-by");
+        assert_eq!(trailers[0].email, "jane@example.com");
+    }
+
+    #[test]
+    fn trailers_after_separator_are_ignored() {
+        let body = format!("{}\nReviewed-by: After Separator <after@example.com>\n", PATCH_BELOW);
+        let trailers = extract_trailers(&body);
+        assert_eq!(trailers.len(), 1);
+        assert_eq!(trailers[0].email, "jane@example.com");
+
+        let signature = "Looks good.\n\nAcked-by: Jane Doe <jane@example.com>\n-- \nTested-by: Sig Nature <sig@example.com>\n";
+        let trailers = extract_trailers(signature);
+        assert_eq!(trailers.len(), 1);
+        assert_eq!(trailers[0].tag, "Acked-by");
+    }
+
+    #[test]
+    fn message_links_with_non_ascii_lines() {
+        let body = "Grüße\n\
+                    Ünïcode line with https://lore.kernel.org/all/20240102.1234-1-jane@example.com/\n\
+                    \n\
+                    Link: https://example.com/bug/42\n\
+                    See https://example.com/not-a-message\n";
+        let links = extract_message_links(body);
+        assert_eq!(links, vec![
+            MessageLink {
+                url: "https://lore.kernel.org/all/20240102.1234-1-jane@example.com/".to_string(),
+                message_id: Some("20240102.1234-1-jane@example.com".to_string()),
+                is_trailer: false,
+            },
+            MessageLink {
+                url: "https://example.com/bug/42".to_string(),
+                message_id: None,
+                is_trailer: true,
+            },
+        ]);
+    }
+
+    const SYZBOT_BODY: &str = "Hello,\n\n\
+                               syzbot found the following issue on:\n\n\
+                               HEAD commit:    8a1d6a2c5b7e Merge branch 'bpf-fixes'\n\
+                               git tree:       bpf-next\n\
+                               console output: https://syzkaller.appspot.com/x/log.txt?x=1234\n\
+                               kernel config:  https://syzkaller.appspot.com/x/.config?x=5678\n\
+                               dashboard link: https://syzkaller.appspot.com/bug?extid=0123456789abcdef&x=1\n\
+                               C reproducer:   https://syzkaller.appspot.com/x/repro.c?x=9abc\n";
+
+    /// Report with the given sender address and body
+    fn syzbot(author_email: &str, body: &str) -> EmailInfo {
+        let mut report = email("[syzbot] [bpf?] KASAN: slab-use-after-free Read in bpf_prog_put", None, body);
+        report.author_email = author_email.to_string();
+        report
+    }
+
+    #[test]
+    fn syzbot_report_fields() {
+        let report = parse_syzbot_report(&syzbot("syzbot+fedcba9876543210@syzkaller.appspotmail.com", SYZBOT_BODY)).unwrap();
+        assert_eq!(report.title, "KASAN: slab-use-after-free Read in bpf_prog_put");
+        // The sender address wins over the dashboard link
+        assert_eq!(report.extid.as_deref(), Some("fedcba9876543210"));
+        assert_eq!(report.head_commit.as_deref(), Some("8a1d6a2c5b7e"));
+        assert_eq!(report.git_tree.as_deref(), Some("bpf-next"));
+        assert_eq!(report.dashboard_url.as_deref(), Some("https://syzkaller.appspot.com/bug?extid=0123456789abcdef&x=1"));
+        assert_eq!(report.console_log_url.as_deref(), Some("https://syzkaller.appspot.com/x/log.txt?x=1234"));
+        assert_eq!(report.kernel_config_url.as_deref(), Some("https://syzkaller.appspot.com/x/.config?x=5678"));
+        assert_eq!(report.c_repro_url.as_deref(), Some("https://syzkaller.appspot.com/x/repro.c?x=9abc"));
+        assert_eq!(report.syz_repro_url, None);
+    }
+
+    #[test]
+    fn syzbot_extid_from_dashboard_link() {
+        // Forwarded by a person: no syzbot+ address, the extid comes from the link
+        let report = parse_syzbot_report(&syzbot("jane@example.com", SYZBOT_BODY)).unwrap();
+        assert_eq!(report.extid.as_deref(), Some("0123456789abcdef"));
+    }
+
+    #[test]
+    fn syzbot_without_dashboard_or_as_reply() {
+        let mention = "syzbot found the following issue, but I lost the link.";
+        assert!(parse_syzbot_report(&syzbot("syzbot+fedcba9876543210@syzkaller.appspotmail.com", mention)).is_none());
+        assert!(parse_syzbot_report(&syzbot("jane@example.com", "A plain bug report.")).is_none());
+
+        let mut reply = syzbot("syzbot+fedcba9876543210@syzkaller.appspotmail.com", SYZBOT_BODY);
+        reply.is_reply = true;
+        assert!(parse_syzbot_report(&reply).is_none());
+    }
+
+    #[test]
+    fn ping_replies() {
+        let quoted = "On Tue, 2 Jan 2024, Jane Doe <jane@example.com> wrote:\n\
+                      > Fix the thing.\n\
+                      > Any update on the verifier rework would be great as well.\n\
+                      \n";
+        for body in [
+            "ping".to_string(),
+            "Gentle ping.".to_string(),
+            "Hi Alexei,\n\nAny update on this?\n\nThanks,\nJane".to_string(),
+            format!("{}Friendly reminder, is there anything I should change?", quoted),
+            "Bump\n\n-- \nJane Doe, Kernel Team, Example Corp. All views are my own and do not represent anything.".to_string(),
+        ] {
+            assert!(is_ping(&reply(&body)), "{:?} not detected", body);
+        }
+    }
+
+    #[test]
+    fn ping_rejects_discussion_and_quotes() {
+        let long = "Any comments on the approach? I tried moving the check into the verifier first, \
+                    but that broke the existing selftests for bounded loops, so this version keeps it \
+                    in the JIT and adds a new selftest instead.";
+        for body in [
+            long,
+            // Only the quoted text asks for an update
+            "On Tue, 2 Jan 2024, Jane Doe <jane@example.com> wrote:\n> Any update on this?\n\nLooks good to me.",
+            "The ping_check helper looks fine.",
+            "Skipping this one for now.",
+            "",
+        ] {
+            assert!(!is_ping(&reply(body)), "{:?} detected", body);
+        }
+
+        // A new thread or a patch is never a ping
+        assert!(!is_ping(&email("[PATCH] net: ping: fix leak", None, "ping")));
+        let with_diff = reply("ping\n\ndiff --git a/x.c b/x.c\n--- a/x.c\n+++ b/x.c\n@@ -1 +1 @@\n-a\n+b\n");
+        assert!(with_diff.diff.is_some());
+        assert!(!is_ping(&with_diff));
+    }
+
+    #[test]
+    fn rejection_replies() {
+        for body in [
+            "NAK",
+            "Nak, this breaks the uapi.",
+            "NAKed.",
+            "Nacked-by: Alexei Starovoitov <ast@kernel.org>",
+            "This changes the uapi, so NAK.",
+            "I don't think we should expose this to unprivileged users.",
+            "Please drop this patch, it was fixed differently in bpf.",
+            "Please revert the series.",
+            "Greg, please drop this.",
+            "I'm not going to apply this.",
+            "This is not the right fix.",
+        ] {
+            assert!(is_rejection(&reply(body)), "{:?} not detected", body);
+        }
+    }
+
+    #[test]
+    fn rejection_ignores_quotes_and_look_alikes() {
+        for body in [
+            // Someone else's NAK, quoted
+            "On Tue, 2 Jan 2024, Jane Doe <jane@example.com> wrote:\n> NAK\n\nI disagree, the uapi is unchanged.",
+            "Nak Kim reviewed the earlier version.",
+            "Thanks, Janak",
+            "The tool is called nak.",
+            "Snake case it is.",
+            "I don't think we need a Fixes tag here.",
+            "Looks good. Please drop the stray newline in v2.",
+            "Please drop this check, the caller already does it.",
+        ] {
+            assert!(!is_rejection(&reply(body)), "{:?} detected", body);
+        }
+
+        // A patch saying NAK in its text is not a rejection
+        assert!(!is_rejection(&email("[PATCH] docs: explain NAK", None, "NAK")));
+    }
+
+    #[test]
+    fn revert_subject_and_commit() {
+        let body = "This reverts commit 1A2B3C4D5E6F7a8b9c0d1e2f3a4b5c6d7e8f9a0b.\n\n\
+                    It broke the selftests.\n\n\
+                    Signed-off-by: Jane Doe <jane@example.com>";
+        let revert = parse_revert(&email("[PATCH bpf] Revert \"bpf: Fix verifier\"", None, body)).unwrap();
+        assert_eq!(revert.subject.as_deref(), Some("bpf: Fix verifier"));
+        assert_eq!(revert.commit.as_deref(), Some("1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b"));
+
+        // Either one is enough
+        let revert = parse_revert(&email("[PATCH] Revert \"bpf: Fix verifier\"", None, "It broke things.")).unwrap();
+        assert_eq!((revert.subject.as_deref(), revert.commit), (Some("bpf: Fix verifier"), None));
+        let revert = parse_revert(&email("[PATCH] bpf: Undo the verifier change", None, "This reverts commit abcdef1.")).unwrap();
+        assert_eq!((revert.subject, revert.commit.as_deref()), (None, Some("abcdef1")));
+    }
+
+    #[test]
+    fn revert_of_a_revert() {
+        let revert = parse_revert(&email("[PATCH] Revert \"Revert \"bpf: Fix verifier\"\"", None, "Reapply it.")).unwrap();
+        assert_eq!(revert.subject.as_deref(), Some("Revert \"bpf: Fix verifier\""));
+    }
+
+    #[test]
+    fn revert_not_detected() {
+        assert!(parse_revert(&email("[PATCH] bpf: Fix verifier", None, "Fix the thing.")).is_none());
+        assert!(parse_revert(&email("[PATCH] Revert the verifier change", None, "No quotes, no commit.")).is_none());
+        assert!(parse_revert(&reply("This reverts commit abcdef1.")).is_none());
+    }
+
+    #[test]
+    fn stable_backport_added_and_failed() {
+        let added = email(
+            "Patch \"[PATCH bpf] bpf: Fix verifier\" has been added to the 6.6-stable tree",
+            None,
+            "This is a note to let you know that I've just added the patch titled\n\n\
+             commit 0123456789abcdef0123456789ABCDEF01234567 upstream.\n",
+        );
+        let backport = parse_stable_backport(&added).unwrap();
+        assert_eq!(backport.kind, "added");
+        assert_eq!(backport.stable_versions, vec!["6.6"]);
+        assert_eq!(backport.original_subject.as_deref(), Some("bpf: Fix verifier"));
+        assert_eq!(backport.upstream_commit.as_deref(), Some("0123456789abcdef0123456789abcdef01234567"));
+
+        let failed = email(
+            "FAILED: patch \"bpf: Fix verifier\" failed to apply to 5.15-stable tree",
+            None,
+            "The patch below does not apply to the 5.15-stable tree.\n",
+        );
+        let backport = parse_stable_backport(&failed).unwrap();
+        assert_eq!(backport.kind, "failed");
+        assert_eq!(backport.stable_versions, vec!["5.15"]);
+        assert_eq!(backport.original_subject.as_deref(), Some("bpf: Fix verifier"));
+        assert_eq!(backport.upstream_commit, None);
+    }
+
+    #[test]
+    fn stable_backport_autosel() {
+        let autosel = email(
+            "[PATCH AUTOSEL 6.1 5.15 03/12] bpf: Fix verifier",
+            None,
+            "From: Jane Doe <jane@example.com>\n\n\
+             [ Upstream commit abcdef0123456789abcdef0123456789abcdef01 ]\n\n\
+             Fix the thing.\n",
+        );
+        let backport = parse_stable_backport(&autosel).unwrap();
+        assert_eq!(backport.kind, "autosel");
+        assert_eq!(backport.stable_versions, vec!["6.1", "5.15"]);
+        assert_eq!(backport.original_subject.as_deref(), Some("bpf: Fix verifier"));
+        assert_eq!(backport.upstream_commit.as_deref(), Some("abcdef0123456789abcdef0123456789abcdef01"));
+    }
+
+    #[test]
+    fn stable_backport_not_detected() {
+        assert!(parse_stable_backport(&email("[PATCH bpf 1/2] bpf: Fix verifier", None, "Fix the thing.")).is_none());
+        assert!(parse_stable_backport(&email("Re: [PATCH AUTOSEL 6.1 03/12] bpf: Fix verifier", Some("autosel@kernel.org"), "Please drop.")).is_none());
+    }
+
+    fn tags(version: Option<i32>, is_rfc: bool, is_resend: bool, target_tree: Option<&str>) -> SubjectTags {
+        SubjectTags { version, is_rfc, is_resend, target_tree: target_tree.map(String::from) }
+    }
+
+    #[test]
+    fn subject_tags_version_and_tree() {
+        assert_eq!(parse_subject_tags("[PATCH bpf-next v3 2/5] bpf: Fix verifier"), tags(Some(3), false, false, Some("bpf-next")));
+        assert_eq!(parse_subject_tags("[PATCHv3] bpf: Fix verifier"), tags(Some(3), false, false, None));
+        assert_eq!(parse_subject_tags("[PATCH net,v2] net: fix leak"), tags(Some(2), false, false, Some("net")));
+        // Tags without a version are the first posting
+        assert_eq!(parse_subject_tags("[PATCH 1/3] bpf: Fix verifier"), tags(Some(1), false, false, None));
+    }
+
+    #[test]
+    fn subject_tags_rfc_resend_and_groups() {
+        assert_eq!(parse_subject_tags("[RFC PATCH bpf-next v2] bpf: New helper"), tags(Some(2), true, false, Some("bpf-next")));
+        assert_eq!(parse_subject_tags("[RESEND PATCH] bpf: Fix verifier"), tags(Some(1), false, true, None));
+        assert!(parse_subject_tags("[PATCH RFC/PATCH] bpf: New helper").is_rfc);
+        // Consecutive groups are combined
+        assert_eq!(parse_subject_tags("[RFC] [PATCH v4 net-next 0/7] net: rework"), tags(Some(4), true, false, Some("net-next")));
+    }
+
+    #[test]
+    fn subject_tags_skip_reply_prefixes() {
+        assert_eq!(parse_subject_tags("Re: [PATCH v2 1/3] bpf: Fix verifier"), tags(Some(2), false, false, None));
+        assert_eq!(parse_subject_tags("RE: Fwd: AW: [PATCH bpf v5] bpf: Fix verifier"), tags(Some(5), false, false, Some("bpf")));
+    }
+
+    #[test]
+    fn subject_tags_without_patch_tags() {
+        assert_eq!(parse_subject_tags("bpf: Fix verifier"), SubjectTags::default());
+        assert_eq!(parse_subject_tags("[syzbot] [bpf?] KASAN: slab-use-after-free"), SubjectTags::default());
+        assert_eq!(parse_subject_tags("Re: [ANNOUNCE] v6.8 released"), SubjectTags::default());
+    }
 }
@@ -0,0 +1,118 @@
+//! Parser for git's `.mailmap` format, used to fold an author's addresses into one identity
+//!
+//! Each line maps an identity seen in mail to its canonical form:
+//!
+//! ```text
+//! Proper Name <commit@email>
+//! <proper@email> <commit@email>
+//! Proper Name <proper@email> <commit@email>
+//! Proper Name <proper@email> Commit Name <commit@email>
+//! ```
+//!
+//! The Linux kernel keeps one at the root of its tree; pass the checkout
+//! directory to `load_mailmap` to use it.
+use std::path::Path;
+use serde::Serialize;
+
+/// One mapping from a commit identity to its canonical name and/or address
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MailmapEntry {
+    pub proper_name: Option<String>,
+    pub proper_email: Option<String>,
+    /// When set, only the identity with this name and `commit_email` is mapped
+    pub commit_name: Option<String>,
+    pub commit_email: String,
+}
+
+/// Split "Name <email> rest" into (name, email, rest)
+fn take_identity(text: &str) -> Option<(Option<String>, String, &str)> {
+    let open = text.find('<')?;
+    let close = open + text[open..].find('>')?;
+    let name = text[..open].trim();
+    let email = text[open + 1..close].trim().to_lowercase();
+    let name = if name.is_empty() { None } else { Some(name.to_string()) };
+    Some((name, email, &text[close + 1..]))
+}
+
+/// Parse a single line, returning None for comments, blank and malformed lines
+fn parse_line(line: &str) -> Option<MailmapEntry> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (proper_name, first_email, rest) = take_identity(line)?;
+    match take_identity(rest) {
+        Some((commit_name, commit_email, _)) => Some(MailmapEntry {
+            proper_name,
+            proper_email: Some(first_email).filter(|e| !e.is_empty()),
+            commit_name,
+            commit_email,
+        }),
+        None => Some(MailmapEntry {
+            proper_name,
+            proper_email: None,
+            commit_name: None,
+            commit_email: first_email,
+        }),
+    }
+    .filter(|entry| !entry.commit_email.is_empty())
+}
+
+/// Parse the contents of a `.mailmap` file
+pub fn parse_mailmap(content: &str) -> Vec<MailmapEntry> {
+    content.lines().filter_map(parse_line).collect()
+}
+
+/// Read a `.mailmap` file, or the one at the root of a directory such as a kernel checkout
+pub fn load_mailmap(path: &Path) -> std::io::Result<Vec<MailmapEntry>> {
+    let file = if path.is_dir() {
+        path.join(".mailmap")
+    } else {
+        path.to_path_buf()
+    };
+    let content = std::fs::read(&file)?;
+    Ok(parse_mailmap(&String::from_utf8_lossy(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(proper_name: Option<&str>, proper_email: Option<&str>, commit_name: Option<&str>, commit_email: &str) -> MailmapEntry {
+        MailmapEntry {
+            proper_name: proper_name.map(String::from),
+            proper_email: proper_email.map(String::from),
+            commit_name: commit_name.map(String::from),
+            commit_email: commit_email.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_all_four_line_forms() {
+        let content = "Jane Doe <jane@old.example.com>\n\
+                       <jane@example.com> <Jane@Old.Example.com>\n\
+                       Jane Doe <jane@example.com> <jdoe@corp.example.com>\n\
+                       Jane Doe <jane@example.com> J. Doe <doe@example.com>\n";
+        assert_eq!(parse_mailmap(content), vec![
+            entry(Some("Jane Doe"), None, None, "jane@old.example.com"),
+            entry(None, Some("jane@example.com"), None, "jane@old.example.com"),
+            entry(Some("Jane Doe"), Some("jane@example.com"), None, "jdoe@corp.example.com"),
+            entry(Some("Jane Doe"), Some("jane@example.com"), Some("J. Doe"), "doe@example.com"),
+        ]);
+    }
+
+    #[test]
+    fn skips_comments_blank_and_malformed_lines() {
+        let content = "# Kernel mailmap\n\
+                       \n\
+                       \t# indented comment\n\
+                       Jane Doe <jane@example.com> <jdoe@corp.example.com> # moved jobs\n\
+                       No Address At All\n\
+                       Unclosed <jane@example.com\n\
+                       Empty Commit Address <jane@example.com> <>\n";
+        assert_eq!(parse_mailmap(content), vec![
+            entry(Some("Jane Doe"), Some("jane@example.com"), None, "jdoe@corp.example.com"),
+        ]);
+    }
+}
@@ -0,0 +1,324 @@
+use std::fs;
+use std::path::Path;
+use serde::Serialize;
+use mailparse::{parse_headers, MailHeaderMap};
+use crate::database::DatabaseManager;
+use crate::git_config::MailingListRepo;
+use crate::git_parser::CommitMetadata;
+use crate::mail_parser::{extract_email, extract_name, parse_email_from_content, EmailInfo, ParseError};
+
+/// Result of importing an mbox file or Maildir
+#[derive(Debug, Serialize)]
+pub struct MailImportResult {
+    pub success: bool,
+    pub messages_found: u32,
+    pub messages_parsed: u32,
+    pub total_authors_inserted: u32,
+    pub total_emails_inserted: u32,
+    pub errors: Vec<String>,
+    /// Stopped early by `cancel_population`
+    pub cancelled: bool,
+}
+
+/// Split the contents of an mbox file into raw messages
+/// Messages start at a "From " separator line; mboxrd-style ">From " quoting is undone.
+/// CRLF line endings are accepted and come out as LF.
+pub fn split_mbox(content: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current: Option<String> = None;
+    let mut previous_blank = true;
+
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with("From ") && previous_blank {
+            if let Some(message) = current.take() {
+                messages.push(message);
+            }
+            current = Some(String::new());
+            previous_blank = false;
+            continue;
+        }
+
+        previous_blank = line.is_empty();
+
+        if let Some(message) = current.as_mut() {
+            let unquoted = if line.starts_with('>') && line.trim_start_matches('>').starts_with("From ") {
+                &line[1..]
+            } else {
+                line
+            };
+            message.push_str(unquoted);
+            message.push('\n');
+        }
+    }
+
+    match current {
+        Some(message) => messages.push(message),
+        // No separator at all: treat the whole file as a single message (.eml)
+        None if messages.is_empty() => messages.push(content.to_string()),
+        None => {}
+    }
+
+    // Drop the trailing blank line that separates messages
+    messages.into_iter()
+        .map(|m| m.trim_end_matches('\n').to_string() + "\n")
+        .filter(|m| !m.trim().is_empty())
+        .collect()
+}
+
+/// Read and split an mbox file (non UTF-8 bytes are replaced)
+pub fn read_mbox_file(path: &str) -> Result<Vec<String>, ParseError> {
+    let bytes = fs::read(path)?;
+    Ok(split_mbox(&String::from_utf8_lossy(&bytes)))
+}
+
+/// Build the metadata that git commits normally provide from the message headers
+fn metadata_from_message(key: &str, raw_message: &str) -> Result<CommitMetadata, ParseError> {
+    let (headers, _) = parse_headers(raw_message.as_bytes())?;
+
+    let from = headers.get_first_value("From").unwrap_or_default();
+    let author_email = extract_email(&from);
+    let author_name = match extract_name(&from) {
+        name if name.is_empty() => author_email.clone(),
+        name => name,
+    };
+
+    Ok(CommitMetadata {
+        commit_hash: key.to_string(),
+        author_name,
+        author_email,
+        subject: headers.get_first_value("Subject").unwrap_or_default(),
+        author_time: None,
+        committer_time: None,
+    })
+}
+
+/// Read every message of a Maildir (one message per file in `cur/` and `new/`)
+/// A plain directory of message files is accepted as well
+pub fn read_maildir(path: &str) -> Result<Vec<String>, ParseError> {
+    let root = Path::new(path);
+    let subdirs: Vec<_> = ["cur", "new"].iter()
+        .map(|name| root.join(name))
+        .filter(|dir| dir.is_dir())
+        .collect();
+    let dirs = if subdirs.is_empty() { vec![root.to_path_buf()] } else { subdirs };
+
+    let mut files = Vec::new();
+    for dir in dirs {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if entry.file_type()?.is_file() && !is_hidden {
+                files.push(entry.path());
+            }
+        }
+    }
+    // Maildir names start with the delivery timestamp, so this is roughly chronological
+    files.sort();
+
+    let mut messages = Vec::with_capacity(files.len());
+    for file in files {
+        let bytes = fs::read(&file)?;
+        messages.push(String::from_utf8_lossy(&bytes).to_string());
+    }
+    Ok(messages)
+}
+
+/// Stable key stored in place of a commit hash, used to skip already imported messages
+fn message_key(source_name: &str, index: usize, raw_message: &str) -> String {
+    let message_id = parse_headers(raw_message.as_bytes())
+        .ok()
+        .and_then(|(headers, _)| headers.get_first_value("Message-ID"));
+
+    match message_id {
+        Some(id) if !id.trim().is_empty() => format!("mbox:{}", id.trim().trim_start_matches('<').trim_end_matches('>')),
+        _ => format!("mbox:{}:{}", source_name, index),
+    }
+}
+
+/// Parse raw messages (from an mbox or Maildir) with the regular mail parser
+/// Returns (successful_emails, errors)
+pub fn parse_mbox_messages(messages: &[String], source_name: &str) -> (Vec<(String, EmailInfo)>, Vec<String>) {
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, raw_message) in messages.iter().enumerate() {
+        let key = message_key(source_name, index, raw_message);
+        let result = metadata_from_message(&key, raw_message)
+            .and_then(|metadata| parse_email_from_content(&key, raw_message, &metadata));
+
+        match result {
+            Ok(email_info) => parsed.push((key, email_info)),
+            Err(e) => errors.push(format!("Error parsing message {} of {}: {}", index + 1, source_name, e)),
+        }
+    }
+
+    (parsed, errors)
+}
+
+/// Import an mbox file into the database through the same insertion pipeline as git population
+///
+/// # Arguments
+/// * `path` - Path to the mbox file
+/// * `list_name` - Mailing list to file the messages under (defaults to the file name)
+/// * `progress_callback` - Receives (current, total, phase)
+pub async fn import_mbox<F>(
+    db: &mut DatabaseManager,
+    path: &str,
+    list_name: Option<String>,
+    progress_callback: Option<F>
+) -> Result<MailImportResult, Box<dyn std::error::Error>>
+where
+    F: Fn(u32, u32, String) + Send + Sync + 'static,
+{
+    let file_path = path.to_string();
+    let messages = tokio::task::spawn_blocking(move || read_mbox_file(&file_path)).await??;
+    import_messages(db, path, messages, list_name, progress_callback).await
+}
+
+/// Import a Maildir (e.g. exported from a mail client) through the same pipeline as `import_mbox`
+pub async fn import_maildir<F>(
+    db: &mut DatabaseManager,
+    path: &str,
+    list_name: Option<String>,
+    progress_callback: Option<F>
+) -> Result<MailImportResult, Box<dyn std::error::Error>>
+where
+    F: Fn(u32, u32, String) + Send + Sync + 'static,
+{
+    let dir_path = path.to_string();
+    let messages = tokio::task::spawn_blocking(move || read_maildir(&dir_path)).await??;
+    import_messages(db, path, messages, list_name, progress_callback).await
+}
+
+/// Parse raw messages and insert them under a mailing list named after the source
+async fn import_messages<F>(
+    db: &mut DatabaseManager,
+    path: &str,
+    messages: Vec<String>,
+    list_name: Option<String>,
+    progress_callback: Option<F>
+) -> Result<MailImportResult, Box<dyn std::error::Error>>
+where
+    F: Fn(u32, u32, String) + Send + Sync + 'static,
+{
+    let source_name = Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mbox".to_string());
+
+    let report = |current: u32, total: u32, phase: &str| {
+        if let Some(callback) = &progress_callback {
+            callback(current, total, phase.to_string());
+        }
+    };
+
+    let messages_found = messages.len() as u32;
+    println!("Found {} messages in {}", messages_found, path);
+    report(0, messages_found, "parsing");
+
+    let parse_source = source_name.clone();
+    let (parsed, mut errors) = tokio::task::spawn_blocking(move || parse_mbox_messages(&messages, &parse_source)).await?;
+    let messages_parsed = parsed.len() as u32;
+    report(messages_parsed, messages_found, "inserting");
+
+    let list = MailingListRepo {
+        name: list_name.unwrap_or_else(|| source_name.clone()),
+        repo_path: path.to_string(),
+        clone_url: String::new(),
+    };
+
+    let result = db.insert_parsed_emails(&list, parsed, |inserted, total| {
+        report(inserted, total, "inserting");
+    }).await?;
+    errors.extend(result.errors);
+
+    report(messages_found, messages_found, "done");
+
+    Ok(MailImportResult {
+        success: errors.is_empty(),
+        messages_found,
+        messages_parsed,
+        total_authors_inserted: result.total_authors_inserted,
+        total_emails_inserted: result.total_emails_inserted,
+        errors,
+        cancelled: result.cancelled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_mbox_separates_messages() {
+        let content = "From alice@example.com Mon Jan  1 00:00:00 2024\n\
+                       From: Alice <alice@example.com>\n\
+                       Subject: one\n\
+                       \n\
+                       first\n\
+                       \n\
+                       From bob@example.com Mon Jan  1 00:00:00 2024\n\
+                       From: Bob <bob@example.com>\n\
+                       Subject: two\n\
+                       \n\
+                       second\n";
+        let messages = split_mbox(content);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0], "From: Alice <alice@example.com>\nSubject: one\n\nfirst\n");
+        assert_eq!(messages[1], "From: Bob <bob@example.com>\nSubject: two\n\nsecond\n");
+    }
+
+    #[test]
+    fn split_mbox_unquotes_from_lines() {
+        // mboxrd: one level of ">" is removed from ">From " lines, more levels keep the rest
+        let content = "From a@b Mon Jan  1 00:00:00 2024\n\
+                       Subject: quoting\n\
+                       \n\
+                       >From the start\n\
+                       >>From a quote\n\
+                       > From is not escaped\n\
+                       From inside a paragraph is not a separator\n";
+        let messages = split_mbox(content);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0],
+            "Subject: quoting\n\nFrom the start\n>From a quote\n> From is not escaped\nFrom inside a paragraph is not a separator\n"
+        );
+    }
+
+    #[test]
+    fn split_mbox_accepts_crlf() {
+        let content = "From a@b Mon Jan  1 00:00:00 2024\r\n\
+                       Subject: one\r\n\
+                       \r\n\
+                       >From body\r\n\
+                       \r\n\
+                       From c@d Mon Jan  1 00:00:00 2024\r\n\
+                       Subject: two\r\n\
+                       \r\n\
+                       body\r\n";
+        let messages = split_mbox(content);
+        assert_eq!(messages, vec!["Subject: one\n\nFrom body\n", "Subject: two\n\nbody\n"]);
+    }
+
+    #[test]
+    fn split_mbox_drops_empty_trailing_message() {
+        let content = "From a@b Mon Jan  1 00:00:00 2024\n\
+                       Subject: one\n\
+                       \n\
+                       body\n\
+                       \n\
+                       From c@d Mon Jan  1 00:00:00 2024\n\
+                       \n\
+                       \n";
+        assert_eq!(split_mbox(content), vec!["Subject: one\n\nbody\n"]);
+        assert!(split_mbox("").is_empty());
+    }
+
+    #[test]
+    fn split_mbox_without_separator_is_one_message() {
+        let eml = "From: Alice <alice@example.com>\nSubject: eml\n\nbody\n";
+        assert_eq!(split_mbox(eml), vec![eml]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Optional Message-ID lookups through the SQLite indexes of a public-inbox v2 inbox
+//!
+//! `msgmap.sqlite3` maps a Message-ID to its article number and `xap15/over.sqlite3`
+//! maps the article number to the git blob holding the message. Mirrors made with
+//! plain `git clone` don't have these files, in which case `MsgMap::open` returns None.
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+
+/// A message found in the msgmap
+#[derive(Debug, Clone, Serialize)]
+pub struct MsgMapEntry {
+    pub message_id: String,
+    /// public-inbox article number
+    pub num: i64,
+    /// OID of the blob holding the raw message (from over.sqlite3)
+    pub blob: Option<String>,
+}
+
+/// Read-only handle on an inbox's msgmap (and overview database, when present)
+pub struct MsgMap {
+    msgmap: SqlitePool,
+    over: Option<SqlitePool>,
+}
+
+/// Open a SQLite database read-only
+async fn open_read_only(path: &Path) -> Result<SqlitePool, sqlx::Error> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true);
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+}
+
+/// Locate over.sqlite3 (kept next to the Xapian shards, e.g. xap15/)
+fn find_over_db(inbox_path: &Path) -> Option<PathBuf> {
+    let mut xap_dirs: Vec<PathBuf> = std::fs::read_dir(inbox_path).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir() && path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("xap"))
+        })
+        .collect();
+    // Newest Xapian schema version first
+    xap_dirs.sort();
+    xap_dirs.into_iter()
+        .rev()
+        .map(|dir| dir.join("over.sqlite3"))
+        .find(|path| path.is_file())
+}
+
+/// Pull the blob OID out of an over.sqlite3 `ddd` column
+/// ddd is zlib-compressed doc data: subject, from, references, to, cc, blob, mid, bytes, lines
+fn blob_from_doc_data(ddd: &[u8]) -> Option<String> {
+    let mut doc_data = String::new();
+    flate2::read::ZlibDecoder::new(ddd).read_to_string(&mut doc_data).ok()?;
+    doc_data.split('\n')
+        .nth(5)
+        .map(|blob| blob.trim().to_string())
+        .filter(|blob| blob.len() == 40 && blob.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+impl MsgMap {
+    /// Open the msgmap of the inbox at `inbox_path` (the directory holding `git/<epoch>.git`)
+    /// Returns None when the inbox has no msgmap.sqlite3
+    pub async fn open(inbox_path: &str) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let inbox_path = Path::new(inbox_path);
+        let msgmap_path = inbox_path.join("msgmap.sqlite3");
+        if !msgmap_path.is_file() {
+            return Ok(None);
+        }
+
+        let msgmap = open_read_only(&msgmap_path).await?;
+        let over = match find_over_db(inbox_path) {
+            Some(over_path) => Some(open_read_only(&over_path).await?),
+            None => None,
+        };
+
+        Ok(Some(Self { msgmap, over }))
+    }
+
+    /// Open the msgmap of the configured archive, if it has one
+    pub async fn open_configured() -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let list = crate::git_parser::default_mailing_list()?;
+        Self::open(&list.repo_path).await
+    }
+
+    /// Resolve a Message-ID (with or without angle brackets) to its article number and blob
+    pub async fn lookup(&self, message_id: &str) -> Result<Option<MsgMapEntry>, Box<dyn std::error::Error>> {
+        let message_id = message_id.trim().trim_start_matches('<').trim_end_matches('>');
+
+        let row: Option<(i64,)> = sqlx::query_as("SELECT num FROM msgmap WHERE mid = ?")
+            .bind(message_id)
+            .fetch_optional(&self.msgmap)
+            .await?;
+        let Some((num,)) = row else {
+            return Ok(None);
+        };
+
+        let blob = match &self.over {
+            Some(over) => {
+                let ddd: Option<(Vec<u8>,)> = sqlx::query_as("SELECT ddd FROM over WHERE num = ?")
+                    .bind(num)
+                    .fetch_optional(over)
+                    .await?;
+                ddd.and_then(|(ddd,)| blob_from_doc_data(&ddd))
+            }
+            None => None,
+        };
+
+        Ok(Some(MsgMapEntry {
+            message_id: message_id.to_string(),
+            num,
+            blob,
+        }))
+    }
+}
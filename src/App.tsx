@@ -54,6 +54,34 @@ interface DatabaseConfig {
   database: string;
 }
 
+// Rejection of connect_database: a `kind` tag plus the fields of that kind,
+// e.g. { kind: "auth_failed", user: "postgres", message: "..." }
+interface ConnectionError {
+  kind: string;
+  message?: string;
+  [field: string]: unknown;
+}
+
+// Readable text for a connect_database rejection ("Auth failed: ...")
+function describeConnectionError(err: unknown): string {
+  if (typeof err === "string") {
+    return err;
+  }
+  const { kind, message, ...fields } = err as ConnectionError;
+  const label = (value: string) => value.charAt(0).toUpperCase() + value.slice(1).replace(/_/g, " ");
+  // Kinds without a driver message (incompatible_schema, pool_too_large) are described by their fields
+  const details = message ?? Object.entries(fields)
+    .map(([name, value]) => {
+      if (value && typeof value === "object") {
+        const { kind: nested, ...rest } = value as ConnectionError;
+        return `${label(nested)} (${Object.entries(rest).map(([k, v]) => `${k}: ${v}`).join(", ")})`;
+      }
+      return `${name}: ${value}`;
+    })
+    .join(", ");
+  return `${label(kind)}: ${details}`;
+}
+
 function App() {
   const [error, setError] = useState<string>("");
   const [loading, setLoading] = useState<boolean>(false);
@@ -182,8 +210,7 @@ function App() {
       
       console.log(message);
     } catch (err) {
-      const errorMessage = err as string;
-      setDbConnectionError(errorMessage);
+      setDbConnectionError(describeConnectionError(err));
       setDatabaseConnected(false);
     } finally {
       setDbConnecting(false);
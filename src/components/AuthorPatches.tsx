@@ -54,8 +54,8 @@ export default function AuthorPatches() {
     setError("");
 
     try {
-      const authorList: Author[] = await invoke("get_authors");
-      setAuthors(authorList);
+      const authorPage: { items: Author[] } = await invoke("get_authors");
+      setAuthors(authorPage.items);
     } catch (err) {
       setError(`Failed to load authors: ${err}`);
     } finally {
@@ -70,10 +70,10 @@ export default function AuthorPatches() {
     setSelectedPatch(null);
 
     try {
-      const patchList: Patch[] = await invoke("get_patches_by_author", {
+      const patchPage: { items: Patch[] } = await invoke("get_patches_by_author", {
         authorId: author.author_id,
       });
-      setPatches(patchList);
+      setPatches(patchPage.items);
     } catch (err) {
       setError(`Failed to load patches: ${err}`);
     } finally {
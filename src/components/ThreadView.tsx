@@ -128,13 +128,13 @@ export default function ThreadView() {
 
     try {
       const offset = (currentPage - 1) * pageSize;
-      const threadList: ThreadSummary[] = await invoke("get_threads", {
+      const page: { items: ThreadSummary[] } = await invoke("get_threads", {
         limit: pageSize,
         offset: offset,
         sortBy: sortBy,
         mergeFilter: mergeFilter === "all" ? null : mergeFilter,
       });
-      setThreads(threadList);
+      setThreads(page.items);
     } catch (err) {
       setError(`Failed to load threads: ${err}`);
     } finally {